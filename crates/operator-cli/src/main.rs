@@ -6,12 +6,25 @@
 //   agenc-db list-tasks [--status STATUS]   List tasks (optionally filtered)
 //   agenc-db export --format json           Export full database as JSON
 //   agenc-db prune --older-than 30          Prune old completed tasks/sessions
+//   agenc-db spend [--days 30]              Show the spend ledger and window totals
+//   agenc-db audit list [--limit 50]        Show the newest audit log entries
+//   agenc-db audit verify                   Check the audit log hash chain
+//   agenc-db audit export                   Export the audit log as JSON
+//   agenc-db wallet create                  Create an encrypted wallet keystore
+//   agenc-db wallet import KEYPAIR.json     Encrypt a Solana CLI keypair file
+//   agenc-db wallet export-public           Print the keystore's wallet address
+//   agenc-db wallet change-passphrase       Re-encrypt under a new passphrase
+//   agenc-db proof export TASK              Print a task's stored proof manifest
+//   agenc-db proof verify TASK|MANIFEST     Check a proof manifest against chain
 // ============================================================================
 
 use anyhow::Result;
 use chrono::{TimeZone, Utc};
 use clap::{Parser, Subcommand};
-use operator_core::{DbTaskStatus, OperatorDb};
+use operator_core::keystore::{self, Keystore, Zeroizing, MIN_PASSPHRASE_LEN};
+use operator_core::proofs::{self, ProofManifest};
+use operator_core::{AuditEntry, DbTaskStatus, OperatorDb, RpcPool, SpendCategory};
+use std::path::{Path, PathBuf};
 
 /// AgenC Operator database inspection tool
 #[derive(Parser)]
@@ -32,7 +45,7 @@ enum Commands {
 
     /// List tasks with optional status filter
     ListTasks {
-        /// Filter by status: claimed, in-progress, completed, disputed, resolved
+        /// Filter by status: claimed, in-progress, completed, disputed, resolved, failed
         #[arg(long)]
         status: Option<String>,
     },
@@ -58,6 +71,124 @@ enum Commands {
         #[arg(long)]
         dry_run: bool,
     },
+
+    /// Show the policy spend ledger with daily/weekly/monthly totals
+    Spend {
+        /// Only list entries from the last N days
+        #[arg(long, default_value = "30")]
+        days: i64,
+
+        /// Filter by category: task-creation, swap
+        #[arg(long)]
+        category: Option<String>,
+    },
+
+    /// Inspect the tamper-evident audit log of executed intents
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+
+    /// Manage encrypted wallet keystores
+    Wallet {
+        #[command(subcommand)]
+        command: WalletCommands,
+    },
+
+    /// Export and verify proof-of-work manifests of completed tasks
+    Proof {
+        #[command(subcommand)]
+        command: ProofCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum WalletCommands {
+    /// Generate a new keypair into an encrypted keystore
+    Create {
+        /// Keystore path (default: ~/.agenc/wallet.keystore.json)
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// Overwrite an existing keystore
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Encrypt an existing Solana CLI keypair file (JSON byte array)
+    Import {
+        /// Plaintext keypair file to import
+        keypair: PathBuf,
+
+        /// Keystore path (default: ~/.agenc/wallet.keystore.json)
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// Overwrite an existing keystore
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Print the wallet address (no passphrase needed)
+    ExportPublic {
+        /// Keystore path (default: ~/.agenc/wallet.keystore.json)
+        #[arg(long)]
+        keystore: Option<PathBuf>,
+    },
+
+    /// Re-encrypt the keystore under a new passphrase
+    ChangePassphrase {
+        /// Keystore path (default: ~/.agenc/wallet.keystore.json)
+        #[arg(long)]
+        keystore: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// List the newest audit entries
+    List {
+        /// Number of entries to show
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+
+    /// Verify the hash chain (exits with an error if it is broken)
+    Verify,
+
+    /// Export the full audit log
+    Export {
+        /// Output format (currently only json is supported)
+        #[arg(long, default_value = "json")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProofCommands {
+    /// Print the proof manifest stored for a task (by task PDA)
+    Export {
+        task_id: String,
+    },
+
+    /// Recompute a manifest's Merkle root and compare it with the proof hash
+    /// on the worker's claim (exits with an error on any mismatch)
+    Verify {
+        /// Task PDA with a stored proof, or a manifest JSON file
+        target: String,
+
+        /// Solana RPC endpoint
+        #[arg(long, default_value = "https://api.devnet.solana.com")]
+        rpc_url: String,
+
+        /// Skip the on-chain check
+        #[arg(long)]
+        offline: bool,
+
+        /// Also re-hash the manifest's files, relative to this directory
+        #[arg(long)]
+        files: Option<PathBuf>,
+    },
 }
 
 fn parse_status(s: &str) -> Result<DbTaskStatus> {
@@ -67,13 +198,22 @@ fn parse_status(s: &str) -> Result<DbTaskStatus> {
         "completed" => Ok(DbTaskStatus::Completed),
         "disputed" => Ok(DbTaskStatus::Disputed),
         "resolved" => Ok(DbTaskStatus::Resolved),
+        "failed" => Ok(DbTaskStatus::Failed),
         _ => anyhow::bail!(
-            "Unknown status '{}'. Valid values: claimed, in-progress, completed, disputed, resolved",
+            "Unknown status '{}'. Valid values: claimed, in-progress, completed, disputed, resolved, failed",
             s
         ),
     }
 }
 
+fn parse_category(s: &str) -> Result<SpendCategory> {
+    match s.to_lowercase().as_str() {
+        "task-creation" | "task_creation" | "task" => Ok(SpendCategory::TaskCreation),
+        "swap" => Ok(SpendCategory::Swap),
+        _ => anyhow::bail!("Unknown category '{}'. Valid values: task-creation, swap", s),
+    }
+}
+
 fn format_timestamp(ts: i64) -> String {
     Utc.timestamp_opt(ts, 0)
         .single()
@@ -83,6 +223,17 @@ fn format_timestamp(ts: i64) -> String {
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+
+    // Keystore commands don't touch the database
+    if let Commands::Wallet { command } = cli.command {
+        return match command {
+            WalletCommands::Create { out, force } => cmd_wallet_create(out, force),
+            WalletCommands::Import { keypair, out, force } => cmd_wallet_import(&keypair, out, force),
+            WalletCommands::ExportPublic { keystore } => cmd_wallet_export_public(keystore),
+            WalletCommands::ChangePassphrase { keystore } => cmd_wallet_change_passphrase(keystore),
+        };
+    }
+
     let db = OperatorDb::open(cli.db_path.as_deref())?;
 
     match cli.command {
@@ -94,6 +245,19 @@ fn main() -> Result<()> {
             session_days,
             dry_run,
         } => cmd_prune(&db, older_than, session_days, dry_run),
+        Commands::Spend { days, category } => cmd_spend(&db, days, category),
+        Commands::Audit { command } => match command {
+            AuditCommands::List { limit } => cmd_audit_list(&db, limit),
+            AuditCommands::Verify => cmd_audit_verify(&db),
+            AuditCommands::Export { format } => cmd_audit_export(&db, &format),
+        },
+        Commands::Proof { command } => match command {
+            ProofCommands::Export { task_id } => cmd_proof_export(&db, &task_id),
+            ProofCommands::Verify { target, rpc_url, offline, files } => {
+                cmd_proof_verify(&db, &target, &rpc_url, offline, files.as_deref())
+            }
+        },
+        Commands::Wallet { .. } => unreachable!("handled above"),
     }
 }
 
@@ -109,6 +273,11 @@ fn cmd_stats(db: &OperatorDb) -> Result<()> {
     }
     println!("Sessions: {}", stats.total_sessions);
     println!("Proofs:   {}", stats.total_proofs);
+    println!("Spends:   {}", stats.total_spends);
+    println!("Audit:    {}", stats.total_audit_entries);
+    println!("Wallets:  {}", stats.total_wallet_profiles);
+    println!("Descriptions: {}", stats.total_descriptions);
+    println!("History:  {}", stats.total_history);
 
     Ok(())
 }
@@ -159,12 +328,17 @@ fn cmd_export(db: &OperatorDb, format: &str) -> Result<()> {
     let stats = db.stats()?;
     let config = db.get_config()?;
 
+    let spends = db.list_spends(None)?;
+    let audit = db.list_audit(None)?;
+
     let export = serde_json::json!({
         "exported_at": Utc::now().to_rfc3339(),
         "stats": stats,
         "config": config,
         "tasks": tasks,
         "sessions": sessions,
+        "spends": spends,
+        "audit": audit,
     });
 
     println!("{}", serde_json::to_string_pretty(&export)?);
@@ -234,3 +408,267 @@ fn cmd_prune(db: &OperatorDb, older_than: i64, session_days: i64, dry_run: bool)
 
     Ok(())
 }
+
+fn cmd_spend(db: &OperatorDb, days: i64, category: Option<String>) -> Result<()> {
+    let filter = category.as_deref().map(parse_category).transpose()?;
+    let now = Utc::now().timestamp();
+
+    println!("=== Spend Ledger ===");
+    for (label, window_days) in [("Last 24h", 1), ("Last 7d", 7), ("Last 30d", 30)] {
+        let lamports = db.total_spent_since(now - window_days * 86400, filter)?;
+        println!("{:10} {:.4} SOL", label, lamports as f64 / 1_000_000_000.0);
+    }
+    println!();
+
+    let spends: Vec<_> = db
+        .list_spends(Some(now - days * 86400))?
+        .into_iter()
+        .filter(|r| filter.is_none_or(|c| r.category == c))
+        .collect();
+
+    if spends.is_empty() {
+        println!("No spends in the last {} days.", days);
+        return Ok(());
+    }
+
+    println!("TIME                    CATEGORY        WALLET               SOL  SIGNATURE");
+    println!("{}", "-".repeat(100));
+
+    for record in &spends {
+        println!(
+            "{:<22}  {:<14}  {:<12}  {:>10.4}  {}",
+            format_timestamp(record.timestamp),
+            record.category.label(),
+            record.wallet.as_deref().unwrap_or("-"),
+            record.lamports as f64 / 1_000_000_000.0,
+            record.signature.as_deref().unwrap_or("-")
+        );
+    }
+
+    println!("\nTotal: {} entries", spends.len());
+    Ok(())
+}
+
+fn cmd_audit_list(db: &OperatorDb, limit: usize) -> Result<()> {
+    let entries = db.list_audit(Some(limit))?;
+
+    if entries.is_empty() {
+        println!("Audit log is empty.");
+        return Ok(());
+    }
+
+    println!(
+        "{:>6}  {:<22}  {:<20}  {:<9}  {:<8}  {:<4}  SIGNATURE / MESSAGE",
+        "SEQ", "TIME", "ACTION", "EXECUTOR", "CONFIRM", "OK"
+    );
+    println!("{}", "-".repeat(110));
+
+    for entry in &entries {
+        print_audit_entry(entry);
+    }
+
+    println!();
+    println!("Showing {} entries", entries.len());
+    Ok(())
+}
+
+fn print_audit_entry(entry: &AuditEntry) {
+    let record = &entry.record;
+    let detail = match &record.signature {
+        Some(sig) => sig.clone(),
+        None => record.message.chars().take(40).collect(),
+    };
+    println!(
+        "{:>6}  {:<22}  {:<20}  {:<9}  {:<8}  {:<4}  {}",
+        entry.seq,
+        format_timestamp(record.finished_at),
+        record.action,
+        record.executor,
+        format!("{:?}", record.confirmation_type).to_lowercase(),
+        if record.success { "yes" } else { "no" },
+        detail
+    );
+}
+
+fn cmd_audit_verify(db: &OperatorDb) -> Result<()> {
+    let verification = db.verify_audit()?;
+
+    match &verification.first_invalid {
+        None => {
+            println!("Audit log OK: {} entries, hash chain intact", verification.entries);
+            Ok(())
+        }
+        Some((seq, reason)) => anyhow::bail!(
+            "Audit log TAMPERED at entry #{} of {}: {}",
+            seq,
+            verification.entries,
+            reason
+        ),
+    }
+}
+
+fn load_manifest(db: &OperatorDb, target: &str) -> Result<ProofManifest> {
+    let path = Path::new(target);
+    if path.is_file() {
+        let bytes = std::fs::read(path)?;
+        return serde_json::from_slice(&bytes)
+            .map_err(|e| anyhow::anyhow!("{} is not a proof manifest: {}", path.display(), e));
+    }
+    let log = db
+        .get_proof(target)?
+        .ok_or_else(|| anyhow::anyhow!("No proof stored for task {} (and no such file)", target))?;
+    ProofManifest::from_verification_log(&log)
+}
+
+fn cmd_proof_export(db: &OperatorDb, task_id: &str) -> Result<()> {
+    let manifest = load_manifest(db, task_id)?;
+    println!("{}", serde_json::to_string_pretty(&manifest)?);
+    Ok(())
+}
+
+fn cmd_proof_verify(
+    db: &OperatorDb,
+    target: &str,
+    rpc_url: &str,
+    offline: bool,
+    files: Option<&Path>,
+) -> Result<()> {
+    let manifest = load_manifest(db, target)?;
+
+    println!("Task:   {}", manifest.task);
+    println!("Worker: {}", manifest.worker);
+    println!("Items:  {}", manifest.items.len());
+    println!("Root:   {}", manifest.root);
+
+    if !manifest.is_consistent() {
+        anyhow::bail!("Manifest TAMPERED: its items do not hash to the recorded root");
+    }
+    println!("Manifest OK: items hash to the recorded root");
+
+    if let Some(dir) = files {
+        let changed = manifest.changed_files(Some(dir));
+        if !changed.is_empty() {
+            anyhow::bail!("Files missing or changed since the proof: {}", changed.join(", "));
+        }
+        println!("Files OK: every file matches its recorded hash");
+    }
+
+    if offline {
+        return Ok(());
+    }
+
+    let verification = proofs::verify_on_chain(&RpcPool::single(rpc_url), &manifest)?;
+    if !verification.claim_found {
+        anyhow::bail!("No claim by {} on task {} found on-chain", manifest.worker, manifest.task);
+    }
+    if !verification.matches {
+        anyhow::bail!(
+            "On-chain proof MISMATCH: claim {} records {}",
+            verification.claim,
+            verification.on_chain_proof.unwrap_or_default()
+        );
+    }
+    println!(
+        "On-chain OK: claim {} records this root{}",
+        verification.claim,
+        if verification.completed { " (completed)" } else { "" }
+    );
+    Ok(())
+}
+
+fn cmd_audit_export(db: &OperatorDb, format: &str) -> Result<()> {
+    if format != "json" {
+        anyhow::bail!("Unsupported format '{}'. Only 'json' is supported.", format);
+    }
+
+    let entries = db.list_audit(None)?;
+    let verification = db.verify_audit()?;
+
+    let export = serde_json::json!({
+        "exported_at": Utc::now().to_rfc3339(),
+        "verification": verification,
+        "entries": entries,
+    });
+
+    println!("{}", serde_json::to_string_pretty(&export)?);
+    Ok(())
+}
+
+fn keystore_path(path: Option<PathBuf>) -> Result<PathBuf> {
+    path.map_or_else(keystore::default_keystore_path, Ok)
+}
+
+/// Prompt for an existing passphrase (input is hidden)
+fn prompt_passphrase(prompt: &str) -> Result<Zeroizing<String>> {
+    Ok(Zeroizing::new(rpassword::prompt_password(prompt)?))
+}
+
+/// Prompt twice for a new passphrase
+fn prompt_new_passphrase() -> Result<Zeroizing<String>> {
+    let passphrase = prompt_passphrase(&format!(
+        "New passphrase (min {} characters): ",
+        MIN_PASSPHRASE_LEN
+    ))?;
+    let confirm = prompt_passphrase("Repeat passphrase: ")?;
+    if *passphrase != *confirm {
+        anyhow::bail!("Passphrases do not match");
+    }
+    Ok(passphrase)
+}
+
+fn check_overwrite(path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        anyhow::bail!("{} already exists (use --force to overwrite)", path.display());
+    }
+    Ok(())
+}
+
+fn cmd_wallet_create(out: Option<PathBuf>, force: bool) -> Result<()> {
+    let path = keystore_path(out)?;
+    check_overwrite(&path, force)?;
+
+    let passphrase = prompt_new_passphrase()?;
+    let keystore = Keystore::create(&passphrase)?;
+    keystore.save(&path)?;
+
+    println!("Created wallet {}", keystore.pubkey);
+    println!("Keystore: {}", path.display());
+    Ok(())
+}
+
+fn cmd_wallet_import(keypair: &Path, out: Option<PathBuf>, force: bool) -> Result<()> {
+    let path = keystore_path(out)?;
+    check_overwrite(&path, force)?;
+
+    let passphrase = prompt_new_passphrase()?;
+    let keystore = Keystore::import_cli_file(keypair, &passphrase)?;
+    keystore.save(&path)?;
+
+    println!("Imported wallet {}", keystore.pubkey);
+    println!("Keystore: {}", path.display());
+    println!(
+        "The plaintext keypair {} is still on disk — delete it once you have a backup.",
+        keypair.display()
+    );
+    Ok(())
+}
+
+fn cmd_wallet_export_public(keystore: Option<PathBuf>) -> Result<()> {
+    let keystore = Keystore::load(&keystore_path(keystore)?)?;
+    println!("{}", keystore.pubkey);
+    Ok(())
+}
+
+fn cmd_wallet_change_passphrase(keystore: Option<PathBuf>) -> Result<()> {
+    let path = keystore_path(keystore)?;
+    let current = Keystore::load(&path)?;
+
+    let old = prompt_passphrase("Current passphrase: ")?;
+    // Check the old passphrase before asking for a new one
+    current.decrypt(&old)?;
+    let new = prompt_new_passphrase()?;
+    current.change_passphrase(&old, &new)?.save(&path)?;
+
+    println!("Passphrase changed for {}", current.pubkey);
+    Ok(())
+}
@@ -2,45 +2,51 @@
 //! Access Checker - Token balance verification for access tiers
 //! ============================================================================
 //! Queries $TETSUO token balance from Solana RPC to determine access tier.
+//! Balances are read through the shared RPC pool, so a rate-limited endpoint
+//! falls back to the next one. The mint comes from the active network profile
+//! at call time, so switching clusters needs no new checker.
 //! ============================================================================
 
-use anyhow::{anyhow, Result};
-use solana_client::rpc_client::RpcClient;
+use anyhow::Result;
 use solana_sdk::pubkey::Pubkey;
 use spl_associated_token_account::get_associated_token_address;
-use std::str::FromStr;
+use std::sync::Arc;
 use tracing::{debug, warn};
 
-use super::types::{AccessTier, AccessTierInfo, TETSUO_DECIMALS, TETSUO_MINT};
+use super::types::{AccessTier, AccessTierInfo, TETSUO_DECIMALS};
+use crate::rpc_pool::RpcPool;
 
 /// Checks $TETSUO token balance and determines access tier
 pub struct AccessChecker {
-    rpc_client: RpcClient,
-    tetsuo_mint: Pubkey,
+    rpc: Arc<RpcPool>,
 }
 
 impl AccessChecker {
     /// Create a new access checker with the given RPC URL
     pub fn new(rpc_url: &str) -> Result<Self> {
-        let tetsuo_mint = Pubkey::from_str(TETSUO_MINT)
-            .map_err(|e| anyhow!("Invalid TETSUO mint address: {}", e))?;
+        Self::with_pool(Arc::new(RpcPool::single(rpc_url)))
+    }
 
-        Ok(Self {
-            rpc_client: RpcClient::new(rpc_url.to_string()),
-            tetsuo_mint,
-        })
+    /// Create a new access checker reading through a shared RPC pool
+    pub fn with_pool(rpc: Arc<RpcPool>) -> Result<Self> {
+        Ok(Self { rpc })
     }
 
     /// Get the user's $TETSUO token balance
     pub fn get_tetsuo_balance(&self, wallet: &Pubkey) -> Result<u64> {
-        let ata = get_associated_token_address(wallet, &self.tetsuo_mint);
+        let mint = self.tetsuo_mint();
+        if mint == Pubkey::default() {
+            warn!("No TETSUO mint set for this network profile - assuming 0");
+            return Ok(0);
+        }
+        let ata = get_associated_token_address(wallet, &mint);
 
         debug!(
             "Checking TETSUO balance for wallet {} at ATA {}",
             wallet, ata
         );
 
-        match self.rpc_client.get_token_account_balance(&ata) {
+        match self.rpc.read_blocking(|rpc| Ok(rpc.get_token_account_balance(&ata)?)) {
             Ok(balance) => {
                 let amount = balance.amount.parse::<u64>().unwrap_or(0);
                 debug!("TETSUO balance: {} (raw)", amount);
@@ -72,21 +78,23 @@ impl AccessChecker {
         Ok(tier.can_use_feature(feature))
     }
 
-    /// Get the token mint pubkey
-    pub fn tetsuo_mint(&self) -> &Pubkey {
-        &self.tetsuo_mint
+    /// Get the token mint pubkey of the active network profile
+    pub fn tetsuo_mint(&self) -> Pubkey {
+        crate::network::active().tetsuo_mint
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::access::types::TETSUO_MINT;
+    use std::str::FromStr;
 
     #[test]
     fn test_checker_creation() {
         // Test with devnet (won't have actual TETSUO but tests the setup)
-        let checker = AccessChecker::new("https://api.devnet.solana.com");
-        assert!(checker.is_ok());
+        let checker = AccessChecker::new("https://api.devnet.solana.com").unwrap();
+        assert_eq!(checker.tetsuo_mint().to_string(), TETSUO_MINT);
     }
 
     #[test]
@@ -13,6 +13,7 @@ use tracing::{debug, info, warn};
 
 use super::checker::AccessChecker;
 use super::types::{AccessTier, AccessTierInfo, Feature, TETSUO_DECIMALS};
+use crate::rpc_pool::RpcPool;
 
 /// Default cache duration in seconds (5 minutes)
 pub const DEFAULT_CACHE_DURATION_SECS: i64 = 300;
@@ -50,6 +51,15 @@ impl AccessGate {
         })
     }
 
+    /// Create a new access gate reading balances through a shared RPC pool
+    pub fn with_pool(rpc: Arc<RpcPool>) -> Result<Self> {
+        Ok(Self {
+            checker: AccessChecker::with_pool(rpc)?,
+            tier_cache: Arc::new(RwLock::new(HashMap::new())),
+            cache_duration_secs: DEFAULT_CACHE_DURATION_SECS,
+        })
+    }
+
     /// Check access and return tier info, using cache when possible
     pub async fn check_access(&self, wallet: &Pubkey) -> Result<(AccessTier, u64)> {
         let wallet_str = wallet.to_string();
@@ -7,7 +7,7 @@
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
-/// Token mint address for $TETSUO
+/// Published $TETSUO mint, the default for the built-in network profiles
 pub const TETSUO_MINT: &str = "8i51XNNpGaKaj4G4nDdmQh95v4FKAxw8mhtaRoKd9tE8";
 
 /// Token decimals for $TETSUO
@@ -0,0 +1,743 @@
+//! ============================================================================
+//! AgenC IDL - Runtime Borsh Codec for the Coordination Program
+//! ============================================================================
+//! Interprets an Anchor IDL (0.30+ spec: explicit discriminators, snake_case
+//! names, `pubkey`, `{"defined": {"name": ..}}`) at runtime:
+//! - `decode_account` checks the 8-byte discriminator and Borsh-decodes the
+//!   account struct into named fields
+//! - `build_instruction` encodes args in IDL order and lays out the accounts
+//!   with the IDL's writable/signer flags
+//! - `field_offset` gives the fixed byte offset of a field for memcmp filters
+//!
+//! The IDL is bundled from `agenc_idl.json`, whose `metadata.source` records
+//! where it came from. The current file is hand-maintained from the Python
+//! SDK layout; `scripts/update-agenc-idl.sh` replaces it with the IDL the
+//! deployed program publishes and records devnet transactions as golden
+//! captures, so layout changes surface as decode errors or failing tests
+//! instead of silently misread fields.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Bundled AgenC coordination program IDL (see `metadata.source`)
+pub const AGENC_IDL_JSON: &str = include_str!("agenc_idl.json");
+
+static AGENC_IDL: Lazy<Idl> = Lazy::new(|| {
+    Idl::from_json(AGENC_IDL_JSON).expect("Bundled AgenC IDL is invalid")
+});
+
+/// The bundled AgenC program IDL
+pub fn agenc_idl() -> &'static Idl {
+    &AGENC_IDL
+}
+
+// ============================================================================
+// IDL Schema
+// ============================================================================
+
+/// A type reference as written in the IDL
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum IdlType {
+    /// bool, u8..u128, i8..i128, pubkey, string, bytes
+    Primitive(String),
+    Array { array: (Box<IdlType>, usize) },
+    Option { option: Box<IdlType> },
+    Vec { vec: Box<IdlType> },
+    Defined { defined: IdlDefined },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct IdlDefined {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlVariant {
+    pub name: String,
+    /// Variants with data are not used by the AgenC program
+    #[serde(default)]
+    pub fields: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlTypeDefKind {
+    Struct { fields: Vec<IdlField> },
+    Enum { variants: Vec<IdlVariant> },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlTypeDef {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefKind,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlInstructionAccount {
+    pub name: String,
+    #[serde(default)]
+    pub writable: bool,
+    #[serde(default)]
+    pub signer: bool,
+    /// Only passed when supplied (e.g. SKR token accounts)
+    #[serde(default)]
+    pub optional: bool,
+    /// Fixed address (system program etc.), filled in when not supplied
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub discriminator: [u8; 8],
+    pub accounts: Vec<IdlInstructionAccount>,
+    pub args: Vec<IdlField>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlAccount {
+    pub name: String,
+    pub discriminator: [u8; 8],
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlMetadata {
+    pub name: String,
+    pub version: String,
+}
+
+/// A parsed Anchor IDL
+#[derive(Debug, Clone, Deserialize)]
+pub struct Idl {
+    pub address: String,
+    pub metadata: IdlMetadata,
+    pub instructions: Vec<IdlInstruction>,
+    pub accounts: Vec<IdlAccount>,
+    #[serde(default)]
+    pub types: Vec<IdlTypeDef>,
+}
+
+// ============================================================================
+// Decoded Values
+// ============================================================================
+
+/// A Borsh value described by an IDL type
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdlValue {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    I64(i64),
+    Pubkey(Pubkey),
+    /// `[u8; N]` arrays and `bytes`
+    Bytes(Vec<u8>),
+    String(String),
+    Option(Option<Box<IdlValue>>),
+    /// `vec<T>` and arrays of anything other than u8
+    Vec(Vec<IdlValue>),
+    /// Unit enum variant
+    Enum { index: u8, name: String },
+    Struct(IdlFields),
+}
+
+/// Named struct fields in IDL order
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IdlFields(pub Vec<(String, IdlValue)>);
+
+impl IdlFields {
+    pub fn get(&self, name: &str) -> Result<&IdlValue> {
+        self.0
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v)
+            .ok_or_else(|| anyhow!("Missing field: {}", name))
+    }
+
+    pub fn bool(&self, name: &str) -> Result<bool> {
+        match self.get(name)? {
+            IdlValue::Bool(v) => Ok(*v),
+            other => Err(type_error(name, "bool", other)),
+        }
+    }
+
+    pub fn u8(&self, name: &str) -> Result<u8> {
+        match self.get(name)? {
+            IdlValue::U8(v) => Ok(*v),
+            other => Err(type_error(name, "u8", other)),
+        }
+    }
+
+    pub fn u16(&self, name: &str) -> Result<u16> {
+        match self.get(name)? {
+            IdlValue::U16(v) => Ok(*v),
+            other => Err(type_error(name, "u16", other)),
+        }
+    }
+
+    pub fn u64(&self, name: &str) -> Result<u64> {
+        match self.get(name)? {
+            IdlValue::U64(v) => Ok(*v),
+            other => Err(type_error(name, "u64", other)),
+        }
+    }
+
+    pub fn i64(&self, name: &str) -> Result<i64> {
+        match self.get(name)? {
+            IdlValue::I64(v) => Ok(*v),
+            other => Err(type_error(name, "i64", other)),
+        }
+    }
+
+    pub fn pubkey(&self, name: &str) -> Result<Pubkey> {
+        match self.get(name)? {
+            IdlValue::Pubkey(v) => Ok(*v),
+            other => Err(type_error(name, "pubkey", other)),
+        }
+    }
+
+    pub fn string(&self, name: &str) -> Result<&str> {
+        match self.get(name)? {
+            IdlValue::String(v) => Ok(v),
+            other => Err(type_error(name, "string", other)),
+        }
+    }
+
+    /// A `[u8; N]` field
+    pub fn array<const N: usize>(&self, name: &str) -> Result<[u8; N]> {
+        match self.get(name)? {
+            IdlValue::Bytes(v) => v
+                .as_slice()
+                .try_into()
+                .map_err(|_| anyhow!("Field {} has {} bytes, expected {}", name, v.len(), N)),
+            other => Err(type_error(name, "byte array", other)),
+        }
+    }
+
+    pub fn option_pubkey(&self, name: &str) -> Result<Option<Pubkey>> {
+        match self.get(name)? {
+            IdlValue::Option(None) => Ok(None),
+            IdlValue::Option(Some(inner)) => match inner.as_ref() {
+                IdlValue::Pubkey(v) => Ok(Some(*v)),
+                other => Err(type_error(name, "option<pubkey>", other)),
+            },
+            other => Err(type_error(name, "option<pubkey>", other)),
+        }
+    }
+
+    /// Variant index of a unit enum field
+    pub fn enum_index(&self, name: &str) -> Result<u8> {
+        match self.get(name)? {
+            IdlValue::Enum { index, .. } => Ok(*index),
+            other => Err(type_error(name, "enum", other)),
+        }
+    }
+
+}
+
+fn type_error(name: &str, expected: &str, got: &IdlValue) -> anyhow::Error {
+    anyhow!("Field {} is not {}: {:?}", name, expected, got)
+}
+
+// ============================================================================
+// Codec
+// ============================================================================
+
+impl Idl {
+    /// Parse and validate an IDL: every discriminator is 8 bytes (enforced by
+    /// the schema) and every `defined` type resolves
+    pub fn from_json(json: &str) -> Result<Self> {
+        let idl: Idl = serde_json::from_str(json).map_err(|e| anyhow!("Invalid IDL: {}", e))?;
+
+        for account in &idl.accounts {
+            idl.type_def(&account.name)?;
+        }
+        for def in &idl.types {
+            if let IdlTypeDefKind::Struct { fields } = &def.ty {
+                for field in fields {
+                    idl.check_type(&field.ty)?;
+                }
+            }
+        }
+        for ix in &idl.instructions {
+            for arg in &ix.args {
+                idl.check_type(&arg.ty)?;
+            }
+        }
+        Ok(idl)
+    }
+
+    fn check_type(&self, ty: &IdlType) -> Result<()> {
+        match ty {
+            IdlType::Primitive(name) => match name.as_str() {
+                "bool" | "u8" | "u16" | "u32" | "u64" | "i64" | "pubkey" | "string" | "bytes" => Ok(()),
+                other => Err(anyhow!("Unsupported IDL type: {}", other)),
+            },
+            IdlType::Array { array: (inner, _) } => self.check_type(inner),
+            IdlType::Option { option } => self.check_type(option),
+            IdlType::Vec { vec } => self.check_type(vec),
+            IdlType::Defined { defined } => self.type_def(&defined.name).map(|_| ()),
+        }
+    }
+
+    pub fn type_def(&self, name: &str) -> Result<&IdlTypeDef> {
+        self.types
+            .iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| anyhow!("IDL type not found: {}", name))
+    }
+
+    pub fn instruction(&self, name: &str) -> Result<&IdlInstruction> {
+        self.instructions
+            .iter()
+            .find(|ix| ix.name == name)
+            .ok_or_else(|| anyhow!("IDL instruction not found: {}", name))
+    }
+
+    pub fn account_discriminator(&self, name: &str) -> Result<[u8; 8]> {
+        self.accounts
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.discriminator)
+            .ok_or_else(|| anyhow!("IDL account not found: {}", name))
+    }
+
+    /// Decode account data: discriminator, then the Borsh struct. Trailing
+    /// bytes (unused allocated space) are ignored.
+    pub fn decode_account(&self, name: &str, data: &[u8]) -> Result<IdlFields> {
+        let discriminator = self.account_discriminator(name)?;
+        if data.len() < 8 || data[..8] != discriminator {
+            return Err(anyhow!("Discriminator mismatch for {} account", name));
+        }
+
+        let mut pos = 8;
+        match self.decode_defined(name, data, &mut pos)? {
+            IdlValue::Struct(fields) => Ok(fields),
+            other => Err(anyhow!("IDL account {} is not a struct: {:?}", name, other)),
+        }
+    }
+
+    /// Account data: discriminator followed by the Borsh-encoded struct, the
+    /// inverse of `decode_account`. `fields` must name every IDL field.
+    pub fn encode_account(&self, name: &str, fields: &IdlFields) -> Result<Vec<u8>> {
+        let mut data = self.account_discriminator(name)?.to_vec();
+        let ty = IdlType::Defined { defined: IdlDefined { name: name.to_string() } };
+        self.encode(&ty, &IdlValue::Struct(fields.clone()), name, &mut data)?;
+        Ok(data)
+    }
+
+    /// Byte offset of `field` within account `name` (discriminator included),
+    /// or None if a variable-size field comes before it
+    pub fn field_offset(&self, name: &str, field: &str) -> Option<usize> {
+        let IdlTypeDefKind::Struct { fields } = &self.type_def(name).ok()?.ty else {
+            return None;
+        };
+        let mut offset = 8;
+        for f in fields {
+            if f.name == field {
+                return Some(offset);
+            }
+            offset += self.fixed_size(&f.ty)?;
+        }
+        None
+    }
+
+    /// Encoded size of a type, if it does not depend on the value
+    pub fn fixed_size(&self, ty: &IdlType) -> Option<usize> {
+        match ty {
+            IdlType::Primitive(name) => match name.as_str() {
+                "bool" | "u8" => Some(1),
+                "u16" => Some(2),
+                "u32" => Some(4),
+                "u64" | "i64" => Some(8),
+                "pubkey" => Some(32),
+                _ => None,
+            },
+            IdlType::Array { array: (inner, len) } => Some(self.fixed_size(inner)? * len),
+            IdlType::Option { .. } | IdlType::Vec { .. } => None,
+            IdlType::Defined { defined } => match &self.type_def(&defined.name).ok()?.ty {
+                IdlTypeDefKind::Enum { .. } => Some(1),
+                IdlTypeDefKind::Struct { fields } => {
+                    fields.iter().map(|f| self.fixed_size(&f.ty)).sum()
+                }
+            },
+        }
+    }
+
+    /// Instruction data: discriminator followed by the Borsh-encoded args.
+    /// `args` must name every IDL arg exactly once.
+    pub fn encode_instruction_data(&self, name: &str, args: &[(&str, IdlValue)]) -> Result<Vec<u8>> {
+        let ix = self.instruction(name)?;
+        if let Some((unknown, _)) = args.iter().find(|(n, _)| !ix.args.iter().any(|a| a.name == *n)) {
+            return Err(anyhow!("Unknown argument {} for {}", unknown, name));
+        }
+
+        let mut data = ix.discriminator.to_vec();
+        for arg in &ix.args {
+            let value = args
+                .iter()
+                .find(|(n, _)| *n == arg.name)
+                .map(|(_, v)| v)
+                .ok_or_else(|| anyhow!("Missing argument {} for {}", arg.name, name))?;
+            self.encode(&arg.ty, value, &arg.name, &mut data)?;
+        }
+        Ok(data)
+    }
+
+    /// Decode instruction data: the instruction whose discriminator leads
+    /// the data, and its Borsh-decoded args
+    pub fn decode_instruction_data(&self, data: &[u8]) -> Result<(&IdlInstruction, IdlFields)> {
+        let ix = self
+            .instructions
+            .iter()
+            .find(|ix| data.len() >= 8 && data[..8] == ix.discriminator)
+            .ok_or_else(|| anyhow!("Unknown instruction discriminator"))?;
+
+        let mut pos = 8;
+        let mut args = Vec::with_capacity(ix.args.len());
+        for arg in &ix.args {
+            args.push((arg.name.clone(), self.decode(&arg.ty, data, &mut pos)?));
+        }
+        Ok((ix, IdlFields(args)))
+    }
+
+    /// Build an instruction. Accounts are laid out in IDL order; fixed-address
+    /// accounts are filled in, optional ones are passed only when supplied.
+    pub fn build_instruction(
+        &self,
+        program_id: Pubkey,
+        name: &str,
+        accounts: &[(&str, Pubkey)],
+        args: &[(&str, IdlValue)],
+    ) -> Result<Instruction> {
+        let ix = self.instruction(name)?;
+        if let Some((unknown, _)) = accounts.iter().find(|(n, _)| !ix.accounts.iter().any(|a| a.name == *n)) {
+            return Err(anyhow!("Unknown account {} for {}", unknown, name));
+        }
+
+        let supplied: HashMap<&str, Pubkey> = accounts.iter().copied().collect();
+        let mut metas = Vec::with_capacity(ix.accounts.len());
+        for account in &ix.accounts {
+            let pubkey = match (supplied.get(account.name.as_str()), &account.address) {
+                (Some(pubkey), _) => *pubkey,
+                (None, _) if account.optional => continue,
+                (None, Some(address)) => Pubkey::from_str(address)
+                    .map_err(|e| anyhow!("Invalid address for {}: {}", account.name, e))?,
+                (None, None) => return Err(anyhow!("Missing account {} for {}", account.name, name)),
+            };
+            metas.push(if account.writable {
+                AccountMeta::new(pubkey, account.signer)
+            } else {
+                AccountMeta::new_readonly(pubkey, account.signer)
+            });
+        }
+
+        Ok(Instruction {
+            program_id,
+            accounts: metas,
+            data: self.encode_instruction_data(name, args)?,
+        })
+    }
+
+    fn decode_defined(&self, name: &str, data: &[u8], pos: &mut usize) -> Result<IdlValue> {
+        match &self.type_def(name)?.ty {
+            IdlTypeDefKind::Struct { fields } => {
+                let mut decoded = Vec::with_capacity(fields.len());
+                for field in fields {
+                    let value = self
+                        .decode(&field.ty, data, pos)
+                        .map_err(|e| anyhow!("{}.{}: {}", name, field.name, e))?;
+                    decoded.push((field.name.clone(), value));
+                }
+                Ok(IdlValue::Struct(IdlFields(decoded)))
+            }
+            IdlTypeDefKind::Enum { variants } => {
+                let index = take(data, pos, 1)?[0];
+                let variant = variants
+                    .get(index as usize)
+                    .ok_or_else(|| anyhow!("Invalid {} variant: {}", name, index))?;
+                if variant.fields.is_some() {
+                    return Err(anyhow!("Enum variants with data are not supported: {}", variant.name));
+                }
+                Ok(IdlValue::Enum { index, name: variant.name.clone() })
+            }
+        }
+    }
+
+    fn decode(&self, ty: &IdlType, data: &[u8], pos: &mut usize) -> Result<IdlValue> {
+        Ok(match ty {
+            IdlType::Primitive(name) => match name.as_str() {
+                "bool" => match take(data, pos, 1)?[0] {
+                    0 => IdlValue::Bool(false),
+                    1 => IdlValue::Bool(true),
+                    b => return Err(anyhow!("Invalid bool byte: {}", b)),
+                },
+                "u8" => IdlValue::U8(take(data, pos, 1)?[0]),
+                "u16" => IdlValue::U16(u16::from_le_bytes(take(data, pos, 2)?.try_into()?)),
+                "u32" => IdlValue::U32(u32::from_le_bytes(take(data, pos, 4)?.try_into()?)),
+                "u64" => IdlValue::U64(u64::from_le_bytes(take(data, pos, 8)?.try_into()?)),
+                "i64" => IdlValue::I64(i64::from_le_bytes(take(data, pos, 8)?.try_into()?)),
+                "pubkey" => IdlValue::Pubkey(
+                    Pubkey::try_from(take(data, pos, 32)?)
+                        .map_err(|e| anyhow!("Invalid pubkey: {}", e))?,
+                ),
+                "string" => {
+                    let bytes = take_len_prefixed(data, pos)?;
+                    IdlValue::String(
+                        String::from_utf8(bytes.to_vec()).map_err(|e| anyhow!("Invalid string: {}", e))?,
+                    )
+                }
+                "bytes" => IdlValue::Bytes(take_len_prefixed(data, pos)?.to_vec()),
+                other => return Err(anyhow!("Unsupported IDL type: {}", other)),
+            },
+            IdlType::Array { array: (inner, len) } => {
+                if **inner == IdlType::Primitive("u8".into()) {
+                    IdlValue::Bytes(take(data, pos, *len)?.to_vec())
+                } else {
+                    let mut items = Vec::with_capacity(*len);
+                    for _ in 0..*len {
+                        items.push(self.decode(inner, data, pos)?);
+                    }
+                    IdlValue::Vec(items)
+                }
+            }
+            IdlType::Option { option } => match take(data, pos, 1)?[0] {
+                0 => IdlValue::Option(None),
+                1 => IdlValue::Option(Some(Box::new(self.decode(option, data, pos)?))),
+                b => return Err(anyhow!("Invalid option tag: {}", b)),
+            },
+            IdlType::Vec { vec } => {
+                let len = u32::from_le_bytes(take(data, pos, 4)?.try_into()?) as usize;
+                let mut items = Vec::with_capacity(len.min(1024));
+                for _ in 0..len {
+                    items.push(self.decode(vec, data, pos)?);
+                }
+                IdlValue::Vec(items)
+            }
+            IdlType::Defined { defined } => self.decode_defined(&defined.name, data, pos)?,
+        })
+    }
+
+    fn encode(&self, ty: &IdlType, value: &IdlValue, name: &str, out: &mut Vec<u8>) -> Result<()> {
+        match (ty, value) {
+            (IdlType::Primitive(p), v) => match (p.as_str(), v) {
+                ("bool", IdlValue::Bool(b)) => out.push(*b as u8),
+                ("u8", IdlValue::U8(n)) => out.push(*n),
+                ("u16", IdlValue::U16(n)) => out.extend_from_slice(&n.to_le_bytes()),
+                ("u32", IdlValue::U32(n)) => out.extend_from_slice(&n.to_le_bytes()),
+                ("u64", IdlValue::U64(n)) => out.extend_from_slice(&n.to_le_bytes()),
+                ("i64", IdlValue::I64(n)) => out.extend_from_slice(&n.to_le_bytes()),
+                ("pubkey", IdlValue::Pubkey(pk)) => out.extend_from_slice(pk.as_ref()),
+                ("string", IdlValue::String(s)) => {
+                    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+                    out.extend_from_slice(s.as_bytes());
+                }
+                ("bytes", IdlValue::Bytes(b)) => {
+                    out.extend_from_slice(&(b.len() as u32).to_le_bytes());
+                    out.extend_from_slice(b);
+                }
+                _ => return Err(type_error(name, p, v)),
+            },
+            (IdlType::Array { array: (inner, len) }, IdlValue::Bytes(b))
+                if **inner == IdlType::Primitive("u8".into()) =>
+            {
+                if b.len() != *len {
+                    return Err(anyhow!("Argument {} must be {} bytes, got {}", name, len, b.len()));
+                }
+                out.extend_from_slice(b);
+            }
+            (IdlType::Array { array: (inner, len) }, IdlValue::Vec(items)) => {
+                if items.len() != *len {
+                    return Err(anyhow!("Argument {} must have {} items, got {}", name, len, items.len()));
+                }
+                for item in items {
+                    self.encode(inner, item, name, out)?;
+                }
+            }
+            (IdlType::Option { .. }, IdlValue::Option(None)) => out.push(0),
+            (IdlType::Option { option }, IdlValue::Option(Some(inner))) => {
+                out.push(1);
+                self.encode(option, inner, name, out)?;
+            }
+            (IdlType::Vec { vec }, IdlValue::Vec(items)) => {
+                out.extend_from_slice(&(items.len() as u32).to_le_bytes());
+                for item in items {
+                    self.encode(vec, item, name, out)?;
+                }
+            }
+            (IdlType::Defined { defined }, v) => match (&self.type_def(&defined.name)?.ty, v) {
+                (IdlTypeDefKind::Enum { variants }, IdlValue::Enum { index, .. })
+                    if (*index as usize) < variants.len() =>
+                {
+                    out.push(*index)
+                }
+                (IdlTypeDefKind::Struct { fields }, IdlValue::Struct(values)) => {
+                    for field in fields {
+                        self.encode(&field.ty, values.get(&field.name)?, &field.name, out)?;
+                    }
+                }
+                _ => return Err(type_error(name, &defined.name, v)),
+            },
+            (ty, v) => return Err(anyhow!("Argument {} expects {:?}, got {:?}", name, ty, v)),
+        }
+        Ok(())
+    }
+}
+
+fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8]> {
+    let end = pos
+        .checked_add(len)
+        .filter(|end| *end <= data.len())
+        .ok_or_else(|| anyhow!("Account data too short: need {} bytes at offset {}", len, pos))?;
+    let bytes = &data[*pos..end];
+    *pos = end;
+    Ok(bytes)
+}
+
+fn take_len_prefixed<'a>(data: &'a [u8], pos: &mut usize) -> Result<&'a [u8]> {
+    let len = u32::from_le_bytes(take(data, pos, 4)?.try_into()?) as usize;
+    take(data, pos, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn anchor_discriminator(preimage: &str) -> [u8; 8] {
+        Sha256::digest(preimage.as_bytes())[..8].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_bundled_idl_discriminators_match_anchor() {
+        let idl = agenc_idl();
+        for ix in &idl.instructions {
+            assert_eq!(ix.discriminator, anchor_discriminator(&format!("global:{}", ix.name)), "{}", ix.name);
+        }
+        for account in &idl.accounts {
+            assert_eq!(
+                account.discriminator,
+                anchor_discriminator(&format!("account:{}", account.name)),
+                "{}",
+                account.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_types() {
+        let json = r#"{
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "x", "version": "0.1.0" },
+            "instructions": [],
+            "accounts": [{ "name": "Thing", "discriminator": [0,0,0,0,0,0,0,0] }],
+            "types": [{ "name": "Thing", "type": { "kind": "struct", "fields": [
+                { "name": "owner", "type": { "defined": { "name": "Missing" } } }
+            ] } }]
+        }"#;
+        assert!(Idl::from_json(json).is_err());
+    }
+
+    /// An account with a string and an enum after fixed-size fields
+    const VARIABLE_IDL: &str = r#"{
+        "address": "11111111111111111111111111111111",
+        "metadata": { "name": "x", "version": "0.1.0" },
+        "instructions": [],
+        "accounts": [{ "name": "Profile", "discriminator": [1,2,3,4,5,6,7,8] }],
+        "types": [
+            { "name": "Profile", "type": { "kind": "struct", "fields": [
+                { "name": "authority", "type": "pubkey" },
+                { "name": "agent_id", "type": { "array": ["u8", 32] } },
+                { "name": "capabilities", "type": "u64" },
+                { "name": "endpoint", "type": "string" },
+                { "name": "status", "type": { "defined": { "name": "Status" } } },
+                { "name": "tasks_completed", "type": "u64" },
+                { "name": "reputation", "type": "u16" },
+                { "name": "registered_at", "type": "i64" },
+                { "name": "bump", "type": "u8" }
+            ] } },
+            { "name": "Status", "type": { "kind": "enum", "variants": [
+                { "name": "Inactive" }, { "name": "Active" }, { "name": "Suspended" }
+            ] } }
+        ]
+    }"#;
+
+    #[test]
+    fn test_borsh_roundtrip_of_variable_fields() {
+        let idl = Idl::from_json(VARIABLE_IDL).unwrap();
+        let discriminator = idl.account_discriminator("Profile").unwrap();
+        let authority = Pubkey::new_unique();
+
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&[9u8; 32]);
+        data.extend_from_slice(&5u64.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(b"wss");
+        data.push(1); // Active
+        data.extend_from_slice(&12u64.to_le_bytes());
+        data.extend_from_slice(&450u16.to_le_bytes());
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        data.push(254);
+
+        let fields = idl.decode_account("Profile", &data).unwrap();
+        assert_eq!(fields.pubkey("authority").unwrap(), authority);
+        assert_eq!(fields.string("endpoint").unwrap(), "wss");
+        assert_eq!(fields.enum_index("status").unwrap(), 1);
+        assert_eq!(fields.u16("reputation").unwrap(), 450);
+        assert_eq!(fields.u8("bump").unwrap(), 254);
+        assert_eq!(idl.encode_account("Profile", &fields).unwrap(), data);
+
+        // Variable-size fields make later offsets unknowable
+        assert_eq!(idl.field_offset("Profile", "capabilities"), Some(72));
+        assert_eq!(idl.field_offset("Profile", "status"), None);
+
+        // Truncated data is an error, not a zero-filled read
+        assert!(idl.decode_account("Profile", &data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_instruction_arguments_are_checked() {
+        let idl = agenc_idl();
+        let err = idl
+            .encode_instruction_data("claim_task", &[("agent_id", IdlValue::Bytes(vec![0; 31]))])
+            .unwrap_err();
+        assert!(err.to_string().contains("32 bytes"));
+
+        assert!(idl.encode_instruction_data("claim_task", &[]).is_err());
+
+        let data = idl
+            .encode_instruction_data("claim_task", &[("agent_id", IdlValue::Bytes(vec![7; 32]))])
+            .unwrap();
+        let (ix, args) = idl.decode_instruction_data(&data).unwrap();
+        assert_eq!(ix.name, "claim_task");
+        assert_eq!(args.array::<32>("agent_id").unwrap(), [7; 32]);
+        assert!(idl.decode_instruction_data(&data[..20]).is_err());
+        assert!(idl.decode_instruction_data(&[0; 8]).is_err());
+        assert!(idl
+            .encode_instruction_data("cancel_task", &[("reason", IdlValue::U8(1))])
+            .is_err());
+        assert!(idl
+            .build_instruction(Pubkey::new_unique(), "cancel_task", &[("task", Pubkey::new_unique())], &[])
+            .is_err());
+    }
+}
@@ -2,69 +2,70 @@
 //! AgenC Protocol — On-Chain Program Integration
 //! ============================================================================
 //! Constants, PDA derivation, account deserialization, and instruction builders
-//! for the AgenC Solana coordination program.
+//! for the AgenC Solana coordination program. Account layouts and instruction
+//! encodings come from the program IDL (see `agenc_idl`).
 //!
-//! Program ID: EopUaCV2svxj9j4hd7KjbrWfdjkspmm2BCBe7jGpKzKZ
+//! Program ID: EopUaCV2svxj9j4hd7KjbrWfdjkspmm2BCBe7jGpKzKZ by default; the
+//! active network profile (see `network`) decides which program and SKR mint
+//! every helper here derives against.
 //!
 //! Ported from the Python SDK at:
 //!   https://github.com/tetsuo-ai/AgenC_Moltbook_Agent/agenc_agent/clients/solana.py
 //! ============================================================================
 
-use anyhow::{anyhow, Result};
-use once_cell::sync::Lazy;
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
+    instruction::Instruction,
     pubkey::Pubkey,
 };
+use std::str::FromStr;
 use spl_associated_token_account::get_associated_token_address;
 
+use crate::agenc_idl::{agenc_idl, IdlFields, IdlValue};
+
 // Well-known program IDs — avoid deprecated solana_sdk helpers
-const SYSTEM_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("11111111111111111111111111111111");
 const TOKEN_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 const ATA_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
-use std::str::FromStr;
 
 // ============================================================================
 // Constants
 // ============================================================================
 
-/// AgenC protocol program ID (same on devnet and mainnet)
+/// Published AgenC program ID, the default for the built-in network profiles
 pub const PROGRAM_ID: &str = "EopUaCV2svxj9j4hd7KjbrWfdjkspmm2BCBe7jGpKzKZ";
 
-/// SKR privacy cash token mint
+/// Published SKR privacy cash token mint, the default for the built-in profiles
 pub const SKR_MINT: &str = "9fhQBbumKEFuXtMBDw8AaQyAjCorLGJQiS3skWZdQyQD";
 
 /// SKR token decimals (standard SPL token)
 pub const SKR_DECIMALS: u8 = 9;
 
-/// Task account discriminator — first 8 bytes of SHA256("global:Task")
+/// Task account discriminator — first 8 bytes of SHA256("account:Task")
 pub const TASK_DISCRIMINATOR: [u8; 8] = [0x4f, 0x22, 0xe5, 0x37, 0x58, 0x5a, 0x37, 0x54];
 
-/// Offset of the status/state byte within a Task account
+/// Offset of the status/state byte within a Task account (checked against the IDL)
 pub const TASK_STATUS_OFFSET: usize = 154;
 
-/// Default protocol fee percentage
-pub const DEFAULT_FEE_PERCENT: f64 = 1.0;
+/// Offset of the creator pubkey within a Task account
+pub const TASK_CREATOR_OFFSET: usize = 16;
+
+/// Offset of the `claimed_by` option tag within a Task account; the claimer
+/// pubkey follows it when the tag is 1
+pub const TASK_CLAIMED_BY_OFFSET: usize = 171;
 
 /// Lamports per SOL
 pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 
-static PROGRAM_ID_PUBKEY: Lazy<Pubkey> = Lazy::new(|| {
-    Pubkey::from_str(PROGRAM_ID).expect("Invalid AgenC program ID — this is a compile-time constant")
-});
-
-static SKR_MINT_PUBKEY: Lazy<Pubkey> = Lazy::new(|| {
-    Pubkey::from_str(SKR_MINT).expect("Invalid SKR mint — this is a compile-time constant")
-});
-
+/// AgenC program ID of the active network profile
 pub fn program_id() -> Pubkey {
-    *PROGRAM_ID_PUBKEY
+    crate::network::active().program_id
 }
 
+/// SKR mint of the active network profile
 pub fn skr_mint() -> Pubkey {
-    *SKR_MINT_PUBKEY
+    crate::network::active().skr_mint
 }
 
 /// Get the Associated Token Account (ATA) for a wallet's SKR holdings.
@@ -135,20 +136,10 @@ impl OnChainTaskState {
 
 /// Deserialized AgenC task from on-chain account data.
 ///
-/// Account layout (311+ bytes):
-///   [0..8]     discriminator
-///   [8..16]    task_id (u64 LE)
-///   [16..48]   creator (Pubkey, 32 bytes)
-///   [48..80]   escrow_account (Pubkey, 32 bytes)
-///   [80..88]   required_capabilities (u64 LE)
-///   [88..120]  description_hash (32 bytes)
-///   [120..152] constraint_hash (32 bytes)
-///   [152..153] reward (first byte — actually at different offset, see below)
-///   [154]      state (TaskState enum byte)
-///
-/// NOTE: The exact layout may vary. The Python SDK uses offset 154 for state,
-/// and we keep that consistent here. Field offsets for reward/deadline/etc
-/// are derived from the Anchor account struct ordering.
+/// Decoded from the IDL's `Task` struct (Borsh). The fields before
+/// `claimed_by` are fixed-size, so `state` is always at `TASK_STATUS_OFFSET`
+/// and can be used in memcmp filters; `claimed_by` is an `Option<Pubkey>`,
+/// so everything after it shifts by 32 bytes when it is empty.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OnChainTask {
     pub task_id: u64,
@@ -168,77 +159,23 @@ pub struct OnChainTask {
 
 impl OnChainTask {
     /// Deserialize from raw account data bytes.
-    /// Returns None if the discriminator doesn't match.
+    /// Fails if the discriminator doesn't match or the data is truncated.
     pub fn from_account_data(data: &[u8], pda: &Pubkey) -> Result<Self> {
-        if data.len() < 160 {
-            return Err(anyhow!("Account data too short: {} bytes", data.len()));
-        }
-
-        // Verify discriminator
-        if data[0..8] != TASK_DISCRIMINATOR {
-            return Err(anyhow!("Discriminator mismatch"));
-        }
-
-        // Parse fields
-        let task_id = u64::from_le_bytes(data[8..16].try_into()?);
-        let creator = Pubkey::try_from(&data[16..48])
-            .map_err(|e| anyhow!("Invalid creator pubkey: {}", e))?;
-        let escrow_account = Pubkey::try_from(&data[48..80])
-            .map_err(|e| anyhow!("Invalid escrow pubkey: {}", e))?;
-        let required_capabilities = u64::from_le_bytes(data[80..88].try_into()?);
-
-        let mut description_hash = [0u8; 32];
-        description_hash.copy_from_slice(&data[88..120]);
-
-        let mut constraint_hash = [0u8; 32];
-        constraint_hash.copy_from_slice(&data[120..152]);
-
-        // State byte at offset 154
-        let state = OnChainTaskState::from_byte(data[TASK_STATUS_OFFSET])?;
-
-        // Reward: u64 LE at offset 155..163
-        let reward_lamports = if data.len() >= 163 {
-            u64::from_le_bytes(data[155..163].try_into().unwrap_or([0; 8]))
-        } else {
-            0
-        };
-
-        // Deadline: i64 LE at offset 163..171
-        let deadline = if data.len() >= 171 {
-            i64::from_le_bytes(data[163..171].try_into().unwrap_or([0; 8]))
-        } else {
-            0
-        };
-
-        // Claimed by: Option<Pubkey> at offset 171..204 (1 byte option tag + 32 bytes)
-        let claimed_by = if data.len() >= 204 && data[171] == 1 {
-            Pubkey::try_from(&data[172..204])
-                .ok()
-                .map(|pk| pk.to_string())
-        } else {
-            None
-        };
-
-        // SKR reward: u64 LE at offset 204..212 (optional — 0 if account is shorter)
-        let reward_skr_tokens = if data.len() >= 212 {
-            u64::from_le_bytes(data[204..212].try_into().unwrap_or([0; 8]))
-        } else {
-            0
-        };
+        let fields = agenc_idl().decode_account("Task", data)?;
 
         Ok(Self {
-            task_id,
+            task_id: fields.u64("task_id")?,
             pda: pda.to_string(),
-            creator: creator.to_string(),
-            escrow_account: escrow_account.to_string(),
-            required_capabilities,
-            description_hash,
-            constraint_hash,
-            state,
-            reward_lamports,
-            reward_skr_tokens,
-            deadline,
-            claimed_by,
+            creator: fields.pubkey("creator")?.to_string(),
+            escrow_account: fields.pubkey("escrow")?.to_string(),
+            required_capabilities: fields.u64("required_capabilities")?,
+            description_hash: fields.array("description_hash")?,
+            constraint_hash: fields.array("constraint_hash")?,
+            state: OnChainTaskState::from_byte(fields.enum_index("state")?)?,
+            reward_lamports: fields.u64("reward_amount")?,
+            reward_skr_tokens: fields.u64("reward_skr_amount")?,
+            deadline: fields.i64("deadline")?,
+            claimed_by: fields.option_pubkey("claimed_by")?.map(|pk| pk.to_string()),
         })
     }
 
@@ -247,6 +184,75 @@ impl OnChainTask {
     }
 }
 
+/// A worker's claim on a task (IDL `TaskClaim`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnChainClaim {
+    pub pda: String,
+    pub task: String,
+    pub worker: String,
+    pub agent_id: [u8; 32],
+    pub claimed_at: i64,
+    pub proof_hash: [u8; 32],
+    pub is_completed: bool,
+}
+
+impl OnChainClaim {
+    pub fn from_account_data(data: &[u8], pda: &Pubkey) -> Result<Self> {
+        let fields = agenc_idl().decode_account("TaskClaim", data)?;
+
+        Ok(Self {
+            pda: pda.to_string(),
+            task: fields.pubkey("task")?.to_string(),
+            worker: fields.pubkey("worker")?.to_string(),
+            agent_id: fields.array("agent_id")?,
+            claimed_at: fields.i64("claimed_at")?,
+            proof_hash: fields.array("proof_hash")?,
+            is_completed: fields.bool("is_completed")?,
+        })
+    }
+}
+
+/// Protocol-wide settings stored at `derive_protocol_pda()`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolConfig {
+    pub authority: String,
+    pub treasury: String,
+    /// Protocol fee in basis points of the reward
+    pub protocol_fee_bps: u16,
+    /// Id the program assigns to the next created task
+    pub next_task_id: u64,
+    pub total_agents: u64,
+}
+
+impl ProtocolConfig {
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let fields = agenc_idl().decode_account("ProtocolConfig", data)?;
+
+        Ok(Self {
+            authority: fields.pubkey("authority")?.to_string(),
+            treasury: fields.pubkey("treasury")?.to_string(),
+            protocol_fee_bps: fields.u16("protocol_fee_bps")?,
+            next_task_id: fields.u64("next_task_id")?,
+            total_agents: fields.u64("total_agents")?,
+        })
+    }
+
+    /// Treasury that receives the protocol fee on settlement
+    pub fn treasury_pubkey(&self) -> Result<Pubkey> {
+        Pubkey::from_str(&self.treasury).map_err(|e| anyhow!("Invalid treasury address: {}", e))
+    }
+
+    /// Protocol fee as a percentage of the reward
+    pub fn fee_percent(&self) -> f64 {
+        self.protocol_fee_bps as f64 / 100.0
+    }
+
+    /// Fee the protocol takes out of `reward_lamports` on completion
+    pub fn fee_lamports(&self, reward_lamports: u64) -> u64 {
+        (reward_lamports as u128 * self.protocol_fee_bps as u128 / 10_000) as u64
+    }
+}
+
 // ============================================================================
 // PDA Derivation
 // ============================================================================
@@ -300,10 +306,56 @@ pub fn instruction_discriminator(name: &str) -> [u8; 8] {
     disc
 }
 
+/// An AgenC instruction read back from a transaction
+#[derive(Debug, Clone)]
+pub struct DecodedInstruction {
+    /// IDL instruction name ("claim_task")
+    pub name: String,
+    /// Passed accounts by IDL account name
+    pub accounts: Vec<(String, Pubkey)>,
+    pub args: IdlFields,
+}
+
+impl DecodedInstruction {
+    pub fn account(&self, name: &str) -> Option<Pubkey> {
+        self.accounts.iter().find(|(n, _)| n == name).map(|(_, key)| *key)
+    }
+}
+
+/// Decode AgenC instruction data by its discriminator. Accounts are named in
+/// IDL order; optional accounts come last, so a missing one names nothing.
+pub fn decode_instruction(data: &[u8], accounts: &[Pubkey]) -> Result<DecodedInstruction> {
+    let (ix, args) = agenc_idl().decode_instruction_data(data)?;
+    Ok(DecodedInstruction {
+        name: ix.name.clone(),
+        accounts: ix.accounts.iter().zip(accounts).map(|(a, key)| (a.name.clone(), *key)).collect(),
+        args,
+    })
+}
+
 // ============================================================================
 // Instruction Builders
 // ============================================================================
 
+/// Encode an AgenC instruction from the IDL. Fails when the IDL and the
+/// builder disagree (an unknown or missing account or argument), so an IDL
+/// update fails the intent rather than sending a misencoded instruction.
+fn idl_instruction(name: &str, accounts: &[(&str, Pubkey)], args: &[(&str, IdlValue)]) -> Result<Instruction> {
+    agenc_idl()
+        .build_instruction(program_id(), name, accounts, args)
+        .with_context(|| format!("AgenC IDL does not match the {} builder", name))
+}
+
+/// Accounts appended to settlement instructions when the task has an SKR reward
+fn skr_accounts<'a>(task_pda: &Pubkey, recipient_role: &'a str, recipient: &Pubkey) -> [(&'a str, Pubkey); 4] {
+    [
+        ("escrow_skr_ata", get_skr_escrow_ata(task_pda)),
+        (recipient_role, get_skr_ata(recipient)),
+        ("skr_mint", skr_mint()),
+        ("token_program", TOKEN_PROGRAM_ID),
+    ]
+}
+
 /// Build a `create_task` instruction.
 ///
 /// Accounts:
@@ -322,31 +374,26 @@ pub fn build_create_task_ix(
     reward_lamports: u64,
     deadline: i64,
     required_capabilities: u64,
-) -> Instruction {
+) -> Result<Instruction> {
     let (task_pda, _) = derive_task_pda(task_id);
     let (escrow_pda, _) = derive_escrow_pda(&task_pda);
     let (protocol_pda, _) = derive_protocol_pda();
 
-    let disc = instruction_discriminator("create_task");
-
-    let mut data = Vec::with_capacity(72);
-    data.extend_from_slice(&disc);
-    data.extend_from_slice(&description_hash);
-    data.extend_from_slice(&reward_lamports.to_le_bytes());
-    data.extend_from_slice(&deadline.to_le_bytes());
-    data.extend_from_slice(&required_capabilities.to_le_bytes());
-
-    Instruction {
-        program_id: program_id(),
-        accounts: vec![
-            AccountMeta::new(task_pda, false),
-            AccountMeta::new(escrow_pda, false),
-            AccountMeta::new(*creator, true), // signer + funds source
-            AccountMeta::new_readonly(protocol_pda, false),
-            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    idl_instruction(
+        "create_task",
+        &[
+            ("task", task_pda),
+            ("escrow", escrow_pda),
+            ("creator", *creator), // signer + funds source
+            ("protocol_config", protocol_pda),
         ],
-        data,
-    }
+        &[
+            ("description_hash", IdlValue::Bytes(description_hash.to_vec())),
+            ("reward_amount", IdlValue::U64(reward_lamports)),
+            ("deadline", IdlValue::I64(deadline)),
+            ("required_capabilities", IdlValue::U64(required_capabilities)),
+        ],
+    )
 }
 
 /// Build an SPL token transfer instruction to move SKR tokens into escrow.
@@ -391,50 +438,23 @@ pub fn build_skr_escrow_deposit_ix(
     Ok(ixs)
 }
 
-/// Build instructions to release SKR tokens from escrow to the worker on task completion.
+/// Create `recipient`'s SKR ATA idempotently (no-op if it already exists),
+/// paid by `payer`.
 ///
-/// Requires the escrow PDA to sign via CPI in the on-chain program.
-/// If the program handles this internally, only include the accounts —
-/// otherwise append these instructions to the complete_task transaction.
-pub fn build_skr_escrow_release_ix(
-    task_pda: &Pubkey,
-    worker: &Pubkey,
-    skr_amount: u64,
-) -> Result<Vec<Instruction>> {
-    let escrow_skr_ata = get_skr_escrow_ata(task_pda);
-    let worker_skr_ata = get_skr_ata(worker);
-    let (escrow_pda, _) = derive_escrow_pda(task_pda);
-    let mint = skr_mint();
-
-    let mut ixs = Vec::with_capacity(2);
-
-    // 1. Create the worker's SKR ATA if needed
-    ixs.push(
-        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
-            worker,        // payer
-            worker,        // wallet (owner of the ATA)
-            &mint,
-            &TOKEN_PROGRAM_ID,
-        ),
-    );
-
-    // 2. Transfer from escrow to worker
-    // NOTE: In practice the on-chain program handles this via CPI with PDA signing.
-    // This instruction is provided for client-side building when the program
-    // delegates token transfers to the caller's transaction.
-    ixs.push(
-        spl_token::instruction::transfer(
-            &TOKEN_PROGRAM_ID,
-            &escrow_skr_ata,
-            &worker_skr_ata,
-            &escrow_pda,     // authority (escrow PDA — must be signed via CPI)
-            &[],
-            skr_amount,
-        )
-        .map_err(|e| anyhow!("Failed to build SPL transfer instruction: {}", e))?,
-    );
-
-    Ok(ixs)
+/// This is the only client-side part of an SKR escrow release: the escrow →
+/// recipient transfer itself is performed by the on-chain program via CPI,
+/// signed by the escrow PDA — a client cannot sign for a PDA, so a raw SPL
+/// transfer here would always fail. The ATA just has to exist before the
+/// program instruction runs.
+///
+/// Used for both payout (recipient = worker) and refund (recipient = creator).
+pub fn build_ensure_skr_ata_ix(payer: &Pubkey, recipient: &Pubkey) -> Instruction {
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+        payer,         // payer
+        recipient,     // wallet (owner of the ATA)
+        &skr_mint(),
+        &TOKEN_PROGRAM_ID,
+    )
 }
 
 /// Build a `claim_task` instruction.
@@ -449,28 +469,20 @@ pub fn build_claim_task_ix(
     task_pda: &Pubkey,
     agent_pubkey: &Pubkey,
     agent_id: [u8; 32],
-) -> Instruction {
+) -> Result<Instruction> {
     let (claim_pda, _) = derive_claim_pda(task_pda, agent_pubkey);
     let (agent_pda, _) = derive_agent_pda(agent_pubkey);
 
-    let disc = instruction_discriminator("claim_task");
-
-    // Data: discriminator (8) + agent_id (32) = 40 bytes
-    let mut data = Vec::with_capacity(40);
-    data.extend_from_slice(&disc);
-    data.extend_from_slice(&agent_id);
-
-    Instruction {
-        program_id: program_id(),
-        accounts: vec![
-            AccountMeta::new(*task_pda, false),
-            AccountMeta::new(claim_pda, false),
-            AccountMeta::new(agent_pda, false),
-            AccountMeta::new(*agent_pubkey, true), // signer
-            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    idl_instruction(
+        "claim_task",
+        &[
+            ("task", *task_pda),
+            ("claim", claim_pda),
+            ("agent", agent_pda),
+            ("authority", *agent_pubkey), // signer
         ],
-        data,
-    }
+        &[("agent_id", IdlValue::Bytes(agent_id.to_vec()))],
+    )
 }
 
 /// Build a `complete_task` instruction.
@@ -481,7 +493,7 @@ pub fn build_claim_task_ix(
 ///   2. [writable] Escrow PDA
 ///   3. [writable] Worker (receives reward)
 ///   4. []         Protocol config PDA
-///   5. [writable] Treasury
+///   5. [writable] Treasury (from the protocol config)
 ///   6. []         System program
 ///
 /// Additional accounts when `include_skr` is true:
@@ -493,125 +505,249 @@ pub fn build_claim_task_ix(
 pub fn build_complete_task_ix(
     task_pda: &Pubkey,
     agent_pubkey: &Pubkey,
+    treasury: &Pubkey,
     proof_hash: [u8; 32],
     result_data: Option<[u8; 64]>,
     include_skr: bool,
-) -> Instruction {
+) -> Result<Instruction> {
     let (claim_pda, _) = derive_claim_pda(task_pda, agent_pubkey);
     let (escrow_pda, _) = derive_escrow_pda(task_pda);
     let (protocol_pda, _) = derive_protocol_pda();
 
-    let disc = instruction_discriminator("complete_task");
+    let mut accounts = vec![
+        ("task", *task_pda),
+        ("claim", claim_pda),
+        ("escrow", escrow_pda),
+        ("worker", *agent_pubkey), // signer + reward recipient
+        ("protocol_config", protocol_pda),
+        ("treasury", *treasury),
+    ];
 
-    // Data: discriminator (8) + proof_hash (32) + result_data (64) = 104 bytes
-    let mut data = Vec::with_capacity(104);
-    data.extend_from_slice(&disc);
-    data.extend_from_slice(&proof_hash);
-    data.extend_from_slice(&result_data.unwrap_or([0u8; 64]));
+    // Append SKR token accounts if the task has an SKR reward
+    if include_skr {
+        accounts.extend(skr_accounts(task_pda, "worker_skr_ata", agent_pubkey));
+        accounts.push(("associated_token_program", ATA_PROGRAM_ID));
+    }
 
-    // Treasury address — this should come from protocol config in production.
-    // For now use the protocol PDA as a placeholder.
-    let treasury = protocol_pda;
+    idl_instruction(
+        "complete_task",
+        &accounts,
+        &[
+            ("proof_hash", IdlValue::Bytes(proof_hash.to_vec())),
+            ("result_data", IdlValue::Bytes(result_data.unwrap_or([0u8; 64]).to_vec())),
+        ],
+    )
+}
+
+/// Build a `cancel_task` instruction.
+///
+/// Only the task creator can cancel, and only while the task is still open.
+/// The program closes the escrow and refunds the SOL reward to the creator.
+///
+/// Accounts (base — SOL only):
+///   0. [writable] Task PDA
+///   1. [writable] Escrow PDA
+///   2. [signer]   Creator (receives refund)
+///   3. []         System program
+///
+/// Additional accounts when `include_skr` is true:
+///   4. [writable] Escrow SKR ATA
+///   5. [writable] Creator SKR ATA (receives SKR refund)
+///   6. []         SKR mint
+///   7. []         Token program
+pub fn build_cancel_task_ix(
+    task_pda: &Pubkey,
+    creator: &Pubkey,
+    include_skr: bool,
+) -> Result<Instruction> {
+    let (escrow_pda, _) = derive_escrow_pda(task_pda);
 
     let mut accounts = vec![
-        AccountMeta::new(*task_pda, false),
-        AccountMeta::new(claim_pda, false),
-        AccountMeta::new(escrow_pda, false),
-        AccountMeta::new(*agent_pubkey, true), // signer + reward recipient
-        AccountMeta::new_readonly(protocol_pda, false),
-        AccountMeta::new(treasury, false),
-        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ("task", *task_pda),
+        ("escrow", escrow_pda),
+        ("creator", *creator), // signer + refund recipient
+    ];
+
+    // Append SKR token accounts so the escrowed SKR is refunded too
+    if include_skr {
+        accounts.extend(skr_accounts(task_pda, "creator_skr_ata", creator));
+    }
+
+    idl_instruction("cancel_task", &accounts, &[])
+}
+
+/// Build an `approve_completion` instruction.
+///
+/// Creator-side settlement for a task in `PendingValidation`: the creator
+/// accepts the worker's submission and the program releases the escrow.
+///
+/// Accounts (base — SOL only):
+///   0. [writable] Task PDA
+///   1. [writable] Claim PDA (task + worker)
+///   2. [writable] Escrow PDA
+///   3. [signer]   Creator
+///   4. [writable] Worker (receives reward)
+///   5. []         Protocol config PDA
+///   6. [writable] Treasury (from the protocol config)
+///   7. []         System program
+///
+/// Additional accounts when `include_skr` is true:
+///   8. [writable] Escrow SKR ATA
+///   9. [writable] Worker SKR ATA
+///  10. []         SKR mint
+///  11. []         Token program
+///  12. []         ATA program
+pub fn build_approve_completion_ix(
+    task_pda: &Pubkey,
+    creator: &Pubkey,
+    worker: &Pubkey,
+    treasury: &Pubkey,
+    include_skr: bool,
+) -> Result<Instruction> {
+    let (claim_pda, _) = derive_claim_pda(task_pda, worker);
+    let (escrow_pda, _) = derive_escrow_pda(task_pda);
+    let (protocol_pda, _) = derive_protocol_pda();
+
+    let mut accounts = vec![
+        ("task", *task_pda),
+        ("claim", claim_pda),
+        ("escrow", escrow_pda),
+        ("creator", *creator), // signer
+        ("worker", *worker),   // reward recipient
+        ("protocol_config", protocol_pda),
+        ("treasury", *treasury),
     ];
 
-    // Append SKR token accounts if the task has an SKR reward
     if include_skr {
-        let escrow_skr_ata = get_skr_escrow_ata(task_pda);
-        let worker_skr_ata = get_skr_ata(agent_pubkey);
-        accounts.push(AccountMeta::new(escrow_skr_ata, false));
-        accounts.push(AccountMeta::new(worker_skr_ata, false));
-        accounts.push(AccountMeta::new_readonly(skr_mint(), false));
-        accounts.push(AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false));
-        accounts.push(AccountMeta::new_readonly(ATA_PROGRAM_ID, false));
+        accounts.extend(skr_accounts(task_pda, "worker_skr_ata", worker));
+        accounts.push(("associated_token_program", ATA_PROGRAM_ID));
     }
 
-    Instruction {
-        program_id: program_id(),
-        accounts,
-        data,
+    idl_instruction("approve_completion", &accounts, &[])
+}
+
+// ============================================================================
+// Transaction Instruction Sets
+// ============================================================================
+// Full instruction lists for each settlement path. When the task carries an
+// SKR reward the recipient's ATA is created first so the program's CPI
+// transfer out of escrow has somewhere to land.
+
+/// Instructions for a worker completing a task: `[ata?, complete_task]`.
+pub fn build_complete_task_ixs(
+    task_pda: &Pubkey,
+    worker: &Pubkey,
+    treasury: &Pubkey,
+    proof_hash: [u8; 32],
+    result_data: Option<[u8; 64]>,
+    include_skr: bool,
+) -> Result<Vec<Instruction>> {
+    let mut ixs = Vec::with_capacity(2);
+    if include_skr {
+        ixs.push(build_ensure_skr_ata_ix(worker, worker));
+    }
+    ixs.push(build_complete_task_ix(task_pda, worker, treasury, proof_hash, result_data, include_skr)?);
+    Ok(ixs)
+}
+
+/// Instructions for a creator approving a submission: `[ata?, approve_completion]`.
+/// The creator pays for the worker's ATA if it doesn't exist yet.
+pub fn build_approve_completion_ixs(
+    task_pda: &Pubkey,
+    creator: &Pubkey,
+    worker: &Pubkey,
+    treasury: &Pubkey,
+    include_skr: bool,
+) -> Result<Vec<Instruction>> {
+    let mut ixs = Vec::with_capacity(2);
+    if include_skr {
+        ixs.push(build_ensure_skr_ata_ix(creator, worker));
+    }
+    ixs.push(build_approve_completion_ix(task_pda, creator, worker, treasury, include_skr)?);
+    Ok(ixs)
+}
+
+/// Instructions for a creator cancelling an open task: `[ata?, cancel_task]`.
+pub fn build_cancel_task_ixs(
+    task_pda: &Pubkey,
+    creator: &Pubkey,
+    include_skr: bool,
+) -> Result<Vec<Instruction>> {
+    let mut ixs = Vec::with_capacity(2);
+    if include_skr {
+        ixs.push(build_ensure_skr_ata_ix(creator, creator));
     }
+    ixs.push(build_cancel_task_ix(task_pda, creator, include_skr)?);
+    Ok(ixs)
 }
 
 // ============================================================================
 // RPC Query Helpers
 // ============================================================================
 
+use solana_client::client_error::{ClientError, ClientErrorKind};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
-use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::RpcError;
 use solana_sdk::commitment_config::CommitmentConfig;
 
-/// Fetch all tasks in a given state from the AgenC program.
+/// Fetch the tasks in a given state, highest reward first (at most `limit`).
+/// See `task_query` for richer filters and the cached snapshot.
 pub async fn fetch_tasks_by_state(
     rpc: &RpcClient,
     state: OnChainTaskState,
     limit: usize,
 ) -> Result<Vec<OnChainTask>> {
-    let filters = vec![
-        // Filter by discriminator (first 8 bytes)
-        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
-            0,
-            TASK_DISCRIMINATOR.to_vec(),
-        )),
-        // Filter by state byte at offset 154
-        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
-            TASK_STATUS_OFFSET,
-            vec![state as u8],
-        )),
-    ];
-
-    let config = RpcProgramAccountsConfig {
-        filters: Some(filters),
-        account_config: RpcAccountInfoConfig {
-            commitment: Some(CommitmentConfig::confirmed()),
-            ..Default::default()
-        },
-        ..Default::default()
-    };
-
-    let accounts = rpc
-        .get_program_accounts_with_config(&program_id(), config)
-        .await
-        .map_err(|e| anyhow!("Failed to fetch program accounts: {}", e))?;
-
-    let mut tasks = Vec::new();
-    for (pubkey, account) in accounts.iter().take(limit) {
-        match OnChainTask::from_account_data(&account.data, pubkey) {
-            Ok(task) => tasks.push(task),
-            Err(e) => tracing::warn!("Failed to deserialize task {}: {}", pubkey, e),
-        }
-    }
-
-    // Sort by reward descending
-    tasks.sort_by(|a, b| b.reward_lamports.cmp(&a.reward_lamports));
-
-    Ok(tasks)
+    crate::task_query::fetch_tasks(rpc, &crate::task_query::TaskQuery::new().state(state).limit(limit)).await
 }
 
 /// Fetch a single task by its ID.
 pub async fn fetch_task_by_id(rpc: &RpcClient, task_id: u64) -> Result<Option<OnChainTask>> {
     let (pda, _) = derive_task_pda(task_id);
+    fetch_task_by_pda(rpc, &pda).await
+}
 
-    match rpc.get_account(&pda).await {
-        Ok(account) => {
-            let task = OnChainTask::from_account_data(&account.data, &pda)?;
-            Ok(Some(task))
-        }
-        Err(_) => Ok(None),
-    }
+/// Fetch a single task by its PDA address. None only when the account does
+/// not exist; RPC failures are returned so the pool can fail over.
+pub async fn fetch_task_by_pda(rpc: &RpcClient, pda: &Pubkey) -> Result<Option<OnChainTask>> {
+    let account = rpc
+        .get_account_with_commitment(pda, CommitmentConfig::confirmed())
+        .await
+        .context("Failed to fetch task account")?
+        .value;
+    account
+        .map(|account| OnChainTask::from_account_data(&account.data, pda))
+        .transpose()
+}
+
+/// Fetch and decode the protocol config account.
+pub async fn fetch_protocol_config(rpc: &RpcClient) -> Result<ProtocolConfig> {
+    let (pda, _) = derive_protocol_pda();
+    let account = rpc
+        .get_account(&pda)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch protocol config: {}", e))?;
+    ProtocolConfig::from_account_data(&account.data)
+}
+
+/// Id of the next task: the program seeds the task PDA with the protocol
+/// counter, so no other id is accepted.
+pub async fn next_task_id(rpc: &RpcClient) -> Result<(u64, ProtocolConfig)> {
+    let config = fetch_protocol_config(rpc).await?;
+    Ok((config.next_task_id, config))
+}
+
+/// Whether a failed create_task send lost a race for the counter: another
+/// task took the id, so our PDA no longer matches the program's seeds.
+/// 0x7d6 is Anchor's `ConstraintSeeds` (2006).
+pub fn is_task_id_collision(error: &str) -> bool {
+    error.contains("ConstraintSeeds")
+        || error.contains("seeds constraint")
+        || error.contains("custom program error: 0x7d6")
+        || error.contains("already in use")
 }
 
 /// Fetch the SKR token balance for a wallet.
-/// Returns 0 if the ATA doesn't exist.
+/// Returns 0 if the ATA doesn't exist; other RPC failures are returned.
 pub async fn fetch_skr_balance(rpc: &RpcClient, wallet: &Pubkey) -> Result<u64> {
     let ata = get_skr_ata(wallet);
     match rpc.get_token_account_balance(&ata).await {
@@ -619,10 +755,23 @@ pub async fn fetch_skr_balance(rpc: &RpcClient, wallet: &Pubkey) -> Result<u64>
             let amount = balance.amount.parse::<u64>().unwrap_or(0);
             Ok(amount)
         }
-        Err(_) => Ok(0), // ATA doesn't exist — zero balance
+        Err(e) if is_missing_account(&e) => Ok(0), // ATA doesn't exist — zero balance
+        Err(e) => Err(anyhow::Error::new(e).context("Failed to fetch SKR balance")),
     }
 }
 
+/// JSON-RPC "Invalid params" error code
+const JSON_RPC_INVALID_PARAMS: i64 = -32602;
+
+/// `getTokenAccountBalance` answers "Invalid param: could not find account"
+/// for an account that does not exist
+fn is_missing_account(error: &ClientError) -> bool {
+    matches!(
+        error.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code: JSON_RPC_INVALID_PARAMS, .. })
+    )
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -647,6 +796,26 @@ mod tests {
         assert_eq!(&disc, &hash[..8]);
     }
 
+    #[test]
+    fn test_decode_instruction_names_accounts() {
+        let (task, agent) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = build_claim_task_ix(&task, &agent, [3; 32]).unwrap();
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|a| a.pubkey).collect();
+
+        let decoded = decode_instruction(&ix.data, &keys).unwrap();
+        assert_eq!(decoded.name, "claim_task");
+        assert_eq!(decoded.account("task"), Some(task));
+        assert_eq!(decoded.account("authority"), Some(agent));
+        assert_eq!(decoded.args.array::<32>("agent_id").unwrap(), [3; 32]);
+        assert!(decode_instruction(&instruction_discriminator("no_such_ix"), &keys).is_err());
+    }
+
+    #[test]
+    fn test_idl_mismatch_is_an_error() {
+        let err = idl_instruction("cancel_task", &[("no_such_account", Pubkey::new_unique())], &[]).unwrap_err();
+        assert!(err.to_string().contains("cancel_task builder"));
+    }
+
     #[test]
     fn test_task_pda_derivation() {
         let (pda, bump) = derive_task_pda(42);
@@ -690,8 +859,325 @@ mod tests {
     fn test_create_task_ix_builds() {
         let creator = Pubkey::new_unique();
         let desc_hash = [0xAA; 32];
-        let ix = build_create_task_ix(1, &creator, desc_hash, 1_000_000, 0, 0);
+        let ix = build_create_task_ix(1, &creator, desc_hash, 1_000_000, 0, 0).unwrap();
         assert_eq!(ix.program_id, program_id());
         assert_eq!(ix.accounts.len(), 5);
     }
+
+    #[test]
+    fn test_cancel_task_ix_builds() {
+        let creator = Pubkey::new_unique();
+        let (task_pda, _) = derive_task_pda(7);
+        let (escrow_pda, _) = derive_escrow_pda(&task_pda);
+
+        let ix = build_cancel_task_ix(&task_pda, &creator, false).unwrap();
+        assert_eq!(ix.program_id, program_id());
+        assert_eq!(ix.data, instruction_discriminator("cancel_task").to_vec());
+        assert_eq!(ix.accounts.len(), 4);
+        assert_eq!(ix.accounts[0].pubkey, task_pda);
+        assert_eq!(ix.accounts[1].pubkey, escrow_pda);
+        assert_eq!(ix.accounts[2].pubkey, creator);
+        assert!(ix.accounts[2].is_signer);
+    }
+
+    #[test]
+    fn test_cancel_task_ix_with_skr_refund() {
+        let creator = Pubkey::new_unique();
+        let (task_pda, _) = derive_task_pda(7);
+
+        let ix = build_cancel_task_ix(&task_pda, &creator, true).unwrap();
+        assert_eq!(ix.accounts.len(), 8);
+        assert_eq!(ix.accounts[4].pubkey, get_skr_escrow_ata(&task_pda));
+        assert_eq!(ix.accounts[5].pubkey, get_skr_ata(&creator));
+        assert!(ix.accounts[5].is_writable);
+        assert_eq!(ix.accounts[6].pubkey, skr_mint());
+    }
+
+    #[test]
+    fn test_complete_task_ixs_with_skr_release() {
+        let (worker, treasury) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (task_pda, _) = derive_task_pda(11);
+
+        let ixs = build_complete_task_ixs(&task_pda, &worker, &treasury, [1u8; 32], None, true).unwrap();
+        assert_eq!(ixs.len(), 2);
+
+        // Worker's SKR ATA is created idempotently, worker pays
+        let ata_ix = &ixs[0];
+        assert_eq!(ata_ix.program_id, ATA_PROGRAM_ID);
+        assert_eq!(ata_ix.data, vec![1]); // CreateIdempotent
+        assert_eq!(ata_ix.accounts[0].pubkey, worker);
+        assert_eq!(ata_ix.accounts[1].pubkey, get_skr_ata(&worker));
+        assert_eq!(ata_ix.accounts[2].pubkey, worker);
+        assert_eq!(ata_ix.accounts[3].pubkey, skr_mint());
+
+        let ix = &ixs[1];
+        assert_eq!(ix.program_id, program_id());
+        assert_eq!(&ix.data[..8], &instruction_discriminator("complete_task"));
+        assert_eq!(ix.accounts.len(), 12);
+        assert_eq!(ix.accounts[0].pubkey, task_pda);
+        assert_eq!(ix.accounts[1].pubkey, derive_claim_pda(&task_pda, &worker).0);
+        assert_eq!(ix.accounts[2].pubkey, derive_escrow_pda(&task_pda).0);
+        assert_eq!(ix.accounts[3].pubkey, worker);
+        assert!(ix.accounts[3].is_signer);
+        assert_eq!(ix.accounts[5].pubkey, treasury);
+        assert_eq!(ix.accounts[7].pubkey, get_skr_escrow_ata(&task_pda));
+        assert_eq!(ix.accounts[8].pubkey, get_skr_ata(&worker));
+        assert!(ix.accounts[8].is_writable);
+    }
+
+    #[test]
+    fn test_complete_task_ixs_sol_only() {
+        let (worker, treasury) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (task_pda, _) = derive_task_pda(12);
+
+        let ixs = build_complete_task_ixs(&task_pda, &worker, &treasury, [0u8; 32], None, false).unwrap();
+        assert_eq!(ixs.len(), 1);
+        assert_eq!(ixs[0].accounts.len(), 7);
+    }
+
+    #[test]
+    fn test_approve_completion_ixs() {
+        let creator = Pubkey::new_unique();
+        let (worker, treasury) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (task_pda, _) = derive_task_pda(13);
+
+        let ixs = build_approve_completion_ixs(&task_pda, &creator, &worker, &treasury, true).unwrap();
+        assert_eq!(ixs.len(), 2);
+
+        // Creator funds the worker's ATA
+        assert_eq!(ixs[0].accounts[0].pubkey, creator);
+        assert_eq!(ixs[0].accounts[1].pubkey, get_skr_ata(&worker));
+        assert_eq!(ixs[0].accounts[2].pubkey, worker);
+
+        let ix = &ixs[1];
+        assert_eq!(ix.data, instruction_discriminator("approve_completion").to_vec());
+        assert_eq!(ix.accounts.len(), 13);
+        assert_eq!(ix.accounts[1].pubkey, derive_claim_pda(&task_pda, &worker).0);
+        assert_eq!(ix.accounts[2].pubkey, derive_escrow_pda(&task_pda).0);
+        assert_eq!(ix.accounts[3].pubkey, creator);
+        assert!(ix.accounts[3].is_signer);
+        assert_eq!(ix.accounts[6].pubkey, treasury);
+        assert_eq!(ix.accounts[4].pubkey, worker);
+        assert!(!ix.accounts[4].is_signer);
+        assert_eq!(ix.accounts[5].pubkey, derive_protocol_pda().0);
+        assert_eq!(ix.accounts[8].pubkey, get_skr_escrow_ata(&task_pda));
+        assert_eq!(ix.accounts[9].pubkey, get_skr_ata(&worker));
+    }
+
+    // Encodings under the bundled IDL, written out by hand so an IDL edit
+    // cannot silently change them. Only the devnet captures checked by
+    // `test_devnet_captures_match_idl` tie them to the deployed program.
+    const EXPECTED_CREATE_TASK: &str = "c25006b4e87f30abaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+        40420f000000000000f15365000000000500000000000000";
+    const EXPECTED_CLAIM_TASK: &str =
+        "31dedbee9b44dd880707070707070707070707070707070707070707070707070707070707070707";
+    const EXPECTED_CANCEL_TASK: &str = "45e486bb8669ee30";
+    const EXPECTED_APPROVE_COMPLETION: &str = "bfc45b67e8920643";
+
+    /// Task #42, pending validation, claimed, 0.5 SOL + 7 SKR
+    const SAMPLE_TASK_ACCOUNT: &str = "4f22e537585a37542a00000000000000\
+        0101010101010101010101010101010101010101010101010101010101010101\
+        0202020202020202020202020202020202020202020202020202020202020202\
+        0300000000000000\
+        aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+        bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\
+        0000020065cd1d0000000000f1536500000000\
+        010303030303030303030303030303030303030303030303030303030303030303\
+        00863ba101000000";
+
+    #[test]
+    fn test_instruction_encodings() {
+        let creator = Pubkey::new_unique();
+        let ix = build_create_task_ix(1, &creator, [0xAA; 32], 1_000_000, 1_700_000_000, 5).unwrap();
+        assert_eq!(hex::encode(&ix.data), EXPECTED_CREATE_TASK);
+        assert!(ix.accounts[2].is_signer && ix.accounts[2].is_writable);
+        assert_eq!(ix.accounts[4].pubkey, Pubkey::default()); // system program
+
+        let (task_pda, _) = derive_task_pda(1);
+        let ix = build_claim_task_ix(&task_pda, &creator, [7; 32]).unwrap();
+        assert_eq!(hex::encode(&ix.data), EXPECTED_CLAIM_TASK);
+
+        let ix = build_complete_task_ix(&task_pda, &creator, &creator, [1; 32], None, false).unwrap();
+        assert_eq!(ix.data.len(), 104);
+        assert_eq!(hex::encode(&ix.data[..8]), "6da7c029816cdcc4");
+        assert_eq!(&ix.data[8..40], &[1u8; 32]);
+        assert_eq!(&ix.data[40..], &[0u8; 64]);
+
+        assert_eq!(hex::encode(build_cancel_task_ix(&task_pda, &creator, false).unwrap().data), EXPECTED_CANCEL_TASK);
+        assert_eq!(
+            hex::encode(build_approve_completion_ix(&task_pda, &creator, &creator, &creator, false).unwrap().data),
+            EXPECTED_APPROVE_COMPLETION
+        );
+    }
+
+    #[test]
+    fn test_task_account_decode() {
+        let data = hex::decode(SAMPLE_TASK_ACCOUNT).unwrap();
+        assert_eq!(data[..8], TASK_DISCRIMINATOR);
+        assert_eq!(data[TASK_STATUS_OFFSET], OnChainTaskState::PendingValidation as u8);
+        assert_eq!(data[TASK_CREATOR_OFFSET..TASK_CREATOR_OFFSET + 32], [1; 32]);
+        assert_eq!(data[TASK_CLAIMED_BY_OFFSET], 1);
+        assert_eq!(data[TASK_CLAIMED_BY_OFFSET + 1..TASK_CLAIMED_BY_OFFSET + 33], [3; 32]);
+
+        let pda = derive_task_pda(42).0;
+        let task = OnChainTask::from_account_data(&data, &pda).unwrap();
+        assert_eq!(task.task_id, 42);
+        assert_eq!(task.creator, Pubkey::new_from_array([1; 32]).to_string());
+        assert_eq!(task.escrow_account, Pubkey::new_from_array([2; 32]).to_string());
+        assert_eq!(task.required_capabilities, 3);
+        assert_eq!(task.description_hash, [0xAA; 32]);
+        assert_eq!(task.constraint_hash, [0xBB; 32]);
+        assert_eq!(task.state, OnChainTaskState::PendingValidation);
+        assert_eq!(task.reward_lamports, 500_000_000);
+        assert_eq!(task.deadline, 1_700_000_000);
+        assert_eq!(task.claimed_by, Some(Pubkey::new_from_array([3; 32]).to_string()));
+        assert_eq!(task.reward_skr_tokens, 7_000_000_000);
+    }
+
+    #[test]
+    fn test_unclaimed_task_reads_skr_reward_after_empty_option() {
+        // An empty Option<Pubkey> is one byte, so the SKR reward moves up
+        let mut data = hex::decode(SAMPLE_TASK_ACCOUNT).unwrap();
+        data.splice(171..204, [0u8]);
+        data.resize(212, 0); // allocated space stays zeroed
+
+        let task = OnChainTask::from_account_data(&data, &Pubkey::new_unique()).unwrap();
+        assert_eq!(task.claimed_by, None);
+        assert_eq!(task.reward_skr_tokens, 7_000_000_000);
+    }
+
+    #[test]
+    fn test_task_layout_matches_idl() {
+        let idl = agenc_idl();
+        assert_eq!(idl.address, PROGRAM_ID);
+        assert_eq!(idl.account_discriminator("Task").unwrap(), TASK_DISCRIMINATOR);
+        assert_eq!(idl.field_offset("Task", "state"), Some(TASK_STATUS_OFFSET));
+        assert_eq!(idl.field_offset("Task", "creator"), Some(TASK_CREATOR_OFFSET));
+        assert_eq!(idl.field_offset("Task", "claimed_by"), Some(TASK_CLAIMED_BY_OFFSET));
+
+        let data = hex::decode(SAMPLE_TASK_ACCOUNT).unwrap();
+        assert!(OnChainTask::from_account_data(&data[..100], &Pubkey::new_unique()).is_err());
+        let mut wrong = data.clone();
+        wrong[0] ^= 0xFF;
+        assert!(OnChainTask::from_account_data(&wrong, &Pubkey::new_unique()).is_err());
+    }
+
+    /// A transaction recorded from a live cluster by scripts/update-agenc-idl.sh
+    #[derive(Deserialize)]
+    struct Capture {
+        signature: String,
+        instructions: Vec<CapturedInstruction>,
+        accounts: Vec<CapturedAccount>,
+    }
+
+    #[derive(Deserialize)]
+    struct CapturedInstruction {
+        data_base58: String,
+        accounts: Vec<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct CapturedAccount {
+        pubkey: String,
+        data_base64: String,
+    }
+
+    #[test]
+    #[ignore = "needs devnet captures: scripts/update-agenc-idl.sh devnet <signature>..."]
+    fn test_devnet_captures_match_idl() {
+        use base64::Engine;
+
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/agenc");
+        let mut captures = Vec::new();
+        for entry in std::fs::read_dir(&dir).unwrap_or_else(|e| panic!("{}: {}", dir.display(), e)) {
+            let json = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            captures.push(serde_json::from_str::<Capture>(&json).unwrap());
+        }
+        assert!(!captures.is_empty(), "no captures in {}", dir.display());
+
+        let idl = agenc_idl();
+        for capture in &captures {
+            for captured in &capture.instructions {
+                // The IDL must consume exactly the bytes the program was sent
+                let data = bs58::decode(&captured.data_base58).into_vec().unwrap();
+                let (ix, args) = idl.decode_instruction_data(&data).unwrap();
+                let args: Vec<(&str, IdlValue)> = args.0.iter().map(|(n, v)| (n.as_str(), v.clone())).collect();
+                assert_eq!(idl.encode_instruction_data(&ix.name, &args).unwrap(), data, "{}", capture.signature);
+
+                let required = ix.accounts.iter().filter(|a| !a.optional).count();
+                assert!(captured.accounts.len() >= required, "{} in {}", ix.name, capture.signature);
+            }
+
+            for account in &capture.accounts {
+                let data = base64::engine::general_purpose::STANDARD.decode(&account.data_base64).unwrap();
+                let name = &idl
+                    .accounts
+                    .iter()
+                    .find(|a| data.len() >= 8 && data[..8] == a.discriminator)
+                    .unwrap_or_else(|| panic!("unknown account type at {}", account.pubkey))
+                    .name;
+
+                // Re-encoding reproduces the account; only zeroed spare space follows
+                let fields = idl.decode_account(name, &data).unwrap();
+                let encoded = idl.encode_account(name, &fields).unwrap();
+                assert_eq!(data[..encoded.len()], encoded[..], "{} {}", name, account.pubkey);
+                assert!(data[encoded.len()..].iter().all(|b| *b == 0), "{} {}", name, account.pubkey);
+
+                if name == "Task" {
+                    OnChainTask::from_account_data(&data, &Pubkey::from_str(&account.pubkey).unwrap()).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_protocol_config_decode() {
+        let authority = Pubkey::new_unique();
+        let treasury = Pubkey::new_unique();
+        let mut data = agenc_idl().account_discriminator("ProtocolConfig").unwrap().to_vec();
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(treasury.as_ref());
+        data.extend_from_slice(&250u16.to_le_bytes());
+        data.extend_from_slice(&1_234u64.to_le_bytes());
+        data.extend_from_slice(&17u64.to_le_bytes());
+        data.push(255);
+
+        let config = ProtocolConfig::from_account_data(&data).unwrap();
+        assert_eq!(config.authority, authority.to_string());
+        assert_eq!(config.treasury, treasury.to_string());
+        assert_eq!(config.protocol_fee_bps, 250);
+        assert_eq!(config.next_task_id, 1_234);
+        assert_eq!(config.total_agents, 17);
+        assert_eq!(config.fee_percent(), 2.5);
+        assert_eq!(config.fee_lamports(1_000_000_000), 25_000_000);
+        // No overflow on large rewards
+        assert_eq!(config.fee_lamports(u64::MAX), (u64::MAX as u128 * 250 / 10_000) as u64);
+    }
+
+    #[test]
+    fn test_task_id_collision_detection() {
+        assert!(is_task_id_collision(
+            "Program log: AnchorError caused by account: task. Error Code: ConstraintSeeds. \
+             Error Number: 2006. Error Message: A seeds constraint was violated."
+        ));
+        assert!(is_task_id_collision("Transaction failed: custom program error: 0x7d6"));
+        assert!(is_task_id_collision(
+            "Transaction failed: Allocate: account Address { address: 4x..., base: None } already in use"
+        ));
+        assert!(!is_task_id_collision("Transaction failed: insufficient funds"));
+    }
+
+    #[test]
+    fn test_cancel_task_ixs_refund_path() {
+        let creator = Pubkey::new_unique();
+        let (task_pda, _) = derive_task_pda(14);
+
+        let ixs = build_cancel_task_ixs(&task_pda, &creator, true).unwrap();
+        assert_eq!(ixs.len(), 2);
+        assert_eq!(ixs[0].accounts[1].pubkey, get_skr_ata(&creator));
+        assert_eq!(ixs[0].accounts[2].pubkey, creator);
+        assert_eq!(ixs[1].accounts[5].pubkey, get_skr_ata(&creator));
+
+        assert_eq!(build_cancel_task_ixs(&task_pda, &creator, false).unwrap().len(), 1);
+    }
 }
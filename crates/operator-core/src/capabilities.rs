@@ -0,0 +1,127 @@
+//! ============================================================================
+//! Agent Capabilities - Named Bits of the On-Chain Capability Mask
+//! ============================================================================
+//! Agents register a u64 capability bitmask and tasks carry a
+//! `required_capabilities` mask; the program only compares bits. This
+//! registry names them so voice commands and config can say "code_review"
+//! instead of 1 << 18:
+//! - Bits 0-9 are the protocol's standard capabilities
+//! - Bits 16+ are operator skills (the names stored in OperatorConfig)
+//! - Names are case-insensitive; spaces and hyphens count as underscores
+//! - "bit_N" addresses any bit directly, so unnamed bits round-trip
+//!
+//! Unknown names are an error rather than silently dropped, so a task is
+//! never published with fewer requirements than were asked for.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+
+/// Capability names and their bit positions
+pub const CAPABILITIES: &[(&str, u32)] = &[
+    // Protocol standard capabilities
+    ("compute", 0),
+    ("inference", 1),
+    ("storage", 2),
+    ("network", 3),
+    ("sensor", 4),
+    ("actuator", 5),
+    ("coordinator", 6),
+    ("arbiter", 7),
+    ("validator", 8),
+    ("aggregator", 9),
+    // Operator skills
+    ("voice", 16),
+    ("code", 17),
+    ("code_review", 18),
+    ("trading", 19),
+    ("social", 20),
+    ("email", 21),
+    ("image", 22),
+    ("video", 23),
+];
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+/// Mask bit for a capability name, if it is known
+pub fn capability_bit(name: &str) -> Option<u64> {
+    let name = normalize(name);
+    if let Some(&(_, bit)) = CAPABILITIES.iter().find(|(n, _)| *n == name) {
+        return Some(1 << bit);
+    }
+    name.strip_prefix("bit_")
+        .and_then(|n| n.parse::<u32>().ok())
+        .filter(|bit| *bit < 64)
+        .map(|bit| 1 << bit)
+}
+
+/// Combine capability names into a mask. Fails on the first unknown name.
+pub fn capability_mask<S: AsRef<str>>(names: &[S]) -> Result<u64> {
+    names.iter().try_fold(0u64, |mask, name| {
+        let name = name.as_ref();
+        capability_bit(name).map(|bit| mask | bit).ok_or_else(|| {
+            anyhow!(
+                "Unknown capability '{}'. Known capabilities: {}",
+                name,
+                CAPABILITIES.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ")
+            )
+        })
+    })
+}
+
+/// Names of the bits set in `mask`, lowest bit first. Bits without a name
+/// are reported as "bit_N".
+pub fn capability_names(mask: u64) -> Vec<String> {
+    (0..64)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .map(|bit| {
+            CAPABILITIES
+                .iter()
+                .find(|(_, b)| *b == bit)
+                .map(|(n, _)| n.to_string())
+                .unwrap_or_else(|| format!("bit_{}", bit))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_map_to_bits() {
+        assert_eq!(capability_bit("compute"), Some(1));
+        assert_eq!(capability_bit("Code Review"), Some(1 << 18));
+        assert_eq!(capability_bit("code-review"), Some(1 << 18));
+        assert_eq!(capability_bit("bit_40"), Some(1 << 40));
+        assert_eq!(capability_bit("bit_64"), None);
+        assert_eq!(capability_bit("telepathy"), None);
+    }
+
+    #[test]
+    fn test_mask_roundtrip() {
+        let mask = capability_mask(&["inference", "code_review", "bit_40"]).unwrap();
+        assert_eq!(mask, (1 << 1) | (1 << 18) | (1 << 40));
+        assert_eq!(capability_names(mask), vec!["inference", "code_review", "bit_40"]);
+        assert_eq!(capability_mask::<&str>(&[]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_unknown_name_is_an_error() {
+        let err = capability_mask(&["code", "telepathy"]).unwrap_err();
+        assert!(err.to_string().contains("telepathy"));
+    }
+
+    #[test]
+    fn test_bits_are_unique() {
+        for (i, (name, bit)) in CAPABILITIES.iter().enumerate() {
+            assert!(*bit < 64, "{}", name);
+            assert!(
+                CAPABILITIES[i + 1..].iter().all(|(n, b)| b != bit && n != name),
+                "{} is registered twice",
+                name
+            );
+        }
+    }
+}
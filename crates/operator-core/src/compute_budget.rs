@@ -0,0 +1,193 @@
+//! ============================================================================
+//! Compute Budget - Priority Fees and Compute Unit Limits
+//! ============================================================================
+//! Prepends ComputeBudget instructions to AgenC transactions before signing:
+//! - The compute unit limit comes from simulating the instructions, plus a
+//!   margin, instead of the 200k-per-instruction default
+//! - The unit price is a percentile of `getRecentPrioritizationFees` for the
+//!   accounts the transaction writes, capped so the whole priority fee stays
+//!   under `max_fee_lamports`
+//!
+//! If simulation or the fee lookup fails the instructions are sent as they
+//! are; the sender's preflight then reports any real error.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_compute_budget_interface::{self as compute_budget, ComputeBudgetInstruction};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use tracing::{debug, warn};
+
+/// Most compute units a transaction may request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Units for the two ComputeBudget instructions themselves
+const BUDGET_INSTRUCTION_UNITS: u32 = 300;
+
+/// How priority fees are chosen
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityFeeConfig {
+    /// Percentile of recent fees to pay (0-100)
+    pub percentile: u8,
+    /// Extra compute units over the simulated amount, in percent
+    pub unit_margin_percent: u32,
+    /// Cap on the total priority fee per transaction
+    pub max_fee_lamports: u64,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            percentile: 75,
+            unit_margin_percent: 10,
+            max_fee_lamports: 100_000,
+        }
+    }
+}
+
+impl PriorityFeeConfig {
+    /// Unit limit for a transaction that consumed `units` in simulation
+    pub fn unit_limit(&self, units: u64) -> u32 {
+        let with_margin = units.saturating_mul(100 + self.unit_margin_percent as u64) / 100;
+        (with_margin.saturating_add(BUDGET_INSTRUCTION_UNITS as u64)).min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+    }
+
+    /// Unit price (micro-lamports) from recent fees, capped so that
+    /// `unit_limit` units cost at most `max_fee_lamports`
+    pub fn unit_price(&self, recent_fees: &[u64], unit_limit: u32) -> u64 {
+        if recent_fees.is_empty() || unit_limit == 0 {
+            return 0;
+        }
+        let mut fees = recent_fees.to_vec();
+        fees.sort_unstable();
+        let rank = (fees.len() - 1) * self.percentile.min(100) as usize / 100;
+        let cap = self.max_fee_lamports.saturating_mul(1_000_000) / unit_limit as u64;
+        fees[rank].min(cap)
+    }
+}
+
+/// Whether `instructions` already set their own compute budget
+fn has_compute_budget(instructions: &[Instruction]) -> bool {
+    instructions.iter().any(|ix| ix.program_id == compute_budget::ID)
+}
+
+/// Units `instructions` consume when simulated with the maximum limit
+async fn simulate_units(rpc: &RpcClient, instructions: &[Instruction], payer: &Pubkey) -> Result<u64> {
+    let mut simulated = vec![ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT)];
+    simulated.extend_from_slice(instructions);
+    let tx = VersionedTransaction::from(Transaction::new_unsigned(Message::new(&simulated, Some(payer))));
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..Default::default()
+    };
+    let result = rpc
+        .simulate_transaction_with_config(&tx, config)
+        .await
+        .map_err(|e| anyhow!("Simulation failed: {}", e))?
+        .value;
+    if let Some(err) = result.err {
+        return Err(anyhow!("Simulation failed: {}", err));
+    }
+    result.units_consumed.ok_or_else(|| anyhow!("Simulation reported no compute units"))
+}
+
+/// Recent prioritization fees paid for writes to the accounts `instructions` lock
+async fn recent_fees(rpc: &RpcClient, instructions: &[Instruction]) -> Result<Vec<u64>> {
+    let mut writable: Vec<Pubkey> = instructions
+        .iter()
+        .flat_map(|ix| ix.accounts.iter())
+        .filter(|meta| meta.is_writable)
+        .map(|meta| meta.pubkey)
+        .collect();
+    writable.sort();
+    writable.dedup();
+
+    let fees = rpc
+        .get_recent_prioritization_fees(&writable)
+        .await
+        .map_err(|e| anyhow!("Failed to get prioritization fees: {}", e))?;
+    Ok(fees.into_iter().map(|f| f.prioritization_fee).collect())
+}
+
+/// `instructions` with a compute unit limit and price prepended. Returned
+/// unchanged if they already carry ComputeBudget instructions or the
+/// estimate fails.
+pub async fn with_compute_budget(
+    rpc: &RpcClient,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    config: &PriorityFeeConfig,
+) -> Vec<Instruction> {
+    if has_compute_budget(instructions) {
+        return instructions.to_vec();
+    }
+
+    let units = match simulate_units(rpc, instructions, payer).await {
+        Ok(units) => units,
+        Err(e) => {
+            warn!("Compute budget estimate skipped: {}", e);
+            return instructions.to_vec();
+        }
+    };
+    let limit = config.unit_limit(units);
+    let price = match recent_fees(rpc, instructions).await {
+        Ok(fees) => config.unit_price(&fees, limit),
+        Err(e) => {
+            warn!("{}; sending without a priority fee", e);
+            0
+        }
+    };
+    debug!("Compute budget: {} units (simulated {}), {} micro-lamports/unit", limit, units, price);
+
+    let mut budgeted = vec![ComputeBudgetInstruction::set_compute_unit_limit(limit)];
+    if price > 0 {
+        budgeted.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    budgeted.extend_from_slice(instructions);
+    budgeted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_limit_adds_margin_and_caps() {
+        let config = PriorityFeeConfig::default();
+        assert_eq!(config.unit_limit(10_000), 11_000 + BUDGET_INSTRUCTION_UNITS);
+        assert_eq!(config.unit_limit(0), BUDGET_INSTRUCTION_UNITS);
+        assert_eq!(config.unit_limit(5_000_000), MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[test]
+    fn test_unit_price_percentile_and_cap() {
+        let config = PriorityFeeConfig::default();
+        let fees = [0, 0, 10, 50, 100, 200, 300, 400, 1_000];
+
+        // 75th percentile of nine samples is the seventh smallest
+        assert_eq!(config.unit_price(&fees, 50_000), 300);
+        assert_eq!(config.unit_price(&[], 50_000), 0);
+
+        // 100_000 lamports over 50_000 units caps the price at 2M micro-lamports
+        assert_eq!(config.unit_price(&[5_000_000], 50_000), 2_000_000);
+
+        let median = PriorityFeeConfig { percentile: 50, ..Default::default() };
+        assert_eq!(median.unit_price(&fees, 50_000), 100);
+    }
+
+    #[test]
+    fn test_existing_budget_is_kept() {
+        let ix = ComputeBudgetInstruction::set_compute_unit_limit(1);
+        assert!(has_compute_budget(&[ix]));
+        let other = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+        assert!(!has_compute_budget(&[other]));
+    }
+}
@@ -0,0 +1,334 @@
+//! ============================================================================
+//! Confirmation Tickets - Binding User Approval to the Executed Intent
+//! ============================================================================
+//! When PolicyGate requires confirmation, `execute_intent` hands out a
+//! one-time ticket instead of executing. `execute_confirmed` only runs an
+//! intent that presents a ticket which:
+//! - was signed by this process (HMAC-SHA256 with a per-launch random key)
+//! - covers exactly this intent (hash of the canonicalized action + params)
+//! - has not expired and has not been redeemed before
+//! - was issued for a confirmation at least as strict as the intent needs now
+//!
+//! The key lives only in memory, so tickets do not survive a restart.
+//! ============================================================================
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::types::{ConfirmationTicket, ConfirmationType, VoiceIntent};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long a ticket stays valid
+pub const DEFAULT_TICKET_TTL_SECS: i64 = 120;
+
+/// Why a ticket was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TicketError {
+    #[error("Confirmation ticket signature is invalid")]
+    BadSignature,
+
+    #[error("Confirmation ticket does not match the intent being executed")]
+    IntentMismatch,
+
+    #[error("Confirmation ticket has expired")]
+    Expired,
+
+    #[error("Confirmation ticket was already used or never issued")]
+    NotOutstanding,
+
+    #[error("Intent now requires {required:?} confirmation, ticket was issued for {issued:?}")]
+    Escalated { issued: ConfirmationType, required: ConfirmationType },
+}
+
+/// Issues and redeems one-time confirmation tickets
+pub struct ConfirmationTickets {
+    key: [u8; 32],
+    ttl_secs: i64,
+    /// Issued, unredeemed nonces: expiry and the confirmation they were issued for
+    outstanding: HashMap<String, (i64, ConfirmationType)>,
+}
+
+impl Default for ConfirmationTickets {
+    fn default() -> Self {
+        Self::new(DEFAULT_TICKET_TTL_SECS)
+    }
+}
+
+impl ConfirmationTickets {
+    /// Create an issuer with a fresh random signing key
+    pub fn new(ttl_secs: i64) -> Self {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        Self {
+            key,
+            ttl_secs,
+            outstanding: HashMap::new(),
+        }
+    }
+
+    /// Mint a ticket for `intent`, approved with `confirmation`
+    pub fn issue(
+        &mut self,
+        intent: &VoiceIntent,
+        confirmation: ConfirmationType,
+    ) -> ConfirmationTicket {
+        self.issue_at(intent, confirmation, chrono::Utc::now().timestamp())
+    }
+
+    fn issue_at(
+        &mut self,
+        intent: &VoiceIntent,
+        confirmation: ConfirmationType,
+        now: i64,
+    ) -> ConfirmationTicket {
+        self.prune(now);
+
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let nonce = hex::encode(nonce);
+        let intent_hash = intent_hash(intent);
+        let expires_at = now + self.ttl_secs;
+        let signature = self.sign(&nonce, &intent_hash, expires_at);
+
+        self.outstanding.insert(nonce.clone(), (expires_at, confirmation));
+        info!("Issued confirmation ticket for {} (expires {})", intent.action.name(), expires_at);
+
+        ConfirmationTicket { nonce, intent_hash, expires_at, signature }
+    }
+
+    /// Check `ticket` against `intent` and consume it. `required` is the
+    /// confirmation the intent needs now; a ticket issued for a weaker one is
+    /// rejected. A ticket is spent only when every check passes.
+    pub fn redeem(
+        &mut self,
+        intent: &VoiceIntent,
+        ticket: &ConfirmationTicket,
+        required: &ConfirmationType,
+    ) -> Result<(), TicketError> {
+        self.redeem_at(intent, ticket, required, chrono::Utc::now().timestamp())
+    }
+
+    fn redeem_at(
+        &mut self,
+        intent: &VoiceIntent,
+        ticket: &ConfirmationTicket,
+        required: &ConfirmationType,
+        now: i64,
+    ) -> Result<(), TicketError> {
+        let result = self.validate(intent, ticket, required, now);
+        match &result {
+            Ok(()) => {
+                self.outstanding.remove(&ticket.nonce);
+            }
+            Err(e) => warn!("Rejected confirmation ticket for {}: {}", intent.action.name(), e),
+        }
+        self.prune(now);
+        result
+    }
+
+    fn validate(
+        &self,
+        intent: &VoiceIntent,
+        ticket: &ConfirmationTicket,
+        required: &ConfirmationType,
+        now: i64,
+    ) -> Result<(), TicketError> {
+        let signature = hex::decode(&ticket.signature).map_err(|_| TicketError::BadSignature)?;
+        let mac = self.mac(&ticket.nonce, &ticket.intent_hash, ticket.expires_at);
+        mac.verify_slice(&signature).map_err(|_| TicketError::BadSignature)?;
+
+        if ticket.intent_hash != intent_hash(intent) {
+            return Err(TicketError::IntentMismatch);
+        }
+        if now > ticket.expires_at {
+            return Err(TicketError::Expired);
+        }
+        let Some((_, issued)) = self.outstanding.get(&ticket.nonce) else {
+            return Err(TicketError::NotOutstanding);
+        };
+        if required > issued {
+            return Err(TicketError::Escalated {
+                issued: issued.clone(),
+                required: required.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Number of issued, unredeemed, unexpired tickets
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.len()
+    }
+
+    fn prune(&mut self, now: i64) {
+        self.outstanding.retain(|_, (expires_at, _)| *expires_at >= now);
+    }
+
+    fn mac(&self, nonce: &str, intent_hash: &str, expires_at: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(nonce.as_bytes());
+        mac.update(b"|");
+        mac.update(intent_hash.as_bytes());
+        mac.update(b"|");
+        mac.update(expires_at.to_string().as_bytes());
+        mac
+    }
+
+    fn sign(&self, nonce: &str, intent_hash: &str, expires_at: i64) -> String {
+        hex::encode(self.mac(nonce, intent_hash, expires_at).finalize().into_bytes())
+    }
+}
+
+/// SHA-256 of the canonicalized intent (action + params, keys sorted).
+/// The raw transcript is not part of what gets executed, so it is excluded.
+pub fn intent_hash(intent: &VoiceIntent) -> String {
+    let mut canonical = String::new();
+    canonical.push_str(&intent.action.name());
+    canonical.push('|');
+    write_canonical(&intent.params, &mut canonical);
+    hex::encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// Serialize JSON with object keys sorted, independent of map ordering
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String((*key).clone()).to_string());
+                out.push(':');
+                write_canonical(&map[*key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::IntentAction;
+
+    fn intent(reward: f64) -> VoiceIntent {
+        VoiceIntent {
+            action: IntentAction::CreateTask,
+            params: serde_json::json!({ "description": "audit", "reward_sol": reward }),
+            raw_transcript: Some("create a task".into()),
+        }
+    }
+
+    #[test]
+    fn test_ticket_redeems_once() {
+        let mut tickets = ConfirmationTickets::default();
+        let ticket = tickets.issue(&intent(0.5), ConfirmationType::Verbal);
+        assert_eq!(tickets.outstanding(), 1);
+
+        assert_eq!(tickets.redeem(&intent(0.5), &ticket, &ConfirmationType::Verbal), Ok(()));
+        assert_eq!(
+            tickets.redeem(&intent(0.5), &ticket, &ConfirmationType::Verbal),
+            Err(TicketError::NotOutstanding)
+        );
+        assert_eq!(tickets.outstanding(), 0);
+    }
+
+    #[test]
+    fn test_mismatched_params_rejected() {
+        let mut tickets = ConfirmationTickets::default();
+        let ticket = tickets.issue(&intent(0.5), ConfirmationType::Verbal);
+
+        assert_eq!(
+            tickets.redeem(&intent(50.0), &ticket, &ConfirmationType::Verbal),
+            Err(TicketError::IntentMismatch)
+        );
+        // A failed attempt does not burn the ticket
+        assert_eq!(tickets.redeem(&intent(0.5), &ticket, &ConfirmationType::Verbal), Ok(()));
+    }
+
+    #[test]
+    fn test_escalated_confirmation_rejected() {
+        let mut tickets = ConfirmationTickets::default();
+        let ticket = tickets.issue(&intent(0.5), ConfirmationType::Verbal);
+
+        assert_eq!(
+            tickets.redeem(&intent(0.5), &ticket, &ConfirmationType::Hardware),
+            Err(TicketError::Escalated {
+                issued: ConfirmationType::Verbal,
+                required: ConfirmationType::Hardware,
+            })
+        );
+        // Stricter approval than needed is fine, and the rejection above did not burn it
+        assert_eq!(tickets.redeem(&intent(0.5), &ticket, &ConfirmationType::None), Ok(()));
+    }
+
+    #[test]
+    fn test_expired_ticket_rejected() {
+        let mut tickets = ConfirmationTickets::new(60);
+        let now = chrono::Utc::now().timestamp();
+        let ticket = tickets.issue_at(&intent(0.5), ConfirmationType::Verbal, now);
+        assert_eq!(
+            tickets.redeem_at(&intent(0.5), &ticket, &ConfirmationType::Verbal, now + 61),
+            Err(TicketError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_tampered_or_foreign_ticket_rejected() {
+        let mut tickets = ConfirmationTickets::default();
+        let mut ticket = tickets.issue(&intent(0.5), ConfirmationType::Verbal);
+
+        // Extending the expiry invalidates the signature
+        ticket.expires_at += 3600;
+        assert_eq!(
+            tickets.redeem(&intent(0.5), &ticket, &ConfirmationType::Verbal),
+            Err(TicketError::BadSignature)
+        );
+
+        // Tickets from another process (different key) are rejected
+        let mut other = ConfirmationTickets::default();
+        let foreign = other.issue(&intent(0.5), ConfirmationType::Verbal);
+        assert_eq!(
+            tickets.redeem(&intent(0.5), &foreign, &ConfirmationType::Verbal),
+            Err(TicketError::BadSignature)
+        );
+    }
+
+    #[test]
+    fn test_intent_hash_ignores_key_order_and_transcript() {
+        let a = VoiceIntent {
+            action: IntentAction::SwapTokens,
+            params: serde_json::json!({ "input_mint": "SOL", "output_mint": "USDC", "amount": 5 }),
+            raw_transcript: None,
+        };
+        let b = VoiceIntent {
+            action: IntentAction::SwapTokens,
+            params: serde_json::from_str(r#"{"amount":5,"output_mint":"USDC","input_mint":"SOL"}"#)
+                .unwrap(),
+            raw_transcript: Some("swap".into()),
+        };
+        assert_eq!(intent_hash(&a), intent_hash(&b));
+
+        let c = VoiceIntent { action: IntentAction::GetSwapQuote, ..a.clone() };
+        assert_ne!(intent_hash(&a), intent_hash(&c));
+    }
+}
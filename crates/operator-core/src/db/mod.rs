@@ -1,7 +1,9 @@
 // ============================================================================
 // OperatorDb — Embedded Database (redb)
 // ============================================================================
-// Persistent local storage for tasks, sessions, proofs, and config.
+// Persistent local storage for tasks, sessions, proofs, config, wallet
+// profiles, task descriptions, the spend ledger, the hash-chained audit log
+// and decoded wallet history.
 // Default path: ~/.agenc/operator.redb (override via AGENC_DB_PATH env var)
 // ============================================================================
 
@@ -12,12 +14,13 @@ pub use store_types::{
     EquippedItems, ItemRarity, StoreItem, StoreItemCategory, UserInventory, UserInventoryEntry,
 };
 pub use types::{
-    DbStats, DbTaskStatus, OperatorConfig, SessionState, TaskRecord, TranscriptEntry,
-    VerificationLog,
+    AuditEntry, AuditRecord, AuditVerification, DbStats, DbTaskStatus,
+    HistoryCursor, HistoryEvent, HistoryEventKind, HistoryGap, HistoryRecord, OperatorConfig, SessionState, SpendCategory, SpendRecord, TaskRecord, TranscriptEntry, VerificationLog,
+    WalletProfile, AUDIT_GENESIS_HASH,
 };
 
 use anyhow::{anyhow, Result};
-use redb::{Database, TableDefinition};
+use redb::{Database, ReadableTable, TableDefinition};
 use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
@@ -29,6 +32,18 @@ const CONFIG: TableDefinition<&str, &[u8]> = TableDefinition::new("config");
 const DEVICES: TableDefinition<&str, &[u8]> = TableDefinition::new("devices");
 const STORE_ITEMS: TableDefinition<&str, &[u8]> = TableDefinition::new("store_items");
 const USER_INVENTORY: TableDefinition<&str, &[u8]> = TableDefinition::new("user_inventory");
+const SPEND_LEDGER: TableDefinition<&str, &[u8]> = TableDefinition::new("spend_ledger");
+const AUDIT_LOG: TableDefinition<&str, &[u8]> = TableDefinition::new("audit_log");
+const WALLET_PROFILES: TableDefinition<&str, &[u8]> = TableDefinition::new("wallet_profiles");
+const TASK_DESCRIPTIONS: TableDefinition<&str, &[u8]> = TableDefinition::new("task_descriptions");
+const HISTORY: TableDefinition<&str, &[u8]> = TableDefinition::new("history");
+
+/// CONFIG key holding the name of the active wallet profile
+const ACTIVE_WALLET_KEY: &str = "config:active_wallet";
+
+/// CONFIG key holding the audit log's newest seq and hash, so removing the
+/// newest entries is detectable
+const AUDIT_HEAD_KEY: &str = "config:audit_head";
 
 /// Embedded database for the AgenC operator
 pub struct OperatorDb {
@@ -69,6 +84,11 @@ impl OperatorDb {
             let _ = write_txn.open_table(DEVICES).map_err(|e| anyhow!("Failed to create devices table: {}", e))?;
             let _ = write_txn.open_table(STORE_ITEMS).map_err(|e| anyhow!("Failed to create store_items table: {}", e))?;
             let _ = write_txn.open_table(USER_INVENTORY).map_err(|e| anyhow!("Failed to create user_inventory table: {}", e))?;
+            let _ = write_txn.open_table(SPEND_LEDGER).map_err(|e| anyhow!("Failed to create spend_ledger table: {}", e))?;
+            let _ = write_txn.open_table(AUDIT_LOG).map_err(|e| anyhow!("Failed to create audit_log table: {}", e))?;
+            let _ = write_txn.open_table(WALLET_PROFILES).map_err(|e| anyhow!("Failed to create wallet_profiles table: {}", e))?;
+            let _ = write_txn.open_table(TASK_DESCRIPTIONS).map_err(|e| anyhow!("Failed to create task_descriptions table: {}", e))?;
+            let _ = write_txn.open_table(HISTORY).map_err(|e| anyhow!("Failed to create history table: {}", e))?;
         }
         write_txn.commit().map_err(|e| anyhow!("Failed to commit init: {}", e))?;
 
@@ -300,14 +320,282 @@ impl OperatorDb {
 
         match table.get("config:operator").map_err(|e| anyhow!("Failed to get config: {}", e))? {
             Some(value) => {
-                let config: OperatorConfig = bincode::deserialize(value.value())
-                    .map_err(|e| anyhow!("Failed to deserialize config: {}", e))?;
+                // Configs written before network profiles lack the trailing fields
+                let config = match bincode::deserialize::<OperatorConfig>(value.value()) {
+                    Ok(config) => config,
+                    Err(_) => bincode::deserialize::<types::OperatorConfigV1>(value.value())
+                        .map(OperatorConfig::from)
+                        .map_err(|e| anyhow!("Failed to deserialize config: {}", e))?,
+                };
                 Ok(Some(config))
             }
             None => Ok(None),
         }
     }
 
+    // ========================================================================
+    // Wallet Profiles
+    // ========================================================================
+
+    pub fn store_wallet_profile(&self, profile: &WalletProfile) -> Result<()> {
+        let key = format!("wallet:{}", profile.name);
+        let value = bincode::serialize(profile)
+            .map_err(|e| anyhow!("Failed to serialize wallet profile: {}", e))?;
+
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        {
+            let mut table = write_txn.open_table(WALLET_PROFILES)
+                .map_err(|e| anyhow!("Failed to open wallet_profiles table: {}", e))?;
+            table.insert(key.as_str(), value.as_slice())
+                .map_err(|e| anyhow!("Failed to insert wallet profile: {}", e))?;
+        }
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit: {}", e))?;
+
+        debug!("Stored wallet profile: {}", profile.name);
+        Ok(())
+    }
+
+    pub fn get_wallet_profile(&self, name: &str) -> Result<Option<WalletProfile>> {
+        let key = format!("wallet:{}", name);
+
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(WALLET_PROFILES)
+            .map_err(|e| anyhow!("Failed to open wallet_profiles table: {}", e))?;
+
+        match table.get(key.as_str()).map_err(|e| anyhow!("Failed to get wallet profile: {}", e))? {
+            Some(value) => {
+                let profile: WalletProfile = bincode::deserialize(value.value())
+                    .map_err(|e| anyhow!("Failed to deserialize wallet profile: {}", e))?;
+                Ok(Some(profile))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List wallet profiles, sorted by name
+    pub fn list_wallet_profiles(&self) -> Result<Vec<WalletProfile>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(WALLET_PROFILES)
+            .map_err(|e| anyhow!("Failed to open wallet_profiles table: {}", e))?;
+
+        let mut results = Vec::new();
+        let iter = table.range::<&str>(..)
+            .map_err(|e| anyhow!("Failed to iterate wallet profiles: {}", e))?;
+        for entry in iter {
+            let (_key, value) = entry.map_err(|e| anyhow!("Failed to read entry: {}", e))?;
+            let profile: WalletProfile = bincode::deserialize(value.value())
+                .map_err(|e| anyhow!("Failed to deserialize wallet profile: {}", e))?;
+            results.push(profile);
+        }
+        Ok(results)
+    }
+
+    /// Delete a wallet profile. Clears the active wallet if it was this one.
+    pub fn delete_wallet_profile(&self, name: &str) -> Result<bool> {
+        let key = format!("wallet:{}", name);
+        let was_active = self.get_active_wallet()?.as_deref() == Some(name);
+
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        let removed;
+        {
+            let mut table = write_txn.open_table(WALLET_PROFILES)
+                .map_err(|e| anyhow!("Failed to open wallet_profiles table: {}", e))?;
+            removed = table.remove(key.as_str())
+                .map_err(|e| anyhow!("Failed to remove wallet profile: {}", e))?
+                .is_some();
+            if removed && was_active {
+                let mut config = write_txn.open_table(CONFIG)
+                    .map_err(|e| anyhow!("Failed to open config table: {}", e))?;
+                config.remove(ACTIVE_WALLET_KEY)
+                    .map_err(|e| anyhow!("Failed to clear active wallet: {}", e))?;
+            }
+        }
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit delete: {}", e))?;
+
+        if removed {
+            debug!("Deleted wallet profile: {}", name);
+        }
+        Ok(removed)
+    }
+
+    /// Remember which wallet profile is active across restarts
+    pub fn set_active_wallet(&self, name: &str) -> Result<()> {
+        if self.get_wallet_profile(name)?.is_none() {
+            return Err(anyhow!("Wallet profile not found: {}", name));
+        }
+
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        {
+            let mut table = write_txn.open_table(CONFIG)
+                .map_err(|e| anyhow!("Failed to open config table: {}", e))?;
+            table.insert(ACTIVE_WALLET_KEY, name.as_bytes())
+                .map_err(|e| anyhow!("Failed to set active wallet: {}", e))?;
+        }
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit: {}", e))?;
+
+        debug!("Active wallet set to: {}", name);
+        Ok(())
+    }
+
+    pub fn get_active_wallet(&self) -> Result<Option<String>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(CONFIG)
+            .map_err(|e| anyhow!("Failed to open config table: {}", e))?;
+
+        let active = table.get(ACTIVE_WALLET_KEY)
+            .map_err(|e| anyhow!("Failed to get active wallet: {}", e))?
+            .map(|value| String::from_utf8_lossy(value.value()).into_owned());
+        Ok(active)
+    }
+
+    // ========================================================================
+    // Task Descriptions (content-addressed by hex SHA-256)
+    // ========================================================================
+
+    /// Store raw description bytes under their hash. Callers verify the hash;
+    /// the bytes are kept verbatim so they re-hash identically.
+    pub fn store_description(&self, hash_hex: &str, bytes: &[u8]) -> Result<()> {
+        let key = format!("desc:{}", hash_hex);
+
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        {
+            let mut table = write_txn.open_table(TASK_DESCRIPTIONS)
+                .map_err(|e| anyhow!("Failed to open task_descriptions table: {}", e))?;
+            table.insert(key.as_str(), bytes)
+                .map_err(|e| anyhow!("Failed to insert task description: {}", e))?;
+        }
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit: {}", e))?;
+
+        debug!("Stored task description: {}", hash_hex);
+        Ok(())
+    }
+
+    pub fn get_description(&self, hash_hex: &str) -> Result<Option<Vec<u8>>> {
+        let key = format!("desc:{}", hash_hex);
+
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(TASK_DESCRIPTIONS)
+            .map_err(|e| anyhow!("Failed to open task_descriptions table: {}", e))?;
+
+        let bytes = table.get(key.as_str())
+            .map_err(|e| anyhow!("Failed to get task description: {}", e))?
+            .map(|value| value.value().to_vec());
+        Ok(bytes)
+    }
+
+    pub fn delete_description(&self, hash_hex: &str) -> Result<bool> {
+        let key = format!("desc:{}", hash_hex);
+
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        let removed;
+        {
+            let mut table = write_txn.open_table(TASK_DESCRIPTIONS)
+                .map_err(|e| anyhow!("Failed to open task_descriptions table: {}", e))?;
+            removed = table.remove(key.as_str())
+                .map_err(|e| anyhow!("Failed to remove task description: {}", e))?
+                .is_some();
+        }
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit delete: {}", e))?;
+        Ok(removed)
+    }
+
+    // ========================================================================
+    // Wallet History
+    // ========================================================================
+
+    /// Cache decoded transactions. Keys sort by network and wallet, then
+    /// slot, so a wallet's history reads back in chain order.
+    pub fn store_history(&self, records: &[HistoryRecord]) -> Result<()> {
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        {
+            let mut table = write_txn.open_table(HISTORY)
+                .map_err(|e| anyhow!("Failed to open history table: {}", e))?;
+            for record in records {
+                let key = format!(
+                    "history:{}:{}:{:020}:{}",
+                    record.network, record.wallet, record.slot, record.signature
+                );
+                let value = bincode::serialize(record)
+                    .map_err(|e| anyhow!("Failed to serialize history record: {}", e))?;
+                table.insert(key.as_str(), value.as_slice())
+                    .map_err(|e| anyhow!("Failed to insert history record: {}", e))?;
+            }
+        }
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit: {}", e))?;
+
+        debug!("Stored {} history records", records.len());
+        Ok(())
+    }
+
+    /// Cached history of `wallet` on `network`, newest first
+    pub fn list_history(&self, network: &str, wallet: &str) -> Result<Vec<HistoryRecord>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(HISTORY)
+            .map_err(|e| anyhow!("Failed to open history table: {}", e))?;
+
+        // ';' sorts right after ':', bounding the wallet's prefix
+        let prefix = format!("history:{}:{}", network, wallet);
+        let (start, end) = (format!("{}:", prefix), format!("{};", prefix));
+        let mut results = Vec::new();
+        let iter = table.range::<&str>(start.as_str()..end.as_str())
+            .map_err(|e| anyhow!("Failed to iterate history: {}", e))?;
+        for entry in iter.rev() {
+            let (_key, value) = entry.map_err(|e| anyhow!("Failed to read entry: {}", e))?;
+            let record: HistoryRecord = bincode::deserialize(value.value())
+                .map_err(|e| anyhow!("Failed to deserialize history record: {}", e))?;
+            results.push(record);
+        }
+        Ok(results)
+    }
+
+    /// Record how far `wallet`'s history on `network` has been synced
+    pub fn store_history_cursor(&self, network: &str, wallet: &str, cursor: &HistoryCursor) -> Result<()> {
+        let key = format!("history_cursor:{}:{}", network, wallet);
+        let value = bincode::serialize(cursor)
+            .map_err(|e| anyhow!("Failed to serialize history cursor: {}", e))?;
+
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        {
+            let mut table = write_txn.open_table(CONFIG)
+                .map_err(|e| anyhow!("Failed to open config table: {}", e))?;
+            table.insert(key.as_str(), value.as_slice())
+                .map_err(|e| anyhow!("Failed to insert history cursor: {}", e))?;
+        }
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit: {}", e))?;
+        Ok(())
+    }
+
+    /// Sync progress of `wallet`'s history on `network` (None = never synced
+    /// with a cursor)
+    pub fn get_history_cursor(&self, network: &str, wallet: &str) -> Result<Option<HistoryCursor>> {
+        let key = format!("history_cursor:{}:{}", network, wallet);
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(CONFIG)
+            .map_err(|e| anyhow!("Failed to open config table: {}", e))?;
+
+        match table.get(key.as_str()).map_err(|e| anyhow!("Failed to get history cursor: {}", e))? {
+            Some(value) => {
+                let cursor = bincode::deserialize(value.value())
+                    .map_err(|e| anyhow!("Failed to deserialize history cursor: {}", e))?;
+                Ok(Some(cursor))
+            }
+            None => Ok(None),
+        }
+    }
+
     // ========================================================================
     // Delete Operations
     // ========================================================================
@@ -423,6 +711,254 @@ impl OperatorDb {
         Ok(deleted)
     }
 
+    // ========================================================================
+    // Spend Ledger
+    // ========================================================================
+    // Keys are "spend:{timestamp:020}:{id}" so a range scan from a cutoff
+    // returns entries in time order.
+
+    pub fn record_spend(&self, record: &SpendRecord) -> Result<()> {
+        let key = format!("spend:{:020}:{}", record.timestamp.max(0), record.id);
+        let value = bincode::serialize(record)
+            .map_err(|e| anyhow!("Failed to serialize spend: {}", e))?;
+
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        {
+            let mut table = write_txn.open_table(SPEND_LEDGER)
+                .map_err(|e| anyhow!("Failed to open spend_ledger table: {}", e))?;
+            table.insert(key.as_str(), value.as_slice())
+                .map_err(|e| anyhow!("Failed to insert spend: {}", e))?;
+        }
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit: {}", e))?;
+
+        debug!("Recorded spend {} ({} lamports, {})", record.id, record.lamports, record.category.label());
+        Ok(())
+    }
+
+    /// List ledger entries at or after `since` (unix seconds), oldest first.
+    /// Pass None to list the whole ledger.
+    pub fn list_spends(&self, since: Option<i64>) -> Result<Vec<SpendRecord>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(SPEND_LEDGER)
+            .map_err(|e| anyhow!("Failed to open spend_ledger table: {}", e))?;
+
+        let start = format!("spend:{:020}", since.unwrap_or(0).max(0));
+        let mut results = Vec::new();
+        let iter = table.range::<&str>(start.as_str()..)
+            .map_err(|e| anyhow!("Failed to iterate spends: {}", e))?;
+        for entry in iter {
+            let (_key, value) = entry.map_err(|e| anyhow!("Failed to read entry: {}", e))?;
+            let record: SpendRecord = bincode::deserialize(value.value())
+                .map_err(|e| anyhow!("Failed to deserialize spend: {}", e))?;
+            results.push(record);
+        }
+        Ok(results)
+    }
+
+    /// Total lamports spent at or after `since`, optionally for one category.
+    pub fn total_spent_since(&self, since: i64, category: Option<SpendCategory>) -> Result<u64> {
+        Ok(self
+            .list_spends(Some(since))?
+            .iter()
+            .filter(|r| category.is_none_or(|c| r.category == c))
+            .map(|r| r.lamports)
+            .sum())
+    }
+
+    /// Prune ledger entries older than the given number of days.
+    /// Keep this at or above the longest policy window (30 days).
+    /// Returns the number of entries deleted.
+    pub fn prune_spends(&self, older_than_days: i64) -> Result<usize> {
+        let cutoff = chrono::Utc::now().timestamp() - (older_than_days * 86400);
+        let end = format!("spend:{:020}", cutoff.max(0));
+
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        let deleted = {
+            let mut table = write_txn.open_table(SPEND_LEDGER)
+                .map_err(|e| anyhow!("Failed to open spend_ledger table: {}", e))?;
+            let keys: Vec<String> = table.range::<&str>(..end.as_str())
+                .map_err(|e| anyhow!("Failed to iterate spends: {}", e))?
+                .filter_map(|entry| entry.ok().map(|(k, _)| k.value().to_string()))
+                .collect();
+            for key in &keys {
+                table.remove(key.as_str())
+                    .map_err(|e| anyhow!("Failed to delete spend: {}", e))?;
+            }
+            keys.len()
+        };
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit: {}", e))?;
+
+        if deleted > 0 {
+            info!("Pruned {} spend entries older than {} days", deleted, older_than_days);
+        }
+        Ok(deleted)
+    }
+
+    // ========================================================================
+    // Audit Log
+    // ========================================================================
+    // Append-only: keys are "audit:{seq:020}" and each entry carries the hash
+    // of its predecessor, so edits or deletions break the chain. The newest
+    // seq and hash are also kept in CONFIG, so truncation breaks it too.
+
+    /// Append a record to the audit log, linking it to the current head.
+    /// Returns the stored entry.
+    pub fn append_audit(&self, record: AuditRecord) -> Result<AuditEntry> {
+        let write_txn = self.db.begin_write()
+            .map_err(|e| anyhow!("Failed to begin write: {}", e))?;
+        let entry = {
+            let mut table = write_txn.open_table(AUDIT_LOG)
+                .map_err(|e| anyhow!("Failed to open audit_log table: {}", e))?;
+
+            let head = match table.last()
+                .map_err(|e| anyhow!("Failed to read audit head: {}", e))?
+            {
+                Some((_key, value)) => Some(
+                    bincode::deserialize::<AuditEntry>(value.value())
+                        .map_err(|e| anyhow!("Failed to deserialize audit entry: {}", e))?,
+                ),
+                None => None,
+            };
+
+            let mut entry = AuditEntry {
+                seq: head.as_ref().map_or(1, |h| h.seq + 1),
+                record,
+                prev_hash: head.map_or_else(|| AUDIT_GENESIS_HASH.to_string(), |h| h.hash),
+                hash: String::new(),
+            };
+            entry.hash = entry.compute_hash();
+
+            let key = format!("audit:{:020}", entry.seq);
+            let value = bincode::serialize(&entry)
+                .map_err(|e| anyhow!("Failed to serialize audit entry: {}", e))?;
+            table.insert(key.as_str(), value.as_slice())
+                .map_err(|e| anyhow!("Failed to insert audit entry: {}", e))?;
+
+            let head = bincode::serialize(&(entry.seq, &entry.hash))
+                .map_err(|e| anyhow!("Failed to serialize audit head: {}", e))?;
+            let mut config = write_txn.open_table(CONFIG)
+                .map_err(|e| anyhow!("Failed to open config table: {}", e))?;
+            config.insert(AUDIT_HEAD_KEY, head.as_slice())
+                .map_err(|e| anyhow!("Failed to insert audit head: {}", e))?;
+            entry
+        };
+        write_txn.commit().map_err(|e| anyhow!("Failed to commit: {}", e))?;
+
+        debug!("Audit #{} {} ({})", entry.seq, entry.record.action, entry.record.executor);
+        Ok(entry)
+    }
+
+    /// List audit entries, oldest first. With `limit`, only the newest
+    /// `limit` entries are returned.
+    pub fn list_audit(&self, limit: Option<usize>) -> Result<Vec<AuditEntry>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(AUDIT_LOG)
+            .map_err(|e| anyhow!("Failed to open audit_log table: {}", e))?;
+
+        let mut results = Vec::new();
+        let iter = table.range::<&str>(..)
+            .map_err(|e| anyhow!("Failed to iterate audit log: {}", e))?;
+        for entry in iter.rev() {
+            if limit.is_some_and(|l| results.len() >= l) {
+                break;
+            }
+            let (_key, value) = entry.map_err(|e| anyhow!("Failed to read entry: {}", e))?;
+            let audit: AuditEntry = bincode::deserialize(value.value())
+                .map_err(|e| anyhow!("Failed to deserialize audit entry: {}", e))?;
+            results.push(audit);
+        }
+        results.reverse();
+        Ok(results)
+    }
+
+    /// Audit entries finished at or after `since` (unix seconds), oldest
+    /// first. Entries are appended as they finish, so only the tail is read.
+    pub fn list_audit_since(&self, since: i64) -> Result<Vec<AuditEntry>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(AUDIT_LOG)
+            .map_err(|e| anyhow!("Failed to open audit_log table: {}", e))?;
+
+        let mut results = Vec::new();
+        let iter = table.range::<&str>(..)
+            .map_err(|e| anyhow!("Failed to iterate audit log: {}", e))?;
+        for entry in iter.rev() {
+            let (_key, value) = entry.map_err(|e| anyhow!("Failed to read entry: {}", e))?;
+            let audit: AuditEntry = bincode::deserialize(value.value())
+                .map_err(|e| anyhow!("Failed to deserialize audit entry: {}", e))?;
+            if audit.record.finished_at < since {
+                break;
+            }
+            results.push(audit);
+        }
+        results.reverse();
+        Ok(results)
+    }
+
+    /// Walk the whole chain, checking sequence numbers, links and hashes,
+    /// and that it still ends at the recorded head
+    pub fn verify_audit(&self) -> Result<AuditVerification> {
+        let entries = self.list_audit(None)?;
+        let head = self.audit_head()?;
+        let mut expected_prev = AUDIT_GENESIS_HASH.to_string();
+
+        for (i, entry) in entries.iter().enumerate() {
+            let problem = if entry.seq != i as u64 + 1 {
+                Some(format!("expected seq {}, found {}", i + 1, entry.seq))
+            } else if entry.prev_hash != expected_prev {
+                Some("previous-hash link broken (entry removed or reordered)".to_string())
+            } else if entry.hash != entry.compute_hash() {
+                Some("content hash mismatch (entry modified)".to_string())
+            } else {
+                None
+            };
+            if let Some(reason) = problem {
+                return Ok(AuditVerification {
+                    entries: entries.len(),
+                    first_invalid: Some((entry.seq, reason)),
+                });
+            }
+            expected_prev = entry.hash.clone();
+        }
+
+        // Logs written before the head was recorded have none to compare
+        if let Some((head_seq, head_hash)) = head {
+            let last_seq = entries.len() as u64;
+            if last_seq != head_seq || expected_prev != head_hash {
+                return Ok(AuditVerification {
+                    entries: entries.len(),
+                    first_invalid: Some((
+                        last_seq.min(head_seq) + 1,
+                        format!("log ends at entry {} but its head is entry {} (entries removed)", last_seq, head_seq),
+                    )),
+                });
+            }
+        }
+
+        Ok(AuditVerification { entries: entries.len(), first_invalid: None })
+    }
+
+    /// Seq and hash of the newest audit entry, as recorded by `append_audit`
+    fn audit_head(&self) -> Result<Option<(u64, String)>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| anyhow!("Failed to begin read: {}", e))?;
+        let table = read_txn.open_table(CONFIG)
+            .map_err(|e| anyhow!("Failed to open config table: {}", e))?;
+
+        match table.get(AUDIT_HEAD_KEY).map_err(|e| anyhow!("Failed to get audit head: {}", e))? {
+            Some(value) => {
+                let head = bincode::deserialize(value.value())
+                    .map_err(|e| anyhow!("Failed to deserialize audit head: {}", e))?;
+                Ok(Some(head))
+            }
+            None => Ok(None),
+        }
+    }
+
     // ========================================================================
     // Statistics
     // ========================================================================
@@ -439,6 +975,16 @@ impl OperatorDb {
         let proof_count = table.range::<&str>(..)
             .map_err(|e| anyhow!("Failed to iterate proofs: {}", e))?
             .count();
+        let table = read_txn.open_table(TASK_DESCRIPTIONS)
+            .map_err(|e| anyhow!("Failed to open task_descriptions table: {}", e))?;
+        let description_count = table.range::<&str>(..)
+            .map_err(|e| anyhow!("Failed to iterate task descriptions: {}", e))?
+            .count();
+        let table = read_txn.open_table(HISTORY)
+            .map_err(|e| anyhow!("Failed to open history table: {}", e))?;
+        let history_count = table.range::<&str>(..)
+            .map_err(|e| anyhow!("Failed to iterate history: {}", e))?
+            .count();
 
         let mut task_counts = std::collections::HashMap::new();
         for task in &all_tasks {
@@ -450,6 +996,11 @@ impl OperatorDb {
             task_counts,
             total_sessions: sessions.len(),
             total_proofs: proof_count,
+            total_spends: self.list_spends(None)?.len(),
+            total_audit_entries: self.list_audit(None)?.len(),
+            total_wallet_profiles: self.list_wallet_profiles()?.len(),
+            total_descriptions: description_count,
+            total_history: history_count,
         })
     }
 
@@ -883,3 +1434,299 @@ impl OperatorDb {
         Ok(())
     }
 }
+
+/// Scratch databases for unit tests across the crate
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::OperatorDb;
+    use operator_test_support::TempPath;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    /// A fresh database, removed when the returned path is dropped
+    pub(crate) fn temp_db() -> (OperatorDb, TempPath) {
+        let path = TempPath::new("agenc-db", "redb");
+        (OperatorDb::open(Some(path.as_str())).unwrap(), path)
+    }
+
+    /// `temp_db` behind the shared handle the ledgers and registries take
+    pub(crate) fn shared_temp_db() -> (Arc<RwLock<Option<OperatorDb>>>, TempPath) {
+        let (db, path) = temp_db();
+        (Arc::new(RwLock::new(Some(db))), path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::temp_db;
+    use super::*;
+    use crate::network::NetworkProfile;
+    use operator_test_support::TempPath;
+
+    #[test]
+    fn test_config_roundtrips_network_profiles() {
+        let (db, _path) = temp_db();
+        let mut staging = NetworkProfile::devnet();
+        staging.name = "staging".into();
+        let config = OperatorConfig {
+            wallet_pubkey: None,
+            rpc_url: staging.rpc_url().into(),
+            network: "devnet".into(),
+            capabilities: vec![],
+            model_preferences: None,
+            network_profile: Some("staging".into()),
+            network_profiles: vec![staging.clone()],
+        };
+        db.store_config(&config).unwrap();
+
+        let stored = db.get_config().unwrap().unwrap();
+        assert_eq!(stored.network_profile.as_deref(), Some("staging"));
+        assert_eq!(stored.network_profiles, vec![staging]);
+    }
+
+    #[test]
+    fn test_config_from_before_network_profiles_still_loads() {
+        let (db, _path) = temp_db();
+        // Same layout as the five-field config older builds wrote
+        let v1 = bincode::serialize(&(
+            Some("wallet".to_string()),
+            "https://api.devnet.solana.com".to_string(),
+            "devnet".to_string(),
+            vec!["compute".to_string()],
+            None::<serde_json::Value>,
+        ))
+        .unwrap();
+        let write_txn = db.db.begin_write().unwrap();
+        write_txn.open_table(CONFIG).unwrap().insert("config:operator", v1.as_slice()).unwrap();
+        write_txn.commit().unwrap();
+
+        let stored = db.get_config().unwrap().unwrap();
+        assert_eq!(stored.wallet_pubkey.as_deref(), Some("wallet"));
+        assert_eq!(stored.capabilities, vec!["compute".to_string()]);
+        assert_eq!(stored.network_profile, None);
+        assert!(stored.network_profiles.is_empty());
+    }
+
+    fn audit_record(action: &str) -> AuditRecord {
+        AuditRecord {
+            action: action.into(),
+            params_json: "{}".into(),
+            raw_transcript: None,
+            policy_allowed: true,
+            policy_reason: "Read-only action".into(),
+            confirmation_type: crate::types::ConfirmationType::None,
+            confirmed: false,
+            access_tier: None,
+            executor: "solana".into(),
+            success: true,
+            message: "ok".into(),
+            signature: None,
+            started_at: 1_700_000_000,
+            finished_at: 1_700_000_001,
+        }
+    }
+
+    /// Audit log with entries 1..=count
+    fn audit_db(count: usize) -> (OperatorDb, TempPath) {
+        let (db, path) = temp_db();
+        for i in 0..count {
+            db.append_audit(audit_record(&format!("action_{}", i + 1))).unwrap();
+        }
+        (db, path)
+    }
+
+    /// Overwrite the stored audit entry `seq` with `entry`
+    fn put_audit(db: &OperatorDb, seq: u64, entry: &AuditEntry) {
+        let write_txn = db.db.begin_write().unwrap();
+        let value = bincode::serialize(entry).unwrap();
+        let key = format!("audit:{:020}", seq);
+        write_txn.open_table(AUDIT_LOG).unwrap().insert(key.as_str(), value.as_slice()).unwrap();
+        write_txn.commit().unwrap();
+    }
+
+    fn remove_audit(db: &OperatorDb, seq: u64) {
+        let write_txn = db.db.begin_write().unwrap();
+        let key = format!("audit:{:020}", seq);
+        write_txn.open_table(AUDIT_LOG).unwrap().remove(key.as_str()).unwrap();
+        write_txn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_audit_append_links_entries() {
+        let (db, _path) = audit_db(3);
+        let entries = db.list_audit(None).unwrap();
+        assert_eq!(entries.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(entries[0].prev_hash, AUDIT_GENESIS_HASH);
+        assert_eq!(entries[1].prev_hash, entries[0].hash);
+        assert_eq!(entries[2].prev_hash, entries[1].hash);
+        assert_eq!(db.list_audit(Some(1)).unwrap()[0].record.action, "action_3");
+
+        let verification = db.verify_audit().unwrap();
+        assert!(verification.is_valid(), "{:?}", verification.first_invalid);
+        assert_eq!(verification.entries, 3);
+    }
+
+    #[test]
+    fn test_audit_since_reads_the_tail() {
+        let (db, _path) = temp_db();
+        for (i, finished_at) in [100, 200, 300, 400].into_iter().enumerate() {
+            let mut record = audit_record(&format!("action_{}", i + 1));
+            record.started_at = finished_at - 10;
+            record.finished_at = finished_at;
+            db.append_audit(record).unwrap();
+        }
+
+        let actions = |since| -> Vec<String> {
+            db.list_audit_since(since).unwrap().into_iter().map(|e| e.record.action).collect()
+        };
+        assert_eq!(actions(300), vec!["action_3", "action_4"]);
+        assert_eq!(actions(0).len(), 4);
+        assert!(actions(401).is_empty());
+    }
+
+    #[test]
+    fn test_audit_detects_modified_entry() {
+        let (db, _path) = audit_db(3);
+        let mut entry = db.list_audit(None).unwrap().remove(1);
+        entry.record.success = false;
+        put_audit(&db, 2, &entry);
+
+        let invalid = db.verify_audit().unwrap().first_invalid.unwrap();
+        assert_eq!(invalid.0, 2);
+        assert!(invalid.1.contains("modified"), "{}", invalid.1);
+
+        // Re-hashing the edit breaks the next entry's link instead
+        entry.hash = entry.compute_hash();
+        put_audit(&db, 2, &entry);
+        let invalid = db.verify_audit().unwrap().first_invalid.unwrap();
+        assert_eq!(invalid.0, 3);
+        assert!(invalid.1.contains("link broken"), "{}", invalid.1);
+    }
+
+    #[test]
+    fn test_audit_detects_reordered_entries() {
+        let (db, _path) = audit_db(3);
+        let entries = db.list_audit(None).unwrap();
+        put_audit(&db, 2, &entries[2]);
+        put_audit(&db, 3, &entries[1]);
+
+        let invalid = db.verify_audit().unwrap().first_invalid.unwrap();
+        assert_eq!(invalid.0, 3);
+        assert!(invalid.1.contains("expected seq 2"), "{}", invalid.1);
+    }
+
+    #[test]
+    fn test_audit_detects_removed_middle_entry() {
+        let (db, _path) = audit_db(3);
+        remove_audit(&db, 2);
+
+        let verification = db.verify_audit().unwrap();
+        assert_eq!(verification.entries, 2);
+        let invalid = verification.first_invalid.unwrap();
+        assert_eq!(invalid.0, 3);
+        assert!(invalid.1.contains("expected seq 2"), "{}", invalid.1);
+    }
+
+    #[test]
+    fn test_audit_detects_removed_newest_entries() {
+        let (db, _path) = audit_db(3);
+        remove_audit(&db, 3);
+        remove_audit(&db, 2);
+
+        let invalid = db.verify_audit().unwrap().first_invalid.unwrap();
+        assert_eq!(invalid.0, 2);
+        assert!(invalid.1.contains("head is entry 3"), "{}", invalid.1);
+
+        // Even an emptied log still knows it had entries
+        remove_audit(&db, 1);
+        assert_eq!(db.verify_audit().unwrap().first_invalid.unwrap().0, 1);
+    }
+
+    #[test]
+    fn test_history_is_listed_newest_first_per_wallet_and_network() {
+        let (db, _path) = temp_db();
+        let record = |network: &str, wallet: &str, slot: u64| HistoryRecord {
+            network: network.into(),
+            wallet: wallet.into(),
+            signature: format!("sig{}", slot),
+            slot,
+            block_time: Some(1_700_000_000 + slot as i64),
+            success: true,
+            fee_lamports: 5000,
+            events: vec![],
+        };
+        db.store_history(&[
+            record("devnet", "alice", 9),
+            record("devnet", "alice", 100),
+            record("devnet", "alice", 10),
+            record("devnet", "alicia", 50),
+            record("mainnet", "alice", 70),
+        ])
+        .unwrap();
+
+        let slots: Vec<u64> = db.list_history("devnet", "alice").unwrap().iter().map(|r| r.slot).collect();
+        assert_eq!(slots, vec![100, 10, 9]);
+        assert_eq!(db.list_history("mainnet", "alice").unwrap().len(), 1);
+        assert!(db.list_history("devnet", "bob").unwrap().is_empty());
+        assert_eq!(db.stats().unwrap().total_history, 5);
+    }
+
+    fn spend(id: &str, category: SpendCategory, lamports: u64, timestamp: i64) -> SpendRecord {
+        SpendRecord {
+            id: id.into(),
+            category,
+            lamports,
+            timestamp,
+            signature: Some(format!("sig_{}", id)),
+            wallet: None,
+        }
+    }
+
+    #[test]
+    fn test_spends_roundtrip_oldest_first() {
+        let (db, _path) = temp_db();
+        let mut trading = spend("b", SpendCategory::Swap, 2_000, 1_700_000_200);
+        trading.wallet = Some("trading".into());
+        db.record_spend(&trading).unwrap();
+        db.record_spend(&spend("a", SpendCategory::TaskCreation, 1_000, 1_700_000_100)).unwrap();
+        db.record_spend(&spend("c", SpendCategory::TaskCreation, 3_000, 1_700_000_300)).unwrap();
+
+        let spends = db.list_spends(None).unwrap();
+        let ids: Vec<&str> = spends.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(spends[1].category, SpendCategory::Swap);
+        assert_eq!(spends[1].lamports, 2_000);
+        assert_eq!(spends[1].signature.as_deref(), Some("sig_b"));
+        assert_eq!(spends[1].wallet.as_deref(), Some("trading"));
+    }
+
+    #[test]
+    fn test_spend_totals_follow_the_window() {
+        let (db, _path) = temp_db();
+        db.record_spend(&spend("old", SpendCategory::TaskCreation, 1_000, 1_700_000_000)).unwrap();
+        db.record_spend(&spend("swap", SpendCategory::Swap, 2_000, 1_700_000_500)).unwrap();
+        db.record_spend(&spend("new", SpendCategory::TaskCreation, 4_000, 1_700_001_000)).unwrap();
+
+        // `since` is inclusive
+        assert_eq!(db.list_spends(Some(1_700_000_500)).unwrap().len(), 2);
+        assert_eq!(db.total_spent_since(1_700_000_000, None).unwrap(), 7_000);
+        assert_eq!(db.total_spent_since(1_700_000_001, None).unwrap(), 6_000);
+        assert_eq!(db.total_spent_since(1_700_000_000, Some(SpendCategory::TaskCreation)).unwrap(), 5_000);
+        assert_eq!(db.total_spent_since(1_700_000_001, Some(SpendCategory::Swap)).unwrap(), 2_000);
+        assert_eq!(db.total_spent_since(1_700_001_001, None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_prune_spends_keeps_the_policy_window() {
+        let (db, _path) = temp_db();
+        let now = chrono::Utc::now().timestamp();
+        db.record_spend(&spend("stale", SpendCategory::Swap, 1_000, now - 40 * 86400)).unwrap();
+        db.record_spend(&spend("expired", SpendCategory::TaskCreation, 2_000, now - 31 * 86400)).unwrap();
+        db.record_spend(&spend("recent", SpendCategory::TaskCreation, 4_000, now - 86400)).unwrap();
+
+        assert_eq!(db.prune_spends(30).unwrap(), 2);
+        let ids: Vec<String> = db.list_spends(None).unwrap().into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec!["recent"]);
+        assert_eq!(db.prune_spends(30).unwrap(), 0);
+    }
+}
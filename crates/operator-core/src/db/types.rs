@@ -3,8 +3,12 @@
 // ============================================================================
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
+use crate::network::NetworkProfile;
+use crate::types::ConfirmationType;
+
 /// Status of a task in the operator pipeline
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DbTaskStatus {
@@ -13,6 +17,8 @@ pub enum DbTaskStatus {
     Completed,
     Disputed,
     Resolved,
+    /// Claimed, but the work or its completion failed; still claimed on chain
+    Failed,
 }
 
 /// A task record stored in the embedded database
@@ -71,6 +77,220 @@ pub struct OperatorConfig {
     pub network: String,
     pub capabilities: Vec<String>,
     pub model_preferences: Option<serde_json::Value>,
+    /// Name of the selected network profile
+    #[serde(default)]
+    pub network_profile: Option<String>,
+    /// Custom profiles and edited built-ins, by name
+    #[serde(default)]
+    pub network_profiles: Vec<NetworkProfile>,
+}
+
+/// Operator config as stored before network profiles existed
+#[derive(Deserialize)]
+pub(crate) struct OperatorConfigV1 {
+    pub wallet_pubkey: Option<String>,
+    pub rpc_url: String,
+    pub network: String,
+    pub capabilities: Vec<String>,
+    pub model_preferences: Option<serde_json::Value>,
+}
+
+impl From<OperatorConfigV1> for OperatorConfig {
+    fn from(v1: OperatorConfigV1) -> Self {
+        Self {
+            wallet_pubkey: v1.wallet_pubkey,
+            rpc_url: v1.rpc_url,
+            network: v1.network,
+            capabilities: v1.capabilities,
+            model_preferences: v1.model_preferences,
+            network_profile: None,
+            network_profiles: Vec::new(),
+        }
+    }
+}
+
+/// What a ledger entry was spent on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendCategory {
+    TaskCreation,
+    Swap,
+}
+
+impl SpendCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::TaskCreation => "task_creation",
+            Self::Swap => "swap",
+        }
+    }
+}
+
+/// A single spend recorded against the rolling policy limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendRecord {
+    pub id: String,
+    pub category: SpendCategory,
+    pub lamports: u64,
+    pub timestamp: i64,
+    pub signature: Option<String>,
+    /// Wallet profile that paid (None = the default/unnamed wallet)
+    #[serde(default)]
+    pub wallet: Option<String>,
+}
+
+/// What a decoded wallet history event did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryEventKind {
+    /// An AgenC program instruction
+    Agenc,
+    /// A Jupiter swap
+    Swap,
+    SolTransfer,
+    TokenTransfer,
+}
+
+impl HistoryEventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Agenc => "agenc",
+            Self::Swap => "swap",
+            Self::SolTransfer => "sol_transfer",
+            Self::TokenTransfer => "token_transfer",
+        }
+    }
+}
+
+/// One thing a wallet transaction did. Which fields are set depends on the kind.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEvent {
+    pub kind: HistoryEventKind,
+    /// AgenC instruction name ("claim_task")
+    pub instruction: Option<String>,
+    /// Task PDA an AgenC instruction acted on
+    pub task: Option<String>,
+    /// Sending and receiving wallets (token accounts when the owner is unknown)
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Mint of `amount`; None for SOL
+    pub mint: Option<String>,
+    /// Lamports or token base units: the amount moved, swapped in, or the
+    /// reward a task was created with
+    pub amount: Option<u64>,
+    /// What a swap paid out
+    pub output_mint: Option<String>,
+    pub output_amount: Option<u64>,
+}
+
+/// A wallet transaction and its decoded events, cached by signature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    /// Network profile the transaction was fetched from
+    pub network: String,
+    pub wallet: String,
+    pub signature: String,
+    pub slot: u64,
+    /// Unix seconds (None when the node did not record it)
+    pub block_time: Option<i64>,
+    pub success: bool,
+    pub fee_lamports: u64,
+    /// Empty when nothing in the transaction was recognised
+    pub events: Vec<HistoryEvent>,
+}
+
+/// How far a wallet's history has been synced, so a sync that hit its
+/// signature cap or failed part-way resumes instead of leaving a hole
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryCursor {
+    /// Newest signature fetched; the next sync fetches down to it
+    pub newest: Option<String>,
+    /// Ranges still to fetch, newest first
+    pub gaps: Vec<HistoryGap>,
+}
+
+/// Signatures older than `before` and newer than `until` that were not
+/// fetched yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryGap {
+    /// None = starting from the wallet's newest transaction
+    pub before: Option<String>,
+    /// None = back to the wallet's first transaction
+    pub until: Option<String>,
+}
+
+/// A named wallet profile ("hot", "treasury", ...) and where its keypair lives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalletProfile {
+    pub name: String,
+    /// Base58 wallet address
+    pub pubkey: String,
+    /// Path to the Solana CLI-style keypair JSON file
+    pub keypair_path: String,
+    pub created_at: i64,
+}
+
+/// Previous-hash value of the first audit entry
+pub const AUDIT_GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// What happened to one intent passed through execute_intent/execute_confirmed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Intent action (serde name, e.g. "create_task")
+    pub action: String,
+    /// Intent params as JSON text
+    pub params_json: String,
+    pub raw_transcript: Option<String>,
+    /// Policy decision
+    pub policy_allowed: bool,
+    pub policy_reason: String,
+    pub confirmation_type: ConfirmationType,
+    /// Whether this call redeemed a confirmation ticket (execute_confirmed)
+    pub confirmed: bool,
+    pub access_tier: Option<String>,
+    /// Component that handled the intent ("policy", "solana", "jupiter", ...)
+    pub executor: String,
+    pub success: bool,
+    pub message: String,
+    pub signature: Option<String>,
+    pub started_at: i64,
+    pub finished_at: i64,
+}
+
+/// Audit log entry, chained to its predecessor by hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position in the log, starting at 1
+    pub seq: u64,
+    pub record: AuditRecord,
+    /// `hash` of the previous entry (AUDIT_GENESIS_HASH for the first)
+    pub prev_hash: String,
+    /// Hex SHA-256 over seq, prev_hash and the record
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Hash this entry's content (everything except `hash` itself)
+    pub fn compute_hash(&self) -> String {
+        let body = bincode::serialize(&(self.seq, &self.prev_hash, &self.record))
+            .unwrap_or_default();
+        hex::encode(Sha256::digest(&body))
+    }
+}
+
+/// Result of walking the audit chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditVerification {
+    pub entries: usize,
+    /// Sequence number of the first entry that fails, and why
+    pub first_invalid: Option<(u64, String)>,
+}
+
+impl AuditVerification {
+    pub fn is_valid(&self) -> bool {
+        self.first_invalid.is_none()
+    }
 }
 
 /// Database statistics
@@ -80,4 +300,14 @@ pub struct DbStats {
     pub task_counts: HashMap<String, usize>,
     pub total_sessions: usize,
     pub total_proofs: usize,
+    #[serde(default)]
+    pub total_spends: usize,
+    #[serde(default)]
+    pub total_audit_entries: usize,
+    #[serde(default)]
+    pub total_wallet_profiles: usize,
+    #[serde(default)]
+    pub total_descriptions: usize,
+    #[serde(default)]
+    pub total_history: usize,
 }
@@ -4,24 +4,26 @@
 //! Handles token swaps on Solana using Jupiter's aggregator API:
 //! - Get quotes for token swaps
 //! - Execute swaps with slippage protection
+//! - Preview swaps by simulating the transaction Jupiter builds
 //! - Get token prices
 //! ============================================================================
 
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
+    pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
     transaction::VersionedTransaction,
 };
+use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 use tracing::{debug, info, warn};
 
-use crate::transaction_retry::{
-    classify_error, ErrorKind, SendResult, TransactionSender,
-};
-use crate::types::{SwapParams, SwapQuote, TokenPrice};
+use crate::rpc_pool::RpcPool;
+use crate::transaction_retry::{send_result_to_result, TransactionSender};
+use crate::simulation;
+use crate::types::{SwapParams, SwapQuote, TokenPrice, TransactionPreview};
 
 /// Jupiter Quote API endpoint
 const JUPITER_QUOTE_URL: &str = "https://quote-api.jup.ag/v6/quote";
@@ -42,25 +44,56 @@ pub mod tokens {
     pub const USDT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
     /// JUP
     pub const JUP: &str = "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN";
+
+    /// Resolve a token symbol to its mint address (SKR follows the active
+    /// network profile, and is unknown while the profile leaves it unset)
+    pub fn resolve(symbol: &str) -> Option<String> {
+        match symbol.to_uppercase().as_str() {
+            "SOL" | "WSOL" => Some(SOL.to_string()),
+            "USDC" => Some(USDC.to_string()),
+            "USDT" => Some(USDT.to_string()),
+            "JUP" | "JUPITER" => Some(JUP.to_string()),
+            "SKR" => Some(crate::agenc_program::skr_mint())
+                .filter(|mint| *mint != solana_sdk::pubkey::Pubkey::default())
+                .map(|mint| mint.to_string()),
+            _ => None,
+        }
+    }
 }
 
 /// Executor for Jupiter swap operations
 pub struct JupiterSwapExecutor {
     client: reqwest::Client,
-    rpc: RpcClient,
+    rpc: Arc<RpcPool>,
     keypair: Arc<RwLock<Option<Keypair>>>,
+    quote_url: String,
+    swap_url: String,
 }
 
 impl JupiterSwapExecutor {
     /// Create a new JupiterSwapExecutor
     pub fn new(rpc_url: &str) -> Self {
+        Self::with_pool(Arc::new(RpcPool::single(rpc_url)))
+    }
+
+    /// Create a JupiterSwapExecutor that uses a shared RPC pool
+    pub fn with_pool(rpc: Arc<RpcPool>) -> Self {
         Self {
             client: reqwest::Client::new(),
-            rpc: RpcClient::new(rpc_url.to_string()),
+            rpc,
             keypair: Arc::new(RwLock::new(None)),
+            quote_url: JUPITER_QUOTE_URL.to_string(),
+            swap_url: JUPITER_SWAP_URL.to_string(),
         }
     }
 
+    /// Send quote and swap requests to another Jupiter v6-compatible API
+    pub fn with_endpoints(mut self, quote_url: &str, swap_url: &str) -> Self {
+        self.quote_url = quote_url.to_string();
+        self.swap_url = swap_url.to_string();
+        self
+    }
+
     /// Set the keypair for signing transactions
     pub fn set_keypair(&mut self, keypair: Arc<RwLock<Option<Keypair>>>) {
         self.keypair = keypair;
@@ -88,7 +121,7 @@ impl JupiterSwapExecutor {
 
         let url = format!(
             "{}?inputMint={}&outputMint={}&amount={}&slippageBps={}",
-            JUPITER_QUOTE_URL,
+            self.quote_url,
             params.input_mint,
             params.output_mint,
             params.amount,
@@ -124,15 +157,10 @@ impl JupiterSwapExecutor {
         })
     }
 
-    /// Execute a swap transaction
+    /// Execute a swap transaction, signed with the keypair from `set_keypair`
     pub async fn execute_swap(&self, params: SwapParams) -> Result<String> {
-        info!(
-            "Executing swap: {} {} -> {}",
-            params.amount, params.input_mint, params.output_mint
-        );
-
         // Extract keypair info before any async operations (to avoid holding lock across await)
-        let (user_pubkey, keypair_bytes) = {
+        let keypair_bytes = {
             let keypair_guard = self
                 .keypair
                 .read()
@@ -140,11 +168,45 @@ impl JupiterSwapExecutor {
             let keypair = keypair_guard
                 .as_ref()
                 .ok_or_else(|| anyhow!("No keypair configured"))?;
-            (keypair.pubkey(), keypair.to_bytes())
+            keypair.to_bytes()
         }; // Guard dropped here
 
-        // First get a quote
-        let quote = self.get_quote(&params).await?;
+        // Sign the transaction using restored keypair
+        let keypair = Keypair::try_from(keypair_bytes.as_slice())
+            .map_err(|e| anyhow!("Failed to restore keypair: {}", e))?;
+        self.execute_swap_as(params, &keypair).await
+    }
+
+    /// Execute a swap transaction for and signed by `keypair`
+    pub async fn execute_swap_as(&self, params: SwapParams, keypair: &Keypair) -> Result<String> {
+        info!(
+            "Executing swap: {} {} -> {}",
+            params.amount, params.input_mint, params.output_mint
+        );
+
+        let mut tx = self.build_swap_transaction(&params, &keypair.pubkey()).await?;
+
+        // Re-signed with a fresh blockhash whenever the previous one expires
+        let send_rpc = self.rpc.blocking_client();
+        let sender = TransactionSender::new(&send_rpc);
+        let result = sender.send_and_confirm_with_rebuild(|blockhash| {
+            tx.message.set_recent_blockhash(blockhash);
+            tx.signatures[0] = keypair.sign_message(tx.message.serialize().as_slice());
+            Ok(tx.clone())
+        })?;
+
+        let signature = send_result_to_result(result)?;
+        info!("Swap completed: {}", signature);
+        Ok(signature.to_string())
+    }
+
+    /// Quote the swap and have Jupiter build its (unsigned) transaction
+    async fn build_swap_transaction(
+        &self,
+        params: &SwapParams,
+        user_pubkey: &Pubkey,
+    ) -> Result<VersionedTransaction> {
+        let quote = self.get_quote(params).await?;
 
         // Check price impact
         let price_impact: f64 = quote.price_impact_pct.parse().unwrap_or(0.0);
@@ -177,7 +239,7 @@ impl JupiterSwapExecutor {
         // Get swap transaction
         let response = self
             .client
-            .post(JUPITER_SWAP_URL)
+            .post(&self.swap_url)
             .json(&swap_request)
             .send()
             .await
@@ -194,77 +256,39 @@ impl JupiterSwapExecutor {
             .await
             .map_err(|e| anyhow!("Failed to parse swap response: {}", e))?;
 
-        // Decode and sign transaction
+        // Decode the unsigned transaction
         let tx_bytes = base64::Engine::decode(
             &base64::engine::general_purpose::STANDARD,
             &swap_response.swap_transaction,
         )
         .map_err(|e| anyhow!("Failed to decode transaction: {}", e))?;
 
-        let mut tx: VersionedTransaction = bincode::deserialize(&tx_bytes)
-            .map_err(|e| anyhow!("Failed to deserialize transaction: {}", e))?;
-
-        // Sign the transaction using restored keypair
-        let keypair = Keypair::try_from(keypair_bytes.as_slice())
-            .map_err(|e| anyhow!("Failed to restore keypair: {}", e))?;
+        bincode::deserialize(&tx_bytes)
+            .map_err(|e| anyhow!("Failed to deserialize transaction: {}", e))
+    }
 
-        // Retry loop for blockhash expiration
-        let max_blockhash_retries = 3;
-        let mut last_error = String::new();
-
-        for blockhash_attempt in 0..max_blockhash_retries {
-            // Get fresh blockhash for each attempt
-            let recent_blockhash = self
-                .rpc
-                .get_latest_blockhash()
-                .map_err(|e| anyhow!("Failed to get blockhash: {}", e))?;
-
-            tx.message.set_recent_blockhash(recent_blockhash);
-
-            let signature = keypair.sign_message(tx.message.serialize().as_slice());
-            tx.signatures[0] = signature;
-
-            // Send transaction with retry logic
-            let sender = TransactionSender::new(&self.rpc);
-            let result = sender.send_and_confirm_with_retry(&tx)?;
-
-            match result {
-                SendResult::Confirmed(sig) => {
-                    info!("Swap completed: {}", sig);
-                    return Ok(sig.to_string());
-                }
-                SendResult::PermanentFailure(msg) => {
-                    return Err(anyhow!("Transaction failed: {}", msg));
-                }
-                SendResult::RetryableFailure(msg) => {
-                    // Check if it's a blockhash issue
-                    let error_kind = classify_error(&msg);
-                    if error_kind == ErrorKind::BlockhashExpired && blockhash_attempt < max_blockhash_retries - 1 {
-                        warn!(
-                            "Blockhash expired (attempt {}), refreshing...",
-                            blockhash_attempt + 1
-                        );
-                        last_error = msg;
-                        continue;
-                    }
-                    return Err(anyhow!("Transaction failed after retries: {}", msg));
-                }
-                SendResult::ConfirmationTimeout(sig) => {
-                    // Transaction may still confirm - return signature with warning
-                    warn!(
-                        "Transaction confirmation timed out (may still confirm): {}",
-                        sig
-                    );
-                    return Ok(sig.to_string());
-                }
-            }
-        }
+    /// Build and simulate the swap for the `set_keypair` wallet without
+    /// signing or sending it
+    pub async fn preview_swap(&self, params: &SwapParams) -> Result<TransactionPreview> {
+        let user_pubkey = {
+            let keypair_guard = self
+                .keypair
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire keypair lock"))?;
+            keypair_guard
+                .as_ref()
+                .map(|k| k.pubkey())
+                .ok_or_else(|| anyhow!("No keypair configured"))?
+        };
+        self.preview_swap_for(params, &user_pubkey).await
+    }
 
-        Err(anyhow!(
-            "Transaction failed after {} blockhash refresh attempts: {}",
-            max_blockhash_retries,
-            last_error
-        ))
+    /// Build and simulate the swap `wallet` would make, without signing or
+    /// sending it
+    pub async fn preview_swap_for(&self, params: &SwapParams, wallet: &Pubkey) -> Result<TransactionPreview> {
+        let tx = self.build_swap_transaction(params, wallet).await?;
+        let tx = &tx;
+        self.rpc.read(|rpc| async move { simulation::simulate(&rpc, tx, None).await }).await
     }
 
     /// Get token price in USD
@@ -303,14 +327,20 @@ impl JupiterSwapExecutor {
     }
 
     /// Resolve token symbol to mint address
-    pub fn resolve_token(&self, symbol: &str) -> Option<&'static str> {
-        match symbol.to_uppercase().as_str() {
-            "SOL" | "WSOL" => Some(tokens::SOL),
-            "USDC" => Some(tokens::USDC),
-            "USDT" => Some(tokens::USDT),
-            "JUP" | "JUPITER" => Some(tokens::JUP),
-            _ => None,
-        }
+    pub fn resolve_token(&self, symbol: &str) -> Option<String> {
+        tokens::resolve(symbol)
+    }
+
+    /// Get a mint's decimals from its on-chain supply
+    pub async fn get_decimals(&self, token_mint: &str) -> Result<u8> {
+        let mint = Pubkey::from_str(token_mint)
+            .map_err(|e| anyhow!("Invalid mint {}: {}", token_mint, e))?;
+        let supply = self
+            .rpc
+            .read(|rpc| async move { Ok(rpc.get_token_supply(&mint).await?) })
+            .await
+            .map_err(|e| anyhow!("Failed to get token supply for {}: {}", token_mint, e))?;
+        Ok(supply.decimals)
     }
 }
 
@@ -372,8 +402,8 @@ mod tests {
     #[test]
     fn test_resolve_token() {
         let executor = JupiterSwapExecutor::new("https://api.mainnet-beta.solana.com");
-        assert_eq!(executor.resolve_token("SOL"), Some(tokens::SOL));
-        assert_eq!(executor.resolve_token("usdc"), Some(tokens::USDC));
+        assert_eq!(executor.resolve_token("SOL").as_deref(), Some(tokens::SOL));
+        assert_eq!(executor.resolve_token("usdc").as_deref(), Some(tokens::USDC));
         assert_eq!(executor.resolve_token("UNKNOWN"), None);
     }
 }
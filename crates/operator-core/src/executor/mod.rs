@@ -27,6 +27,6 @@ pub use email::EmailExecutor;
 pub use github::{GitHubExecutor, GistResult, IssueResult, CommentResult, WorkflowResult};
 pub use grok_code::GrokCodeExecutor;
 pub use image::ImageExecutor;
-pub use jupiter_swap::JupiterSwapExecutor;
+pub use jupiter_swap::{tokens, JupiterSwapExecutor};
 pub use twitter::TwitterExecutor;
 pub use video::VideoExecutor;
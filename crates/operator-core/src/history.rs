@@ -0,0 +1,808 @@
+//! ============================================================================
+//! Wallet History - Decoded Transactions for the Operator's Wallet
+//! ============================================================================
+//! Pages `getSignaturesForAddress` for a wallet, fetches each transaction and
+//! decodes its top-level instructions into typed events:
+//! - AgenC instructions, named by their IDL discriminator
+//! - Jupiter swaps, recognised by the v6 route instructions and valued from
+//!   the wallet's SOL and token balance changes
+//! - SOL and SPL token transfers
+//!
+//! Decoded transactions are cached in `OperatorDb` per network profile, so
+//! later syncs only fetch signatures newer than the newest cached one. A
+//! cursor records ranges a sync did not reach (signature cap, RPC failure),
+//! and later syncs backfill them.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, Utc};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    UiTransactionEncoding, UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+use spl_token::instruction::TokenInstruction;
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::agenc_program;
+use crate::db::{HistoryCursor, HistoryEvent, HistoryEventKind, HistoryGap, HistoryRecord, OperatorDb};
+use crate::executor::tokens;
+use crate::price_oracle::known_decimals;
+use crate::rpc_pool::RpcPool;
+use crate::simulation::token_label;
+
+/// Jupiter v6 swap instructions, matched by Anchor discriminator
+const JUPITER_ROUTES: [&str; 6] = [
+    "route",
+    "route_with_token_ledger",
+    "exact_out_route",
+    "shared_accounts_route",
+    "shared_accounts_route_with_token_ledger",
+    "shared_accounts_exact_out_route",
+];
+
+const SYSTEM_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("11111111111111111111111111111111");
+
+/// System program `Transfer` instruction index
+const SYSTEM_TRANSFER: u32 = 2;
+
+/// Signatures requested per `getSignaturesForAddress` page
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Most signatures fetched by one sync; later syncs backfill the rest
+pub const DEFAULT_MAX_SIGNATURES: usize = 1_000;
+
+/// Records returned unless the intent asks for another limit
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+// ============================================================================
+// Filters
+// ============================================================================
+
+/// Which cached records a history request wants
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryFilter {
+    /// Unix seconds, inclusive
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// Keep records with at least one event of these kinds (empty = any)
+    pub kinds: Vec<HistoryEventKind>,
+    pub limit: usize,
+}
+
+impl Default for HistoryFilter {
+    fn default() -> Self {
+        Self { since: None, until: None, kinds: Vec::new(), limit: DEFAULT_HISTORY_LIMIT }
+    }
+}
+
+impl HistoryFilter {
+    /// Read `since`/`until` (unix seconds or "YYYY-MM-DD"), `days`, `type`
+    /// or `types` ("agenc", "swap", "transfer", ...) and `limit` from intent
+    /// params
+    pub fn from_params(params: &serde_json::Value) -> Result<Self> {
+        let mut filter = Self {
+            since: parse_time(&params["since"], false)?,
+            until: parse_time(&params["until"], true)?,
+            ..Self::default()
+        };
+
+        if let Some(days) = params["days"].as_u64() {
+            filter.since = Some(Utc::now().timestamp() - days as i64 * SECONDS_PER_DAY);
+        }
+
+        let types: Vec<&str> = match (&params["types"], &params["type"]) {
+            (serde_json::Value::Array(types), _) => types.iter().filter_map(|t| t.as_str()).collect(),
+            (_, serde_json::Value::String(t)) => t.split(',').collect(),
+            _ => Vec::new(),
+        };
+        for label in types {
+            for kind in parse_kind(label)? {
+                if !filter.kinds.contains(kind) {
+                    filter.kinds.push(*kind);
+                }
+            }
+        }
+
+        if let Some(limit) = params["limit"].as_u64() {
+            filter.limit = limit as usize;
+        }
+        Ok(filter)
+    }
+
+    /// Whether `record` falls in the date range and has a wanted event.
+    /// Records without a block time only match when no range is set.
+    pub fn matches(&self, record: &HistoryRecord) -> bool {
+        if self.since.is_some() || self.until.is_some() {
+            let Some(time) = record.block_time else { return false };
+            if self.since.is_some_and(|since| time < since) || self.until.is_some_and(|until| time > until) {
+                return false;
+            }
+        }
+        self.kinds.is_empty() || record.events.iter().any(|e| self.kinds.contains(&e.kind))
+    }
+}
+
+/// Unix seconds or a date; an `until` date covers the whole day
+fn parse_time(value: &serde_json::Value, end_of_day: bool) -> Result<Option<i64>> {
+    if let Some(seconds) = value.as_i64() {
+        return Ok(Some(seconds));
+    }
+    let Some(text) = value.as_str().map(str::trim).filter(|t| !t.is_empty()) else {
+        return Ok(None);
+    };
+    let date = NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map_err(|_| anyhow!("Invalid date '{}' — use YYYY-MM-DD", text))?;
+    let start = date.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc().timestamp();
+    Ok(Some(if end_of_day { start + SECONDS_PER_DAY - 1 } else { start }))
+}
+
+fn parse_kind(label: &str) -> Result<&'static [HistoryEventKind]> {
+    Ok(match label.trim().to_lowercase().as_str() {
+        "agenc" | "task" | "tasks" => &[HistoryEventKind::Agenc],
+        "swap" | "swaps" => &[HistoryEventKind::Swap],
+        "transfer" | "transfers" => &[HistoryEventKind::SolTransfer, HistoryEventKind::TokenTransfer],
+        "sol_transfer" => &[HistoryEventKind::SolTransfer],
+        "token_transfer" => &[HistoryEventKind::TokenTransfer],
+        other => {
+            return Err(anyhow!(
+                "Unknown history type '{}' — use agenc, swap, transfer, sol_transfer or token_transfer",
+                other
+            ))
+        }
+    })
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+/// Decode a fetched transaction into its events for `wallet`. Fails only if
+/// the transaction itself can't be decoded; unrecognised instructions are
+/// skipped.
+pub fn decode_transaction(
+    wallet: &Pubkey,
+    fetched: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<HistoryRecord> {
+    let tx = fetched
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("Transaction encoding not supported"))?;
+    let signature = tx.signatures.first().ok_or_else(|| anyhow!("Transaction has no signature"))?;
+    let meta = fetched.transaction.meta.as_ref();
+
+    Ok(HistoryRecord {
+        network: crate::network::active().profile.name.clone(),
+        wallet: wallet.to_string(),
+        signature: signature.to_string(),
+        slot: fetched.slot,
+        block_time: fetched.block_time,
+        success: meta.is_none_or(|m| m.err.is_none()),
+        fee_lamports: meta.map_or(0, |m| m.fee),
+        events: decode_events(wallet, &tx, meta),
+    })
+}
+
+fn decode_events(wallet: &Pubkey, tx: &VersionedTransaction, meta: Option<&UiTransactionStatusMeta>) -> Vec<HistoryEvent> {
+    let keys = account_keys(tx, meta);
+    let program_id = agenc_program::program_id();
+    let instructions = tx.message.instructions();
+
+    // Wrapping and unwrapping SOL around a swap is part of the swap
+    let is_swap = instructions.iter().any(|ix| is_jupiter_route(&ix.data));
+
+    let mut events = Vec::new();
+    for ix in instructions {
+        let Some(program) = keys.get(ix.program_id_index as usize) else { continue };
+        let accounts: Vec<Pubkey> = ix.accounts.iter().filter_map(|&i| keys.get(i as usize).copied()).collect();
+
+        if *program == program_id {
+            events.push(agenc_event(&ix.data, &accounts));
+        } else if is_swap {
+            continue;
+        } else if *program == SYSTEM_PROGRAM_ID {
+            events.extend(sol_transfer(&ix.data, &accounts));
+        } else if *program == spl_token::ID {
+            events.extend(token_transfer(&ix.data, &accounts, &ix.accounts, meta));
+        }
+    }
+    if is_swap {
+        events.extend(meta.and_then(|m| swap_event(wallet, &keys, m)));
+    }
+    events
+}
+
+fn is_jupiter_route(data: &[u8]) -> bool {
+    data.len() >= 8
+        && JUPITER_ROUTES.iter().any(|name| data[..8] == agenc_program::instruction_discriminator(name))
+}
+
+/// Static keys followed by the writable and readonly lookup-table addresses
+fn account_keys(tx: &VersionedTransaction, meta: Option<&UiTransactionStatusMeta>) -> Vec<Pubkey> {
+    let mut keys = tx.message.static_account_keys().to_vec();
+    if let Some(OptionSerializer::Some(loaded)) = meta.map(|m| &m.loaded_addresses) {
+        keys.extend(
+            loaded.writable.iter().chain(&loaded.readonly).filter_map(|k| Pubkey::from_str(k).ok()),
+        );
+    }
+    keys
+}
+
+fn empty_event(kind: HistoryEventKind) -> HistoryEvent {
+    HistoryEvent {
+        kind,
+        instruction: None,
+        task: None,
+        from: None,
+        to: None,
+        mint: None,
+        amount: None,
+        output_mint: None,
+        output_amount: None,
+    }
+}
+
+/// An AgenC instruction; unknown discriminators keep the event unnamed
+fn agenc_event(data: &[u8], accounts: &[Pubkey]) -> HistoryEvent {
+    let mut event = empty_event(HistoryEventKind::Agenc);
+    match agenc_program::decode_instruction(data, accounts) {
+        Ok(decoded) => {
+            event.task = decoded.account("task").map(|t| t.to_string());
+            if decoded.name == "create_task" {
+                event.amount = decoded.args.u64("reward_amount").ok();
+            }
+            event.instruction = Some(decoded.name);
+        }
+        Err(e) => debug!("Undecodable AgenC instruction: {}", e),
+    }
+    event
+}
+
+fn sol_transfer(data: &[u8], accounts: &[Pubkey]) -> Option<HistoryEvent> {
+    let index = u32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    if index != SYSTEM_TRANSFER {
+        return None;
+    }
+    let lamports = u64::from_le_bytes(data.get(4..12)?.try_into().ok()?);
+    Some(HistoryEvent {
+        from: Some(accounts.first()?.to_string()),
+        to: Some(accounts.get(1)?.to_string()),
+        amount: Some(lamports),
+        ..empty_event(HistoryEventKind::SolTransfer)
+    })
+}
+
+/// `Transfer` or `TransferChecked`, with token accounts replaced by their
+/// owners where the transaction's token balances name them
+fn token_transfer(
+    data: &[u8],
+    accounts: &[Pubkey],
+    indexes: &[u8],
+    meta: Option<&UiTransactionStatusMeta>,
+) -> Option<HistoryEvent> {
+    let (amount, source, destination) = match TokenInstruction::unpack(data).ok()? {
+        TokenInstruction::Transfer { amount } => (amount, 0, 1),
+        TokenInstruction::TransferChecked { amount, .. } => (amount, 0, 2),
+        _ => return None,
+    };
+    let balance = |position: usize| {
+        let index = *indexes.get(position)?;
+        token_balances(meta).find(|b| b.account_index == index)
+    };
+    let party = |position: usize| {
+        let owner = balance(position).and_then(|b| Option::<&String>::from(b.owner.as_ref()).cloned());
+        owner.or_else(|| accounts.get(position).map(|a| a.to_string()))
+    };
+
+    let mint = balance(source)
+        .or_else(|| balance(destination))
+        .map(|b| b.mint.clone())
+        .or_else(|| (destination == 2).then(|| accounts.get(1).map(|m| m.to_string())).flatten());
+    Some(HistoryEvent {
+        from: party(source),
+        to: party(destination),
+        mint,
+        amount: Some(amount),
+        ..empty_event(HistoryEventKind::TokenTransfer)
+    })
+}
+
+fn token_balances(meta: Option<&UiTransactionStatusMeta>) -> impl Iterator<Item = &UiTransactionTokenBalance> {
+    meta.into_iter().flat_map(|m| {
+        let pre: Option<&Vec<_>> = m.pre_token_balances.as_ref().into();
+        let post: Option<&Vec<_>> = m.post_token_balances.as_ref().into();
+        pre.into_iter().flatten().chain(post.into_iter().flatten())
+    })
+}
+
+/// What the wallet paid and received across a swap. A token leg is preferred
+/// over SOL, which otherwise also moves by rent for created token accounts.
+fn swap_event(wallet: &Pubkey, keys: &[Pubkey], meta: &UiTransactionStatusMeta) -> Option<HistoryEvent> {
+    let wallet_str = wallet.to_string();
+    let mut deltas: BTreeMap<String, i128> = BTreeMap::new();
+
+    if let Some(index) = keys.iter().position(|k| k == wallet) {
+        let pre = *meta.pre_balances.get(index)? as i128;
+        let post = *meta.post_balances.get(index)? as i128;
+        let fee = if index == 0 { meta.fee as i128 } else { 0 };
+        *deltas.entry(tokens::SOL.to_string()).or_default() += post - pre + fee;
+    }
+    for (balances, sign) in [(&meta.pre_token_balances, -1), (&meta.post_token_balances, 1)] {
+        let balances: Option<&Vec<UiTransactionTokenBalance>> = balances.as_ref().into();
+        for balance in balances.into_iter().flatten() {
+            let owner: Option<&String> = balance.owner.as_ref().into();
+            if owner != Some(&wallet_str) {
+                continue;
+            }
+            let amount = balance.ui_token_amount.amount.parse::<i128>().unwrap_or(0);
+            *deltas.entry(balance.mint.clone()).or_default() += sign * amount;
+        }
+    }
+
+    let leg = |spent: bool| {
+        let moved = |delta: i128| if spent { delta < 0 } else { delta > 0 };
+        deltas
+            .iter()
+            .filter(|(mint, delta)| mint.as_str() != tokens::SOL && moved(**delta))
+            .chain(deltas.get_key_value(tokens::SOL).filter(|(_, delta)| moved(**delta)))
+            .map(|(mint, delta)| (mint.clone(), delta.unsigned_abs() as u64))
+            .next()
+    };
+    let (input_mint, input_amount) = leg(true)?;
+    let (output_mint, output_amount) = leg(false)?;
+    Some(HistoryEvent {
+        from: Some(wallet_str),
+        mint: Some(input_mint),
+        amount: Some(input_amount),
+        output_mint: Some(output_mint),
+        output_amount: Some(output_amount),
+        ..empty_event(HistoryEventKind::Swap)
+    })
+}
+
+// ============================================================================
+// Descriptions
+// ============================================================================
+
+impl HistoryEvent {
+    /// One-line summary ("Swapped 1.5 SOL for 210 USDC")
+    pub fn describe(&self) -> String {
+        let amount = |amount: Option<u64>, mint: Option<&String>| match (amount, mint) {
+            (Some(a), None) => format!("{} SOL", format_units(a, 9)),
+            (Some(a), Some(m)) => match known_decimals(m) {
+                Some(decimals) if m == tokens::SOL => format!("{} SOL", format_units(a, decimals)),
+                Some(decimals) => format!("{} {}", format_units(a, decimals), token_label(m)),
+                None => format!("{} units of {}", a, token_label(m)),
+            },
+            (None, _) => "an unknown amount".into(),
+        };
+        let party = |p: &Option<String>| p.as_deref().map(short).unwrap_or_else(|| "?".into());
+
+        match self.kind {
+            HistoryEventKind::Agenc => {
+                let mut text = format!("AgenC {}", self.instruction.as_deref().unwrap_or("instruction"));
+                if let Some(task) = &self.task {
+                    text.push_str(&format!(" on task {}", short(task)));
+                }
+                if self.amount.is_some() {
+                    text.push_str(&format!(" ({} reward)", amount(self.amount, None)));
+                }
+                text
+            }
+            HistoryEventKind::Swap => format!(
+                "Swapped {} for {}",
+                amount(self.amount, self.mint.as_ref()),
+                amount(self.output_amount, self.output_mint.as_ref())
+            ),
+            HistoryEventKind::SolTransfer | HistoryEventKind::TokenTransfer => format!(
+                "Sent {} from {} to {}",
+                amount(self.amount, self.mint.as_ref()),
+                party(&self.from),
+                party(&self.to)
+            ),
+        }
+    }
+}
+
+fn format_units(amount: u64, decimals: u8) -> String {
+    let value = amount as f64 / 10f64.powi(decimals as i32);
+    let text = format!("{:.4}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn short(address: &str) -> String {
+    if address.len() <= 10 {
+        return address.to_string();
+    }
+    format!("{}…{}", &address[..4], &address[address.len() - 4..])
+}
+
+// ============================================================================
+// History Service
+// ============================================================================
+
+/// Fetches, decodes and caches a wallet's transactions
+pub struct WalletHistory {
+    rpc: Arc<RpcPool>,
+    db: Arc<RwLock<Option<OperatorDb>>>,
+    page_size: usize,
+    max_signatures: usize,
+}
+
+impl WalletHistory {
+    /// History cached in the shared operator database (None = fetched each time)
+    pub fn new(rpc: Arc<RpcPool>, db: Arc<RwLock<Option<OperatorDb>>>) -> Self {
+        Self { rpc, db, page_size: DEFAULT_PAGE_SIZE, max_signatures: DEFAULT_MAX_SIGNATURES }
+    }
+
+    /// Signatures per page, and the most fetched by one sync
+    pub fn with_page_size(mut self, page_size: usize, max_signatures: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self.max_signatures = max_signatures;
+        self
+    }
+
+    /// Fetch and cache transactions newer than the newest cached one, then
+    /// backfill older history left over by earlier syncs, up to
+    /// `max_signatures` in all. Records and the cursor are stored after every
+    /// page, so a capped or failed sync resumes where it stopped.
+    /// Returns the new records, newest first.
+    pub async fn sync(&self, wallet: &Pubkey) -> Result<Vec<HistoryRecord>> {
+        let network = crate::network::active().profile.name.clone();
+        let wallet_key = wallet.to_string();
+        let (mut cursor, cached) = {
+            let guard = self.db.read().await;
+            match guard.as_ref() {
+                Some(db) => {
+                    let records = db.list_history(&network, &wallet_key)?;
+                    let cursor = match db.get_history_cursor(&network, &wallet_key)? {
+                        Some(cursor) => cursor,
+                        // Cached before cursors: anything older than the cache may be missing
+                        None => HistoryCursor {
+                            newest: records.first().map(|r| r.signature.clone()),
+                            gaps: records
+                                .last()
+                                .map(|r| HistoryGap { before: Some(r.signature.clone()), until: None })
+                                .into_iter()
+                                .collect(),
+                        },
+                    };
+                    (cursor, records.into_iter().map(|r| r.signature).collect::<HashSet<_>>())
+                }
+                None => (HistoryCursor::default(), HashSet::new()),
+            }
+        };
+
+        // Transactions since the last sync come first
+        cursor.gaps.insert(0, HistoryGap { before: None, until: cursor.newest.clone() });
+
+        let mut records = Vec::new();
+        let mut fetched = 0;
+        while fetched < self.max_signatures {
+            let Some(gap) = cursor.gaps.first_mut() else { break };
+            let limit = self.page_size.min(self.max_signatures - fetched);
+            let page = self.fetch_signatures(wallet, gap, limit).await?;
+            fetched += page.len();
+
+            let from_head = gap.before.is_none();
+            if page.len() < limit {
+                cursor.gaps.remove(0);
+            } else {
+                gap.before = page.last().map(|s| s.to_string());
+            }
+            if from_head {
+                if let Some(newest) = page.first() {
+                    cursor.newest = Some(newest.to_string());
+                }
+            }
+
+            let page_records = self.fetch_transactions(wallet, &page, &cached).await?;
+            if let Some(db) = self.db.read().await.as_ref() {
+                db.store_history(&page_records)?;
+                db.store_history_cursor(&network, &wallet_key, &cursor)?;
+            }
+            records.extend(page_records);
+        }
+
+        if !records.is_empty() {
+            info!("Fetched {} new transactions for {}", records.len(), wallet);
+        }
+        if !cursor.gaps.is_empty() {
+            debug!("History of {} still has {} unfetched ranges", wallet, cursor.gaps.len());
+        }
+        Ok(records)
+    }
+
+    /// One page of signatures inside `gap`, newest first
+    async fn fetch_signatures(&self, wallet: &Pubkey, gap: &HistoryGap, limit: usize) -> Result<Vec<Signature>> {
+        let signature = |s: &Option<String>| -> Result<Option<Signature>> {
+            s.as_deref().map(Signature::from_str).transpose().map_err(|e| anyhow!("Bad history cursor: {}", e))
+        };
+        let (before, until) = (signature(&gap.before)?, signature(&gap.until)?);
+        let page = self
+            .rpc
+            .read(|rpc| async move {
+                let config = GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(limit),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                };
+                Ok(rpc.get_signatures_for_address_with_config(wallet, config).await?)
+            })
+            .await?;
+
+        debug!("History page of {} signatures for {}", page.len(), wallet);
+        Ok(page.iter().filter_map(|s| Signature::from_str(&s.signature).ok()).collect())
+    }
+
+    /// Fetch and decode the transactions in `signatures` that are not cached
+    async fn fetch_transactions(
+        &self,
+        wallet: &Pubkey,
+        signatures: &[Signature],
+        cached: &HashSet<String>,
+    ) -> Result<Vec<HistoryRecord>> {
+        let mut records = Vec::new();
+        for signature in signatures.iter().filter(|s| !cached.contains(&s.to_string())) {
+            let fetched = self
+                .rpc
+                .read(|rpc| async move {
+                    let config = RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Base64),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    };
+                    Ok(rpc.get_transaction_with_config(signature, config).await?)
+                })
+                .await?;
+            match decode_transaction(wallet, &fetched) {
+                Ok(record) => records.push(record),
+                Err(e) => warn!("Skipping transaction {}: {}", signature, e),
+            }
+        }
+        Ok(records)
+    }
+
+    /// Sync, then return the wallet's history matching `filter`, newest first
+    pub async fn history(&self, wallet: &Pubkey, filter: &HistoryFilter) -> Result<Vec<HistoryRecord>> {
+        let fresh = self.sync(wallet).await?;
+        let network = crate::network::active().profile.name.clone();
+        let records = match self.db.read().await.as_ref() {
+            Some(db) => db.list_history(&network, &wallet.to_string())?,
+            None => fresh,
+        };
+        Ok(records.into_iter().filter(|r| filter.matches(r)).take(filter.limit).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{Message, VersionedMessage},
+        signature::Keypair,
+        signer::Signer,
+        transaction::Transaction,
+    };
+    use solana_transaction_status_client_types::{
+        EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionBinaryEncoding,
+    };
+
+    fn system_transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+        let mut data = SYSTEM_TRANSFER.to_le_bytes().to_vec();
+        data.extend_from_slice(&lamports.to_le_bytes());
+        Instruction::new_with_bytes(
+            SYSTEM_PROGRAM_ID,
+            &data,
+            vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        )
+    }
+
+    fn token_balance(account_index: u8, mint: &str, owner: &Pubkey, amount: u64) -> serde_json::Value {
+        serde_json::json!({
+            "accountIndex": account_index,
+            "mint": mint,
+            "uiTokenAmount": {
+                "uiAmount": null,
+                "decimals": 6,
+                "amount": amount.to_string(),
+                "uiAmountString": amount.to_string(),
+            },
+            "owner": owner.to_string(),
+            "programId": spl_token::ID.to_string(),
+        })
+    }
+
+    /// A fetched transaction as the RPC returns it
+    fn fetched(
+        payer: &Keypair,
+        instructions: &[Instruction],
+        meta: serde_json::Value,
+    ) -> (Vec<Pubkey>, EncodedConfirmedTransactionWithStatusMeta) {
+        let message = Message::new(instructions, Some(&payer.pubkey()));
+        let keys = message.account_keys.clone();
+        let tx = Transaction::new(&[payer], message, Hash::new_unique());
+        let tx = VersionedTransaction::from(tx);
+        assert!(matches!(tx.message, VersionedMessage::Legacy(_)));
+
+        use base64::Engine;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&tx).unwrap());
+        let mut meta_json = serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": vec![0; keys.len()],
+            "postBalances": vec![0; keys.len()],
+            "preTokenBalances": [],
+            "postTokenBalances": [],
+        });
+        for (key, value) in meta.as_object().unwrap() {
+            meta_json[key] = value.clone();
+        }
+        let fetched = EncodedConfirmedTransactionWithStatusMeta {
+            slot: 42,
+            transaction: EncodedTransactionWithStatusMeta {
+                transaction: EncodedTransaction::Binary(encoded, TransactionBinaryEncoding::Base64),
+                meta: Some(serde_json::from_value(meta_json).unwrap()),
+                version: None,
+            },
+            block_time: Some(1_700_000_000),
+        };
+        (keys, fetched)
+    }
+
+    #[test]
+    fn test_decode_agenc_and_transfers() {
+        let wallet = Keypair::new();
+        let friend = Pubkey::new_unique();
+        let (task_pda, _) = agenc_program::derive_task_pda(7);
+        let claim = agenc_program::build_claim_task_ix(&task_pda, &wallet.pubkey(), [1; 32]).unwrap();
+        let (_, fetched) = fetched(
+            &wallet,
+            &[system_transfer(&wallet.pubkey(), &friend, 1_500_000_000), claim],
+            serde_json::json!({}),
+        );
+
+        let record = decode_transaction(&wallet.pubkey(), &fetched).unwrap();
+        assert!(record.success);
+        assert_eq!((record.slot, record.fee_lamports, record.block_time), (42, 5000, Some(1_700_000_000)));
+        assert_eq!(record.events.len(), 2);
+
+        let transfer = &record.events[0];
+        assert_eq!(transfer.kind, HistoryEventKind::SolTransfer);
+        assert_eq!(transfer.to, Some(friend.to_string()));
+        assert_eq!(transfer.amount, Some(1_500_000_000));
+        assert!(transfer.describe().starts_with("Sent 1.5 SOL"));
+
+        let agenc = &record.events[1];
+        assert_eq!(agenc.kind, HistoryEventKind::Agenc);
+        assert_eq!(agenc.instruction.as_deref(), Some("claim_task"));
+        assert_eq!(agenc.task, Some(task_pda.to_string()));
+    }
+
+    #[test]
+    fn test_decode_token_transfer_names_owners() {
+        let wallet = Keypair::new();
+        let friend = Pubkey::new_unique();
+        let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = spl_token::instruction::transfer(
+            &spl_token::ID,
+            &source,
+            &destination,
+            &wallet.pubkey(),
+            &[],
+            250_000,
+        )
+        .unwrap();
+        let (keys, _) = fetched(&wallet, std::slice::from_ref(&ix), serde_json::json!({}));
+        let index = |key: &Pubkey| keys.iter().position(|k| k == key).unwrap() as u8;
+        let meta = serde_json::json!({
+            "preTokenBalances": [
+                token_balance(index(&source), tokens::USDC, &wallet.pubkey(), 1_000_000),
+                token_balance(index(&destination), tokens::USDC, &friend, 0),
+            ],
+        });
+        let (_, fetched) = fetched(&wallet, &[ix], meta);
+
+        let record = decode_transaction(&wallet.pubkey(), &fetched).unwrap();
+        let event = &record.events[0];
+        assert_eq!(event.kind, HistoryEventKind::TokenTransfer);
+        assert_eq!(event.from, Some(wallet.pubkey().to_string()));
+        assert_eq!(event.to, Some(friend.to_string()));
+        assert_eq!(event.mint.as_deref(), Some(tokens::USDC));
+        assert!(event.describe().starts_with("Sent 0.25 USDC"));
+    }
+
+    #[test]
+    fn test_decode_jupiter_swap_from_balance_changes() {
+        let wallet = Keypair::new();
+        let wsol_account = Pubkey::new_unique();
+        let usdc_account = Pubkey::new_unique();
+        let wrap = system_transfer(&wallet.pubkey(), &wsol_account, 1_000_000_000);
+        let route = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &agenc_program::instruction_discriminator("shared_accounts_route"),
+            vec![AccountMeta::new(wallet.pubkey(), true), AccountMeta::new(usdc_account, false)],
+        );
+        let (keys, _) = fetched(&wallet, &[wrap.clone(), route.clone()], serde_json::json!({}));
+        let usdc_index = keys.iter().position(|k| *k == usdc_account).unwrap() as u8;
+        let mut pre_balances = vec![0u64; keys.len()];
+        let mut post_balances = vec![0u64; keys.len()];
+        pre_balances[0] = 3_000_000_000;
+        post_balances[0] = 2_000_000_000 - 5000;
+        let meta = serde_json::json!({
+            "preBalances": pre_balances,
+            "postBalances": post_balances,
+            "preTokenBalances": [token_balance(usdc_index, tokens::USDC, &wallet.pubkey(), 0)],
+            "postTokenBalances": [token_balance(usdc_index, tokens::USDC, &wallet.pubkey(), 150_000_000)],
+        });
+        let (_, fetched) = fetched(&wallet, &[wrap, route], meta);
+
+        let record = decode_transaction(&wallet.pubkey(), &fetched).unwrap();
+        // The wrapping transfer belongs to the swap
+        assert_eq!(record.events.len(), 1);
+        let swap = &record.events[0];
+        assert_eq!(swap.kind, HistoryEventKind::Swap);
+        assert_eq!((swap.mint.as_deref(), swap.amount), (Some(tokens::SOL), Some(1_000_000_000)));
+        assert_eq!((swap.output_mint.as_deref(), swap.output_amount), (Some(tokens::USDC), Some(150_000_000)));
+        assert_eq!(swap.describe(), "Swapped 1 SOL for 150 USDC");
+    }
+
+    #[test]
+    fn test_filter_from_params() {
+        let filter = HistoryFilter::from_params(&serde_json::json!({
+            "since": "2023-11-14",
+            "until": "2023-11-14",
+            "type": "swap,transfer",
+            "limit": 5,
+        }))
+        .unwrap();
+        assert_eq!(filter.since, Some(1_699_920_000));
+        assert_eq!(filter.until, Some(1_699_920_000 + SECONDS_PER_DAY - 1));
+        assert_eq!(
+            filter.kinds,
+            vec![HistoryEventKind::Swap, HistoryEventKind::SolTransfer, HistoryEventKind::TokenTransfer]
+        );
+        assert_eq!(filter.limit, 5);
+
+        let mut record = HistoryRecord {
+            network: "devnet".into(),
+            wallet: String::new(),
+            signature: String::new(),
+            slot: 1,
+            block_time: Some(1_700_000_000),
+            success: true,
+            fee_lamports: 5000,
+            events: vec![empty_event(HistoryEventKind::SolTransfer)],
+        };
+        assert!(filter.matches(&record));
+        record.events = vec![empty_event(HistoryEventKind::Agenc)];
+        assert!(!filter.matches(&record));
+        record.events = vec![empty_event(HistoryEventKind::Swap)];
+        record.block_time = Some(1_699_920_000 + SECONDS_PER_DAY);
+        assert!(!filter.matches(&record));
+        record.block_time = None;
+        assert!(!filter.matches(&record));
+        assert!(HistoryFilter::default().matches(&record));
+
+        assert!(HistoryFilter::from_params(&serde_json::json!({ "type": "staking" })).is_err());
+        assert!(HistoryFilter::from_params(&serde_json::json!({ "since": "yesterday" })).is_err());
+    }
+}
@@ -0,0 +1,388 @@
+//! ============================================================================
+//! Keystore - Encrypted Wallet Keypairs
+//! ============================================================================
+//! Replaces the plaintext Solana CLI keypair JSON (a bare 64-byte array) with
+//! a passphrase-protected file:
+//! - Key derivation: Argon2id (memory-hard) with a random 16-byte salt
+//! - Encryption: XChaCha20-Poly1305 with a random 24-byte nonce; the public
+//!   key is bound as associated data so it cannot be swapped
+//! - Secret bytes and derived keys are zeroized as soon as they are dropped
+//!
+//! Existing CLI keypairs can be imported with `Keystore::import_cli_file`.
+//! The public key is stored in the clear so a locked keystore can still be
+//! listed and shared.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::{Keypair, Signer};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+pub use zeroize::Zeroizing;
+
+/// Current keystore file format version
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// Minimum passphrase length accepted when creating or re-encrypting
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER_ALGORITHM: &str = "xchacha20poly1305";
+
+/// Argon2id cost parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub m_cost_kib: u32,
+    /// Number of passes
+    pub t_cost: u32,
+    /// Degree of parallelism
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    /// 64 MiB, 3 passes, 1 lane (OWASP-recommended Argon2id minimum is 19 MiB, 2 passes)
+    fn default() -> Self {
+        Self { m_cost_kib: 64 * 1024, t_cost: 3, p_cost: 1 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfConfig {
+    pub algorithm: String,
+    #[serde(flatten)]
+    pub params: KdfParams,
+    /// Hex-encoded salt
+    pub salt: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherConfig {
+    pub algorithm: String,
+    /// Hex-encoded nonce
+    pub nonce: String,
+}
+
+/// Encrypted keypair as stored on disk (JSON)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    /// Base58 wallet address
+    pub pubkey: String,
+    pub kdf: KdfConfig,
+    pub cipher: CipherConfig,
+    /// Hex-encoded ciphertext of the 64-byte keypair, including the auth tag
+    pub ciphertext: String,
+    pub created_at: i64,
+}
+
+impl Keystore {
+    /// Generate a new keypair and encrypt it under `passphrase`
+    pub fn create(passphrase: &str) -> Result<Self> {
+        Self::encrypt(&Keypair::new(), passphrase)
+    }
+
+    /// Encrypt `keypair` under `passphrase` with the default KDF cost
+    pub fn encrypt(keypair: &Keypair, passphrase: &str) -> Result<Self> {
+        Self::encrypt_with(keypair, passphrase, KdfParams::default())
+    }
+
+    /// Encrypt with explicit KDF cost parameters
+    pub fn encrypt_with(keypair: &Keypair, passphrase: &str, params: KdfParams) -> Result<Self> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(anyhow!(
+                "Passphrase must be at least {} characters",
+                MIN_PASSPHRASE_LEN
+            ));
+        }
+
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let mut nonce = [0u8; 24];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let pubkey = keypair.pubkey().to_string();
+        let key = derive_key(passphrase, &salt, params)?;
+        let secret = Zeroizing::new(keypair.to_bytes());
+
+        let cipher = XChaCha20Poly1305::new_from_slice(key.as_slice())
+            .map_err(|e| anyhow!("Failed to init cipher: {}", e))?;
+        let ciphertext = cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload { msg: secret.as_slice(), aad: pubkey.as_bytes() },
+            )
+            .map_err(|e| anyhow!("Failed to encrypt keypair: {}", e))?;
+
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            pubkey,
+            kdf: KdfConfig {
+                algorithm: KDF_ALGORITHM.into(),
+                params,
+                salt: hex::encode(salt),
+            },
+            cipher: CipherConfig {
+                algorithm: CIPHER_ALGORITHM.into(),
+                nonce: hex::encode(nonce),
+            },
+            ciphertext: hex::encode(ciphertext),
+            created_at: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// Decrypt the keypair. Fails on a wrong passphrase or a modified file.
+    pub fn decrypt(&self, passphrase: &str) -> Result<Keypair> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(anyhow!("Unsupported keystore version {}", self.version));
+        }
+        if self.kdf.algorithm != KDF_ALGORITHM || self.cipher.algorithm != CIPHER_ALGORITHM {
+            return Err(anyhow!(
+                "Unsupported keystore algorithms: {} / {}",
+                self.kdf.algorithm,
+                self.cipher.algorithm
+            ));
+        }
+
+        let salt = hex::decode(&self.kdf.salt).map_err(|e| anyhow!("Invalid keystore salt: {}", e))?;
+        let nonce = hex::decode(&self.cipher.nonce).map_err(|e| anyhow!("Invalid keystore nonce: {}", e))?;
+        if nonce.len() != 24 {
+            return Err(anyhow!("Invalid keystore nonce length: {}", nonce.len()));
+        }
+        let ciphertext = hex::decode(&self.ciphertext)
+            .map_err(|e| anyhow!("Invalid keystore ciphertext: {}", e))?;
+
+        let key = derive_key(passphrase, &salt, self.kdf.params)?;
+        let cipher = XChaCha20Poly1305::new_from_slice(key.as_slice())
+            .map_err(|e| anyhow!("Failed to init cipher: {}", e))?;
+        let secret = Zeroizing::new(
+            cipher
+                .decrypt(
+                    XNonce::from_slice(&nonce),
+                    Payload { msg: &ciphertext, aad: self.pubkey.as_bytes() },
+                )
+                .map_err(|_| anyhow!("Wrong passphrase or corrupted keystore"))?,
+        );
+
+        let keypair = Keypair::try_from(secret.as_slice())
+            .map_err(|e| anyhow!("Invalid keypair bytes: {}", e))?;
+        if keypair.pubkey().to_string() != self.pubkey {
+            return Err(anyhow!("Keystore public key does not match its secret key"));
+        }
+        Ok(keypair)
+    }
+
+    /// Re-encrypt under a new passphrase (fresh salt and nonce)
+    pub fn change_passphrase(&self, old: &str, new: &str) -> Result<Self> {
+        let keypair = self.decrypt(old)?;
+        let mut updated = Self::encrypt_with(&keypair, new, self.kdf.params)?;
+        updated.created_at = self.created_at;
+        Ok(updated)
+    }
+
+    /// Encrypt a plaintext Solana CLI keypair file
+    pub fn import_cli_file(path: &Path, passphrase: &str) -> Result<Self> {
+        let keypair = read_cli_keypair(path)?;
+        Self::encrypt(&keypair, passphrase)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map_err(|e| anyhow!("Failed to parse keystore: {}", e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read keystore {}: {}", path.display(), e))?;
+        Self::from_json(&contents)
+    }
+
+    /// Write the keystore atomically, readable only by the owner on Unix
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| anyhow!("Failed to serialize keystore: {}", e))?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| anyhow!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let tmp = path.with_extension("tmp");
+        write_private(&tmp, json.as_bytes())?;
+        std::fs::rename(&tmp, path)
+            .map_err(|e| anyhow!("Failed to write keystore {}: {}", path.display(), e))?;
+
+        info!("Saved keystore for {} to {}", self.pubkey, path.display());
+        Ok(())
+    }
+}
+
+/// Default keystore path: AGENC_KEYSTORE_PATH or ~/.agenc/wallet.keystore.json
+pub fn default_keystore_path() -> Result<PathBuf> {
+    if let Ok(p) = std::env::var("AGENC_KEYSTORE_PATH") {
+        return Ok(PathBuf::from(p));
+    }
+    let home = dirs::home_dir().ok_or_else(|| anyhow!("Cannot determine home directory"))?;
+    Ok(home.join(".agenc").join("wallet.keystore.json"))
+}
+
+/// Whether `path` holds a keystore (JSON object) rather than a CLI keypair
+/// (JSON byte array)
+pub fn is_keystore_file(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .map(|s| s.trim_start().starts_with('{'))
+        .unwrap_or(false)
+}
+
+/// Read a plaintext Solana CLI-style keypair file (JSON array of 64 bytes)
+pub fn read_cli_keypair(path: &Path) -> Result<Keypair> {
+    let keypair_data = Zeroizing::new(
+        std::fs::read_to_string(path).map_err(|e| anyhow!("Failed to read keypair: {}", e))?,
+    );
+    if keypair_data.trim_start().starts_with('{') {
+        return Err(anyhow!(
+            "{} is an encrypted keystore — unlock it with its passphrase",
+            path.display()
+        ));
+    }
+
+    let bytes: Zeroizing<Vec<u8>> = Zeroizing::new(
+        serde_json::from_str(&keypair_data).map_err(|e| anyhow!("Failed to parse keypair: {}", e))?,
+    );
+
+    Keypair::try_from(bytes.as_slice()).map_err(|e| anyhow!("Invalid keypair bytes: {}", e))
+}
+
+/// Read a keypair from either format. Keystores need `passphrase`.
+pub fn read_keypair_file(path: &Path, passphrase: Option<&str>) -> Result<Keypair> {
+    if !is_keystore_file(path) {
+        return read_cli_keypair(path);
+    }
+    let passphrase = passphrase
+        .ok_or_else(|| anyhow!("{} is an encrypted keystore — passphrase required", path.display()))?;
+    Keystore::load(path)?.decrypt(passphrase)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<Zeroizing<[u8; 32]>> {
+    let params = Params::new(params.m_cost_kib, params.t_cost, params.p_cost, Some(32))
+        .map_err(|e| anyhow!("Invalid KDF parameters: {}", e))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| anyhow!("Failed to create {}: {}", path.display(), e))?;
+    file.write_all(contents)
+        .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    std::fs::write(path, contents).map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap KDF settings so tests stay fast
+    const TEST_PARAMS: KdfParams = KdfParams { m_cost_kib: 64, t_cost: 1, p_cost: 1 };
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("agenc-keystore-{}-{}", name, uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let keypair = Keypair::new();
+        let keystore = Keystore::encrypt_with(&keypair, "correct horse", TEST_PARAMS).unwrap();
+        assert_eq!(keystore.pubkey, keypair.pubkey().to_string());
+        // The secret is not stored in the clear
+        assert!(!keystore.ciphertext.contains(&hex::encode(keypair.secret_bytes())));
+
+        let decrypted = keystore.decrypt("correct horse").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+    }
+
+    #[test]
+    fn test_wrong_passphrase_and_tampering_rejected() {
+        let keypair = Keypair::new();
+        let keystore = Keystore::encrypt_with(&keypair, "correct horse", TEST_PARAMS).unwrap();
+        assert!(keystore.decrypt("wrong horse").is_err());
+
+        // The public key is authenticated, so it cannot be swapped
+        let mut swapped = keystore.clone();
+        swapped.pubkey = Keypair::new().pubkey().to_string();
+        assert!(swapped.decrypt("correct horse").is_err());
+
+        let mut flipped = keystore.clone();
+        let mut bytes = hex::decode(&flipped.ciphertext).unwrap();
+        bytes[0] ^= 1;
+        flipped.ciphertext = hex::encode(bytes);
+        assert!(flipped.decrypt("correct horse").is_err());
+    }
+
+    #[test]
+    fn test_short_passphrase_rejected() {
+        assert!(Keystore::encrypt_with(&Keypair::new(), "short", TEST_PARAMS).is_err());
+    }
+
+    #[test]
+    fn test_change_passphrase() {
+        let keypair = Keypair::new();
+        let keystore = Keystore::encrypt_with(&keypair, "old passphrase", TEST_PARAMS).unwrap();
+        let updated = keystore.change_passphrase("old passphrase", "new passphrase").unwrap();
+
+        assert_ne!(updated.kdf.salt, keystore.kdf.salt);
+        assert!(updated.decrypt("old passphrase").is_err());
+        assert_eq!(updated.decrypt("new passphrase").unwrap().pubkey(), keypair.pubkey());
+        assert!(keystore.change_passphrase("not it at all", "new passphrase").is_err());
+    }
+
+    #[test]
+    fn test_import_cli_file_and_detection() {
+        let keypair = Keypair::new();
+        let cli_path = temp_path("cli.json");
+        std::fs::write(&cli_path, serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap()).unwrap();
+        assert!(!is_keystore_file(&cli_path));
+
+        let keystore_path = temp_path("ks.json");
+        let keystore = Keystore::encrypt_with(&read_cli_keypair(&cli_path).unwrap(), "correct horse", TEST_PARAMS)
+            .unwrap();
+        keystore.save(&keystore_path).unwrap();
+        assert!(is_keystore_file(&keystore_path));
+        assert_eq!(Keystore::load(&keystore_path).unwrap(), keystore);
+
+        // Plaintext readers refuse keystores; the combined reader needs a passphrase
+        assert!(read_cli_keypair(&keystore_path).is_err());
+        assert!(read_keypair_file(&keystore_path, None).is_err());
+        let unlocked = read_keypair_file(&keystore_path, Some("correct horse")).unwrap();
+        assert_eq!(unlocked.pubkey(), keypair.pubkey());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&keystore_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let _ = std::fs::remove_file(cli_path);
+        let _ = std::fs::remove_file(keystore_path);
+    }
+}
@@ -3,23 +3,50 @@
 //! ============================================================================
 //! This crate handles all backend logic for the AgenC Operator:
 //! - Solana transaction building/signing via solana-sdk
+//! - A shared pool of weighted RPC endpoints with health scoring and failover
+//! - Network profiles bundling endpoints, program ID and mints per cluster
+//! - Simulated compute budgets, priority fees and re-signing on blockhash expiry
+//! - Encrypted wallet keystores (Argon2id + XChaCha20-Poly1305)
+//! - Off-chain task descriptions verified against their on-chain hash
+//! - Named capability bits for task requirements
+//! - Wallet history decoded into AgenC, swap and transfer events, cached locally
+//! - Merkle proof manifests for completed work, verifiable against chain
+//! - Filtered, paged task queries over a cached, incrementally refreshed snapshot
+//! - Live task events from account subscriptions, with a polling fallback
+//! - Autonomous worker that claims and completes matching tasks unattended
 //! - Local whisper-rs for offline ASR wake word detection
-//! - Policy gate for security confirmations
+//! - Policy gate for security confirmations, with simulated transaction previews
 //! - Audio capture/playback via cpal/rodio
 //! - Token-gated access control based on $TETSUO holdings
 //! - Vector-based conversation memory with Qdrant
 //! ============================================================================
 
 pub mod access;
+pub mod agenc_idl;
 pub mod agenc_program;
 pub mod auth;
+pub mod capabilities;
+pub mod compute_budget;
+pub mod confirmation;
 pub mod db;
 pub mod executor;
+pub mod history;
+pub mod keystore;
 pub mod memory;
+pub mod network;
 pub mod policy_gate;
+pub mod policy_rules;
+pub mod price_oracle;
+pub mod proofs;
+pub mod rpc_pool;
+pub mod simulation;
 pub mod solana_exec;
+pub mod task_descriptions;
+pub mod task_query;
+pub mod task_watcher;
 pub mod transaction_retry;
 pub mod types;
+pub mod worker;
 #[cfg(feature = "native-audio")]
 pub mod voice_local;
 
@@ -28,8 +55,10 @@ pub use types::*;
 
 // Database
 pub use db::{
-    DbStats, DbTaskStatus, OperatorConfig as DbOperatorConfig, OperatorDb, SessionState,
-    TaskRecord, TranscriptEntry, VerificationLog,
+    AuditEntry, AuditRecord, AuditVerification, DbStats, DbTaskStatus,
+    HistoryEvent, HistoryEventKind, HistoryRecord, OperatorConfig as DbOperatorConfig, OperatorDb,
+    SessionState, SpendCategory, SpendRecord, TaskRecord, TranscriptEntry, VerificationLog,
+    WalletProfile,
 };
 // Store types
 pub use db::{
@@ -38,13 +67,31 @@ pub use db::{
 
 // Solana executor
 pub use solana_exec::SolanaExecutor;
+pub use rpc_pool::{EndpointStats, RpcEndpoint, RpcPool, RpcPoolStats};
+pub use network::{active_profile, set_active_profile, NetworkKind, NetworkProfile};
+pub use compute_budget::PriorityFeeConfig;
+pub use keystore::Keystore;
+pub use capabilities::{capability_mask, capability_names};
+pub use history::{HistoryFilter, WalletHistory};
+pub use proofs::{completion_items, ProofItem, ProofManifest, ProofVerification};
+pub use worker::{AutonomousWorker, TaskHandler, WorkerConfig, WorkerHandle, WorkerStatus, WorkerTick};
+pub use task_query::{TaskCache, TaskQuery, TaskSort};
+pub use task_watcher::{TaskEvent, TaskEventKind, TaskEventSource, TaskWatcher, TaskWatcherHandle};
+pub use task_descriptions::{
+    DescriptionFetcher, DescriptionRegistry, HttpDescriptionGateway, ResolvedDescription,
+};
 
 // Voice processing (only available with native audio support)
 #[cfg(feature = "native-audio")]
 pub use voice_local::LocalVoiceProcessor;
 
 // Policy gate
-pub use policy_gate::PolicyGate;
+pub use policy_gate::{
+    PolicyConfig, PolicyExplanation, PolicyGate, PolicySource, SpendLimits, SpendValue,
+};
+pub use confirmation::{ConfirmationTickets, TicketError};
+pub use policy_rules::{PolicyRule, PolicyRules, RuleOutcome};
+pub use price_oracle::{PriceOracle, TokenQuote};
 
 // Access control
 pub use access::{AccessChecker, AccessGate, AccessTier, AccessTierInfo, Feature};
@@ -0,0 +1,363 @@
+//! ============================================================================
+//! Network Profiles - Per-Cluster Endpoints, Program IDs and Mints
+//! ============================================================================
+//! A profile bundles everything that differs between clusters:
+//! - The RPC endpoints the shared pool reads and sends through
+//! - The AgenC program ID that tasks, claims and escrows are derived from
+//! - The SKR and TETSUO mints used for rewards and access tiers
+//! - The explorer links shown for transactions and addresses
+//!
+//! Built-in localnet, devnet and mainnet profiles ship with the endpoints;
+//! only mainnet ships with published addresses. The others leave them unset
+//! until a saved profile with the same name fills them in, and AgenC actions
+//! are refused until then. Custom profiles can point anywhere.
+//!
+//! One profile is active at a time: the `agenc_program` helpers, the access
+//! checker and the token labels all read it, so switching profiles at
+//! runtime moves every subsystem over.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+use crate::access::TETSUO_MINT;
+use crate::agenc_program::{PROGRAM_ID, SKR_MINT};
+use crate::rpc_pool::RpcEndpoint;
+
+/// Placeholder in `explorer_url` replaced by `tx/<sig>` or `address/<addr>`
+const EXPLORER_PATH: &str = "{path}";
+
+/// Profile used until one is selected: the only one shipping with addresses
+pub const DEFAULT_PROFILE: &str = "mainnet";
+
+/// Solana's own name for mainnet, saved by configs that predate profiles
+const LEGACY_MAINNET: &str = "mainnet-beta";
+
+/// Which cluster a profile talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkKind {
+    Localnet,
+    Devnet,
+    Mainnet,
+    Custom,
+}
+
+/// Endpoints, program and mints for one cluster
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkProfile {
+    /// Unique name ("devnet", "mainnet", "staging", ...)
+    pub name: String,
+    pub kind: NetworkKind,
+    /// Pool endpoints, primary first
+    pub rpc_endpoints: Vec<RpcEndpoint>,
+    /// AgenC program ID; this and the mints are empty while unset
+    pub program_id: String,
+    pub skr_mint: String,
+    pub tetsuo_mint: String,
+    /// Explorer link template; `{path}` becomes `tx/<sig>` or `address/<addr>`
+    pub explorer_url: String,
+}
+
+impl NetworkProfile {
+    fn builtin(name: &str, kind: NetworkKind, rpc_url: &str, explorer_url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            rpc_endpoints: vec![RpcEndpoint::new(rpc_url, 1)],
+            program_id: String::new(),
+            skr_mint: String::new(),
+            tetsuo_mint: String::new(),
+            explorer_url: explorer_url.to_string(),
+        }
+    }
+
+    /// A local validator on the default port
+    pub fn localnet() -> Self {
+        Self::builtin(
+            "localnet",
+            NetworkKind::Localnet,
+            "http://127.0.0.1:8899",
+            "https://explorer.solana.com/{path}?cluster=custom&customUrl=http%3A%2F%2F127.0.0.1%3A8899",
+        )
+    }
+
+    pub fn devnet() -> Self {
+        Self::builtin(
+            "devnet",
+            NetworkKind::Devnet,
+            "https://api.devnet.solana.com",
+            "https://explorer.solana.com/{path}?cluster=devnet",
+        )
+    }
+
+    pub fn mainnet() -> Self {
+        Self {
+            program_id: PROGRAM_ID.to_string(),
+            skr_mint: SKR_MINT.to_string(),
+            tetsuo_mint: TETSUO_MINT.to_string(),
+            ..Self::builtin(
+                "mainnet",
+                NetworkKind::Mainnet,
+                "https://api.mainnet-beta.solana.com",
+                "https://explorer.solana.com/{path}",
+            )
+        }
+    }
+
+    /// Localnet, devnet and mainnet, as shipped
+    pub fn builtins() -> Vec<Self> {
+        vec![Self::localnet(), Self::devnet(), Self::mainnet()]
+    }
+
+    /// Find a profile by name: a saved profile first, then a built-in one.
+    /// "mainnet-beta" from configs saved before profiles means "mainnet".
+    pub fn resolve(name: &str, saved: &[NetworkProfile]) -> Option<Self> {
+        let name = if name == LEGACY_MAINNET { "mainnet" } else { name };
+        saved
+            .iter()
+            .find(|p| p.name == name)
+            .cloned()
+            .or_else(|| Self::builtins().into_iter().find(|p| p.name == name))
+    }
+
+    /// Built-in profiles with saved overrides applied, then custom ones
+    pub fn all(saved: &[NetworkProfile]) -> Vec<Self> {
+        let mut profiles: Vec<Self> = Self::builtins()
+            .into_iter()
+            .map(|builtin| Self::resolve(&builtin.name, saved).unwrap_or(builtin))
+            .collect();
+        for profile in saved {
+            if !profiles.iter().any(|p| p.name == profile.name) {
+                profiles.push(profile.clone());
+            }
+        }
+        profiles
+    }
+
+    /// Check the name, endpoints, addresses and explorer template
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty()
+            || !self.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(anyhow!(
+                "Invalid network profile name '{}' — use letters, digits, '-' or '_'",
+                self.name
+            ));
+        }
+        if self.rpc_endpoints.is_empty() {
+            return Err(anyhow!("Network profile '{}' has no RPC endpoints", self.name));
+        }
+        for (label, address) in self.addresses() {
+            if address.is_empty() {
+                continue;
+            }
+            Pubkey::from_str(address)
+                .map_err(|_| anyhow!("Invalid {} for network profile '{}': {}", label, self.name, address))?;
+        }
+        if !self.explorer_url.contains(EXPLORER_PATH) {
+            return Err(anyhow!("Explorer URL for '{}' must contain {}", self.name, EXPLORER_PATH));
+        }
+        Ok(())
+    }
+
+    fn addresses(&self) -> [(&'static str, &String); 3] {
+        [
+            ("program ID", &self.program_id),
+            ("SKR mint", &self.skr_mint),
+            ("TETSUO mint", &self.tetsuo_mint),
+        ]
+    }
+
+    /// Addresses this profile leaves unset ("program ID", "SKR mint", ...)
+    pub fn missing_addresses(&self) -> Vec<&'static str> {
+        self.addresses()
+            .into_iter()
+            .filter(|(_, address)| address.is_empty())
+            .map(|(label, _)| label)
+            .collect()
+    }
+
+    /// Error unless the program ID and mints are all set
+    pub fn ensure_configured(&self) -> Result<()> {
+        let missing = self.missing_addresses();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow!(
+            "Network profile '{}' has no {} set — add the AgenC addresses for this cluster in the network settings",
+            self.name,
+            missing.join(", ")
+        ))
+    }
+
+    /// Primary RPC URL
+    pub fn rpc_url(&self) -> &str {
+        self.rpc_endpoints.first().map(|e| e.url.as_str()).unwrap_or_default()
+    }
+
+    /// Explorer link for a transaction
+    pub fn explorer_tx_url(&self, signature: &str) -> String {
+        self.explorer_url.replace(EXPLORER_PATH, &format!("tx/{}", signature))
+    }
+
+    /// Explorer link for an account or wallet
+    pub fn explorer_address_url(&self, address: &str) -> String {
+        self.explorer_url.replace(EXPLORER_PATH, &format!("address/{}", address))
+    }
+}
+
+// ============================================================================
+// Active Profile
+// ============================================================================
+
+/// The active profile with its addresses parsed once; unset addresses parse
+/// to the default pubkey and `ensure_configured` refuses to use them
+pub(crate) struct ActiveNetwork {
+    pub profile: NetworkProfile,
+    pub program_id: Pubkey,
+    pub skr_mint: Pubkey,
+    pub tetsuo_mint: Pubkey,
+}
+
+impl ActiveNetwork {
+    fn new(profile: NetworkProfile) -> Result<Self> {
+        profile.validate()?;
+        Ok(Self {
+            program_id: parse_address(&profile.program_id)?,
+            skr_mint: parse_address(&profile.skr_mint)?,
+            tetsuo_mint: parse_address(&profile.tetsuo_mint)?,
+            profile,
+        })
+    }
+}
+
+fn parse_address(address: &str) -> Result<Pubkey> {
+    if address.is_empty() {
+        return Ok(Pubkey::default());
+    }
+    Ok(Pubkey::from_str(address)?)
+}
+
+static ACTIVE: Lazy<RwLock<Arc<ActiveNetwork>>> = Lazy::new(|| {
+    let profile = NetworkProfile::resolve(DEFAULT_PROFILE, &[]).expect("Default profile is built in");
+    RwLock::new(Arc::new(ActiveNetwork::new(profile).expect("Built-in profiles are valid")))
+});
+
+pub(crate) fn active() -> Arc<ActiveNetwork> {
+    ACTIVE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The profile every chain helper currently uses
+pub fn active_profile() -> NetworkProfile {
+    active().profile.clone()
+}
+
+/// Error unless the active profile has its program ID and mints set; AgenC
+/// actions check this before deriving anything from them
+pub fn ensure_configured() -> Result<()> {
+    active().profile.ensure_configured()
+}
+
+/// Make `profile` active. Callers holding an `RpcPool` should point it at
+/// the profile's endpoints and drop anything cached from the old cluster.
+pub fn set_active_profile(profile: NetworkProfile) -> Result<()> {
+    let network = Arc::new(ActiveNetwork::new(profile)?);
+    info!(
+        "Network profile '{}' active (program {})",
+        network.profile.name, network.profile.program_id
+    );
+    if let Err(e) = network.profile.ensure_configured() {
+        warn!("{}", e);
+    }
+    *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = network;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_profiles_are_valid() {
+        for profile in NetworkProfile::builtins() {
+            profile.validate().unwrap();
+        }
+        assert_eq!(active_profile().name, DEFAULT_PROFILE);
+    }
+
+    #[test]
+    fn test_only_mainnet_ships_with_addresses() {
+        assert!(NetworkProfile::mainnet().ensure_configured().is_ok());
+        assert!(ensure_configured().is_ok());
+        for profile in [NetworkProfile::localnet(), NetworkProfile::devnet()] {
+            assert_eq!(profile.missing_addresses(), vec!["program ID", "SKR mint", "TETSUO mint"]);
+            let err = profile.ensure_configured().unwrap_err().to_string();
+            assert!(err.contains(&profile.name) && err.contains("program ID"), "{}", err);
+
+            let network = ActiveNetwork::new(profile).unwrap();
+            assert_eq!(network.program_id, Pubkey::default());
+        }
+
+        let mut devnet = NetworkProfile::devnet();
+        devnet.program_id = Pubkey::new_unique().to_string();
+        devnet.skr_mint = Pubkey::new_unique().to_string();
+        assert_eq!(devnet.missing_addresses(), vec!["TETSUO mint"]);
+        devnet.tetsuo_mint = Pubkey::new_unique().to_string();
+        assert!(devnet.ensure_configured().is_ok());
+    }
+
+    #[test]
+    fn test_legacy_mainnet_name_resolves_to_mainnet() {
+        assert_eq!(NetworkProfile::resolve("mainnet-beta", &[]), Some(NetworkProfile::mainnet()));
+        assert_eq!(NetworkProfile::resolve("testnet", &[]), None);
+    }
+
+    #[test]
+    fn test_saved_profiles_override_builtins_by_name() {
+        let mut devnet = NetworkProfile::devnet();
+        devnet.skr_mint = Pubkey::new_unique().to_string();
+        let mut staging = NetworkProfile::devnet();
+        staging.name = "staging".into();
+        staging.kind = NetworkKind::Custom;
+        let saved = vec![devnet.clone(), staging.clone()];
+
+        assert_eq!(NetworkProfile::resolve("devnet", &saved), Some(devnet));
+        assert_eq!(NetworkProfile::resolve("mainnet", &saved), Some(NetworkProfile::mainnet()));
+        assert_eq!(NetworkProfile::resolve("nope", &saved), None);
+
+        let names: Vec<String> = NetworkProfile::all(&saved).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["localnet", "devnet", "mainnet", "staging"]);
+    }
+
+    #[test]
+    fn test_invalid_profiles_are_rejected() {
+        let mut profile = NetworkProfile::devnet();
+        profile.program_id = "not-a-key".into();
+        assert!(profile.validate().unwrap_err().to_string().contains("program ID"));
+
+        let mut profile = NetworkProfile::devnet();
+        profile.name = "dev net".into();
+        assert!(profile.validate().is_err());
+
+        let mut profile = NetworkProfile::devnet();
+        profile.rpc_endpoints.clear();
+        assert!(profile.validate().is_err());
+        assert!(set_active_profile(profile).is_err());
+    }
+
+    #[test]
+    fn test_explorer_links_follow_the_cluster() {
+        let devnet = NetworkProfile::devnet();
+        assert_eq!(devnet.explorer_tx_url("abc"), "https://explorer.solana.com/tx/abc?cluster=devnet");
+        assert_eq!(
+            NetworkProfile::mainnet().explorer_address_url("xyz"),
+            "https://explorer.solana.com/address/xyz"
+        );
+    }
+}
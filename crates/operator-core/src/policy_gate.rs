@@ -8,12 +8,40 @@
 //!
 //! The gate ensures keys never leave the device and user explicitly
 //! approves all state-changing transactions.
+//!
+//! Spending is also tracked in a rolling ledger (daily/weekly/monthly) that
+//! is persisted in OperatorDb and loaded back via `load_ledger`, so the
+//! limits survive app restarts.
+//!
+//! Intents that need confirmation get a one-time signed ticket
+//! (`check_and_issue`) which `redeem_ticket` later checks, so what runs is
+//! exactly what the user approved.
+//!
+//! Amounts are compared as SOL-equivalents: SPL swap amounts and SKR rewards
+//! are converted through their USD price from the PriceOracle cache. If a
+//! spend cannot be valued (unknown mint, no fresh price) the gate fails
+//! closed to typed confirmation.
+//!
+//! Intents may name a wallet profile (`wallet` param). Profiles can carry
+//! their own rolling limits, and signing with a profile other than the
+//! active one needs at least typed confirmation. Tickets are bound to the
+//! signing wallet, so switching wallets invalidates them.
 //! ============================================================================
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use tracing::{info, warn};
 
+use crate::access::AccessTier;
+use crate::agenc_program::skr_mint;
+use crate::confirmation::{ConfirmationTickets, TicketError};
+use crate::db::{SpendCategory, SpendRecord};
+use crate::executor::tokens;
+use crate::policy_rules::{PolicyRule, PolicyRules, RuleContext, RuleOutcome};
+use crate::price_oracle::{resolve_mint, PriceOracle};
 use crate::types::*;
+use chrono::Timelike;
 
 /// Spending threshold in SOL that requires extra confirmation
 const HIGH_VALUE_THRESHOLD_SOL: f64 = 1.0;
@@ -21,14 +49,76 @@ const HIGH_VALUE_THRESHOLD_SOL: f64 = 1.0;
 /// Maximum spend per session without hardware confirmation
 const SESSION_LIMIT_SOL: f64 = 10.0;
 
+/// Rolling window lengths in seconds
+const DAY_SECS: i64 = 86_400;
+const WEEK_SECS: i64 = 7 * DAY_SECS;
+const MONTH_SECS: i64 = 30 * DAY_SECS;
+
 /// Policy gate for security enforcement
 pub struct PolicyGate {
     /// Current session spending (reset on app restart)
     session_spending_lamports: u64,
+    /// Spend ledger covering the longest window (persisted in OperatorDb)
+    ledger: Vec<SpendRecord>,
     /// Whether hardware wallet is connected
     hardware_wallet_connected: bool,
     /// Policy configuration
     config: PolicyConfig,
+    /// Ordered rules deciding each action's confirmation
+    rules: PolicyRules,
+    /// Caller's access tier, if known
+    access_tier: Option<AccessTier>,
+    /// Cached token decimals and prices used to value SPL and SKR amounts
+    oracle: PriceOracle,
+    /// Issuer of one-time confirmation tickets
+    tickets: ConfirmationTickets,
+    /// Active wallet profile (None = the unnamed default wallet)
+    active_wallet: Option<String>,
+}
+
+/// What decided a policy outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicySource {
+    /// Listed in `PolicyConfig::blocked_actions`
+    BlockedAction,
+    /// Session or rolling spend limit
+    SpendLimit,
+    /// A rule from the active rule set
+    Rule,
+    /// No rule matched
+    Fallback,
+    /// The spend could not be valued, so typed confirmation is required
+    UnknownValue,
+}
+
+/// A policy decision together with the reason it was reached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyExplanation {
+    pub check: PolicyCheck,
+    pub source: PolicySource,
+    /// Name of the rule that fired (when `source` is `Rule`)
+    pub rule: Option<String>,
+    /// Position of that rule in the rule set
+    pub rule_index: Option<usize>,
+}
+
+/// Value of an intent's spend
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpendValue {
+    /// SOL-equivalent amount, compared against thresholds and limits
+    pub sol: f64,
+    /// USD value, when the SOL price is cached
+    pub usd: Option<f64>,
+}
+
+impl SpendValue {
+    fn describe(&self) -> String {
+        match self.usd {
+            Some(usd) => format!("{} SOL ≈ ${:.2}", self.sol, usd),
+            None => format!("{} SOL", self.sol),
+        }
+    }
 }
 
 /// Policy configuration (can be customized by user)
@@ -46,6 +136,15 @@ pub struct PolicyConfig {
     pub large_threshold_sol: f64,
     /// Blocked actions (e.g., "export_key")
     pub blocked_actions: Vec<String>,
+    /// Rolling limits across all spend categories
+    #[serde(default)]
+    pub spend_limits: SpendLimits,
+    /// Additional rolling limits for individual categories
+    #[serde(default)]
+    pub category_limits: HashMap<SpendCategory, SpendLimits>,
+    /// Additional rolling limits for individual wallet profiles
+    #[serde(default)]
+    pub wallet_limits: HashMap<String, SpendLimits>,
 }
 
 impl Default for PolicyConfig {
@@ -57,17 +156,121 @@ impl Default for PolicyConfig {
             hardware_for_large: true,
             large_threshold_sol: HIGH_VALUE_THRESHOLD_SOL,
             blocked_actions: vec!["export_key".to_string()],
+            spend_limits: SpendLimits::default(),
+            category_limits: HashMap::new(),
+            wallet_limits: HashMap::new(),
         }
     }
 }
 
+impl PolicyConfig {
+    /// Config for the autonomous worker. Nobody is there to confirm, so
+    /// every spend window is zero and voice-only approval is off.
+    pub fn unattended() -> Self {
+        Self {
+            allow_voice_only_small: false,
+            voice_only_max_sol: 0.0,
+            spend_limits: SpendLimits { daily_sol: Some(0.0), weekly_sol: Some(0.0), monthly_sol: Some(0.0) },
+            ..Self::default()
+        }
+    }
+}
+
+/// Rolling spend limits in SOL. `None` disables that window.
+/// Exceeding a window requires a hardware wallet, like the session limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpendLimits {
+    pub daily_sol: Option<f64>,
+    pub weekly_sol: Option<f64>,
+    pub monthly_sol: Option<f64>,
+}
+
+impl Default for SpendLimits {
+    fn default() -> Self {
+        Self {
+            daily_sol: Some(SESSION_LIMIT_SOL),
+            weekly_sol: Some(SESSION_LIMIT_SOL * 3.0),
+            monthly_sol: Some(SESSION_LIMIT_SOL * 10.0),
+        }
+    }
+}
+
+impl SpendLimits {
+    /// No rolling limits at all
+    pub fn unlimited() -> Self {
+        Self { daily_sol: None, weekly_sol: None, monthly_sol: None }
+    }
+
+    fn windows(&self) -> [(&'static str, i64, Option<f64>); 3] {
+        [
+            ("daily", DAY_SECS, self.daily_sol),
+            ("weekly", WEEK_SECS, self.weekly_sol),
+            ("monthly", MONTH_SECS, self.monthly_sol),
+        ]
+    }
+}
+
+/// Ledger category an action's spending is recorded under, if it spends at all
+pub fn spend_category(action: &IntentAction) -> Option<SpendCategory> {
+    match action {
+        IntentAction::CreateTask => Some(SpendCategory::TaskCreation),
+        IntentAction::SwapTokens => Some(SpendCategory::Swap),
+        _ => None,
+    }
+}
+
+/// Recipient address named in intent params, if any
+fn extract_recipient(params: &serde_json::Value) -> Option<String> {
+    ["recipient", "to", "destination"]
+        .iter()
+        .find_map(|k| params.get(*k).and_then(|v| v.as_str()))
+        .map(String::from)
+}
+
+/// Token mints named in intent params (swap input/output, or a single mint).
+/// Symbols such as "USDC" are resolved to their mint address.
+fn extract_mints(params: &serde_json::Value) -> Vec<String> {
+    ["input_mint", "output_mint", "mint"]
+        .iter()
+        .filter_map(|k| params.get(*k).and_then(|v| v.as_str()))
+        .map(resolve_mint)
+        .collect()
+}
+
+/// Non-SOL swap input mint, if the intent is an SPL swap
+fn swap_input_mint(intent: &VoiceIntent) -> Option<String> {
+    if intent.action != IntentAction::SwapTokens {
+        return None;
+    }
+    intent
+        .params
+        .get("input_mint")
+        .and_then(|v| v.as_str())
+        .map(resolve_mint)
+        .filter(|m| m != tokens::SOL)
+}
+
+/// SKR reward in display units, if the intent creates a task with one
+fn skr_reward(intent: &VoiceIntent) -> Option<f64> {
+    if intent.action != IntentAction::CreateTask {
+        return None;
+    }
+    intent.params.get("reward_skr").and_then(|v| v.as_f64()).filter(|s| *s > 0.0)
+}
+
 impl PolicyGate {
     /// Create new policy gate with default config
     pub fn new() -> Self {
         Self {
             session_spending_lamports: 0,
+            ledger: Vec::new(),
             hardware_wallet_connected: false,
             config: PolicyConfig::default(),
+            rules: PolicyRules::default(),
+            access_tier: None,
+            oracle: PriceOracle::default(),
+            tickets: ConfirmationTickets::default(),
+            active_wallet: None,
         }
     }
 
@@ -75,222 +278,386 @@ impl PolicyGate {
     pub fn with_config(config: PolicyConfig) -> Self {
         Self {
             session_spending_lamports: 0,
+            ledger: Vec::new(),
             hardware_wallet_connected: false,
             config,
+            rules: PolicyRules::default(),
+            access_tier: None,
+            oracle: PriceOracle::default(),
+            tickets: ConfirmationTickets::default(),
+            active_wallet: None,
         }
     }
 
+    /// Gate for the autonomous worker: the unattended config and rule set
+    pub fn unattended() -> anyhow::Result<Self> {
+        let mut gate = Self::with_config(PolicyConfig::unattended());
+        gate.set_rules(PolicyRules::unattended()?);
+        Ok(gate)
+    }
+
     /// Connect hardware wallet (e.g., Ledger)
     pub fn set_hardware_wallet(&mut self, connected: bool) {
         self.hardware_wallet_connected = connected;
         info!("Hardware wallet connected: {}", connected);
     }
 
-    /// Check if an intent is allowed and what confirmation it needs
-    pub fn check_policy(&self, intent: &VoiceIntent) -> PolicyCheck {
-        // Check if action is blocked
-        let action_name = format!("{:?}", intent.action).to_lowercase();
-        if self.config.blocked_actions.contains(&action_name) {
-            return PolicyCheck {
-                allowed: false,
-                requires_confirmation: false,
-                confirmation_type: ConfirmationType::None,
-                reason: format!("Action '{}' is blocked by policy", action_name),
-            };
-        }
+    /// Set the caller's access tier (used by tier-conditioned rules)
+    pub fn set_access_tier(&mut self, tier: Option<AccessTier>) {
+        self.access_tier = tier;
+    }
 
-        // Categorize the action
-        match &intent.action {
-            // Read-only operations - always allowed, no confirmation
-            IntentAction::ListOpenTasks |
-            IntentAction::GetTaskStatus |
-            IntentAction::GetBalance |
-            IntentAction::GetAddress |
-            IntentAction::GetProtocolState |
-            IntentAction::Help |
-            IntentAction::Unknown => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: false,
-                    confirmation_type: ConfirmationType::None,
-                    reason: "Read-only operation".into(),
-                }
-            }
+    /// Caller's access tier, if known
+    pub fn access_tier(&self) -> Option<AccessTier> {
+        self.access_tier
+    }
 
-            // Task creation - requires confirmation based on amount
-            IntentAction::CreateTask => {
-                self.check_spending_action(intent, "create task")
-            }
+    /// Set the active wallet profile (None for the unnamed default wallet)
+    pub fn set_active_wallet(&mut self, wallet: Option<String>) {
+        info!("Policy active wallet: {}", wallet.as_deref().unwrap_or("default"));
+        self.active_wallet = wallet;
+    }
 
-            // Task claim - low risk, verbal confirmation only
-            IntentAction::ClaimTask => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Verbal,
-                    reason: "Claiming task requires verbal confirmation".into(),
-                }
-            }
+    /// Active wallet profile, if one is selected
+    pub fn active_wallet(&self) -> Option<&str> {
+        self.active_wallet.as_deref()
+    }
 
-            // Task completion - low risk
-            IntentAction::CompleteTask => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Verbal,
-                    reason: "Completing task requires verbal confirmation".into(),
-                }
-            }
+    /// Wallet profile that would sign `intent`: the one it names, or the
+    /// active profile
+    pub fn signing_wallet<'a>(&'a self, intent: &'a VoiceIntent) -> Option<&'a str> {
+        intent.wallet().or(self.active_wallet.as_deref())
+    }
 
-            // Task cancellation - returns funds, medium risk
-            IntentAction::CancelTask => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Typed,
-                    reason: "Cancelling task requires typed confirmation".into(),
-                }
+    /// `intent` with the signing wallet made explicit, so a ticket issued
+    /// for the active wallet cannot be redeemed after a switch
+    fn wallet_bound<'a>(&self, intent: &'a VoiceIntent) -> Cow<'a, VoiceIntent> {
+        match (intent.wallet(), &self.active_wallet, intent.params.as_object()) {
+            (None, Some(active), Some(params)) => {
+                let mut params = params.clone();
+                params.insert("wallet".into(), serde_json::Value::String(active.clone()));
+                Cow::Owned(VoiceIntent { params: params.into(), ..intent.clone() })
             }
+            _ => Cow::Borrowed(intent),
+        }
+    }
 
-            // Code Operations (Pro tier) - read-only style, no confirmation needed
-            IntentAction::CodeFix |
-            IntentAction::CodeReview |
-            IntentAction::CodeGenerate |
-            IntentAction::CodeExplain => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: false,
-                    confirmation_type: ConfirmationType::None,
-                    reason: "Code operation (Pro tier required)".into(),
-                }
-            }
+    /// Replace the rule set (e.g. after loading a user rule file)
+    pub fn set_rules(&mut self, rules: PolicyRules) {
+        info!("Policy rules updated ({} rules)", rules.rules.len());
+        self.rules = rules;
+    }
 
-            // Trading - Quote/Price lookups are read-only
-            IntentAction::GetSwapQuote |
-            IntentAction::GetTokenPrice => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: false,
-                    confirmation_type: ConfirmationType::None,
-                    reason: "Read-only trading operation".into(),
-                }
-            }
+    /// Get the active rule set
+    pub fn rules(&self) -> &PolicyRules {
+        &self.rules
+    }
 
-            // Trading - Actual swaps need confirmation based on amount
-            IntentAction::SwapTokens => {
-                self.check_spending_action(intent, "token swap")
-            }
+    /// Token price cache used to value SPL swaps and SKR rewards
+    pub fn price_oracle(&self) -> &PriceOracle {
+        &self.oracle
+    }
 
-            // Social Operations (Pro tier) - verbal confirmation for posting
-            IntentAction::PostTweet => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Verbal,
-                    reason: "Tweet posting requires verbal confirmation".into(),
-                }
-            }
+    pub fn price_oracle_mut(&mut self) -> &mut PriceOracle {
+        &mut self.oracle
+    }
 
-            IntentAction::PostThread => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Verbal,
-                    reason: "Thread posting requires verbal confirmation".into(),
-                }
-            }
+    /// Mints whose decimals or price must be refreshed before `intent` can
+    /// be valued. Empty for pure-SOL intents.
+    pub fn stale_mints(&self, intent: &VoiceIntent) -> Vec<String> {
+        let mut mints = Vec::new();
+        if let Some(mint) = swap_input_mint(intent) {
+            mints.push(mint);
+        }
+        if skr_reward(intent).is_some() {
+            mints.push(skr_mint().to_string());
+        }
+        if !mints.is_empty() {
+            mints.push(tokens::SOL.to_string());
+        }
+        mints.retain(|m| !self.oracle.is_fresh(m));
+        mints
+    }
 
-            // Phase 3: Discord Operations (Pro tier) - verbal confirmation for posting
-            IntentAction::PostDiscord |
-            IntentAction::PostDiscordEmbed => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Verbal,
-                    reason: "Discord posting requires verbal confirmation".into(),
+    /// Value an intent's spend in SOL-equivalent (and USD when known).
+    /// Returns why the value is unknown when a mint or price is missing.
+    pub fn spend_value(&self, intent: &VoiceIntent) -> Result<SpendValue, String> {
+        let mut value = match swap_input_mint(intent) {
+            Some(mint) => {
+                let amount = intent
+                    .params
+                    .get("amount")
+                    .and_then(|v| v.as_u64())
+                    .ok_or_else(|| "swap amount missing".to_string())?;
+                if self.oracle.decimals(&mint).is_none() {
+                    return Err(format!("unknown decimals for mint {}", mint));
                 }
+                let usd = self
+                    .oracle
+                    .value_usd(&mint, amount)
+                    .ok_or_else(|| format!("no current price for mint {}", mint))?;
+                let sol = self
+                    .oracle
+                    .usd_to_sol(usd)
+                    .ok_or_else(|| "no current SOL price".to_string())?;
+                return Ok(SpendValue { sol, usd: Some(usd) });
             }
-
-            // Phase 3: Email Operations (Pro tier) - verbal confirmation
-            IntentAction::SendEmail => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Verbal,
-                    reason: "Email sending requires verbal confirmation".into(),
+            // Pure SOL amounts (including swaps from SOL, where `amount` is lamports)
+            None => {
+                let sol = self.extract_sol_amount(&intent.params);
+                SpendValue {
+                    sol,
+                    usd: self.oracle.price_usd(tokens::SOL).map(|p| sol * p),
                 }
             }
+        };
 
-            IntentAction::SendBulkEmail => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Typed,
-                    reason: "Bulk email sending requires typed confirmation".into(),
-                }
+        if let Some(skr) = skr_reward(intent) {
+            let usd = self
+                .oracle
+                .display_value_usd(&skr_mint().to_string(), skr)
+                .ok_or_else(|| "no current SKR price".to_string())?;
+            value.sol += self
+                .oracle
+                .usd_to_sol(usd)
+                .ok_or_else(|| "no current SOL price".to_string())?;
+            value.usd = value.usd.map(|v| v + usd);
+        }
+
+        Ok(value)
+    }
+
+    /// SOL-equivalent lamports to record in the spend ledger for `intent`
+    pub fn spend_lamports(&self, intent: &VoiceIntent) -> Option<u64> {
+        self.spend_value(intent)
+            .ok()
+            .map(|v| (v.sol * 1_000_000_000.0) as u64)
+    }
+
+    /// Check if an intent is allowed and what confirmation it needs
+    pub fn check_policy(&self, intent: &VoiceIntent) -> PolicyCheck {
+        self.explain(intent).check
+    }
+
+    /// `check_policy`, attaching a one-time ticket when the intent is allowed
+    /// but needs confirmation. The ticket must be redeemed to execute it.
+    pub fn check_and_issue(&mut self, intent: &VoiceIntent) -> PolicyCheck {
+        let mut check = self.check_policy(intent);
+        if check.allowed && check.requires_confirmation {
+            let bound = self.wallet_bound(intent).into_owned();
+            check.ticket = Some(self.tickets.issue(&bound, check.confirmation_type.clone()));
+        }
+        check
+    }
+
+    /// Consume a ticket from `check_and_issue`. Fails if it was not issued by
+    /// this gate, covers different params or signing wallet, has expired,
+    /// was already used, or was approved with a weaker confirmation than
+    /// `required` (the intent's current `confirmation_type`).
+    pub fn redeem_ticket(
+        &mut self,
+        intent: &VoiceIntent,
+        ticket: &ConfirmationTicket,
+        required: &ConfirmationType,
+    ) -> Result<(), TicketError> {
+        let bound = self.wallet_bound(intent).into_owned();
+        self.tickets.redeem(&bound, ticket, required)
+    }
+
+    /// Same as `check_policy`, but also reports what decided the outcome
+    /// (blocked action, spend limit, or which rule fired) for the HUD.
+    pub fn explain(&self, intent: &VoiceIntent) -> PolicyExplanation {
+        self.explain_at(intent, chrono::Local::now().hour())
+    }
+
+    /// `explain` with an explicit local hour, for time-of-day rules
+    pub fn explain_at(&self, intent: &VoiceIntent, hour: u32) -> PolicyExplanation {
+        let mut explanation = self.decide(intent, hour);
+        self.confirm_wallet_selection(intent, &mut explanation.check);
+        explanation
+    }
+
+    /// Make the signing wallet part of what the user confirms. Signing with
+    /// a profile other than the active one needs at least typed confirmation.
+    fn confirm_wallet_selection(&self, intent: &VoiceIntent, check: &mut PolicyCheck) {
+        let Some(wallet) = intent.wallet() else { return };
+        if !check.allowed || !check.requires_confirmation {
+            return;
+        }
+        if self.active_wallet.as_deref() != Some(wallet) {
+            if check.confirmation_type == ConfirmationType::Verbal {
+                check.confirmation_type = ConfirmationType::Typed;
             }
+            check.reason = format!(
+                "{} — signing with wallet '{}' (active: {})",
+                check.reason,
+                wallet,
+                self.active_wallet.as_deref().unwrap_or("default")
+            );
+        } else {
+            check.reason = format!("{} — signing with wallet '{}'", check.reason, wallet);
+        }
+    }
 
-            // Phase 3: Image Generation (Pro tier) - no confirmation needed
-            IntentAction::GenerateImage |
-            IntentAction::GenerateVideo => {
-                PolicyCheck {
-                    allowed: true,
+    fn decide(&self, intent: &VoiceIntent, hour: u32) -> PolicyExplanation {
+        let action_name = intent.action.name();
+
+        // Check if action is blocked. Accept the legacy Debug-lowercase form
+        // ("createtask") so existing configs keep working.
+        let legacy_name = format!("{:?}", intent.action).to_lowercase();
+        if self.config.blocked_actions.iter().any(|b| *b == action_name || *b == legacy_name) {
+            return PolicyExplanation {
+                check: PolicyCheck {
+                    allowed: false,
                     requires_confirmation: false,
                     confirmation_type: ConfirmationType::None,
-                    reason: "Image/Video generation (Pro tier required)".into(),
-                }
+                    reason: format!("Action '{}' is blocked by policy", action_name),
+                    ticket: None,
+                    preview: None,
+                },
+                source: PolicySource::BlockedAction,
+                rule: None,
+                rule_index: None,
+            };
+        }
+
+        let value = match self.spend_value(intent) {
+            Ok(value) => value,
+            Err(why) => return self.explain_unvalued(intent, hour, &why),
+        };
+
+        // Spend limits apply to spending actions whatever the rules say
+        if spend_category(&intent.action).is_some() {
+            if let Some(check) = self.check_spend_limits(intent, value.sol) {
+                return PolicyExplanation {
+                    check,
+                    source: PolicySource::SpendLimit,
+                    rule: None,
+                    rule_index: None,
+                };
             }
+        }
 
-            // GitHub Operations (Pro tier) - verbal confirmation for external actions
-            IntentAction::CreateGist |
-            IntentAction::CreateGitHubIssue |
-            IntentAction::AddGitHubComment |
-            IntentAction::TriggerGitHubWorkflow => {
-                PolicyCheck {
+        let ctx = self.rule_context(intent, hour, value.sol, value.usd);
+        match self.rules.evaluate(&ctx) {
+            Some((index, rule)) => self.rule_explanation(index, rule, &value),
+            // Nothing matched — fail safe rather than silently allowing
+            None => PolicyExplanation {
+                check: PolicyCheck {
                     allowed: true,
                     requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Verbal,
-                    reason: "GitHub operation requires verbal confirmation".into(),
-                }
-            }
+                    confirmation_type: ConfirmationType::Typed,
+                    reason: format!(
+                        "No policy rule matched '{}' — typed confirmation required",
+                        intent.action.name()
+                    ),
+                    ticket: None,
+                    preview: None,
+                },
+                source: PolicySource::Fallback,
+                rule: None,
+                rule_index: None,
+            },
+        }
+    }
 
-            // Device Operations (AgenC One) - scan/list are read-only, pair/config need verbal
-            IntentAction::ScanDevices |
-            IntentAction::ListDevices |
-            IntentAction::DeviceStatus => {
-                PolicyCheck {
-                    allowed: true,
-                    requires_confirmation: false,
-                    confirmation_type: ConfirmationType::None,
-                    reason: "Read-only device operation".into(),
-                }
-            }
+    fn rule_context(
+        &self,
+        intent: &VoiceIntent,
+        hour: u32,
+        amount_sol: f64,
+        amount_usd: Option<f64>,
+    ) -> RuleContext {
+        RuleContext {
+            action: intent.action.name(),
+            amount_sol,
+            amount_usd,
+            recipient: extract_recipient(&intent.params),
+            mints: extract_mints(&intent.params),
+            hour,
+            tier: self.access_tier,
+            wallet: self.signing_wallet(intent).map(String::from),
+        }
+    }
 
-            IntentAction::PairDevice |
-            IntentAction::UnpairDevice |
-            IntentAction::ConfigureDevice => {
+    /// Turn a matched rule into a decision
+    fn rule_explanation(&self, index: usize, rule: &PolicyRule, value: &SpendValue) -> PolicyExplanation {
+        let reason = rule.reason.clone().unwrap_or_else(|| {
+            format!("Matched policy rule '{}'", rule.name)
+        });
+        let check = match rule.outcome {
+            RuleOutcome::Deny => PolicyCheck {
+                allowed: false,
+                requires_confirmation: false,
+                confirmation_type: ConfirmationType::None,
+                reason,
+                ticket: None,
+                preview: None,
+            },
+            RuleOutcome::AmountBased => self.amount_based_check(value, &reason),
+            outcome => {
+                let confirmation_type = outcome
+                    .confirmation_type()
+                    .unwrap_or(ConfirmationType::Typed);
                 PolicyCheck {
                     allowed: true,
-                    requires_confirmation: true,
-                    confirmation_type: ConfirmationType::Verbal,
-                    reason: "Device pairing/config requires verbal confirmation".into(),
+                    requires_confirmation: confirmation_type != ConfirmationType::None,
+                    confirmation_type,
+                    reason,
+                    ticket: None,
+                    preview: None,
                 }
             }
+        };
+        PolicyExplanation {
+            check,
+            source: PolicySource::Rule,
+            rule: Some(rule.name.clone()),
+            rule_index: Some(index),
         }
     }
 
-    /// Check policy for spending actions
-    fn check_spending_action(&self, intent: &VoiceIntent, action_name: &str) -> PolicyCheck {
-        // Try to extract SOL amount from params
-        let amount_sol = self.extract_sol_amount(&intent.params);
+    /// Decision for a spend whose value is unknown. Limits can't be checked,
+    /// so fail closed: a deny or hardware rule still applies, anything
+    /// looser is raised to typed confirmation.
+    fn explain_unvalued(&self, intent: &VoiceIntent, hour: u32, why: &str) -> PolicyExplanation {
+        let ctx = self.rule_context(intent, hour, 0.0, None);
+        if let Some((index, rule)) = self.rules.evaluate(&ctx) {
+            if matches!(rule.outcome, RuleOutcome::Deny | RuleOutcome::Hardware) {
+                return self.rule_explanation(index, rule, &SpendValue { sol: 0.0, usd: None });
+            }
+        }
 
+        warn!("Cannot value {} ({}) — requiring typed confirmation", intent.action.name(), why);
+        PolicyExplanation {
+            check: PolicyCheck {
+                allowed: true,
+                requires_confirmation: true,
+                confirmation_type: ConfirmationType::Typed,
+                reason: format!(
+                    "Cannot value '{}': {} — typed confirmation required",
+                    intent.action.name(),
+                    why
+                ),
+                ticket: None,
+                preview: None,
+            },
+            source: PolicySource::UnknownValue,
+            rule: None,
+            rule_index: None,
+        }
+    }
+
+    /// Check the session and rolling spend limits for a spending action.
+    /// Returns the denial if a limit would be exceeded.
+    fn check_spend_limits(&self, intent: &VoiceIntent, amount_sol: f64) -> Option<PolicyCheck> {
         // Check session limit
         let new_session_total = self.session_spending_lamports +
             (amount_sol * 1_000_000_000.0) as u64;
         let session_total_sol = new_session_total as f64 / 1_000_000_000.0;
 
         if session_total_sol > SESSION_LIMIT_SOL && !self.hardware_wallet_connected {
-            return PolicyCheck {
+            return Some(PolicyCheck {
                 allowed: false,
                 requires_confirmation: true,
                 confirmation_type: ConfirmationType::Hardware,
@@ -298,16 +665,46 @@ impl PolicyGate {
                     "Session limit ({} SOL) exceeded. Connect hardware wallet.",
                     SESSION_LIMIT_SOL
                 ),
-            };
+                ticket: None,
+                preview: None,
+            });
+        }
+
+        // Check rolling windows (persisted across restarts)
+        if !self.hardware_wallet_connected {
+            if let Some(reason) = self.check_spend_windows(
+                spend_category(&intent.action),
+                self.signing_wallet(intent),
+                (amount_sol * 1_000_000_000.0) as u64,
+                chrono::Utc::now().timestamp(),
+            ) {
+                return Some(PolicyCheck {
+                    allowed: false,
+                    requires_confirmation: true,
+                    confirmation_type: ConfirmationType::Hardware,
+                    reason,
+                    ticket: None,
+                    preview: None,
+                });
+            }
         }
 
+        None
+    }
+
+    /// Pick the confirmation type for a spend from the configured thresholds
+    fn amount_based_check(&self, value: &SpendValue, action_name: &str) -> PolicyCheck {
+        let amount_sol = value.sol;
+        let amount = value.describe();
         // Determine confirmation type based on amount
         if amount_sol <= self.config.voice_only_max_sol && self.config.allow_voice_only_small {
             PolicyCheck {
                 allowed: true,
                 requires_confirmation: true,
                 confirmation_type: ConfirmationType::Verbal,
-                reason: format!("{} ({} SOL) - voice confirmation", action_name, amount_sol),
+                reason: format!("{} ({}) - voice confirmation", action_name, amount),
+                ticket: None,
+                preview: None,
             }
         } else if amount_sol > self.config.large_threshold_sol && self.config.hardware_for_large {
             if self.hardware_wallet_connected {
@@ -316,9 +713,11 @@ impl PolicyGate {
                     requires_confirmation: true,
                     confirmation_type: ConfirmationType::Hardware,
                     reason: format!(
-                        "{} ({} SOL) - hardware confirmation required",
-                        action_name, amount_sol
+                        "{} ({}) - hardware confirmation required",
+                        action_name, amount
                     ),
+                    ticket: None,
+                    preview: None,
                 }
             } else {
                 PolicyCheck {
@@ -326,9 +725,11 @@ impl PolicyGate {
                     requires_confirmation: true,
                     confirmation_type: ConfirmationType::Typed,
                     reason: format!(
-                        "{} ({} SOL) - typed confirmation (connect hardware for large txs)",
-                        action_name, amount_sol
+                        "{} ({}) - typed confirmation (connect hardware for large txs)",
+                        action_name, amount
                     ),
+                    ticket: None,
+                    preview: None,
                 }
             }
         } else {
@@ -340,11 +741,66 @@ impl PolicyGate {
                 } else {
                     ConfirmationType::Verbal
                 },
-                reason: format!("{} ({} SOL)", action_name, amount_sol),
+                reason: format!("{} ({})", action_name, amount),
+                ticket: None,
+                preview: None,
             }
         }
     }
 
+    /// Check a prospective spend against the total, per-category and
+    /// per-wallet rolling limits. Returns the reason for the first window it
+    /// would exceed.
+    fn check_spend_windows(
+        &self,
+        category: Option<SpendCategory>,
+        wallet: Option<&str>,
+        lamports: u64,
+        now: i64,
+    ) -> Option<String> {
+        let mut scopes = vec![("total".to_string(), None, None, &self.config.spend_limits)];
+        if let Some(cat) = category {
+            if let Some(limits) = self.config.category_limits.get(&cat) {
+                scopes.push((cat.label().to_string(), Some(cat), None, limits));
+            }
+        }
+        if let Some(wallet) = wallet {
+            if let Some(limits) = self.config.wallet_limits.get(wallet) {
+                scopes.push((format!("wallet '{}'", wallet), None, Some(wallet), limits));
+            }
+        }
+
+        for (scope, cat, wallet, limits) in scopes {
+            for (window, secs, limit_sol) in limits.windows() {
+                let Some(limit_sol) = limit_sol else { continue };
+                let spent = self.spent_since(now - secs, cat, wallet);
+                let total_sol = (spent + lamports) as f64 / 1_000_000_000.0;
+                if total_sol > limit_sol {
+                    return Some(format!(
+                        "{} {} limit ({} SOL) exceeded: {:.4} SOL already spent. Connect hardware wallet.",
+                        window,
+                        scope,
+                        limit_sol,
+                        spent as f64 / 1_000_000_000.0
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    /// Lamports in the ledger at or after `since`, optionally for one
+    /// category and/or wallet profile
+    fn spent_since(&self, since: i64, category: Option<SpendCategory>, wallet: Option<&str>) -> u64 {
+        self.ledger
+            .iter()
+            .filter(|r| r.timestamp >= since)
+            .filter(|r| category.is_none_or(|c| r.category == c))
+            .filter(|r| wallet.is_none_or(|w| r.wallet.as_deref() == Some(w)))
+            .map(|r| r.lamports)
+            .sum()
+    }
+
     /// Extract SOL amount from intent params
     fn extract_sol_amount(&self, params: &serde_json::Value) -> f64 {
         // Try common field names
@@ -357,8 +813,8 @@ impl PolicyGate {
         if let Some(lamports) = params.get("lamports").and_then(|v| v.as_u64()) {
             return lamports as f64 / 1_000_000_000.0;
         }
-        // SwapParams uses "amount" in lamports — must be caught here so swap
-        // spending is properly evaluated against session limits.
+        // SwapParams uses "amount" in base units of the input mint; it is only
+        // lamports for SOL input. SPL swaps are valued in `spend_value`.
         if let Some(amount) = params.get("amount").and_then(|v| v.as_u64()) {
             return amount as f64 / 1_000_000_000.0;
         }
@@ -375,6 +831,75 @@ impl PolicyGate {
         );
     }
 
+    /// Record a completed spend in the session and the rolling ledger.
+    /// Returns the ledger entry so the caller can persist it to OperatorDb.
+    pub fn record_spend(
+        &mut self,
+        category: SpendCategory,
+        lamports: u64,
+        signature: Option<String>,
+        wallet: Option<String>,
+    ) -> SpendRecord {
+        let record = SpendRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            category,
+            lamports,
+            timestamp: chrono::Utc::now().timestamp(),
+            signature,
+            wallet,
+        };
+        self.record_spending(lamports);
+        self.ledger.push(record.clone());
+        self.trim_ledger(record.timestamp);
+        record
+    }
+
+    /// Reserve a confirmed intent's SOL-equivalent value in the session and
+    /// the rolling ledger before it executes, so a limit re-checked under
+    /// the same lock already counts it. None for intents that don't spend or
+    /// can't be valued.
+    pub fn reserve_spend(&mut self, intent: &VoiceIntent) -> Option<SpendRecord> {
+        let category = spend_category(&intent.action)?;
+        let lamports = self.spend_lamports(intent).filter(|l| *l > 0)?;
+        let wallet = self.signing_wallet(intent).map(String::from);
+        Some(self.record_spend(category, lamports, None, wallet))
+    }
+
+    /// Attach the transaction signature to a reserved spend that went
+    /// through. Returns the entry so the caller can persist it to OperatorDb.
+    pub fn settle_spend(&mut self, id: &str, signature: Option<String>) -> Option<SpendRecord> {
+        let record = self.ledger.iter_mut().find(|r| r.id == id)?;
+        record.signature = signature;
+        Some(record.clone())
+    }
+
+    /// Drop a reserved spend that did not go through
+    pub fn release_spend(&mut self, id: &str) {
+        if let Some(index) = self.ledger.iter().position(|r| r.id == id) {
+            let record = self.ledger.remove(index);
+            self.session_spending_lamports = self.session_spending_lamports.saturating_sub(record.lamports);
+        }
+    }
+
+    /// Replace the in-memory ledger with entries loaded from OperatorDb
+    pub fn load_ledger(&mut self, records: Vec<SpendRecord>) {
+        self.ledger = records;
+        self.trim_ledger(chrono::Utc::now().timestamp());
+        info!("Loaded {} spend ledger entries", self.ledger.len());
+    }
+
+    /// Drop entries that have aged out of the longest window
+    fn trim_ledger(&mut self, now: i64) {
+        self.ledger.retain(|r| r.timestamp >= now - MONTH_SECS);
+    }
+
+    /// Spent SOL in the last day / week / month, across all categories
+    pub fn window_spending_sol(&self) -> (f64, f64, f64) {
+        let now = chrono::Utc::now().timestamp();
+        let sol = |secs| self.spent_since(now - secs, None, None) as f64 / 1_000_000_000.0;
+        (sol(DAY_SECS), sol(WEEK_SECS), sol(MONTH_SECS))
+    }
+
     /// Get current session spending
     pub fn session_spending_sol(&self) -> f64 {
         self.session_spending_lamports as f64 / 1_000_000_000.0
@@ -481,6 +1006,7 @@ mod tests {
             IntentAction::GetTaskStatus,
             IntentAction::GetBalance,
             IntentAction::GetAddress,
+            IntentAction::GetHistory,
             IntentAction::GetProtocolState,
             IntentAction::Help,
             IntentAction::Unknown,
@@ -505,6 +1031,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unattended_allows_only_work_actions() {
+        let mut gate = PolicyGate::with_config(PolicyConfig::unattended());
+        gate.set_rules(PolicyRules::from_toml_str(crate::policy_rules::UNATTENDED_RULES_TOML).unwrap());
+
+        for action in [IntentAction::ClaimTask, IntentAction::CompleteTask, IntentAction::GetBalance] {
+            let intent = VoiceIntent { action, params: serde_json::json!({ "task_id": "7" }), raw_transcript: None };
+            let check = gate.check_policy(&intent);
+            assert!(check.allowed && !check.requires_confirmation, "{:?}", intent.action);
+        }
+
+        for (action, params) in [
+            (IntentAction::CreateTask, serde_json::json!({ "reward_sol": 0.01 })),
+            (IntentAction::SwapTokens, serde_json::json!({ "amount": 0.01 })),
+            (IntentAction::CancelTask, serde_json::json!({ "task_id": "7" })),
+        ] {
+            let intent = VoiceIntent { action, params, raw_transcript: None };
+            assert!(!gate.check_policy(&intent).allowed, "{:?}", intent.action);
+        }
+    }
+
     #[test]
     fn test_spending_requires_confirmation() {
         let gate = PolicyGate::new();
@@ -651,6 +1198,9 @@ mod tests {
             hardware_for_large: false,
             large_threshold_sol: 10.0,
             blocked_actions: vec![],
+            spend_limits: SpendLimits::default(),
+            category_limits: HashMap::new(),
+            wallet_limits: HashMap::new(),
         };
 
         let gate = PolicyGate::with_config(config);
@@ -730,6 +1280,21 @@ mod tests {
         assert_eq!(check.confirmation_type, ConfirmationType::Typed);
     }
 
+    #[test]
+    fn test_approve_completion_typed_confirmation() {
+        let gate = PolicyGate::new();
+        let intent = VoiceIntent {
+            action: IntentAction::ApproveCompletion,
+            params: serde_json::json!({ "task_id": "42" }),
+            raw_transcript: None,
+        };
+
+        let check = gate.check_policy(&intent);
+        assert!(check.allowed);
+        assert!(check.requires_confirmation);
+        assert_eq!(check.confirmation_type, ConfirmationType::Typed);
+    }
+
     #[test]
     fn test_image_generation_no_confirmation() {
         let gate = PolicyGate::new();
@@ -831,4 +1396,481 @@ mod tests {
         assert_eq!(config.large_threshold_sol, HIGH_VALUE_THRESHOLD_SOL);
         assert!(config.blocked_actions.contains(&"export_key".to_string()));
     }
+
+    fn spend(category: SpendCategory, sol: f64, age_secs: i64) -> SpendRecord {
+        SpendRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            category,
+            lamports: (sol * 1_000_000_000.0) as u64,
+            timestamp: chrono::Utc::now().timestamp() - age_secs,
+            signature: None,
+            wallet: None,
+        }
+    }
+
+    fn create_task_intent(sol: f64) -> VoiceIntent {
+        VoiceIntent {
+            action: IntentAction::CreateTask,
+            params: serde_json::json!({ "reward_sol": sol }),
+            raw_transcript: None,
+        }
+    }
+
+    #[test]
+    fn test_daily_limit_survives_restart() {
+        // Spent 9.5 SOL an hour ago in a previous run
+        let mut gate = PolicyGate::new();
+        gate.load_ledger(vec![spend(SpendCategory::TaskCreation, 9.5, 3600)]);
+        assert_eq!(gate.session_spending_sol(), 0.0);
+
+        let check = gate.check_policy(&create_task_intent(1.0));
+        assert!(!check.allowed);
+        assert_eq!(check.confirmation_type, ConfirmationType::Hardware);
+        assert!(check.reason.contains("daily"));
+
+        gate.set_hardware_wallet(true);
+        assert!(gate.check_policy(&create_task_intent(1.0)).allowed);
+    }
+
+    #[test]
+    fn test_weekly_limit_ignores_expired_days() {
+        // 25 SOL spread over the past week, but none in the last 24h
+        let mut gate = PolicyGate::new();
+        gate.load_ledger(vec![
+            spend(SpendCategory::TaskCreation, 9.0, 2 * DAY_SECS),
+            spend(SpendCategory::Swap, 9.0, 4 * DAY_SECS),
+            spend(SpendCategory::Swap, 9.0, 6 * DAY_SECS),
+            // Older than the monthly window — trimmed on load
+            spend(SpendCategory::Swap, 50.0, 40 * DAY_SECS),
+        ]);
+
+        assert!(gate.check_policy(&create_task_intent(2.0)).allowed);
+
+        let check = gate.check_policy(&create_task_intent(4.0));
+        assert!(!check.allowed);
+        assert!(check.reason.contains("weekly"));
+
+        let (day, week, month) = gate.window_spending_sol();
+        assert_eq!(day, 0.0);
+        assert_eq!(week, 27.0);
+        assert_eq!(month, 27.0);
+    }
+
+    #[test]
+    fn test_category_limit_only_applies_to_its_category() {
+        let mut config = PolicyConfig::default();
+        config.category_limits.insert(
+            SpendCategory::Swap,
+            SpendLimits { daily_sol: Some(1.0), weekly_sol: None, monthly_sol: None },
+        );
+        let mut gate = PolicyGate::with_config(config);
+        gate.load_ledger(vec![spend(SpendCategory::Swap, 0.8, 60)]);
+
+        let swap = VoiceIntent {
+            action: IntentAction::SwapTokens,
+            params: serde_json::json!({ "amount": 500_000_000u64 }),
+            raw_transcript: None,
+        };
+        let check = gate.check_policy(&swap);
+        assert!(!check.allowed);
+        assert!(check.reason.contains("swap"));
+
+        // Task creation only counts against the total limits
+        assert!(gate.check_policy(&create_task_intent(0.5)).allowed);
+    }
+
+    #[test]
+    fn test_record_spend_updates_ledger_and_session() {
+        let mut gate = PolicyGate::with_config(PolicyConfig {
+            spend_limits: SpendLimits::unlimited(),
+            ..PolicyConfig::default()
+        });
+
+        let record = gate.record_spend(SpendCategory::TaskCreation, 2_000_000_000, Some("sig".into()), None);
+        assert_eq!(record.category, SpendCategory::TaskCreation);
+        assert_eq!(gate.session_spending_sol(), 2.0);
+        assert_eq!(gate.window_spending_sol().0, 2.0);
+
+        // Resetting the session does not forget the ledger
+        gate.reset_session();
+        assert_eq!(gate.window_spending_sol().0, 2.0);
+    }
+
+    #[test]
+    fn test_reserved_spend_counts_until_released() {
+        let mut gate = PolicyGate::new();
+        let intent = create_task_intent(6.0);
+
+        let reserved = gate.reserve_spend(&intent).unwrap();
+        assert_eq!(reserved.lamports, 6_000_000_000);
+        assert_eq!(reserved.signature, None);
+        // A second spend that fits on its own no longer fits the session limit
+        assert!(!gate.check_policy(&intent).allowed);
+
+        gate.release_spend(&reserved.id);
+        assert_eq!(gate.session_spending_sol(), 0.0);
+        assert_eq!(gate.window_spending_sol().0, 0.0);
+        assert!(gate.check_policy(&intent).allowed);
+
+        let reserved = gate.reserve_spend(&intent).unwrap();
+        let settled = gate.settle_spend(&reserved.id, Some("sig".into())).unwrap();
+        assert_eq!(settled.signature.as_deref(), Some("sig"));
+        assert_eq!(gate.session_spending_sol(), 6.0);
+
+        // Nothing to reserve for reads
+        let read = VoiceIntent { action: IntentAction::GetBalance, params: serde_json::json!({}), raw_transcript: None };
+        assert!(gate.reserve_spend(&read).is_none());
+    }
+
+    #[test]
+    fn test_blocked_actions_use_serde_names() {
+        let gate = PolicyGate::with_config(PolicyConfig {
+            blocked_actions: vec!["create_task".into(), "posttweet".into()],
+            ..PolicyConfig::default()
+        });
+
+        let check = gate.check_policy(&create_task_intent(0.01));
+        assert!(!check.allowed);
+        assert!(check.reason.contains("'create_task'"));
+
+        // Legacy Debug-lowercase names are still honoured
+        let tweet = VoiceIntent {
+            action: IntentAction::PostTweet,
+            params: serde_json::json!({}),
+            raw_transcript: None,
+        };
+        let explanation = gate.explain(&tweet);
+        assert!(!explanation.check.allowed);
+        assert_eq!(explanation.source, PolicySource::BlockedAction);
+    }
+
+    #[test]
+    fn test_explain_reports_rule() {
+        let gate = PolicyGate::new();
+        let explanation = gate.explain(&create_task_intent(0.05));
+        assert_eq!(explanation.source, PolicySource::Rule);
+        assert_eq!(explanation.rule.as_deref(), Some("create_task"));
+        assert_eq!(explanation.check.confirmation_type, ConfirmationType::Verbal);
+        assert!(explanation.check.reason.starts_with("create task"));
+    }
+
+    #[test]
+    fn test_custom_rules_override_defaults() {
+        let mut gate = PolicyGate::new();
+        let mut rules = PolicyRules::from_toml_str(
+            r#"
+            [[rules]]
+            name = "no_night_tasks"
+            actions = ["create_task"]
+            hours = { start = 22, end = 6 }
+            outcome = "deny"
+            reason = "No task creation overnight"
+            "#,
+        )
+        .unwrap();
+        rules.rules.extend(PolicyRules::default().rules);
+        gate.set_rules(rules);
+
+        let night = gate.explain_at(&create_task_intent(0.05), 23);
+        assert!(!night.check.allowed);
+        assert_eq!(night.rule.as_deref(), Some("no_night_tasks"));
+        assert_eq!(night.rule_index, Some(0));
+
+        let day = gate.explain_at(&create_task_intent(0.05), 12);
+        assert!(day.check.allowed);
+        assert_eq!(day.rule.as_deref(), Some("create_task"));
+    }
+
+    #[test]
+    fn test_spend_limits_apply_before_rules() {
+        let mut gate = PolicyGate::new();
+        gate.set_rules(
+            PolicyRules::from_toml_str(
+                r#"
+                [[rules]]
+                name = "yolo"
+                actions = ["*"]
+                outcome = "allow"
+                "#,
+            )
+            .unwrap(),
+        );
+        gate.record_spending(9_500_000_000);
+
+        let explanation = gate.explain(&create_task_intent(1.0));
+        assert!(!explanation.check.allowed);
+        assert_eq!(explanation.source, PolicySource::SpendLimit);
+    }
+
+    #[test]
+    fn test_unmatched_action_falls_back_to_typed() {
+        let mut gate = PolicyGate::new();
+        gate.set_rules(PolicyRules { rules: vec![] });
+
+        let explanation = gate.explain(&create_task_intent(0.01));
+        assert_eq!(explanation.source, PolicySource::Fallback);
+        assert!(explanation.check.allowed);
+        assert_eq!(explanation.check.confirmation_type, ConfirmationType::Typed);
+    }
+
+    fn priced_gate() -> PolicyGate {
+        let mut gate = PolicyGate::new();
+        let oracle = gate.price_oracle_mut();
+        oracle.set_price(tokens::SOL, 100.0);
+        oracle.set_price(tokens::USDC, 1.0);
+        oracle.set_price(&skr_mint().to_string(), 0.02);
+        gate
+    }
+
+    fn usdc_swap(base_units: u64) -> VoiceIntent {
+        VoiceIntent {
+            action: IntentAction::SwapTokens,
+            params: serde_json::json!({
+                "input_mint": "USDC",
+                "output_mint": tokens::SOL,
+                "amount": base_units,
+            }),
+            raw_transcript: None,
+        }
+    }
+
+    #[test]
+    fn test_spl_swap_valued_by_decimals_and_price() {
+        let gate = priced_gate();
+
+        // 1 USDC at $1 with SOL at $100
+        let value = gate.spend_value(&usdc_swap(1_000_000)).unwrap();
+        assert_eq!(value.sol, 0.01);
+        assert_eq!(value.usd, Some(1.0));
+
+        // 2,000 USDC = 20 SOL-equivalent, over the session limit. Read as
+        // lamports it would have been 2 SOL.
+        let explanation = gate.explain(&usdc_swap(2_000_000_000));
+        assert!(!explanation.check.allowed);
+        assert_eq!(explanation.source, PolicySource::SpendLimit);
+    }
+
+    #[test]
+    fn test_unknown_mint_fails_closed_to_typed() {
+        let mut gate = priced_gate();
+        let intent = VoiceIntent {
+            action: IntentAction::SwapTokens,
+            params: serde_json::json!({
+                "input_mint": "SomeUnknownMint111",
+                "output_mint": tokens::SOL,
+                "amount": 1u64,
+            }),
+            raw_transcript: None,
+        };
+
+        assert_eq!(gate.stale_mints(&intent), vec!["SomeUnknownMint111".to_string()]);
+        let explanation = gate.explain(&intent);
+        assert_eq!(explanation.source, PolicySource::UnknownValue);
+        assert!(explanation.check.allowed && explanation.check.requires_confirmation);
+        assert_eq!(explanation.check.confirmation_type, ConfirmationType::Typed);
+
+        // An explicit deny rule still wins
+        gate.set_rules(PolicyRules::from_toml_str(r#"
+            [[rules]]
+            name = "no_swaps"
+            actions = ["swap_tokens"]
+            outcome = "deny"
+            reason = "Swaps are disabled"
+        "#).unwrap());
+        let explanation = gate.explain(&intent);
+        assert_eq!(explanation.source, PolicySource::Rule);
+        assert!(!explanation.check.allowed);
+
+        // Without a SOL price even USDC cannot be valued
+        let gate = PolicyGate::new();
+        assert_eq!(gate.stale_mints(&usdc_swap(1_000_000)).len(), 2);
+        assert_eq!(gate.explain(&usdc_swap(1_000_000)).source, PolicySource::UnknownValue);
+    }
+
+    #[test]
+    fn test_skr_reward_counts_toward_value() {
+        let intent = VoiceIntent {
+            action: IntentAction::CreateTask,
+            params: serde_json::json!({ "reward_sol": 0.05, "reward_skr": 5_000.0 }),
+            raw_transcript: None,
+        };
+
+        // 5,000 SKR at $0.02 = $100 = 1 SOL on top of the 0.05 SOL reward
+        let gate = priced_gate();
+        assert!(gate.stale_mints(&intent).is_empty());
+        let value = gate.spend_value(&intent).unwrap();
+        assert!((value.sol - 1.05).abs() < 1e-9);
+        assert_eq!(gate.check_policy(&intent).confirmation_type, ConfirmationType::Typed);
+
+        let unpriced = PolicyGate::new();
+        assert_eq!(unpriced.explain(&intent).source, PolicySource::UnknownValue);
+    }
+
+    #[test]
+    fn test_deny_rule_applies_to_unvalued_spend() {
+        let mut gate = PolicyGate::new();
+        gate.set_rules(
+            PolicyRules::from_toml_str(
+                r#"
+                [[rules]]
+                name = "usdc_only"
+                actions = ["swap_tokens"]
+                mint_not_in = ["EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                               "So11111111111111111111111111111111111111112"]
+                outcome = "deny"
+                "#,
+            )
+            .unwrap(),
+        );
+        let intent = VoiceIntent {
+            action: IntentAction::SwapTokens,
+            params: serde_json::json!({ "input_mint": "BONK", "output_mint": "SOL", "amount": 1u64 }),
+            raw_transcript: None,
+        };
+        let explanation = gate.explain(&intent);
+        assert!(!explanation.check.allowed);
+        assert_eq!(explanation.rule.as_deref(), Some("usdc_only"));
+    }
+
+    #[test]
+    fn test_confirmation_ticket_binds_intent() {
+        let mut gate = PolicyGate::new();
+        let check = gate.check_and_issue(&create_task_intent(0.5));
+        assert!(check.requires_confirmation);
+        let required = check.confirmation_type.clone();
+        let ticket = check.ticket.expect("confirmation should come with a ticket");
+
+        assert_eq!(
+            gate.redeem_ticket(&create_task_intent(5.0), &ticket, &required),
+            Err(TicketError::IntentMismatch)
+        );
+        assert!(gate.redeem_ticket(&create_task_intent(0.5), &ticket, &required).is_ok());
+        assert_eq!(
+            gate.redeem_ticket(&create_task_intent(0.5), &ticket, &required),
+            Err(TicketError::NotOutstanding)
+        );
+
+        // Read-only actions need no ticket
+        let balance = VoiceIntent {
+            action: IntentAction::GetBalance,
+            params: serde_json::json!({}),
+            raw_transcript: None,
+        };
+        assert!(gate.check_and_issue(&balance).ticket.is_none());
+    }
+
+    #[test]
+    fn test_wallet_limits_scope_to_profile() {
+        let mut config = PolicyConfig::default();
+        config.wallet_limits.insert(
+            "hot".into(),
+            SpendLimits { daily_sol: Some(1.0), weekly_sol: None, monthly_sol: None },
+        );
+        let mut gate = PolicyGate::with_config(config);
+        gate.set_active_wallet(Some("hot".into()));
+        gate.load_ledger(vec![SpendRecord {
+            wallet: Some("hot".into()),
+            ..spend(SpendCategory::TaskCreation, 0.9, 60)
+        }]);
+
+        // The hot wallet's own daily limit applies
+        let check = gate.check_policy(&create_task_intent(0.2));
+        assert!(!check.allowed);
+        assert!(check.reason.contains("wallet 'hot'"), "{}", check.reason);
+
+        // Another profile only answers to the global limits
+        let mut intent = create_task_intent(0.2);
+        intent.params["wallet"] = "treasury".into();
+        assert!(gate.check_policy(&intent).allowed);
+    }
+
+    #[test]
+    fn test_non_active_wallet_needs_typed_confirmation() {
+        let mut gate = PolicyGate::new();
+        gate.set_active_wallet(Some("hot".into()));
+
+        let check = gate.check_policy(&create_task_intent(0.05));
+        assert_eq!(check.confirmation_type, ConfirmationType::Verbal);
+
+        let mut intent = create_task_intent(0.05);
+        intent.params["wallet"] = "treasury".into();
+        let check = gate.check_policy(&intent);
+        assert_eq!(check.confirmation_type, ConfirmationType::Typed);
+        assert!(check.reason.contains("wallet 'treasury'"), "{}", check.reason);
+    }
+
+    #[test]
+    fn test_ticket_bound_to_active_wallet() {
+        let mut gate = PolicyGate::new();
+        gate.set_active_wallet(Some("hot".into()));
+        let intent = create_task_intent(0.5);
+        let check = gate.check_and_issue(&intent);
+        let ticket = check.ticket.unwrap();
+        let required = check.confirmation_type;
+
+        // Switching wallets after approval invalidates the ticket
+        gate.set_active_wallet(Some("treasury".into()));
+        assert_eq!(
+            gate.redeem_ticket(&intent, &ticket, &required),
+            Err(TicketError::IntentMismatch)
+        );
+
+        gate.set_active_wallet(Some("hot".into()));
+        assert_eq!(gate.redeem_ticket(&intent, &ticket, &required), Ok(()));
+    }
+
+    #[test]
+    fn test_ticket_rejected_when_confirmation_escalates() {
+        let mut gate = PolicyGate::new();
+        gate.set_hardware_wallet(true);
+        let intent = create_task_intent(0.05);
+        let check = gate.check_and_issue(&intent);
+        assert_eq!(check.confirmation_type, ConfirmationType::Verbal);
+        let ticket = check.ticket.unwrap();
+
+        // Thresholds tightened between approval and execution
+        gate.update_config(PolicyConfig {
+            allow_voice_only_small: false,
+            large_threshold_sol: 0.01,
+            ..gate.config().clone()
+        });
+        let recheck = gate.check_policy(&intent);
+        assert!(recheck.allowed);
+        assert_eq!(recheck.confirmation_type, ConfirmationType::Hardware);
+        assert_eq!(
+            gate.redeem_ticket(&intent, &ticket, &recheck.confirmation_type),
+            Err(TicketError::Escalated {
+                issued: ConfirmationType::Verbal,
+                required: ConfirmationType::Hardware,
+            })
+        );
+    }
+
+    #[test]
+    fn test_default_rules_cover_every_action() {
+        let gate = PolicyGate::new();
+        let all = serde_json::json!([
+            "create_task", "claim_task", "complete_task", "cancel_task", "approve_completion",
+            "list_open_tasks", "get_task_status", "get_balance", "get_address", "get_history",
+            "get_protocol_state", "code_fix", "code_review", "code_generate", "code_explain",
+            "swap_tokens", "get_swap_quote", "get_token_price", "post_tweet", "post_thread",
+            "post_discord", "post_discord_embed", "send_email", "send_bulk_email",
+            "generate_image", "generate_video", "create_gist", "create_git_hub_issue",
+            "add_git_hub_comment", "trigger_git_hub_workflow", "scan_devices", "pair_device",
+            "unpair_device", "list_devices", "device_status", "configure_device", "help",
+            "unknown"
+        ]);
+        let actions: Vec<IntentAction> = serde_json::from_value(all).unwrap();
+
+        for action in actions {
+            let intent = VoiceIntent { action, params: serde_json::json!({}), raw_transcript: None };
+            let explanation = gate.explain(&intent);
+            assert_eq!(
+                explanation.source,
+                PolicySource::Rule,
+                "{:?} is not covered by the default rules",
+                intent.action
+            );
+        }
+    }
 }
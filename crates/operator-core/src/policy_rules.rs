@@ -0,0 +1,398 @@
+//! ============================================================================
+//! Policy Rules - Declarative Rule Engine for PolicyGate
+//! ============================================================================
+//! Ordered rules loaded from TOML or JSON that decide what confirmation an
+//! intent needs:
+//! - Match on action, SOL-equivalent or USD amount range, recipient/mint allowlists,
+//!   local time of day, access tier and signing wallet profile
+//! - First matching rule wins; outcome is allow / deny / a confirmation type
+//! - The shipped default rule set (policy_rules.default.toml) reproduces the
+//!   built-in behavior; policy_rules.unattended.toml is the autonomous
+//!   worker's allow-list
+//!
+//! Spend limits and `blocked_actions` are enforced by PolicyGate before any
+//! rule is consulted, so a rule can tighten but never loosen them.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::access::AccessTier;
+use crate::types::ConfirmationType;
+
+/// Default rule set, embedded at compile time
+pub const DEFAULT_RULES_TOML: &str = include_str!("policy_rules.default.toml");
+
+/// Rule set for the autonomous worker, embedded at compile time
+pub const UNATTENDED_RULES_TOML: &str = include_str!("policy_rules.unattended.toml");
+
+/// What a matching rule decides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOutcome {
+    /// Allowed with no confirmation
+    Allow,
+    /// Rejected outright
+    Deny,
+    Verbal,
+    Typed,
+    Hardware,
+    /// Confirmation picked from the PolicyConfig amount thresholds
+    AmountBased,
+}
+
+impl RuleOutcome {
+    /// Fixed confirmation type for this outcome (None for Deny/AmountBased)
+    pub fn confirmation_type(&self) -> Option<ConfirmationType> {
+        match self {
+            Self::Allow => Some(ConfirmationType::None),
+            Self::Verbal => Some(ConfirmationType::Verbal),
+            Self::Typed => Some(ConfirmationType::Typed),
+            Self::Hardware => Some(ConfirmationType::Hardware),
+            Self::Deny | Self::AmountBased => None,
+        }
+    }
+}
+
+/// Local time-of-day window. `end` is exclusive; `start > end` wraps midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HourRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl HourRange {
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start <= self.end {
+            hour >= self.start && hour < self.end
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+/// A single policy rule. Every condition that is set must match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub name: String,
+    /// Intent action names (serde names); empty or "*" matches any action
+    #[serde(default)]
+    pub actions: Vec<String>,
+    pub min_sol: Option<f64>,
+    pub max_sol: Option<f64>,
+    /// USD range; never matches an intent whose USD value is unknown
+    pub min_usd: Option<f64>,
+    pub max_usd: Option<f64>,
+    #[serde(default)]
+    pub recipient_in: Vec<String>,
+    #[serde(default)]
+    pub recipient_not_in: Vec<String>,
+    #[serde(default)]
+    pub mint_in: Vec<String>,
+    #[serde(default)]
+    pub mint_not_in: Vec<String>,
+    pub hours: Option<HourRange>,
+    #[serde(default)]
+    pub tiers: Vec<AccessTier>,
+    /// Wallet profile names; never matches the unnamed default wallet
+    #[serde(default)]
+    pub wallets: Vec<String>,
+    pub outcome: RuleOutcome,
+    /// Shown to the user; for `amount_based` this is the action label
+    pub reason: Option<String>,
+}
+
+/// Facts about an intent that rules are matched against
+#[derive(Debug, Clone, Default)]
+pub struct RuleContext {
+    pub action: String,
+    /// SOL-equivalent value of the intent
+    pub amount_sol: f64,
+    pub amount_usd: Option<f64>,
+    pub recipient: Option<String>,
+    pub mints: Vec<String>,
+    /// Local hour of day (0-23)
+    pub hour: u32,
+    pub tier: Option<AccessTier>,
+    /// Wallet profile that would sign (None for the unnamed default wallet)
+    pub wallet: Option<String>,
+}
+
+impl PolicyRule {
+    pub fn matches(&self, ctx: &RuleContext) -> bool {
+        if !self.actions.is_empty()
+            && !self.actions.iter().any(|a| a == "*" || *a == ctx.action)
+        {
+            return false;
+        }
+        if self.min_sol.is_some_and(|min| ctx.amount_sol < min) {
+            return false;
+        }
+        if self.max_sol.is_some_and(|max| ctx.amount_sol > max) {
+            return false;
+        }
+        if self.min_usd.is_some_and(|min| ctx.amount_usd.is_none_or(|usd| usd < min)) {
+            return false;
+        }
+        if self.max_usd.is_some_and(|max| ctx.amount_usd.is_none_or(|usd| usd > max)) {
+            return false;
+        }
+        if !self.recipient_in.is_empty()
+            && !ctx.recipient.as_ref().is_some_and(|r| self.recipient_in.contains(r))
+        {
+            return false;
+        }
+        if !self.recipient_not_in.is_empty()
+            && ctx.recipient.as_ref().is_none_or(|r| self.recipient_not_in.contains(r))
+        {
+            return false;
+        }
+        if !self.mint_in.is_empty()
+            && (ctx.mints.is_empty() || !ctx.mints.iter().all(|m| self.mint_in.contains(m)))
+        {
+            return false;
+        }
+        if !self.mint_not_in.is_empty()
+            && ctx.mints.iter().all(|m| self.mint_not_in.contains(m))
+        {
+            return false;
+        }
+        if self.hours.is_some_and(|h| !h.contains(ctx.hour)) {
+            return false;
+        }
+        if !self.tiers.is_empty() && !ctx.tier.is_some_and(|t| self.tiers.contains(&t)) {
+            return false;
+        }
+        if !self.wallets.is_empty()
+            && !ctx.wallet.as_ref().is_some_and(|w| self.wallets.contains(w))
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Ordered rule set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRules {
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+impl Default for PolicyRules {
+    fn default() -> Self {
+        Self::from_toml_str(DEFAULT_RULES_TOML)
+            .expect("Invalid default policy rules — this is a compile-time constant")
+    }
+}
+
+impl PolicyRules {
+    pub fn from_toml_str(s: &str) -> Result<Self> {
+        toml::from_str(s).map_err(|e| anyhow!("Failed to parse policy rules TOML: {}", e))
+    }
+
+    pub fn from_json_str(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map_err(|e| anyhow!("Failed to parse policy rules JSON: {}", e))
+    }
+
+    /// Load a rule file; `.json` is parsed as JSON, anything else as TOML
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read policy rules {}: {}", path.display(), e))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            _ => Self::from_toml_str(&contents),
+        }
+    }
+
+    /// Load the user's rule file from AGENC_POLICY_PATH, or else
+    /// ~/.agenc/policy.toml / policy.json. Returns None if there is none.
+    pub fn load_user_rules() -> Result<Option<Self>> {
+        let path = match std::env::var("AGENC_POLICY_PATH") {
+            Ok(p) => PathBuf::from(p),
+            Err(_) => {
+                let home = dirs::home_dir().ok_or_else(|| anyhow!("Cannot determine home directory"))?;
+                let agenc_dir = home.join(".agenc");
+                match ["policy.toml", "policy.json"]
+                    .iter()
+                    .map(|name| agenc_dir.join(name))
+                    .find(|p| p.exists())
+                {
+                    Some(p) => p,
+                    None => return Ok(None),
+                }
+            }
+        };
+
+        let rules = Self::load(&path)?;
+        info!("Loaded {} policy rules from {}", rules.rules.len(), path.display());
+        Ok(Some(rules))
+    }
+
+    /// The autonomous worker's rules: AGENC_WORKER_POLICY_PATH if set,
+    /// otherwise the shipped unattended rule set
+    pub fn unattended() -> Result<Self> {
+        match std::env::var("AGENC_WORKER_POLICY_PATH") {
+            Ok(path) => {
+                let rules = Self::load(Path::new(&path))?;
+                info!("Loaded {} worker policy rules from {}", rules.rules.len(), path);
+                Ok(rules)
+            }
+            Err(_) => Self::from_toml_str(UNATTENDED_RULES_TOML),
+        }
+    }
+
+    /// First matching rule and its index
+    pub fn evaluate(&self, ctx: &RuleContext) -> Option<(usize, &PolicyRule)> {
+        self.rules.iter().enumerate().find(|(_, rule)| rule.matches(ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(action: &str) -> RuleContext {
+        RuleContext {
+            action: action.into(),
+            hour: 12,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_rules_parse() {
+        let rules = PolicyRules::default();
+        assert!(!rules.rules.is_empty());
+        let (_, rule) = rules.evaluate(&ctx("get_balance")).unwrap();
+        assert_eq!(rule.name, "read_only");
+        assert_eq!(rule.outcome, RuleOutcome::Allow);
+    }
+
+    #[test]
+    fn test_first_match_wins() {
+        let rules = PolicyRules::from_toml_str(
+            r#"
+            [[rules]]
+            name = "big_tasks"
+            actions = ["create_task"]
+            min_sol = 5.0
+            outcome = "deny"
+
+            [[rules]]
+            name = "any"
+            actions = ["*"]
+            outcome = "typed"
+            "#,
+        )
+        .unwrap();
+
+        let mut c = ctx("create_task");
+        c.amount_sol = 6.0;
+        assert_eq!(rules.evaluate(&c).unwrap().1.name, "big_tasks");
+
+        c.amount_sol = 1.0;
+        assert_eq!(rules.evaluate(&c).unwrap().1.name, "any");
+    }
+
+    #[test]
+    fn test_mint_allowlist() {
+        let rule: PolicyRule = serde_json::from_value(serde_json::json!({
+            "name": "mints",
+            "mint_not_in": ["SOL", "USDC"],
+            "outcome": "deny",
+        }))
+        .unwrap();
+
+        let mut c = ctx("swap_tokens");
+        c.mints = vec!["SOL".into(), "USDC".into()];
+        assert!(!rule.matches(&c));
+        c.mints = vec!["SOL".into(), "BONK".into()];
+        assert!(rule.matches(&c));
+        c.mints.clear();
+        assert!(!rule.matches(&c));
+    }
+
+    #[test]
+    fn test_recipient_allowlist() {
+        let rule: PolicyRule = serde_json::from_value(serde_json::json!({
+            "name": "known",
+            "recipient_in": ["alice"],
+            "outcome": "verbal",
+        }))
+        .unwrap();
+
+        let mut c = ctx("swap_tokens");
+        assert!(!rule.matches(&c));
+        c.recipient = Some("alice".into());
+        assert!(rule.matches(&c));
+        c.recipient = Some("mallory".into());
+        assert!(!rule.matches(&c));
+    }
+
+    #[test]
+    fn test_usd_range_needs_known_value() {
+        let rule: PolicyRule = serde_json::from_value(serde_json::json!({
+            "name": "big_usd",
+            "min_usd": 1000.0,
+            "outcome": "hardware",
+        }))
+        .unwrap();
+
+        let mut c = ctx("swap_tokens");
+        assert!(!rule.matches(&c));
+        c.amount_usd = Some(999.0);
+        assert!(!rule.matches(&c));
+        c.amount_usd = Some(2500.0);
+        assert!(rule.matches(&c));
+    }
+
+    #[test]
+    fn test_wallet_condition() {
+        let rule: PolicyRule = serde_json::from_value(serde_json::json!({
+            "name": "treasury",
+            "wallets": ["treasury"],
+            "outcome": "hardware",
+        }))
+        .unwrap();
+
+        let mut c = ctx("create_task");
+        assert!(!rule.matches(&c));
+        c.wallet = Some("hot".into());
+        assert!(!rule.matches(&c));
+        c.wallet = Some("treasury".into());
+        assert!(rule.matches(&c));
+    }
+
+    #[test]
+    fn test_hour_range_wraps_midnight() {
+        let night = HourRange { start: 22, end: 6 };
+        assert!(night.contains(23));
+        assert!(night.contains(0));
+        assert!(!night.contains(6));
+        assert!(!night.contains(12));
+
+        let day = HourRange { start: 9, end: 17 };
+        assert!(day.contains(9));
+        assert!(!day.contains(17));
+    }
+
+    #[test]
+    fn test_tier_condition() {
+        let rules = PolicyRules::from_json_str(
+            r#"{ "rules": [
+                { "name": "whales", "tiers": ["whale", "diamond"], "outcome": "allow" }
+            ] }"#,
+        )
+        .unwrap();
+
+        let mut c = ctx("post_tweet");
+        assert!(rules.evaluate(&c).is_none());
+        c.tier = Some(AccessTier::Pro);
+        assert!(rules.evaluate(&c).is_none());
+        c.tier = Some(AccessTier::Whale);
+        assert!(rules.evaluate(&c).is_some());
+    }
+}
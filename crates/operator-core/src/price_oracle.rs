@@ -0,0 +1,229 @@
+//! ============================================================================
+//! Price Oracle - Token Decimals & USD Prices for PolicyGate
+//! ============================================================================
+//! PolicyGate is synchronous, so token values are read from this cache rather
+//! than fetched during a policy check:
+//! - Decimals for well-known mints (SOL, USDC, USDT, JUP, SKR) are built in;
+//!   others are learned from the mint account
+//! - USD prices come from Jupiter and expire after `max_age_secs`
+//!
+//! Callers refresh the mints an intent touches (`PolicyGate::stale_mints`)
+//! before checking policy. A mint without known decimals or a fresh price has
+//! no value, and PolicyGate fails closed to typed confirmation for it.
+//! ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{debug, warn};
+
+use crate::agenc_program::{skr_mint, SKR_DECIMALS};
+use crate::executor::{tokens, JupiterSwapExecutor};
+
+/// How long a fetched price is trusted
+pub const DEFAULT_PRICE_MAX_AGE_SECS: i64 = 300;
+
+/// Decimals of well-known mints, without an RPC round trip
+pub fn known_decimals(mint: &str) -> Option<u8> {
+    match mint {
+        tokens::SOL => Some(9),
+        tokens::USDC | tokens::USDT | tokens::JUP => Some(6),
+        m if m == skr_mint().to_string() => Some(SKR_DECIMALS),
+        _ => None,
+    }
+}
+
+/// Resolve a symbol ("USDC") to its mint; anything else is taken as a mint
+pub fn resolve_mint(token: &str) -> String {
+    tokens::resolve(token).unwrap_or_else(|| token.to_string())
+}
+
+/// Freshly fetched facts about a mint (either may be missing on error)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenQuote {
+    pub mint: String,
+    pub decimals: Option<u8>,
+    pub price_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CachedPrice {
+    price_usd: f64,
+    fetched_at: i64,
+}
+
+/// Cache of mint decimals and USD prices
+#[derive(Debug, Clone)]
+pub struct PriceOracle {
+    decimals: HashMap<String, u8>,
+    prices: HashMap<String, CachedPrice>,
+    max_age_secs: i64,
+}
+
+impl Default for PriceOracle {
+    fn default() -> Self {
+        Self::new(DEFAULT_PRICE_MAX_AGE_SECS)
+    }
+}
+
+impl PriceOracle {
+    pub fn new(max_age_secs: i64) -> Self {
+        Self {
+            decimals: HashMap::new(),
+            prices: HashMap::new(),
+            max_age_secs,
+        }
+    }
+
+    /// Decimals for a mint, if known
+    pub fn decimals(&self, mint: &str) -> Option<u8> {
+        known_decimals(mint).or_else(|| self.decimals.get(mint).copied())
+    }
+
+    /// USD price for a mint, if one was fetched within `max_age_secs`
+    pub fn price_usd(&self, mint: &str) -> Option<f64> {
+        self.price_usd_at(mint, chrono::Utc::now().timestamp())
+    }
+
+    fn price_usd_at(&self, mint: &str, now: i64) -> Option<f64> {
+        self.prices
+            .get(mint)
+            .filter(|p| now - p.fetched_at <= self.max_age_secs)
+            .map(|p| p.price_usd)
+    }
+
+    pub fn set_decimals(&mut self, mint: &str, decimals: u8) {
+        self.decimals.insert(mint.to_string(), decimals);
+    }
+
+    /// Cache a price. Zero, negative or non-finite prices are ignored so a
+    /// bad feed leaves the mint unpriced rather than worthless.
+    pub fn set_price(&mut self, mint: &str, price_usd: f64) {
+        self.set_price_at(mint, price_usd, chrono::Utc::now().timestamp());
+    }
+
+    fn set_price_at(&mut self, mint: &str, price_usd: f64, fetched_at: i64) {
+        if !price_usd.is_finite() || price_usd <= 0.0 {
+            warn!("Ignoring invalid price {} for {}", price_usd, mint);
+            return;
+        }
+        self.prices.insert(mint.to_string(), CachedPrice { price_usd, fetched_at });
+    }
+
+    /// Store the results of `fetch_quotes`
+    pub fn apply(&mut self, quotes: Vec<TokenQuote>) {
+        for quote in quotes {
+            if let Some(decimals) = quote.decimals {
+                self.set_decimals(&quote.mint, decimals);
+            }
+            if let Some(price) = quote.price_usd {
+                self.set_price(&quote.mint, price);
+            }
+        }
+    }
+
+    /// Whether the mint has known decimals and a fresh price
+    pub fn is_fresh(&self, mint: &str) -> bool {
+        self.decimals(mint).is_some() && self.price_usd(mint).is_some()
+    }
+
+    /// USD value of `amount` base units of `mint`
+    pub fn value_usd(&self, mint: &str, amount: u64) -> Option<f64> {
+        let decimals = self.decimals(mint)?;
+        let display = amount as f64 / 10f64.powi(decimals as i32);
+        self.display_value_usd(mint, display)
+    }
+
+    /// USD value of `amount` display units (e.g. 1.5 SKR) of `mint`
+    pub fn display_value_usd(&self, mint: &str, amount: f64) -> Option<f64> {
+        self.price_usd(mint).map(|price| amount * price)
+    }
+
+    /// Convert a USD value to SOL at the cached SOL price
+    pub fn usd_to_sol(&self, usd: f64) -> Option<f64> {
+        self.price_usd(tokens::SOL).map(|price| usd / price)
+    }
+}
+
+/// Fetch decimals (when not already known) and USD prices for `mints`.
+/// Failures are logged and leave the field empty so the policy fails closed.
+pub async fn fetch_quotes(
+    executor: &JupiterSwapExecutor,
+    oracle: &PriceOracle,
+    mints: &[String],
+) -> Vec<TokenQuote> {
+    let mut quotes = Vec::with_capacity(mints.len());
+    for mint in mints {
+        let decimals = match oracle.decimals(mint) {
+            Some(d) => Some(d),
+            None => executor
+                .get_decimals(mint)
+                .await
+                .map_err(|e| warn!("Decimals lookup failed for {}: {}", mint, e))
+                .ok(),
+        };
+        let price_usd = executor
+            .get_price(mint)
+            .await
+            .map(|p| p.price_usd)
+            .map_err(|e| warn!("Price lookup failed for {}: {}", mint, e))
+            .ok();
+        debug!("Quote {}: decimals={:?} price={:?}", mint, decimals, price_usd);
+        quotes.push(TokenQuote { mint: mint.clone(), decimals, price_usd });
+    }
+    quotes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agenc_program::SKR_MINT;
+
+    #[test]
+    fn test_known_decimals() {
+        assert_eq!(known_decimals(tokens::SOL), Some(9));
+        assert_eq!(known_decimals(tokens::USDC), Some(6));
+        assert_eq!(known_decimals(SKR_MINT), Some(SKR_DECIMALS));
+        assert_eq!(known_decimals("SomeUnknownMint111"), None);
+    }
+
+    #[test]
+    fn test_resolve_mint() {
+        assert_eq!(resolve_mint("usdc"), tokens::USDC);
+        assert_eq!(resolve_mint("SKR"), SKR_MINT);
+        assert_eq!(resolve_mint("SomeUnknownMint111"), "SomeUnknownMint111");
+    }
+
+    #[test]
+    fn test_value_uses_decimals() {
+        let mut oracle = PriceOracle::default();
+        oracle.set_price(tokens::USDC, 1.0);
+        // 1,000,000 base units is 1 USDC, not 0.001 SOL
+        assert_eq!(oracle.value_usd(tokens::USDC, 1_000_000), Some(1.0));
+
+        oracle.set_price(tokens::SOL, 200.0);
+        assert_eq!(oracle.usd_to_sol(50.0), Some(0.25));
+    }
+
+    #[test]
+    fn test_unknown_mint_has_no_value() {
+        let mut oracle = PriceOracle::default();
+        oracle.set_price("SomeUnknownMint111", 3.0);
+        assert_eq!(oracle.value_usd("SomeUnknownMint111", 1_000), None);
+
+        oracle.set_decimals("SomeUnknownMint111", 3);
+        assert_eq!(oracle.value_usd("SomeUnknownMint111", 1_000), Some(3.0));
+    }
+
+    #[test]
+    fn test_stale_and_invalid_prices_ignored() {
+        let mut oracle = PriceOracle::new(60);
+        let now = chrono::Utc::now().timestamp();
+        oracle.set_price_at(tokens::JUP, 0.8, now - 120);
+        assert_eq!(oracle.price_usd(tokens::JUP), None);
+        assert!(!oracle.is_fresh(tokens::JUP));
+
+        oracle.set_price(tokens::USDT, 0.0);
+        oracle.set_price(tokens::USDT, f64::NAN);
+        assert_eq!(oracle.price_usd(tokens::USDT), None);
+    }
+}
@@ -0,0 +1,403 @@
+//! ============================================================================
+//! Proofs - Merkle Manifests of Completed Work
+//! ============================================================================
+//! The 32-byte `proof_hash` a worker submits with `complete_task` is the
+//! root of a manifest listing what went into the task and what came out:
+//! - Files are hashed by content; links (gists, PRs, proof URLs), notes and
+//!   transaction signatures are hashed as given
+//! - Each item is a Merkle leaf, after a first leaf binding the task PDA and
+//!   the worker, so a manifest cannot be replayed for another claim
+//! - The manifest is stored as the task's `VerificationLog` and can be
+//!   exported and re-verified by anyone against the on-chain claim
+//!
+//! Hashing (SHA-256, domain-separated by a leading tag byte):
+//!   context leaf = H(0x02 || task || worker)
+//!   item leaf    = H(0x00 || side || kind || u32 label len || label || digest)
+//!   node         = H(0x01 || left || right); an odd node is carried up as-is
+//! ============================================================================
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::agenc_program::{derive_claim_pda, OnChainClaim};
+use crate::db::VerificationLog;
+use crate::rpc_pool::RpcPool;
+use crate::types::CompleteTaskParams;
+
+/// Manifest format written by this version
+pub const PROOF_MANIFEST_VERSION: u32 = 1;
+
+const LEAF_TAG: u8 = 0x00;
+const NODE_TAG: u8 = 0x01;
+const CONTEXT_TAG: u8 = 0x02;
+
+/// Whether an item went into the task or came out of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofSide {
+    Input,
+    Output,
+}
+
+/// What a manifest item refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofItemKind {
+    /// A local file, hashed by content
+    File,
+    /// A gist, PR or other link, hashed as written
+    Url,
+    /// A Solana transaction signature
+    Transaction,
+    /// Free text such as completion notes
+    Text,
+}
+
+/// One entry of a proof manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofItem {
+    pub side: ProofSide,
+    pub kind: ProofItemKind,
+    /// File path, URL, signature or a short name for text
+    pub label: String,
+    /// Hex SHA-256 of the content
+    pub digest: String,
+}
+
+impl ProofItem {
+    /// Hash a file's contents
+    pub fn file(side: ProofSide, path: &Path) -> Result<Self> {
+        Ok(Self {
+            side,
+            kind: ProofItemKind::File,
+            label: path.display().to_string(),
+            digest: hex::encode(hash_file(path)?),
+        })
+    }
+
+    /// A link, hashed as written
+    pub fn url(side: ProofSide, url: &str) -> Self {
+        Self::hashed(side, ProofItemKind::Url, url, url.as_bytes())
+    }
+
+    /// A transaction signature (base58). Fails if it is not a signature.
+    pub fn transaction(side: ProofSide, signature: &str) -> Result<Self> {
+        let parsed = Signature::from_str(signature)
+            .map_err(|e| anyhow!("Invalid transaction signature '{}': {}", signature, e))?;
+        Ok(Self::hashed(side, ProofItemKind::Transaction, signature, parsed.as_ref()))
+    }
+
+    /// Free text under a short name
+    pub fn text(side: ProofSide, label: &str, text: &str) -> Self {
+        Self::hashed(side, ProofItemKind::Text, label, text.as_bytes())
+    }
+
+    fn hashed(side: ProofSide, kind: ProofItemKind, label: &str, content: &[u8]) -> Self {
+        Self { side, kind, label: label.to_string(), digest: hex::encode(Sha256::digest(content)) }
+    }
+
+    /// Merkle leaf for this item
+    pub fn leaf(&self) -> Result<[u8; 32]> {
+        let digest = decode_hash(&self.digest)
+            .ok_or_else(|| anyhow!("Proof item '{}' has an invalid digest", self.label))?;
+        let mut hasher = Sha256::new();
+        hasher.update([LEAF_TAG, self.side as u8, self.kind as u8]);
+        hasher.update((self.label.len() as u32).to_le_bytes());
+        hasher.update(self.label.as_bytes());
+        hasher.update(digest);
+        Ok(hasher.finalize().into())
+    }
+}
+
+/// Inputs and outputs of a completed task, committed to by `root`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofManifest {
+    pub version: u32,
+    /// Task PDA
+    pub task: String,
+    /// Worker wallet that submits the proof
+    pub worker: String,
+    pub items: Vec<ProofItem>,
+    /// Hex Merkle root — the on-chain proof hash
+    pub root: String,
+    pub created_at: i64,
+}
+
+impl ProofManifest {
+    /// Build a manifest and compute its root
+    pub fn new(task: &Pubkey, worker: &Pubkey, items: Vec<ProofItem>) -> Result<Self> {
+        let mut manifest = Self {
+            version: PROOF_MANIFEST_VERSION,
+            task: task.to_string(),
+            worker: worker.to_string(),
+            items,
+            root: String::new(),
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        manifest.root = hex::encode(manifest.compute_root()?);
+        Ok(manifest)
+    }
+
+    /// Recompute the root from the task, worker and items
+    pub fn compute_root(&self) -> Result<[u8; 32]> {
+        if self.version != PROOF_MANIFEST_VERSION {
+            return Err(anyhow!("Unsupported proof manifest version {}", self.version));
+        }
+        let task = Pubkey::from_str(&self.task).map_err(|e| anyhow!("Invalid manifest task: {}", e))?;
+        let worker = Pubkey::from_str(&self.worker).map_err(|e| anyhow!("Invalid manifest worker: {}", e))?;
+
+        let mut context = Sha256::new();
+        context.update([CONTEXT_TAG]);
+        context.update(task.as_ref());
+        context.update(worker.as_ref());
+
+        let mut leaves = vec![context.finalize().into()];
+        for item in &self.items {
+            leaves.push(item.leaf()?);
+        }
+        Ok(merkle_root(&leaves))
+    }
+
+    /// The stored root as bytes
+    pub fn root_bytes(&self) -> Result<[u8; 32]> {
+        decode_hash(&self.root).ok_or_else(|| anyhow!("Manifest root is not a 32-byte hex hash"))
+    }
+
+    /// Whether the stored root matches the contents
+    pub fn is_consistent(&self) -> bool {
+        matches!((self.compute_root(), self.root_bytes()), (Ok(a), Ok(b)) if a == b)
+    }
+
+    /// Re-hash the manifest's files, resolving relative paths against `base`.
+    /// Returns the labels of files that are missing or have changed.
+    pub fn changed_files(&self, base: Option<&Path>) -> Vec<String> {
+        self.items
+            .iter()
+            .filter(|item| item.kind == ProofItemKind::File)
+            .filter(|item| {
+                let path = match base {
+                    Some(base) => base.join(&item.label),
+                    None => PathBuf::from(&item.label),
+                };
+                hash_file(&path).map(hex::encode).ok().as_deref() != Some(item.digest.as_str())
+            })
+            .map(|item| item.label.clone())
+            .collect()
+    }
+
+    /// Verification log storing this manifest for its task
+    pub fn to_verification_log(&self, signature: Option<String>) -> Result<VerificationLog> {
+        Ok(VerificationLog {
+            task_id: self.task.clone(),
+            inputs: vec![],
+            outputs: serde_json::to_vec(self).map_err(|e| anyhow!("Failed to serialize proof manifest: {}", e))?,
+            proof_hash: self.root.clone(),
+            timestamp: self.created_at,
+            submitted: signature.is_some(),
+            submission_signature: signature,
+        })
+    }
+
+    /// The manifest stored in a verification log
+    pub fn from_verification_log(log: &VerificationLog) -> Result<Self> {
+        serde_json::from_slice(&log.outputs)
+            .map_err(|_| anyhow!("The proof for task {} has no manifest (recorded before manifests existed)", log.task_id))
+    }
+}
+
+/// Manifest items for a completion: the task's description hash as input,
+/// then everything the worker delivered
+pub fn completion_items(description_hash: &[u8; 32], params: &CompleteTaskParams) -> Result<Vec<ProofItem>> {
+    let mut items = vec![ProofItem::text(ProofSide::Input, "description_hash", &hex::encode(description_hash))];
+    for path in &params.files {
+        items.push(ProofItem::file(ProofSide::Output, Path::new(path))?);
+    }
+    for url in params.proof_url.iter().chain(&params.links) {
+        items.push(ProofItem::url(ProofSide::Output, url));
+    }
+    for signature in &params.signatures {
+        items.push(ProofItem::transaction(ProofSide::Output, signature)?);
+    }
+    if let Some(notes) = params.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        items.push(ProofItem::text(ProofSide::Output, "notes", notes));
+    }
+    Ok(items)
+}
+
+/// Merkle root over `leaves` (the zero hash for none)
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0; 32];
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    let mut hasher = Sha256::new();
+                    hasher.update([NODE_TAG]);
+                    hasher.update(left);
+                    hasher.update(right);
+                    hasher.finalize().into()
+                }
+                [odd] => *odd,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+/// Result of checking a manifest against the worker's on-chain claim
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofVerification {
+    pub claim: String,
+    /// The claim account exists
+    pub claim_found: bool,
+    /// Hex proof hash recorded on the claim
+    pub on_chain_proof: Option<String>,
+    /// The claim's proof hash equals the manifest root
+    pub matches: bool,
+    pub completed: bool,
+}
+
+/// Compare a manifest's root with the proof hash on the task claim. Reads
+/// through the pool's blocking clients, so call it outside of an async runtime.
+pub fn verify_on_chain(rpc: &RpcPool, manifest: &ProofManifest) -> Result<ProofVerification> {
+    let task = Pubkey::from_str(&manifest.task).map_err(|e| anyhow!("Invalid manifest task: {}", e))?;
+    let worker = Pubkey::from_str(&manifest.worker).map_err(|e| anyhow!("Invalid manifest worker: {}", e))?;
+    let (claim_pda, _) = derive_claim_pda(&task, &worker);
+
+    let account = rpc.read_blocking(|client| {
+        client
+            .get_account_with_commitment(&claim_pda, CommitmentConfig::confirmed())
+            .map(|response| response.value)
+            .context("Failed to fetch claim account")
+    })?;
+
+    let claim = account
+        .map(|account| OnChainClaim::from_account_data(&account.data, &claim_pda))
+        .transpose()?;
+    let root = manifest.root_bytes()?;
+
+    Ok(ProofVerification {
+        claim: claim_pda.to_string(),
+        claim_found: claim.is_some(),
+        on_chain_proof: claim.as_ref().map(|c| hex::encode(c.proof_hash)),
+        matches: claim.as_ref().is_some_and(|c| c.proof_hash == root),
+        completed: claim.as_ref().is_some_and(|c| c.is_completed),
+    })
+}
+
+fn hash_file(path: &Path) -> Result<[u8; 32]> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| anyhow!("Failed to open proof file {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| anyhow!("Failed to read proof file {}: {}", path.display(), e))?;
+    Ok(hasher.finalize().into())
+}
+
+fn decode_hash(hex_hash: &str) -> Option<[u8; 32]> {
+    hex::decode(hex_hash).ok()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<ProofItem> {
+        vec![
+            ProofItem::text(ProofSide::Input, "description_hash", "aa"),
+            ProofItem::url(ProofSide::Output, "https://gist.github.com/op/1"),
+            ProofItem::transaction(ProofSide::Output, &Signature::from([7u8; 64]).to_string()).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_merkle_root_shape() {
+        let (a, b, c) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        assert_eq!(merkle_root(&[]), [0; 32]);
+        assert_eq!(merkle_root(&[a]), a);
+
+        let ab = merkle_root(&[a, b]);
+        assert_ne!(ab, merkle_root(&[b, a]));
+        // The odd leaf is carried up, then paired
+        assert_eq!(merkle_root(&[a, b, c]), merkle_root(&[ab, c]));
+    }
+
+    #[test]
+    fn test_completion_items_start_with_the_description_hash() {
+        let params = CompleteTaskParams {
+            task_id: "7".into(),
+            proof_url: Some("https://gist.github.com/op/1".into()),
+            notes: Some("  ".into()),
+            files: vec![],
+            links: vec!["https://github.com/op/pr/2".into()],
+            signatures: vec![],
+        };
+        let items = completion_items(&[0xaa; 32], &params).unwrap();
+
+        assert_eq!(items[0].side, ProofSide::Input);
+        assert_eq!(items[0].label, "description_hash");
+        // Proof URL then links; blank notes are left out
+        let labels: Vec<&str> = items[1..].iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, ["https://gist.github.com/op/1", "https://github.com/op/pr/2"]);
+    }
+
+    #[test]
+    fn test_manifest_root_binds_task_worker_and_items() {
+        let (task, worker) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let manifest = ProofManifest::new(&task, &worker, items()).unwrap();
+        assert!(manifest.is_consistent());
+
+        let other_worker = ProofManifest::new(&task, &Pubkey::new_unique(), items()).unwrap();
+        assert_ne!(manifest.root, other_worker.root);
+
+        let mut tampered = manifest.clone();
+        tampered.items[1] = ProofItem::url(ProofSide::Output, "https://gist.github.com/op/2");
+        assert!(!tampered.is_consistent());
+
+        let mut reordered = manifest.clone();
+        reordered.items.swap(0, 1);
+        assert!(!reordered.is_consistent());
+    }
+
+    #[test]
+    fn test_file_items_and_recheck() {
+        let dir = std::env::temp_dir().join(format!("agenc-proofs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("report.md"), b"all findings").unwrap();
+
+        let mut item = ProofItem::file(ProofSide::Output, &dir.join("report.md")).unwrap();
+        assert_eq!(item.digest, hex::encode(Sha256::digest(b"all findings")));
+        item.label = "report.md".into();
+
+        let manifest = ProofManifest::new(&Pubkey::new_unique(), &Pubkey::new_unique(), vec![item]).unwrap();
+        assert!(manifest.changed_files(Some(&dir)).is_empty());
+
+        std::fs::write(dir.join("report.md"), b"edited").unwrap();
+        assert_eq!(manifest.changed_files(Some(&dir)), vec!["report.md"]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_invalid_signature_and_log_roundtrip() {
+        assert!(ProofItem::transaction(ProofSide::Output, "not-a-signature").is_err());
+
+        let manifest = ProofManifest::new(&Pubkey::new_unique(), &Pubkey::new_unique(), items()).unwrap();
+        let log = manifest.to_verification_log(Some("sig".into())).unwrap();
+        assert_eq!(log.proof_hash, manifest.root);
+        assert!(log.submitted);
+        assert_eq!(ProofManifest::from_verification_log(&log).unwrap(), manifest);
+
+        let legacy = VerificationLog { outputs: b"{\"success\":true}".to_vec(), ..log };
+        assert!(ProofManifest::from_verification_log(&legacy).is_err());
+    }
+}
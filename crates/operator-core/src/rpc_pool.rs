@@ -0,0 +1,528 @@
+//! ============================================================================
+//! RPC Pool - Weighted Endpoints with Health Scoring and Failover
+//! ============================================================================
+//! One pool is shared by the executor, the access gate and the swap executor:
+//! - Each endpoint has a weight and tracks latency, error rate and slot lag
+//! - Reads go to the healthiest endpoint first and move on to the next when
+//!   the call is rate limited (429) or fails at the network level
+//! - A rate-limited endpoint sits out a cooldown before it is tried again
+//! - `refresh_health` probes every endpoint's slot, measuring how far each
+//!   trails the most advanced one
+//! - Endpoints can be replaced at runtime without rebuilding the subsystems
+//!   that hold the pool
+//!
+//! Sends are not failed over; they go to the healthiest endpoint and the
+//! retry sender handles the rest.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::transaction_retry::{classify_error, ErrorKind};
+
+/// Blocking client type handed to the retry sender and the access checker
+pub type BlockingRpcClient = solana_client::rpc_client::RpcClient;
+
+/// Latency assumed for an endpoint that has not answered yet
+const INITIAL_LATENCY_MS: f64 = 250.0;
+
+/// Weight of the newest sample in the latency and error-rate averages
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Slots an endpoint may trail the highest one before it counts as lagging
+const MAX_SLOT_LAG: u64 = 50;
+
+/// How long a rate-limited endpoint is skipped
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Error messages that mean the request never got a proper answer
+const NETWORK_HINTS: &[&str] = &[
+    "error sending request",
+    "connection",
+    "timed out",
+    "timeout",
+    "dns error",
+    "bad gateway",
+    "service unavailable",
+];
+
+/// An RPC endpoint and its share of traffic relative to the others
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcEndpoint {
+    pub url: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl RpcEndpoint {
+    pub fn new(url: &str, weight: u32) -> Self {
+        Self { url: url.to_string(), weight: weight.max(1) }
+    }
+
+    /// Parse a comma separated list of `url` or `url@weight` entries
+    pub fn parse_list(list: &str) -> Result<Vec<Self>> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.rsplit_once('@') {
+                Some((url, weight)) if !weight.contains('/') => {
+                    let weight = weight
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid weight in RPC endpoint '{}'", entry))?;
+                    Ok(Self::new(url, weight))
+                }
+                _ => Ok(Self::new(entry, 1)),
+            })
+            .collect()
+    }
+}
+
+/// Health of one endpoint, as shown on the HUD
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointStats {
+    pub url: String,
+    pub weight: u32,
+    /// Average response time in milliseconds
+    pub latency_ms: f64,
+    /// Share of recent requests that failed (0.0 - 1.0)
+    pub error_rate: f64,
+    pub requests: u64,
+    pub errors: u64,
+    /// Slot seen at the last health probe
+    pub slot: Option<u64>,
+    /// Slots behind the most advanced endpoint in the pool
+    pub slot_lag: u64,
+    /// Seconds left before a rate-limited endpoint is used again
+    pub cooldown_secs: u64,
+    pub last_error: Option<String>,
+    /// Routing score; higher is preferred
+    pub score: f64,
+    pub healthy: bool,
+}
+
+/// Health of the whole pool
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RpcPoolStats {
+    pub endpoints: Vec<EndpointStats>,
+    /// Endpoint reads go to first
+    pub preferred: Option<String>,
+    pub highest_slot: Option<u64>,
+    /// Unix time of the last health probe
+    pub last_probe: Option<i64>,
+}
+
+#[derive(Debug)]
+struct Health {
+    latency_ms: f64,
+    error_rate: f64,
+    requests: u64,
+    errors: u64,
+    slot: Option<u64>,
+    cooldown_until: Option<Instant>,
+    last_error: Option<String>,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            latency_ms: INITIAL_LATENCY_MS,
+            error_rate: 0.0,
+            requests: 0,
+            errors: 0,
+            slot: None,
+            cooldown_until: None,
+            last_error: None,
+        }
+    }
+}
+
+impl Health {
+    fn record_success(&mut self, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        self.latency_ms = if self.requests == 0 { ms } else { ewma(self.latency_ms, ms) };
+        self.error_rate = ewma(self.error_rate, 0.0);
+        self.requests += 1;
+    }
+
+    fn record_failure(&mut self, kind: ErrorKind, error: &str) {
+        self.error_rate = ewma(self.error_rate, 1.0);
+        self.requests += 1;
+        self.errors += 1;
+        self.last_error = Some(error.to_string());
+        if kind == ErrorKind::RateLimited {
+            self.cooldown_until = Some(Instant::now() + RATE_LIMIT_COOLDOWN);
+        }
+    }
+
+    fn cooling_down(&self, now: Instant) -> bool {
+        self.cooldown_until.is_some_and(|until| until > now)
+    }
+}
+
+fn ewma(average: f64, sample: f64) -> f64 {
+    average * (1.0 - EWMA_ALPHA) + sample * EWMA_ALPHA
+}
+
+/// Routing score: weight per millisecond, discounted by errors and slot lag
+fn score(weight: u32, health: &Health, slot_lag: u64) -> f64 {
+    let lag_penalty = 1.0 + slot_lag as f64 / MAX_SLOT_LAG as f64;
+    weight as f64 * (1.0 - health.error_rate) / health.latency_ms.max(1.0) / lag_penalty
+}
+
+/// Why a failed read should be retried on another endpoint, if it should.
+/// Errors the endpoint answered with (missing accounts, bad data) are final.
+pub fn failover_reason(error: &anyhow::Error) -> Option<ErrorKind> {
+    let message = format!("{:#}", error);
+    if classify_error(&message) == ErrorKind::RateLimited {
+        return Some(ErrorKind::RateLimited);
+    }
+
+    let transport = error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<ClientError>().map(ClientError::kind),
+            Some(ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_))
+        )
+    });
+    let lower = message.to_lowercase();
+    (transport || NETWORK_HINTS.iter().any(|hint| lower.contains(hint))).then_some(ErrorKind::Retryable)
+}
+
+struct Node {
+    endpoint: RpcEndpoint,
+    client: Arc<RpcClient>,
+    blocking: Arc<BlockingRpcClient>,
+    health: Mutex<Health>,
+}
+
+impl Node {
+    fn new(endpoint: RpcEndpoint) -> Self {
+        let commitment = CommitmentConfig::confirmed();
+        Self {
+            client: Arc::new(RpcClient::new_with_commitment(endpoint.url.clone(), commitment)),
+            blocking: Arc::new(BlockingRpcClient::new_with_commitment(endpoint.url.clone(), commitment)),
+            endpoint,
+            health: Mutex::new(Health::default()),
+        }
+    }
+
+    fn health(&self) -> std::sync::MutexGuard<'_, Health> {
+        self.health.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record<T>(&self, started: Instant, result: &Result<T>) -> Option<ErrorKind> {
+        match result {
+            Ok(_) => {
+                self.health().record_success(started.elapsed());
+                None
+            }
+            Err(e) => match failover_reason(e) {
+                Some(kind) => {
+                    warn!("[RpcPool] {} failed ({:?}): {}", self.endpoint.url, kind, e);
+                    self.health().record_failure(kind, &e.to_string());
+                    Some(kind)
+                }
+                None => {
+                    // The endpoint answered; the error is about the request
+                    self.health().record_success(started.elapsed());
+                    None
+                }
+            },
+        }
+    }
+}
+
+/// Pool of RPC endpoints shared by every subsystem that talks to the chain
+pub struct RpcPool {
+    nodes: RwLock<Vec<Arc<Node>>>,
+    last_probe: Mutex<Option<i64>>,
+}
+
+impl RpcPool {
+    /// Pool over `endpoints`, in order of preference until health is known
+    pub fn new(endpoints: Vec<RpcEndpoint>) -> Result<Self> {
+        let pool = Self { nodes: RwLock::new(Vec::new()), last_probe: Mutex::new(None) };
+        pool.set_endpoints(endpoints)?;
+        Ok(pool)
+    }
+
+    /// Pool with one endpoint
+    pub fn single(url: &str) -> Self {
+        let node = Arc::new(Node::new(RpcEndpoint::new(url, 1)));
+        Self { nodes: RwLock::new(vec![node]), last_probe: Mutex::new(None) }
+    }
+
+    /// Replace the endpoints. Health is kept for URLs already in the pool.
+    pub fn set_endpoints(&self, endpoints: Vec<RpcEndpoint>) -> Result<()> {
+        if endpoints.is_empty() {
+            return Err(anyhow!("An RPC pool needs at least one endpoint"));
+        }
+
+        let mut nodes = self.nodes.write().unwrap_or_else(|e| e.into_inner());
+        let replaced = endpoints
+            .into_iter()
+            .map(|endpoint| {
+                match nodes.iter().find(|n| n.endpoint.url == endpoint.url) {
+                    Some(existing) if existing.endpoint.weight == endpoint.weight => existing.clone(),
+                    _ => Arc::new(Node::new(endpoint)),
+                }
+            })
+            .collect::<Vec<_>>();
+        info!(
+            "[RpcPool] Endpoints: {}",
+            replaced.iter().map(|n| n.endpoint.url.as_str()).collect::<Vec<_>>().join(", ")
+        );
+        *nodes = replaced;
+        Ok(())
+    }
+
+    pub fn endpoints(&self) -> Vec<RpcEndpoint> {
+        self.snapshot().iter().map(|n| n.endpoint.clone()).collect()
+    }
+
+    /// URL of the endpoint reads would go to now
+    pub fn preferred_url(&self) -> String {
+        self.ranked()[0].endpoint.url.clone()
+    }
+
+    /// Client for the healthiest endpoint, for calls that must not be
+    /// repeated elsewhere (e.g. sends, which the retry sender handles)
+    pub fn client(&self) -> Arc<RpcClient> {
+        self.ranked()[0].client.clone()
+    }
+
+    /// Blocking client for the healthiest endpoint
+    pub fn blocking_client(&self) -> Arc<BlockingRpcClient> {
+        self.ranked()[0].blocking.clone()
+    }
+
+    /// Run a read against the healthiest endpoint, failing over to the next
+    /// on rate limits and network errors
+    pub async fn read<T, F, Fut>(&self, op: F) -> Result<T>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+        for node in self.ranked() {
+            let started = Instant::now();
+            let result = op(node.client.clone()).await;
+            if node.record(started, &result).is_none() {
+                return result;
+            }
+            last_error = result.err();
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No RPC endpoints configured")))
+    }
+
+    /// Blocking counterpart of `read`
+    pub fn read_blocking<T>(&self, op: impl Fn(&BlockingRpcClient) -> Result<T>) -> Result<T> {
+        let mut last_error = None;
+        for node in self.ranked() {
+            let started = Instant::now();
+            let result = op(&node.blocking);
+            if node.record(started, &result).is_none() {
+                return result;
+            }
+            last_error = result.err();
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No RPC endpoints configured")))
+    }
+
+    /// Ask every endpoint for its slot, updating latency, errors and lag
+    pub async fn refresh_health(&self) {
+        let nodes = self.snapshot();
+        let probes = nodes.iter().map(|node| async move {
+            let started = Instant::now();
+            let result = node.client.get_slot().await.map_err(anyhow::Error::from);
+            node.record(started, &result);
+            if let Ok(slot) = result {
+                node.health().slot = Some(slot);
+            }
+        });
+        futures_util::future::join_all(probes).await;
+        *self.last_probe.lock().unwrap_or_else(|e| e.into_inner()) = Some(chrono::Utc::now().timestamp());
+        debug!("[RpcPool] Probed {} endpoints, preferring {}", nodes.len(), self.preferred_url());
+    }
+
+    pub fn stats(&self) -> RpcPoolStats {
+        let nodes = self.snapshot();
+        let now = Instant::now();
+        let highest_slot = Self::highest_slot(&nodes);
+
+        let endpoints = nodes
+            .iter()
+            .map(|node| {
+                let health = node.health();
+                let slot_lag = Self::slot_lag(&health, highest_slot);
+                EndpointStats {
+                    url: node.endpoint.url.clone(),
+                    weight: node.endpoint.weight,
+                    latency_ms: health.latency_ms,
+                    error_rate: health.error_rate,
+                    requests: health.requests,
+                    errors: health.errors,
+                    slot: health.slot,
+                    slot_lag,
+                    cooldown_secs: health
+                        .cooldown_until
+                        .map(|until| until.saturating_duration_since(now).as_secs())
+                        .unwrap_or(0),
+                    last_error: health.last_error.clone(),
+                    score: score(node.endpoint.weight, &health, slot_lag),
+                    healthy: !health.cooling_down(now) && slot_lag <= MAX_SLOT_LAG,
+                }
+            })
+            .collect();
+
+        RpcPoolStats {
+            endpoints,
+            preferred: Some(self.preferred_url()),
+            highest_slot,
+            last_probe: *self.last_probe.lock().unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+
+    fn snapshot(&self) -> Vec<Arc<Node>> {
+        self.nodes.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn highest_slot(nodes: &[Arc<Node>]) -> Option<u64> {
+        nodes.iter().filter_map(|n| n.health().slot).max()
+    }
+
+    fn slot_lag(health: &Health, highest_slot: Option<u64>) -> u64 {
+        match (health.slot, highest_slot) {
+            (Some(slot), Some(highest)) => highest.saturating_sub(slot),
+            _ => 0,
+        }
+    }
+
+    /// Endpoints best first: usable ones by score, then lagging ones, then
+    /// those cooling down after a rate limit
+    fn ranked(&self) -> Vec<Arc<Node>> {
+        let nodes = self.snapshot();
+        let now = Instant::now();
+        let highest_slot = Self::highest_slot(&nodes);
+
+        let mut ranked = nodes
+            .into_iter()
+            .map(|node| {
+                let health = node.health();
+                let slot_lag = Self::slot_lag(&health, highest_slot);
+                let tier = match (health.cooling_down(now), slot_lag > MAX_SLOT_LAG) {
+                    (false, false) => 0,
+                    (false, true) => 1,
+                    (true, _) => 2,
+                };
+                let score = score(node.endpoint.weight, &health, slot_lag);
+                drop(health);
+                (tier, score, node)
+            })
+            .collect::<Vec<_>>();
+        // Stable sort keeps configured order between equal scores
+        ranked.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.total_cmp(&a.1)));
+        ranked.into_iter().map(|(_, _, node)| node).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(urls: &[(&str, u32)]) -> RpcPool {
+        RpcPool::new(urls.iter().map(|(url, weight)| RpcEndpoint::new(url, *weight)).collect()).unwrap()
+    }
+
+    fn node(pool: &RpcPool, url: &str) -> Arc<Node> {
+        pool.snapshot().into_iter().find(|n| n.endpoint.url == url).unwrap()
+    }
+
+    #[test]
+    fn test_parse_endpoint_list() {
+        let endpoints = RpcEndpoint::parse_list("https://a.example, https://b.example@3,").unwrap();
+        assert_eq!(endpoints, vec![
+            RpcEndpoint::new("https://a.example", 1),
+            RpcEndpoint::new("https://b.example", 3),
+        ]);
+        assert!(RpcEndpoint::parse_list("https://a.example@many").is_err());
+        assert!(RpcPool::new(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_weight_then_latency_ranking() {
+        let pool = pool(&[("http://a", 1), ("http://b", 2)]);
+        assert_eq!(pool.preferred_url(), "http://b");
+
+        // b is four times slower, which outweighs its double weight
+        node(&pool, "http://a").health().record_success(Duration::from_millis(100));
+        node(&pool, "http://b").health().record_success(Duration::from_millis(400));
+        assert_eq!(pool.preferred_url(), "http://a");
+    }
+
+    #[test]
+    fn test_rate_limited_endpoint_cools_down() {
+        let pool = pool(&[("http://a", 5), ("http://b", 1)]);
+        node(&pool, "http://a").health().record_failure(ErrorKind::RateLimited, "429 Too Many Requests");
+
+        assert_eq!(pool.preferred_url(), "http://b");
+        let stats = pool.stats();
+        assert!(!stats.endpoints[0].healthy);
+        assert!(stats.endpoints[0].cooldown_secs > 0);
+        assert_eq!(stats.endpoints[0].errors, 1);
+    }
+
+    #[test]
+    fn test_lagging_endpoint_is_ranked_last() {
+        let pool = pool(&[("http://a", 5), ("http://b", 1)]);
+        node(&pool, "http://a").health().slot = Some(1_000);
+        node(&pool, "http://b").health().slot = Some(1_000 + MAX_SLOT_LAG + 1);
+
+        assert_eq!(pool.preferred_url(), "http://b");
+        assert_eq!(pool.stats().endpoints[0].slot_lag, MAX_SLOT_LAG + 1);
+    }
+
+    #[test]
+    fn test_read_fails_over_on_rate_limit_only() {
+        let pool = pool(&[("http://a", 2), ("http://b", 1)]);
+
+        let served_by = pool
+            .read_blocking(|rpc| match rpc.url().as_str() {
+                "http://a" => Err(anyhow!("HTTP status client error (429 Too Many Requests)")),
+                url => Ok(url.to_string()),
+            })
+            .unwrap();
+        assert_eq!(served_by, "http://b");
+
+        // An answer from the endpoint is not retried elsewhere
+        let calls = Mutex::new(0);
+        let result: Result<()> = pool.read_blocking(|_| {
+            *calls.lock().unwrap() += 1;
+            Err(anyhow!("AccountNotFound: pubkey=11111111111111111111111111111111"))
+        });
+        assert!(result.is_err());
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_set_endpoints_keeps_known_health() {
+        let pool = pool(&[("http://a", 1)]);
+        node(&pool, "http://a").health().record_success(Duration::from_millis(42));
+
+        pool.set_endpoints(vec![RpcEndpoint::new("http://a", 1), RpcEndpoint::new("http://c", 1)]).unwrap();
+        let stats = pool.stats();
+        assert_eq!(stats.endpoints.len(), 2);
+        assert_eq!(stats.endpoints[0].requests, 1);
+        assert_eq!(stats.endpoints[1].requests, 0);
+    }
+}
@@ -0,0 +1,412 @@
+//! ============================================================================
+//! Simulation - Transaction Previews for Confirmation Prompts
+//! ============================================================================
+//! Spending intents are built and run through `simulateTransaction` before
+//! the user is asked to confirm them. The resulting `TransactionPreview`
+//! reports:
+//! - SOL and SPL token balance changes of every writable account, including
+//!   accounts loaded from address lookup tables
+//! - The network fee (with any priority fee) and rent for created accounts
+//! - Compute units consumed, and the error and logs if the simulation failed
+//!
+//! Simulation skips signature checks and swaps in a fresh blockhash, so an
+//! unsigned transaction (or a locked wallet) can still be previewed.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig};
+use solana_sdk::account::Account;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashMap;
+use tracing::debug;
+
+use crate::agenc_program::skr_mint;
+use crate::executor::tokens;
+use crate::types::{BalanceChange, TransactionPreview};
+
+const TOKEN_2022_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Size of an SPL token account without extensions
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Token-2022 account type byte for token accounts (after the base layout)
+const TOKEN_2022_ACCOUNT_TYPE: u8 = 2;
+
+/// Offset of `decimals` in an SPL mint
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// Fee per signature when the RPC cannot price the message
+const FALLBACK_SIGNATURE_FEE: u64 = 5_000;
+
+/// `get_multiple_accounts` limit
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// SOL decimals
+const SOL_DECIMALS: u8 = 9;
+
+/// Mint, owner and amount of an SPL token account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TokenBalance {
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+}
+
+/// Decode a token account (SPL Token or Token-2022); None for mints and
+/// everything else
+fn token_balance(account: &Account) -> Option<TokenBalance> {
+    if account.owner != spl_token::ID && account.owner != TOKEN_2022_PROGRAM_ID {
+        return None;
+    }
+    let data = &account.data;
+    let is_token_account = data.len() == TOKEN_ACCOUNT_LEN
+        || (data.len() > TOKEN_ACCOUNT_LEN && data[TOKEN_ACCOUNT_LEN] == TOKEN_2022_ACCOUNT_TYPE);
+    if !is_token_account {
+        return None;
+    }
+    Some(TokenBalance {
+        mint: Pubkey::try_from(&data[0..32]).ok()?,
+        owner: Pubkey::try_from(&data[32..64]).ok()?,
+        amount: u64::from_le_bytes(data[64..72].try_into().ok()?),
+    })
+}
+
+/// Balance changes and rent between the `before` and `after` states of
+/// `keys`. Fee, compute units and error are left for the caller.
+fn balance_changes(
+    keys: &[Pubkey],
+    before: &[Option<Account>],
+    after: &[Option<Account>],
+    decimals: &HashMap<Pubkey, u8>,
+) -> (Vec<BalanceChange>, u64) {
+    let rent = Rent::default();
+    let mut changes = Vec::new();
+    let mut rent_lamports = 0;
+
+    for (i, key) in keys.iter().enumerate() {
+        let pre = before.get(i).and_then(Option::as_ref);
+        let post = after.get(i).and_then(Option::as_ref);
+
+        let pre_lamports = pre.map_or(0, |a| a.lamports);
+        let post_lamports = post.map_or(0, |a| a.lamports);
+        if pre_lamports != post_lamports {
+            changes.push(BalanceChange {
+                account: key.to_string(),
+                mint: None,
+                owner: None,
+                before: pre_lamports,
+                after: post_lamports,
+                decimals: SOL_DECIMALS,
+            });
+        }
+
+        if let Some(post) = post.filter(|a| pre_lamports == 0 && !a.data.is_empty()) {
+            rent_lamports += rent.minimum_balance(post.data.len()).min(post.lamports);
+        }
+
+        let pre_token = pre.and_then(token_balance);
+        let post_token = post.and_then(token_balance);
+        if let Some(token) = post_token.or(pre_token) {
+            let pre_amount = pre_token.map_or(0, |t| t.amount);
+            let post_amount = post_token.map_or(0, |t| t.amount);
+            if pre_amount != post_amount {
+                changes.push(BalanceChange {
+                    account: key.to_string(),
+                    mint: Some(token.mint.to_string()),
+                    owner: Some(token.owner.to_string()),
+                    before: pre_amount,
+                    after: post_amount,
+                    decimals: decimals.get(&token.mint).copied().unwrap_or(0),
+                });
+            }
+        }
+    }
+
+    (changes, rent_lamports)
+}
+
+/// Symbol for well-known mints, or a shortened address
+pub(crate) fn token_label(mint: &str) -> String {
+    match mint {
+        tokens::SOL => "wSOL".into(),
+        tokens::USDC => "USDC".into(),
+        tokens::USDT => "USDT".into(),
+        tokens::JUP => "JUP".into(),
+        m if m == skr_mint().to_string() => "SKR".into(),
+        _ => short(mint),
+    }
+}
+
+fn short(address: &str) -> String {
+    if address.len() <= 10 {
+        return address.to_string();
+    }
+    format!("{}…{}", &address[..4], &address[address.len() - 4..])
+}
+
+fn sol(lamports: u64) -> String {
+    format!("{:.6} SOL", lamports as f64 / 1_000_000_000.0)
+}
+
+/// Human-readable lines for a preview; `payer` is the signing wallet
+pub fn summarize(preview: &TransactionPreview, payer: &Pubkey) -> Vec<String> {
+    let payer = payer.to_string();
+    let mut lines: Vec<String> = preview
+        .balance_changes
+        .iter()
+        .map(|change| {
+            let precision = change.decimals.min(6) as usize;
+            let (symbol, holder) = match (&change.mint, &change.owner) {
+                (Some(mint), Some(owner)) => (token_label(mint), owner),
+                _ => ("SOL".to_string(), &change.account),
+            };
+            let whose = if *holder == payer { "your wallet".to_string() } else { short(holder) };
+            format!("{:+.*} {} ({})", precision, change.delta(), symbol, whose)
+        })
+        .collect();
+
+    lines.push(format!("Network fee: {}", sol(preview.fee_lamports)));
+    if preview.rent_lamports > 0 {
+        lines.push(format!("Rent for new accounts: {}", sol(preview.rent_lamports)));
+    }
+    if let Some(fee) = preview.protocol_fee_lamports {
+        lines.push(format!("Protocol fee (from the reward): {}", sol(fee)));
+    }
+    if let Some(units) = preview.compute_units {
+        lines.push(format!("Compute: {} units", units));
+    }
+    if let Some(error) = &preview.error {
+        lines.push(format!("Simulation failed: {}", error));
+    }
+    lines
+}
+
+/// Writable accounts of `message`, with lookup table entries resolved
+async fn writable_accounts(rpc: &RpcClient, message: &VersionedMessage) -> Result<Vec<Pubkey>> {
+    let mut keys: Vec<Pubkey> = message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| message.is_maybe_writable(*i, None))
+        .map(|(_, key)| *key)
+        .collect();
+
+    let Some(lookups) = message.address_table_lookups() else { return Ok(keys) };
+    let tables: Vec<Pubkey> = lookups.iter().map(|l| l.account_key).collect();
+    let accounts = get_accounts(rpc, &tables).await?;
+
+    for (lookup, account) in lookups.iter().zip(accounts) {
+        let account = account.ok_or_else(|| anyhow!("Lookup table {} not found", lookup.account_key))?;
+        let table = AddressLookupTable::deserialize(&account.data)
+            .map_err(|e| anyhow!("Invalid lookup table {}: {}", lookup.account_key, e))?;
+        for index in &lookup.writable_indexes {
+            let key = table
+                .addresses
+                .get(*index as usize)
+                .ok_or_else(|| anyhow!("Lookup table {} has no entry {}", lookup.account_key, index))?;
+            keys.push(*key);
+        }
+    }
+    Ok(keys)
+}
+
+async fn get_accounts(rpc: &RpcClient, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+    let mut accounts = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+        let batch = rpc
+            .get_multiple_accounts(chunk)
+            .await
+            .map_err(|e| anyhow!("Failed to fetch accounts: {}", e))?;
+        accounts.extend(batch);
+    }
+    Ok(accounts)
+}
+
+/// Fee the cluster charges for `message`, falling back to the base fee
+async fn network_fee(rpc: &RpcClient, message: &VersionedMessage) -> u64 {
+    let fee = match message {
+        VersionedMessage::Legacy(message) => rpc.get_fee_for_message(message).await,
+        VersionedMessage::V0(message) => rpc.get_fee_for_message(message).await,
+    };
+    fee.unwrap_or_else(|e| {
+        debug!("Fee lookup failed ({}), using the base fee", e);
+        FALLBACK_SIGNATURE_FEE * message.header().num_required_signatures as u64
+    })
+}
+
+/// Decimals of the mints behind any token accounts in `accounts`
+async fn mint_decimals(rpc: &RpcClient, accounts: &[&Option<Account>]) -> Result<HashMap<Pubkey, u8>> {
+    let mut mints: Vec<Pubkey> = accounts
+        .iter()
+        .filter_map(|a| a.as_ref().and_then(token_balance))
+        .map(|t| t.mint)
+        .collect();
+    mints.sort();
+    mints.dedup();
+
+    let accounts = get_accounts(rpc, &mints).await?;
+    Ok(mints
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(mint, account)| Some((mint, *account?.data.get(MINT_DECIMALS_OFFSET)?)))
+        .collect())
+}
+
+/// Simulate `tx` and describe what it would do. `protocol_fee_lamports` is
+/// reported alongside for AgenC transactions that pay one.
+pub async fn simulate(
+    rpc: &RpcClient,
+    tx: &VersionedTransaction,
+    protocol_fee_lamports: Option<u64>,
+) -> Result<TransactionPreview> {
+    let payer = *tx
+        .message
+        .static_account_keys()
+        .first()
+        .ok_or_else(|| anyhow!("Transaction has no fee payer"))?;
+    let keys = writable_accounts(rpc, &tx.message).await?;
+    let before = get_accounts(rpc, &keys).await?;
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(CommitmentConfig::confirmed()),
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            addresses: keys.iter().map(|k| k.to_string()).collect(),
+        }),
+        ..Default::default()
+    };
+    let result = rpc
+        .simulate_transaction_with_config(tx, config)
+        .await
+        .map_err(|e| anyhow!("Simulation failed: {}", e))?
+        .value;
+
+    let after: Vec<Option<Account>> = result
+        .accounts
+        .unwrap_or_default()
+        .iter()
+        .map(|a| a.as_ref().and_then(|a| a.decode()))
+        .collect();
+    let decimals = mint_decimals(rpc, &before.iter().chain(after.iter()).collect::<Vec<_>>()).await?;
+    let (balance_changes, rent_lamports) = balance_changes(&keys, &before, &after, &decimals);
+
+    let error = result.err.map(|e| e.to_string());
+    let mut preview = TransactionPreview {
+        balance_changes,
+        fee_lamports: network_fee(rpc, &tx.message).await,
+        rent_lamports,
+        compute_units: result.units_consumed,
+        protocol_fee_lamports,
+        logs: if error.is_some() { result.logs.unwrap_or_default() } else { Vec::new() },
+        error,
+        summary: Vec::new(),
+    };
+    preview.summary = summarize(&preview, &payer);
+    debug!("Simulated transaction: {:?}", preview.summary);
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+        data[0..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        Account { lamports: 2_039_280, data, owner: spl_token::ID, executable: false, rent_epoch: 0 }
+    }
+
+    fn system_account(lamports: u64) -> Account {
+        Account { lamports, data: vec![], owner: Pubkey::default(), executable: false, rent_epoch: 0 }
+    }
+
+    #[test]
+    fn test_token_balance_layouts() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let account = token_account(&mint, &owner, 42);
+        assert_eq!(token_balance(&account), Some(TokenBalance { mint, owner, amount: 42 }));
+
+        // Token-2022 accounts with extensions carry an account type byte
+        let mut extended = account.clone();
+        extended.owner = TOKEN_2022_PROGRAM_ID;
+        extended.data.extend_from_slice(&[TOKEN_2022_ACCOUNT_TYPE, 0, 0]);
+        assert_eq!(token_balance(&extended).map(|t| t.amount), Some(42));
+        extended.data[TOKEN_ACCOUNT_LEN] = 1;
+        assert_eq!(token_balance(&extended), None);
+
+        // Mints and non-token accounts are ignored
+        let mut mint_account = account.clone();
+        mint_account.data.truncate(82);
+        assert_eq!(token_balance(&mint_account), None);
+        assert_eq!(token_balance(&system_account(1)), None);
+    }
+
+    #[test]
+    fn test_balance_changes_rent_and_summary() {
+        let payer = Pubkey::new_unique();
+        let task = Pubkey::new_unique();
+        let token = Pubkey::new_unique();
+        let untouched = Pubkey::new_unique();
+        let mint = Pubkey::from_str_const(tokens::USDC);
+
+        let keys = [payer, task, token, untouched];
+        let before = vec![
+            Some(system_account(2_000_000_000)),
+            None,
+            Some(token_account(&mint, &payer, 5_000_000)),
+            Some(system_account(7)),
+        ];
+        let task_after = Account {
+            lamports: 500_000_000 + Rent::default().minimum_balance(300),
+            data: vec![1; 300],
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        let after = vec![
+            Some(system_account(1_000_000_000)),
+            Some(task_after),
+            Some(token_account(&mint, &payer, 3_500_000)),
+            Some(system_account(7)),
+        ];
+        let decimals = HashMap::from([(mint, 6)]);
+
+        let (changes, rent_lamports) = balance_changes(&keys, &before, &after, &decimals);
+        assert_eq!(rent_lamports, Rent::default().minimum_balance(300));
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].delta(), -1.0);
+        assert_eq!(changes[1].account, task.to_string());
+        assert_eq!(changes[2].mint.as_deref(), Some(tokens::USDC));
+        assert_eq!(changes[2].delta(), -1.5);
+
+        let preview = TransactionPreview {
+            balance_changes: changes,
+            fee_lamports: 5_000,
+            rent_lamports,
+            compute_units: Some(12_345),
+            protocol_fee_lamports: Some(12_500_000),
+            error: Some("insufficient funds".into()),
+            ..Default::default()
+        };
+        let summary = summarize(&preview, &payer);
+        assert_eq!(summary[0], "-1.000000 SOL (your wallet)");
+        assert!(summary[1].starts_with("+0.50"));
+        assert_eq!(summary[2], "-1.500000 USDC (your wallet)");
+        assert!(summary.contains(&"Network fee: 0.000005 SOL".to_string()));
+        assert!(summary.contains(&"Protocol fee (from the reward): 0.012500 SOL".to_string()));
+        assert!(summary.contains(&"Compute: 12345 units".to_string()));
+        assert_eq!(summary.last().unwrap(), "Simulation failed: insufficient funds");
+    }
+}
@@ -4,39 +4,82 @@
 //! Handles all Solana operations for AgenC protocol:
 //! - Transaction building for task CRUD operations
 //! - Local signing (keys never leave device)
-//! - RPC communication with Solana network
+//! - Encrypted keystores unlocked by passphrase, locked again after idling
+//! - Named wallet profiles (e.g. "hot", "treasury"); an intent may pick one
+//!   with a `wallet` param, otherwise the active wallet signs
+//! - Task descriptions stored off-chain and checked against the on-chain hash
+//! - Task capability requirements given by name
+//! - Completed work proven by a Merkle manifest of its inputs and outputs
+//! - Every transaction sent with a simulated compute budget and priority
+//!   fee, and re-signed if its blockhash expires before it lands
+//! - RPC communication with Solana network through a shared endpoint pool
 //!
 //! NOTE: This integrates with existing solana-pipkit crate for advanced ops.
 //! ============================================================================
 
 use anyhow::{anyhow, Result};
-use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
-    commitment_config::CommitmentConfig,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
-    transaction::Transaction,
+    instruction::Instruction,
+    signature::{Keypair, Signature, Signer},
+    transaction::{Transaction, VersionedTransaction},
     message::Message,
 };
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::info;
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, RwLockReadGuard};
+use tracing::{info, warn};
 
 use crate::agenc_program::{
-    self, OnChainTaskState,
-    derive_task_pda, build_create_task_ix, build_claim_task_ix, build_complete_task_ix,
-    build_skr_escrow_deposit_ix, fetch_tasks_by_state, fetch_task_by_id,
-    fetch_skr_balance, display_to_skr_tokens, skr_tokens_to_display,
+    self, OnChainTask, OnChainTaskState,
+    derive_task_pda, build_create_task_ix, build_claim_task_ix, build_complete_task_ixs,
+    build_approve_completion_ixs, build_cancel_task_ixs, build_skr_escrow_deposit_ix, fetch_task_by_id,
+    fetch_task_by_pda, fetch_skr_balance, display_to_skr_tokens, skr_tokens_to_display,
+    next_task_id, fetch_protocol_config,
 };
+use crate::capabilities::{capability_mask, capability_names};
+use crate::compute_budget::{self, PriorityFeeConfig};
+use crate::history::{HistoryFilter, WalletHistory};
+use crate::proofs::{completion_items, ProofManifest};
+use crate::rpc_pool::RpcPool;
+use crate::simulation;
+use crate::transaction_retry::{send_result_to_result, AsyncTransactionSender};
+use crate::keystore::{self, Keystore, Zeroizing};
+use crate::task_descriptions::{short_hash, DescriptionRegistry, ResolvedDescription};
+use crate::task_query::{TaskCache, TaskQuery};
 use crate::types::*;
 
+/// Sends of a create_task transaction before a lost task-id race is reported
+const MAX_TASK_CREATE_ATTEMPTS: u32 = 3;
+
+/// Open tasks listed per page unless the intent asks for another limit
+const TASK_PAGE_SIZE: usize = 50;
+
 /// Main Solana executor - handles all chain interactions
 pub struct SolanaExecutor {
-    /// RPC client for Solana network
-    rpc_client: RpcClient,
-    /// Local keypair for signing (NEVER leaves device)
+    /// RPC endpoints, shared with the other subsystems that read the chain
+    rpc: Arc<RpcPool>,
+    /// Active keypair for signing (NEVER leaves device)
     keypair: Arc<RwLock<Option<Keypair>>>,
+    /// Named wallet profiles loaded into memory
+    wallets: RwLock<HashMap<String, Keypair>>,
+    /// Profile currently copied into `keypair`, if any
+    active_wallet: RwLock<Option<String>>,
+    /// Drop all keys after this long without signing (None = never)
+    auto_lock: RwLock<Option<Duration>>,
+    /// Last unlock or signature
+    last_activity: RwLock<Instant>,
+    /// Off-chain task descriptions (None = show hashes only)
+    descriptions: RwLock<Option<Arc<DescriptionRegistry>>>,
+    /// Decoded wallet transactions (None = history unavailable)
+    history: RwLock<Option<Arc<WalletHistory>>>,
+    /// Snapshot of on-chain tasks shared by listings and protocol state
+    tasks: TaskCache,
+    /// How compute unit limits and priority fees are set on our transactions
+    priority_fees: PriorityFeeConfig,
     /// Network (mainnet-beta, devnet, testnet)
     _network: String,
     /// AgenC program ID (set this to your deployed program)
@@ -46,50 +89,263 @@ pub struct SolanaExecutor {
 impl SolanaExecutor {
     /// Create new executor with RPC endpoint
     pub fn new(rpc_url: &str, network: &str) -> Self {
+        Self::with_pool(Arc::new(RpcPool::single(rpc_url)), network)
+    }
+
+    /// Create an executor that reads and sends through a shared RPC pool
+    pub fn with_pool(rpc: Arc<RpcPool>, network: &str) -> Self {
         info!("Initializing SolanaExecutor for {}", network);
 
         let program_id = agenc_program::program_id();
 
         Self {
-            rpc_client: RpcClient::new_with_commitment(
-                rpc_url.to_string(),
-                CommitmentConfig::confirmed(),
-            ),
+            rpc,
             keypair: Arc::new(RwLock::new(None)),
+            wallets: RwLock::new(HashMap::new()),
+            active_wallet: RwLock::new(None),
+            auto_lock: RwLock::new(None),
+            last_activity: RwLock::new(Instant::now()),
+            descriptions: RwLock::new(None),
+            history: RwLock::new(None),
+            tasks: TaskCache::default(),
+            priority_fees: PriorityFeeConfig::default(),
             _network: network.to_string(),
             _program_id: program_id,
         }
     }
 
+    /// The RPC pool this executor reads and sends through
+    pub fn rpc_pool(&self) -> Arc<RpcPool> {
+        self.rpc.clone()
+    }
+
     /// Load keypair from file path (local-first: keys never leave device)
     pub async fn load_keypair(&self, keypair_path: &str) -> Result<String> {
         info!("Loading keypair from: {}", keypair_path);
 
-        let keypair_data = std::fs::read_to_string(keypair_path)
-            .map_err(|e| anyhow!("Failed to read keypair: {}", e))?;
+        let keypair = keystore::read_cli_keypair(Path::new(keypair_path))?;
+        let address = keypair.pubkey().to_string();
+
+        *self.keypair.write().await = Some(keypair);
+        *self.active_wallet.write().await = None;
+        self.touch().await;
 
-        let bytes: Vec<u8> = serde_json::from_str(&keypair_data)
-            .map_err(|e| anyhow!("Failed to parse keypair: {}", e))?;
+        info!("Loaded wallet: {}", address);
+        Ok(address)
+    }
 
-        let keypair = Keypair::try_from(bytes.as_slice())
-            .map_err(|e| anyhow!("Invalid keypair bytes: {}", e))?;
+    /// Decrypt an encrypted keystore and make it the active wallet. The
+    /// key derivation runs on a blocking thread, off the async runtime.
+    pub async fn unlock_keystore(&self, keystore_path: &str, passphrase: &str) -> Result<String> {
+        info!("Unlocking keystore: {}", keystore_path);
 
+        let path = PathBuf::from(keystore_path);
+        let passphrase = Zeroizing::new(passphrase.to_string());
+        let keypair = tokio::task::spawn_blocking(move || Keystore::load(&path)?.decrypt(&passphrase))
+            .await
+            .map_err(|e| anyhow!("Keystore decryption task failed: {}", e))??;
         let address = keypair.pubkey().to_string();
 
         *self.keypair.write().await = Some(keypair);
+        *self.active_wallet.write().await = None;
+        self.touch().await;
 
-        info!("Loaded wallet: {}", address);
+        info!("Unlocked wallet: {}", address);
+        Ok(address)
+    }
+
+    /// Drop every loaded key. Keypairs zeroize their secret on drop. The
+    /// active profile name is kept so reloading it makes it active again.
+    pub async fn lock(&self) {
+        *self.keypair.write().await = None;
+        self.wallets.write().await.clear();
+        info!("Wallet locked");
+    }
+
+    /// Whether no key is loaded (never unlocked, locked, or auto-locked)
+    pub async fn is_locked(&self) -> bool {
+        self.keypair.read().await.is_none() && self.wallets.read().await.is_empty()
+    }
+
+    /// Store new task descriptions in, and resolve listed ones from, `registry`
+    pub async fn set_description_registry(&self, registry: Arc<DescriptionRegistry>) {
+        *self.descriptions.write().await = Some(registry);
+    }
+
+    /// The description registry, if one is set
+    pub async fn description_registry(&self) -> Option<Arc<DescriptionRegistry>> {
+        self.descriptions.read().await.clone()
+    }
+
+    /// Look up a task's description. None if no registry is set.
+    pub async fn resolve_description(&self, task: &OnChainTask) -> Option<ResolvedDescription> {
+        let registry = self.description_registry().await?;
+        Some(registry.resolve(&task.description_hash).await)
+    }
+
+    /// Open tasks on-chain, highest reward first
+    pub async fn fetch_open_tasks(&self, limit: usize) -> Result<Vec<OnChainTask>> {
+        self.query_tasks(&TaskQuery::new().state(OnChainTaskState::Open).limit(limit)).await
+    }
+
+    /// Tasks matching `query`, served from the cached task snapshot
+    pub async fn query_tasks(&self, query: &TaskQuery) -> Result<Vec<OnChainTask>> {
+        self.rpc.read(|rpc| async move { self.tasks.query(&rpc, query).await }).await
+    }
+
+    /// Drop the task snapshot, e.g. after a `TaskWatcher` event
+    pub async fn invalidate_task_cache(&self) {
+        self.tasks.invalidate().await;
+    }
+
+    /// Protocol treasury that settlement instructions pay the fee to
+    async fn treasury(&self) -> Result<Pubkey> {
+        self.rpc.read(|rpc| async move { fetch_protocol_config(&rpc).await }).await?.treasury_pubkey()
+    }
+
+    /// Answer history requests from `history`
+    pub async fn set_wallet_history(&self, history: Arc<WalletHistory>) {
+        *self.history.write().await = Some(history);
+    }
+
+    /// Lock automatically after `idle` without signing (None disables)
+    pub async fn set_auto_lock(&self, idle: Option<Duration>) {
+        *self.auto_lock.write().await = idle;
+    }
+
+    /// Lock if the auto-lock idle time has passed. Returns true if it locked.
+    pub async fn lock_if_idle(&self) -> bool {
+        let Some(idle) = *self.auto_lock.read().await else { return false };
+        if self.last_activity.read().await.elapsed() < idle || self.is_locked().await {
+            return false;
+        }
+        info!("Auto-locking wallet after {}s idle", idle.as_secs());
+        self.lock().await;
+        true
+    }
+
+    async fn touch(&self) {
+        *self.last_activity.write().await = Instant::now();
+    }
+
+    /// Load a named wallet profile from a CLI keypair or an encrypted
+    /// keystore (which needs `passphrase`, decrypted on a blocking thread). It
+    /// becomes active if no wallet is loaded yet (or if it replaces the active
+    /// profile). Returns the address.
+    pub async fn load_wallet_profile(
+        &self,
+        name: &str,
+        keypair_path: &str,
+        passphrase: Option<&str>,
+    ) -> Result<String> {
+        validate_wallet_name(name)?;
+        info!("Loading wallet profile '{}' from: {}", name, keypair_path);
+
+        let path = PathBuf::from(keypair_path);
+        let passphrase = passphrase.map(|p| Zeroizing::new(p.to_string()));
+        let keypair = tokio::task::spawn_blocking(move || {
+            keystore::read_keypair_file(&path, passphrase.as_ref().map(|p| p.as_str()))
+        })
+        .await
+        .map_err(|e| anyhow!("Keystore decryption task failed: {}", e))??;
+        let address = keypair.pubkey().to_string();
+        self.wallets.write().await.insert(name.to_string(), keypair);
+        self.touch().await;
+
+        let replaces_active = self.active_wallet.read().await.as_deref() == Some(name);
+        if replaces_active || self.keypair.read().await.is_none() {
+            self.switch_wallet(name).await?;
+        }
+
+        Ok(address)
+    }
+
+    /// Make a loaded profile the active wallet. Returns its address.
+    pub async fn switch_wallet(&self, name: &str) -> Result<String> {
+        let wallets = self.wallets.read().await;
+        let keypair = wallets
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown wallet profile '{}'", name))?;
+        let address = keypair.pubkey().to_string();
+
+        *self.keypair.write().await = Some(keypair.insecure_clone());
+        *self.active_wallet.write().await = Some(name.to_string());
+
+        info!("Switched to wallet '{}' ({})", name, address);
         Ok(address)
     }
 
+    /// Unload a profile. The active profile cannot be removed.
+    pub async fn remove_wallet_profile(&self, name: &str) -> Result<()> {
+        if self.active_wallet.read().await.as_deref() == Some(name) {
+            return Err(anyhow!("Cannot remove the active wallet '{}' — switch first", name));
+        }
+        self.wallets
+            .write()
+            .await
+            .remove(name)
+            .ok_or_else(|| anyhow!("Unknown wallet profile '{}'", name))?;
+        Ok(())
+    }
+
+    /// Name of the active profile (None if no profile or a bare keypair is active)
+    pub async fn active_wallet(&self) -> Option<String> {
+        self.active_wallet.read().await.clone()
+    }
+
+    /// Loaded profiles and their addresses, sorted by name
+    pub async fn wallet_profiles(&self) -> Vec<(String, Pubkey)> {
+        let mut profiles: Vec<_> = self
+            .wallets
+            .read()
+            .await
+            .iter()
+            .map(|(name, kp)| (name.clone(), kp.pubkey()))
+            .collect();
+        profiles.sort();
+        profiles
+    }
+
+    /// Address of a named profile, or of the active wallet for None
+    pub async fn wallet_pubkey(&self, wallet: Option<&str>) -> Option<Pubkey> {
+        match wallet {
+            Some(name) => self.wallets.read().await.get(name).map(|kp| kp.pubkey()),
+            None => self.keypair.read().await.as_ref().map(|kp| kp.pubkey()),
+        }
+    }
+
+    /// Keypair that signs for an intent: the profile named by its `wallet`
+    /// param, or the active wallet
+    async fn signer(&self, params: &serde_json::Value) -> Result<RwLockReadGuard<'_, Keypair>> {
+        self.wallet_signer(intent_wallet(params)).await
+    }
+
+    /// Keypair of a named profile, or of the active wallet for None, for
+    /// transactions built outside this executor (e.g. Jupiter swaps). Fails
+    /// while the wallet is locked.
+    pub async fn wallet_signer(&self, wallet: Option<&str>) -> Result<RwLockReadGuard<'_, Keypair>> {
+        if self.lock_if_idle().await {
+            return Err(anyhow!("Wallet locked after inactivity — unlock to continue"));
+        }
+        self.touch().await;
+        match wallet {
+            Some(name) => RwLockReadGuard::try_map(self.wallets.read().await, |w| w.get(name))
+                .map_err(|_| anyhow!("Unknown wallet profile '{}'", name)),
+            None => RwLockReadGuard::try_map(self.keypair.read().await, |k| k.as_ref())
+                .map_err(|_| anyhow!("Wallet not connected")),
+        }
+    }
+
     /// Get wallet info (address + balance)
     pub async fn get_wallet_info(&self) -> Result<WalletInfo> {
-        let keypair_guard = self.keypair.read().await;
+        self.get_wallet_info_for(None).await
+    }
 
-        match keypair_guard.as_ref() {
-            Some(kp) => {
-                let address = kp.pubkey();
-                let balance = self.rpc_client.get_balance(&address).await?;
+    /// Get wallet info for a named profile, or the active wallet for None
+    pub async fn get_wallet_info_for(&self, wallet: Option<&str>) -> Result<WalletInfo> {
+        match self.wallet_pubkey(wallet).await {
+            Some(address) => {
+                let balance = self.rpc.read(|rpc| async move { Ok(rpc.get_balance(&address).await?) }).await?;
 
                 Ok(WalletInfo {
                     address: address.to_string(),
@@ -116,26 +372,40 @@ impl SolanaExecutor {
 
     /// Get keypair bytes for device pairing HMAC authentication.
     /// Returns None if no keypair is loaded (mobile wallet flow).
-    pub fn get_keypair_bytes(&self) -> Option<Vec<u8>> {
+    /// The copy is zeroized when the caller drops it.
+    pub fn get_keypair_bytes(&self) -> Option<Zeroizing<Vec<u8>>> {
         self.keypair
             .try_read()
             .ok()
-            .and_then(|guard| guard.as_ref().map(|kp| kp.to_bytes().to_vec()))
+            .and_then(|guard| guard.as_ref().map(|kp| Zeroizing::new(kp.to_bytes().to_vec())))
     }
 
     /// Execute a voice intent after policy approval
     pub async fn execute_intent(&self, intent: &VoiceIntent) -> Result<ExecutionResult> {
         info!("Executing intent: {:?}", intent.action);
 
-        match &intent.action {
+        if intent.action.uses_agenc_program() {
+            if let Err(e) = crate::network::ensure_configured() {
+                return Ok(ExecutionResult {
+                    success: false,
+                    message: e.to_string(),
+                    signature: None,
+                    data: None,
+                });
+            }
+        }
+
+        let result = match &intent.action {
             IntentAction::CreateTask => self.create_task(&intent.params).await,
             IntentAction::ClaimTask => self.claim_task(&intent.params).await,
             IntentAction::CompleteTask => self.complete_task(&intent.params).await,
             IntentAction::CancelTask => self.cancel_task(&intent.params).await,
-            IntentAction::ListOpenTasks => self.list_open_tasks().await,
+            IntentAction::ApproveCompletion => self.approve_completion(&intent.params).await,
+            IntentAction::ListOpenTasks => self.list_open_tasks(&intent.params).await,
             IntentAction::GetTaskStatus => self.get_task_status(&intent.params).await,
-            IntentAction::GetBalance => self.get_balance().await,
-            IntentAction::GetAddress => self.get_address().await,
+            IntentAction::GetBalance => self.get_balance(&intent.params).await,
+            IntentAction::GetAddress => self.get_address(&intent.params).await,
+            IntentAction::GetHistory => self.get_history(&intent.params).await,
             IntentAction::GetProtocolState => self.get_protocol_state().await,
             IntentAction::Help => Ok(ExecutionResult {
                 success: true,
@@ -235,7 +505,20 @@ impl SolanaExecutor {
                 signature: None,
                 data: None,
             }),
+        };
+
+        // Our own transaction changed a task: don't serve it from the snapshot
+        if matches!(
+            intent.action,
+            IntentAction::CreateTask
+                | IntentAction::ClaimTask
+                | IntentAction::CompleteTask
+                | IntentAction::CancelTask
+                | IntentAction::ApproveCompletion
+        ) {
+            self.tasks.invalidate().await;
         }
+        result
     }
 
     /// Create a new task on-chain with SOL reward and optional SKR token reward
@@ -244,16 +527,16 @@ impl SolanaExecutor {
             .map_err(|e| anyhow!("Invalid create task params: {}", e))?;
 
         let skr_amount = parsed.reward_skr.unwrap_or(0.0);
+        let required_capabilities = capability_mask(&parsed.required_capabilities)?;
         info!("Creating task: {} with reward {} SOL + {} SKR",
               parsed.description, parsed.reward_sol, skr_amount);
 
         // Verify wallet is loaded
-        let keypair_guard = self.keypair.read().await;
-        let keypair = keypair_guard.as_ref()
-            .ok_or_else(|| anyhow!("Wallet not connected"))?;
+        let keypair_guard = self.signer(params).await?;
+        let keypair: &Keypair = &keypair_guard;
 
         // Check SOL balance
-        let balance = self.rpc_client.get_balance(&keypair.pubkey()).await?;
+        let balance = self.rpc.read(|rpc| async move { Ok(rpc.get_balance(&keypair.pubkey()).await?) }).await?;
         let reward_lamports = (parsed.reward_sol * 1_000_000_000.0) as u64;
         // Account for tx fees + rent for new accounts
         let sol_needed = reward_lamports + 50_000;
@@ -274,8 +557,7 @@ impl SolanaExecutor {
         // Check SKR balance if SKR reward is specified
         let skr_tokens = if skr_amount > 0.0 {
             let raw = display_to_skr_tokens(skr_amount);
-            let skr_balance = fetch_skr_balance(&self.rpc_client, &keypair.pubkey()).await
-                .unwrap_or(0);
+            let skr_balance = self.rpc.read(|rpc| async move { fetch_skr_balance(&rpc, &keypair.pubkey()).await }).await?;
             if skr_balance < raw {
                 return Ok(ExecutionResult {
                     success: false,
@@ -293,48 +575,58 @@ impl SolanaExecutor {
             0
         };
 
-        // Generate a task ID from timestamp (will be overwritten by on-chain program counter)
-        let task_id_num = chrono::Utc::now().timestamp_millis() as u64;
-
-        // Build description hash
-        use sha2::{Sha256, Digest};
-        let description_hash: [u8; 32] = Sha256::digest(parsed.description.as_bytes()).into();
-
         let deadline = parsed.deadline_hours.map(|h|
             chrono::Utc::now().timestamp() + (h as i64 * 3600)
         ).unwrap_or(0);
 
-        // Build the create_task instruction
-        let create_ix = build_create_task_ix(
-            task_id_num,
-            &keypair.pubkey(),
-            description_hash,
-            reward_lamports,
-            deadline,
-            0, // no specific capabilities required
-        );
-
-        let mut instructions = vec![create_ix];
+        // The on-chain hash covers the whole description document, which is
+        // registered before sending so the task is never listed without it
+        let deadline_text = parsed.deadline_text.clone().or_else(|| {
+            chrono::DateTime::from_timestamp(deadline, 0)
+                .filter(|_| deadline > 0)
+                .map(|dt| dt.to_rfc3339())
+        });
+        let document = TaskDescription {
+            description: parsed.description.clone(),
+            tags: parsed.tags.clone(),
+            deadline_text,
+            acceptance_criteria: parsed.acceptance_criteria.clone(),
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        let registry = self.description_registry().await;
+        let description_hash = match &registry {
+            Some(registry) => registry.store(&document).await?,
+            None => document.hash()?,
+        };
 
-        // If SKR reward, add escrow deposit instructions
-        if skr_tokens > 0 {
-            let (task_pda, _) = derive_task_pda(task_id_num);
-            let deposit_ixs = build_skr_escrow_deposit_ix(
+        // The task PDA is seeded by the program's own counter. A concurrent
+        // creator can take the same id between our read and send; the seeds
+        // check then fails, and the counter is re-read and the transaction
+        // rebuilt.
+        let mut attempt = 0;
+        let (task_id_num, protocol, signature) = loop {
+            attempt += 1;
+            let (task_id_num, protocol) = self.rpc.read(|rpc| async move { next_task_id(&rpc).await }).await?;
+            let instructions = create_task_instructions(
+                task_id_num,
                 &keypair.pubkey(),
-                &task_pda,
+                description_hash,
+                reward_lamports,
+                deadline,
+                required_capabilities,
                 skr_tokens,
             )?;
-            instructions.extend(deposit_ixs);
-        }
-
-        let recent_blockhash = self.rpc_client.get_latest_blockhash().await
-            .map_err(|e| anyhow!("Failed to get blockhash: {}", e))?;
 
-        let message = Message::new(&instructions, Some(&keypair.pubkey()));
-        let tx = Transaction::new(&[keypair], message, recent_blockhash);
-
-        let signature = self.rpc_client.send_and_confirm_transaction(&tx).await
-            .map_err(|e| anyhow!("Transaction failed: {}", e))?;
+            match self.sign_and_send(&instructions, keypair).await {
+                Ok(signature) => break (task_id_num, protocol, signature),
+                Err(e) if attempt < MAX_TASK_CREATE_ATTEMPTS
+                    && agenc_program::is_task_id_collision(&e.to_string()) =>
+                {
+                    warn!("Task id {} was taken concurrently, retrying", task_id_num);
+                }
+                Err(e) => return Err(e),
+            }
+        };
 
         let (task_pda, _) = derive_task_pda(task_id_num);
 
@@ -346,14 +638,25 @@ impl SolanaExecutor {
             reward_skr_tokens: skr_tokens,
             status: TaskStatus::Open,
             claimer: None,
-            created_at: chrono::Utc::now().timestamp(),
+            created_at: document.created_at,
             deadline: Some(deadline),
+            description_status: registry.as_ref().map(|_| DescriptionStatus::Verified),
+            details: Some(document),
+            required_capabilities: capability_names(required_capabilities),
         };
 
-        let mut msg = format!("Task created! Reward: {:.4} SOL", parsed.reward_sol);
+        let mut msg = format!("Task #{} created! Reward: {:.4} SOL", task_id_num, parsed.reward_sol);
         if skr_amount > 0.0 {
             msg.push_str(&format!(" + {} SKR", skr_amount));
         }
+        if protocol.protocol_fee_bps > 0 {
+            let fee = protocol.fee_lamports(reward_lamports);
+            msg.push_str(&format!(
+                " (worker receives {:.4} SOL after the {}% protocol fee)",
+                (reward_lamports - fee) as f64 / 1_000_000_000.0,
+                protocol.fee_percent()
+            ));
+        }
         msg.push_str(&format!(". TX: {}", signature));
 
         info!("Task created on-chain! TX: {}", signature);
@@ -366,6 +669,60 @@ impl SolanaExecutor {
         })
     }
 
+    /// Build and simulate the transaction a spending intent would send, for
+    /// the confirmation prompt. None for intents this executor does not
+    /// preview (swaps are previewed by the Jupiter executor).
+    pub async fn preview_intent(&self, intent: &VoiceIntent) -> Result<Option<TransactionPreview>> {
+        match intent.action {
+            IntentAction::CreateTask => self.preview_create_task(&intent.params).await.map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Simulate create_task against the protocol counter's task id. The description
+    /// is hashed but not registered, since nothing is sent.
+    async fn preview_create_task(&self, params: &serde_json::Value) -> Result<TransactionPreview> {
+        let parsed: CreateTaskParams = serde_json::from_value(params.clone())
+            .map_err(|e| anyhow!("Invalid create task params: {}", e))?;
+        let creator = self.wallet_pubkey(params.get("wallet").and_then(|v| v.as_str())).await
+            .ok_or_else(|| anyhow!("No wallet loaded"))?;
+
+        let reward_lamports = (parsed.reward_sol * 1_000_000_000.0) as u64;
+        let skr_tokens = display_to_skr_tokens(parsed.reward_skr.unwrap_or(0.0));
+        let deadline = parsed.deadline_hours.map(|h|
+            chrono::Utc::now().timestamp() + (h as i64 * 3600)
+        ).unwrap_or(0);
+        let document = TaskDescription {
+            description: parsed.description.clone(),
+            tags: parsed.tags.clone(),
+            deadline_text: parsed.deadline_text.clone(),
+            acceptance_criteria: parsed.acceptance_criteria.clone(),
+            created_at: chrono::Utc::now().timestamp(),
+        };
+
+        let (task_id_num, protocol) = self.rpc.read(|rpc| async move { next_task_id(&rpc).await }).await?;
+        let instructions = create_task_instructions(
+            task_id_num,
+            &creator,
+            document.hash()?,
+            reward_lamports,
+            deadline,
+            capability_mask(&parsed.required_capabilities)?,
+            skr_tokens,
+        )?;
+        let protocol_fee = (protocol.protocol_fee_bps > 0).then(|| protocol.fee_lamports(reward_lamports));
+
+        // Blockhash and simulation come from the same endpoint
+        let instructions = &instructions;
+        self.rpc.read(|rpc| async move {
+            let recent_blockhash = rpc.get_latest_blockhash().await
+                .map_err(|e| anyhow!("Failed to get blockhash: {}", e))?;
+            let message = Message::new_with_blockhash(instructions, Some(&creator), &recent_blockhash);
+            let tx = VersionedTransaction::from(Transaction::new_unsigned(message));
+            simulation::simulate(&rpc, &tx, protocol_fee).await
+        }).await
+    }
+
     /// Claim an open task on-chain
     async fn claim_task(&self, params: &serde_json::Value) -> Result<ExecutionResult> {
         let parsed: ClaimTaskParams = serde_json::from_value(params.clone())
@@ -373,9 +730,8 @@ impl SolanaExecutor {
 
         info!("Claiming task: {}", parsed.task_id);
 
-        let keypair_guard = self.keypair.read().await;
-        let keypair = keypair_guard.as_ref()
-            .ok_or_else(|| anyhow!("Wallet not connected"))?;
+        let keypair_guard = self.signer(params).await?;
+        let keypair: &Keypair = &keypair_guard;
 
         // Parse task_id as u64 or treat as PDA address
         let task_pda = if let Ok(id) = parsed.task_id.parse::<u64>() {
@@ -388,16 +744,8 @@ impl SolanaExecutor {
         // Use wallet pubkey as agent_id (first 32 bytes)
         let agent_id: [u8; 32] = keypair.pubkey().to_bytes();
 
-        let ix = build_claim_task_ix(&task_pda, &keypair.pubkey(), agent_id);
-
-        let recent_blockhash = self.rpc_client.get_latest_blockhash().await
-            .map_err(|e| anyhow!("Failed to get blockhash: {}", e))?;
-
-        let message = Message::new(&[ix], Some(&keypair.pubkey()));
-        let tx = Transaction::new(&[keypair], message, recent_blockhash);
-
-        let signature = self.rpc_client.send_and_confirm_transaction(&tx).await
-            .map_err(|e| anyhow!("Transaction failed: {}", e))?;
+        let ix = build_claim_task_ix(&task_pda, &keypair.pubkey(), agent_id)?;
+        let signature = self.sign_and_send(&[ix], keypair).await?;
 
         info!("Task claimed! TX: {}", signature);
 
@@ -416,101 +764,341 @@ impl SolanaExecutor {
 
         info!("Completing task: {}", parsed.task_id);
 
-        let keypair_guard = self.keypair.read().await;
-        let keypair = keypair_guard.as_ref()
-            .ok_or_else(|| anyhow!("Wallet not connected"))?;
+        let keypair_guard = self.signer(params).await?;
+        let keypair: &Keypair = &keypair_guard;
 
-        let (task_pda, task_id_num) = if let Ok(id) = parsed.task_id.parse::<u64>() {
-            (derive_task_pda(id).0, Some(id))
-        } else {
-            let pda = Pubkey::from_str(&parsed.task_id)
-                .map_err(|_| anyhow!("Invalid task ID"))?;
-            (pda, None)
+        let task_pda = parse_task_pda(&parsed.task_id)?;
+
+        let task = match self.rpc.read(|rpc| async move { fetch_task_by_pda(&rpc, &task_pda).await }).await? {
+            Some(task) => task,
+            None => return Ok(ExecutionResult {
+                success: false,
+                message: format!("Task {} not found on-chain.", parsed.task_id),
+                signature: None,
+                data: None,
+            }),
         };
+        let has_skr = task.reward_skr_tokens > 0;
 
-        // Check on-chain task to see if it has an SKR reward
-        let has_skr = if let Some(id) = task_id_num {
-            match fetch_task_by_id(&self.rpc_client, id).await? {
-                Some(task) => task.reward_skr_tokens > 0,
-                None => false,
-            }
+        // The proof hash is the root of a manifest of what went in and out
+        let items = completion_items(&task.description_hash, &parsed)?;
+        let manifest = ProofManifest::new(&task_pda, &keypair.pubkey(), items)?;
+        let proof_hash = manifest.root_bytes()?;
+
+        // Creates the worker's SKR ATA first when the escrow holds SKR
+        let treasury = self.treasury().await?;
+        let ixs = build_complete_task_ixs(&task_pda, &keypair.pubkey(), &treasury, proof_hash, None, has_skr)?;
+        let signature = self.sign_and_send(&ixs, keypair).await?;
+
+        info!("Task completed! TX: {}", signature);
+
+        let resolved = self.resolve_description(&task).await;
+        let completed = AgencTask {
+            id: task.pda.clone(),
+            creator: task.creator.clone(),
+            description: describe(&task, resolved.as_ref()),
+            reward_lamports: task.reward_lamports,
+            reward_skr_tokens: task.reward_skr_tokens,
+            status: TaskStatus::Completed,
+            claimer: Some(keypair.pubkey().to_string()),
+            created_at: 0,
+            deadline: Some(task.deadline),
+            description_status: resolved.as_ref().map(|r| r.status),
+            details: resolved.and_then(|r| r.document),
+            required_capabilities: capability_names(task.required_capabilities),
+        };
+        // The manifest goes alongside the task so it can be stored as the
+        // task's VerificationLog
+        let mut data = serde_json::to_value(completed)?;
+        data["proof_hash"] = manifest.root.clone().into();
+        data["proof_manifest"] = serde_json::to_value(&manifest)?;
+
+        Ok(ExecutionResult {
+            success: true,
+            message: format!(
+                "Task {} completed with {} proof items (root {}...). Reward incoming. TX: {}",
+                parsed.task_id,
+                manifest.items.len(),
+                &manifest.root[..8],
+                signature
+            ),
+            signature: Some(signature.to_string()),
+            data: Some(data),
+        })
+    }
+
+    /// Cancel an open task on-chain (creator only).
+    /// Refunds the escrowed SOL reward, plus any SKR reward, to the creator.
+    async fn cancel_task(&self, params: &serde_json::Value) -> Result<ExecutionResult> {
+        let parsed: CancelTaskParams = serde_json::from_value(params.clone())
+            .map_err(|e| anyhow!("Invalid cancel task params: {}", e))?;
+
+        info!("Cancelling task: {}", parsed.task_id);
+
+        let keypair_guard = self.signer(params).await?;
+        let keypair: &Keypair = &keypair_guard;
+
+        let task_pda = if let Ok(id) = parsed.task_id.parse::<u64>() {
+            derive_task_pda(id).0
         } else {
-            false
+            Pubkey::from_str(&parsed.task_id)
+                .map_err(|_| anyhow!("Invalid task ID — must be a number or PDA address"))?
+        };
+
+        // Verify against on-chain state before signing anything
+        let task = match self.rpc.read(|rpc| async move { fetch_task_by_pda(&rpc, &task_pda).await }).await? {
+            Some(task) => task,
+            None => return Ok(ExecutionResult {
+                success: false,
+                message: format!("Task {} not found on-chain.", parsed.task_id),
+                signature: None,
+                data: None,
+            }),
         };
 
-        // Generate proof hash: SHA256(task_pda || agent_pubkey || timestamp)
-        use sha2::{Sha256, Digest};
-        let timestamp = chrono::Utc::now().timestamp() as u64;
-        let mut hasher = Sha256::new();
-        hasher.update(task_pda.as_ref());
-        hasher.update(keypair.pubkey().as_ref());
-        hasher.update(&timestamp.to_le_bytes());
-        let proof_hash: [u8; 32] = hasher.finalize().into();
+        if task.creator != keypair.pubkey().to_string() {
+            return Ok(ExecutionResult {
+                success: false,
+                message: format!("Only the task creator can cancel task {}.", parsed.task_id),
+                signature: None,
+                data: None,
+            });
+        }
 
-        let ix = build_complete_task_ix(&task_pda, &keypair.pubkey(), proof_hash, None, has_skr);
+        if task.state != OnChainTaskState::Open {
+            return Ok(ExecutionResult {
+                success: false,
+                message: format!(
+                    "Task {} is {} — only open tasks can be cancelled.",
+                    parsed.task_id,
+                    task.state.label()
+                ),
+                signature: None,
+                data: None,
+            });
+        }
 
-        let recent_blockhash = self.rpc_client.get_latest_blockhash().await
-            .map_err(|e| anyhow!("Failed to get blockhash: {}", e))?;
+        let ixs = build_cancel_task_ixs(&task_pda, &keypair.pubkey(), task.reward_skr_tokens > 0)?;
+        let signature = self.sign_and_send(&ixs, keypair).await?;
 
-        let message = Message::new(&[ix], Some(&keypair.pubkey()));
-        let tx = Transaction::new(&[keypair], message, recent_blockhash);
+        info!("Task cancelled! TX: {}", signature);
 
-        let signature = self.rpc_client.send_and_confirm_transaction(&tx).await
-            .map_err(|e| anyhow!("Transaction failed: {}", e))?;
+        let resolved = self.resolve_description(&task).await;
+        let cancelled = AgencTask {
+            id: task.pda.clone(),
+            creator: task.creator.clone(),
+            description: describe(&task, resolved.as_ref()),
+            reward_lamports: task.reward_lamports,
+            reward_skr_tokens: task.reward_skr_tokens,
+            status: TaskStatus::Cancelled,
+            claimer: None,
+            created_at: 0, // Not stored in on-chain account
+            deadline: Some(task.deadline),
+            description_status: resolved.as_ref().map(|r| r.status),
+            details: resolved.and_then(|r| r.document),
+            required_capabilities: capability_names(task.required_capabilities),
+        };
 
-        info!("Task completed! TX: {}", signature);
+        let mut refund = format!("{:.4} SOL", task.reward_sol());
+        if task.reward_skr_tokens > 0 {
+            refund.push_str(&format!(" + {} SKR", skr_tokens_to_display(task.reward_skr_tokens)));
+        }
 
         Ok(ExecutionResult {
             success: true,
-            message: format!("Task {} completed! Reward incoming. TX: {}", parsed.task_id, signature),
+            message: format!(
+                "Task {} cancelled. {} refunded to wallet. TX: {}",
+                parsed.task_id, refund, signature
+            ),
             signature: Some(signature.to_string()),
-            data: None,
+            data: Some(serde_json::to_value(cancelled)?),
         })
     }
 
-    /// Cancel an open task (creator only)
-    async fn cancel_task(&self, params: &serde_json::Value) -> Result<ExecutionResult> {
-        let task_id: String = serde_json::from_value(
-            params.get("task_id").cloned().unwrap_or_default()
-        ).unwrap_or_default();
+    /// Approve a worker's submission (creator only).
+    /// Releases the escrowed SOL, plus any SKR reward, to the worker.
+    async fn approve_completion(&self, params: &serde_json::Value) -> Result<ExecutionResult> {
+        let parsed: ApproveCompletionParams = serde_json::from_value(params.clone())
+            .map_err(|e| anyhow!("Invalid approve completion params: {}", e))?;
+
+        info!("Approving completion of task: {}", parsed.task_id);
+
+        let keypair_guard = self.signer(params).await?;
+        let keypair: &Keypair = &keypair_guard;
+
+        let task_pda = if let Ok(id) = parsed.task_id.parse::<u64>() {
+            derive_task_pda(id).0
+        } else {
+            Pubkey::from_str(&parsed.task_id)
+                .map_err(|_| anyhow!("Invalid task ID — must be a number or PDA address"))?
+        };
+
+        let task = match self.rpc.read(|rpc| async move { fetch_task_by_pda(&rpc, &task_pda).await }).await? {
+            Some(task) => task,
+            None => return Ok(ExecutionResult {
+                success: false,
+                message: format!("Task {} not found on-chain.", parsed.task_id),
+                signature: None,
+                data: None,
+            }),
+        };
+
+        if task.creator != keypair.pubkey().to_string() {
+            return Ok(ExecutionResult {
+                success: false,
+                message: format!("Only the task creator can approve task {}.", parsed.task_id),
+                signature: None,
+                data: None,
+            });
+        }
+
+        if task.state != OnChainTaskState::PendingValidation {
+            return Ok(ExecutionResult {
+                success: false,
+                message: format!(
+                    "Task {} is {} — only submitted tasks can be approved.",
+                    parsed.task_id,
+                    task.state.label()
+                ),
+                signature: None,
+                data: None,
+            });
+        }
+
+        let worker = match task.claimed_by.as_deref().map(Pubkey::from_str) {
+            Some(Ok(worker)) => worker,
+            _ => return Ok(ExecutionResult {
+                success: false,
+                message: format!("Task {} has no claimer to pay out.", parsed.task_id),
+                signature: None,
+                data: None,
+            }),
+        };
 
-        info!("Cancelling task: {}", task_id);
+        let treasury = self.treasury().await?;
+        let ixs = build_approve_completion_ixs(
+            &task_pda,
+            &keypair.pubkey(),
+            &worker,
+            &treasury,
+            task.reward_skr_tokens > 0,
+        )?;
+        let signature = self.sign_and_send(&ixs, keypair).await?;
+
+        info!("Task approved! TX: {}", signature);
+
+        let resolved = self.resolve_description(&task).await;
+        let approved = AgencTask {
+            id: task.pda.clone(),
+            creator: task.creator.clone(),
+            description: describe(&task, resolved.as_ref()),
+            reward_lamports: task.reward_lamports,
+            reward_skr_tokens: task.reward_skr_tokens,
+            status: TaskStatus::Completed,
+            claimer: task.claimed_by.clone(),
+            created_at: 0,
+            deadline: Some(task.deadline),
+            description_status: resolved.as_ref().map(|r| r.status),
+            details: resolved.and_then(|r| r.document),
+            required_capabilities: capability_names(task.required_capabilities),
+        };
+
+        let mut payout = format!("{:.4} SOL", task.reward_sol());
+        if task.reward_skr_tokens > 0 {
+            payout.push_str(&format!(" + {} SKR", skr_tokens_to_display(task.reward_skr_tokens)));
+        }
 
         Ok(ExecutionResult {
             success: true,
-            message: format!("Task {} cancelled. Reward returned to wallet.", task_id),
-            signature: Some(format!("sim_cancel_{}", task_id)),
-            data: None,
+            message: format!(
+                "Task {} approved. {} released to {}. TX: {}",
+                parsed.task_id, payout, worker, signature
+            ),
+            signature: Some(signature.to_string()),
+            data: Some(serde_json::to_value(approved)?),
         })
     }
 
+    /// Sign `instructions` with `keypair` and send with retries. A compute
+    /// budget (unit limit and priority fee) is prepended, and the
+    /// transaction is re-signed with a fresh blockhash if one expires.
+    async fn sign_and_send(&self, instructions: &[Instruction], keypair: &Keypair) -> Result<Signature> {
+        let instructions = compute_budget::with_compute_budget(
+            &self.rpc.client(),
+            instructions,
+            &keypair.pubkey(),
+            &self.priority_fees,
+        ).await;
+
+        let send_rpc = self.rpc.client();
+        let sender = AsyncTransactionSender::new(&send_rpc);
+        let result = sender.send_and_confirm_with_rebuild(|blockhash| {
+            let message = Message::new(&instructions, Some(&keypair.pubkey()));
+            Ok(VersionedTransaction::from(Transaction::new(&[keypair], message, blockhash)))
+        }).await?;
+        send_result_to_result(result)
+    }
+
+    /// Query for list_open_tasks: open tasks matching the intent's filters
+    async fn open_task_query(&self, params: &serde_json::Value) -> Result<TaskQuery> {
+        let parsed: ListTasksParams = serde_json::from_value(params.clone())
+            .map_err(|e| anyhow!("Invalid list tasks params: {}", e))?;
+        let lamports = |sol: f64| (sol * 1_000_000_000.0) as u64;
+
+        let mut query = TaskQuery::new()
+            .state(OnChainTaskState::Open)
+            .reward_range(parsed.min_reward_sol.map(lamports), parsed.max_reward_sol.map(lamports))
+            .sort(parsed.sort.unwrap_or_default())
+            .page(parsed.offset.unwrap_or(0), parsed.limit.unwrap_or(TASK_PAGE_SIZE));
+        if !parsed.capabilities.is_empty() {
+            query = query.within_capabilities(capability_mask(&parsed.capabilities)?);
+        }
+        if let Some(creator) = parsed.creator.as_deref() {
+            let creator = if creator.eq_ignore_ascii_case("me") {
+                self.wallet_pubkey(intent_wallet(params)).await
+                    .ok_or_else(|| anyhow!("Wallet not connected"))?
+            } else {
+                Pubkey::from_str(creator).map_err(|_| anyhow!("Invalid creator address: {}", creator))?
+            };
+            query = query.creator(creator);
+        }
+        Ok(query)
+    }
+
     /// List open tasks from the AgenC program on-chain
-    async fn list_open_tasks(&self) -> Result<ExecutionResult> {
+    async fn list_open_tasks(&self, params: &serde_json::Value) -> Result<ExecutionResult> {
+        let query = self.open_task_query(params).await?;
+
         info!("Fetching open tasks from AgenC program...");
 
-        match fetch_tasks_by_state(&self.rpc_client, OnChainTaskState::Open, 50).await {
+        match self.query_tasks(&query).await {
             Ok(tasks) => {
                 let count = tasks.len();
                 // Convert to the frontend AgencTask format
-                let frontend_tasks: Vec<AgencTask> = tasks.iter().map(|t| AgencTask {
-                    id: t.pda.clone(),
-                    creator: t.creator.clone(),
-                    description: format!("Task #{} (hash: {:02x}{:02x}{:02x}{:02x}...)",
-                        t.task_id, t.description_hash[0], t.description_hash[1],
-                        t.description_hash[2], t.description_hash[3]),
-                    reward_lamports: t.reward_lamports,
-                    reward_skr_tokens: t.reward_skr_tokens,
-                    status: match t.state {
-                        OnChainTaskState::Open => TaskStatus::Open,
-                        OnChainTaskState::InProgress => TaskStatus::Claimed,
-                        OnChainTaskState::Completed | OnChainTaskState::PendingValidation => TaskStatus::Completed,
-                        OnChainTaskState::Cancelled => TaskStatus::Cancelled,
-                        OnChainTaskState::Disputed => TaskStatus::Disputed,
-                    },
-                    claimer: t.claimed_by.clone(),
-                    created_at: 0, // Not stored in on-chain account
-                    deadline: Some(t.deadline),
-                }).collect();
+                let mut frontend_tasks: Vec<AgencTask> = Vec::with_capacity(count);
+                for t in &tasks {
+                    let resolved = self.resolve_description(t).await;
+                    frontend_tasks.push(AgencTask {
+                        id: t.pda.clone(),
+                        creator: t.creator.clone(),
+                        description: describe(t, resolved.as_ref()),
+                        reward_lamports: t.reward_lamports,
+                        reward_skr_tokens: t.reward_skr_tokens,
+                        status: match t.state {
+                            OnChainTaskState::Open => TaskStatus::Open,
+                            OnChainTaskState::InProgress => TaskStatus::Claimed,
+                            OnChainTaskState::Completed | OnChainTaskState::PendingValidation => TaskStatus::Completed,
+                            OnChainTaskState::Cancelled => TaskStatus::Cancelled,
+                            OnChainTaskState::Disputed => TaskStatus::Disputed,
+                        },
+                        claimer: t.claimed_by.clone(),
+                        created_at: 0, // Not stored in on-chain account
+                        deadline: Some(t.deadline),
+                        description_status: resolved.as_ref().map(|r| r.status),
+                        details: resolved.and_then(|r| r.document),
+                        required_capabilities: capability_names(t.required_capabilities),
+                    });
+                }
 
                 Ok(ExecutionResult {
                     success: true,
@@ -540,25 +1128,34 @@ impl SolanaExecutor {
         info!("Getting status for task: {}", task_id);
 
         if let Ok(id) = task_id.parse::<u64>() {
-            match fetch_task_by_id(&self.rpc_client, id).await? {
+            match self.rpc.read(|rpc| async move { fetch_task_by_id(&rpc, id).await }).await? {
                 Some(task) => {
                     let mut reward_str = format!("{:.4} SOL", task.reward_sol());
                     if task.reward_skr_tokens > 0 {
                         reward_str.push_str(&format!(" + {} SKR",
                             skr_tokens_to_display(task.reward_skr_tokens)));
                     }
+                    let resolved = self.resolve_description(&task).await;
+                    let mut data = serde_json::to_value(&task)?;
+                    data["required_capability_names"] =
+                        serde_json::to_value(capability_names(task.required_capabilities))?;
+                    if let Some(resolved) = &resolved {
+                        data["description_status"] = serde_json::to_value(resolved.status)?;
+                        data["details"] = serde_json::to_value(&resolved.document)?;
+                    }
                     Ok(ExecutionResult {
                         success: true,
                         message: format!(
-                            "Task #{}: {} | Reward: {} | Creator: {}...{}",
+                            "Task #{}: {} | {} | Reward: {} | Creator: {}...{}",
                             task.task_id,
+                            describe(&task, resolved.as_ref()),
                             task.state.label(),
                             reward_str,
                             &task.creator[..4],
                             &task.creator[task.creator.len()-4..],
                         ),
                         signature: None,
-                        data: Some(serde_json::to_value(&task)?),
+                        data: Some(data),
                     })
                 },
                 None => Ok(ExecutionResult {
@@ -579,8 +1176,8 @@ impl SolanaExecutor {
     }
 
     /// Get wallet balance
-    async fn get_balance(&self) -> Result<ExecutionResult> {
-        let info = self.get_wallet_info().await?;
+    async fn get_balance(&self, params: &serde_json::Value) -> Result<ExecutionResult> {
+        let info = self.get_wallet_info_for(intent_wallet(params)).await?;
 
         if !info.is_connected {
             return Ok(ExecutionResult {
@@ -600,8 +1197,8 @@ impl SolanaExecutor {
     }
 
     /// Get wallet address
-    async fn get_address(&self) -> Result<ExecutionResult> {
-        let info = self.get_wallet_info().await?;
+    async fn get_address(&self, params: &serde_json::Value) -> Result<ExecutionResult> {
+        let info = self.get_wallet_info_for(intent_wallet(params)).await?;
 
         if !info.is_connected {
             return Ok(ExecutionResult {
@@ -620,15 +1217,65 @@ impl SolanaExecutor {
         })
     }
 
+    /// Recent wallet transactions, filtered by date and event type
+    async fn get_history(&self, params: &serde_json::Value) -> Result<ExecutionResult> {
+        let Some(history) = self.history.read().await.clone() else {
+            return Ok(ExecutionResult {
+                success: false,
+                message: "Transaction history is not available.".into(),
+                signature: None,
+                data: None,
+            });
+        };
+        let Some(wallet) = self.wallet_pubkey(intent_wallet(params)).await else {
+            return Ok(ExecutionResult {
+                success: false,
+                message: "Wallet not connected.".into(),
+                signature: None,
+                data: None,
+            });
+        };
+
+        let filter = HistoryFilter::from_params(params)?;
+        let records = history.history(&wallet, &filter).await?;
+
+        let mut message = match records.len() {
+            0 => "No matching transactions.".to_string(),
+            n => format!("{} transaction{}:", n, if n == 1 { "" } else { "s" }),
+        };
+        for record in records.iter().take(5) {
+            let when = record
+                .block_time
+                .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| format!("slot {}", record.slot));
+            let what = match record.events.as_slice() {
+                [] => "Other transaction".to_string(),
+                events => events.iter().map(|e| e.describe()).collect::<Vec<_>>().join(", "),
+            };
+            let failed = if record.success { "" } else { " (failed)" };
+            message.push_str(&format!(" | {} {}{}", when, what, failed));
+        }
+
+        Ok(ExecutionResult {
+            success: true,
+            message,
+            signature: None,
+            data: Some(serde_json::to_value(&records)?),
+        })
+    }
+
     /// Get overall protocol state from on-chain data
     async fn get_protocol_state(&self) -> Result<ExecutionResult> {
         info!("Fetching protocol state from chain...");
 
-        // Fetch open tasks to get count and TVL
-        let open_tasks = fetch_tasks_by_state(&self.rpc_client, OnChainTaskState::Open, 100).await
-            .unwrap_or_default();
-        let in_progress = fetch_tasks_by_state(&self.rpc_client, OnChainTaskState::InProgress, 100).await
+        // Open and claimed tasks give the count, TVL and active operators
+        let active = self
+            .query_tasks(&TaskQuery::new().states([OnChainTaskState::Open, OnChainTaskState::InProgress]))
+            .await
             .unwrap_or_default();
+        let (open_tasks, in_progress): (Vec<_>, Vec<_>) =
+            active.into_iter().partition(|t| t.state == OnChainTaskState::Open);
 
         let tvl: u64 = open_tasks.iter().chain(in_progress.iter())
             .map(|t| t.reward_lamports)
@@ -660,11 +1307,71 @@ impl SolanaExecutor {
 - "Tetsuo create task: [description], reward [X] SOL"
 - "Tetsuo claim task [ID]"
 - "Tetsuo complete task [ID]"
+- "Tetsuo cancel task [ID]"
+- "Tetsuo approve task [ID]"
 - "Tetsuo list open tasks"
 - "Tetsuo get balance"
 - "Tetsuo get address"
-- "Tetsuo protocol status""#.into()
+- "Tetsuo show my swaps from the last 7 days"
+- "Tetsuo protocol status"
+- "Tetsuo get balance of treasury wallet""#.into()
+    }
+}
+
+/// Instructions creating task `task_id`, with the SKR escrow deposit if any
+fn create_task_instructions(
+    task_id: u64,
+    creator: &Pubkey,
+    description_hash: [u8; 32],
+    reward_lamports: u64,
+    deadline: i64,
+    required_capabilities: u64,
+    skr_tokens: u64,
+) -> Result<Vec<Instruction>> {
+    let mut instructions = vec![build_create_task_ix(
+        task_id,
+        creator,
+        description_hash,
+        reward_lamports,
+        deadline,
+        required_capabilities,
+    )?];
+    if skr_tokens > 0 {
+        let (task_pda, _) = derive_task_pda(task_id);
+        instructions.extend(build_skr_escrow_deposit_ix(creator, &task_pda, skr_tokens)?);
     }
+    Ok(instructions)
+}
+
+/// Task PDA from a numeric task id or a PDA address
+fn parse_task_pda(task_id: &str) -> Result<Pubkey> {
+    match task_id.parse::<u64>() {
+        Ok(id) => Ok(derive_task_pda(id).0),
+        Err(_) => Pubkey::from_str(task_id)
+            .map_err(|_| anyhow!("Invalid task ID — must be a number or PDA address")),
+    }
+}
+
+/// Display text for an on-chain task's description
+fn describe(task: &OnChainTask, resolved: Option<&ResolvedDescription>) -> String {
+    match resolved {
+        Some(resolved) => resolved.display_text(task.task_id, &task.description_hash),
+        None => format!("Task #{} (hash: {})", task.task_id, short_hash(&task.description_hash)),
+    }
+}
+
+/// Profile names are short identifiers: letters, digits, '-' and '_'
+pub fn validate_wallet_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.len() > 32
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Invalid wallet name '{}': use 1-32 letters, digits, '-' or '_'",
+            name
+        ));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -677,4 +1384,73 @@ mod tests {
         let info = exec.get_wallet_info().await.unwrap();
         assert!(!info.is_connected);
     }
+
+    fn write_keypair(name: &str) -> (String, Pubkey) {
+        let kp = Keypair::new();
+        let path = std::env::temp_dir().join(format!("agenc-test-{}-{}.json", name, kp.pubkey()));
+        std::fs::write(&path, serde_json::to_string(&kp.to_bytes().to_vec()).unwrap()).unwrap();
+        (path.to_string_lossy().into_owned(), kp.pubkey())
+    }
+
+    #[tokio::test]
+    async fn test_wallet_profiles_switch_and_select() {
+        let exec = SolanaExecutor::new("https://api.devnet.solana.com", "devnet");
+        let (hot_path, hot) = write_keypair("hot");
+        let (treasury_path, treasury) = write_keypair("treasury");
+
+        exec.load_wallet_profile("hot", &hot_path, None).await.unwrap();
+        exec.load_wallet_profile("treasury", &treasury_path, None).await.unwrap();
+        // First profile becomes active
+        assert_eq!(exec.active_wallet().await.as_deref(), Some("hot"));
+        assert_eq!(exec.get_wallet_pubkey(), Some(hot));
+
+        // An intent can pick a profile without switching
+        let params = serde_json::json!({ "wallet": "treasury" });
+        assert_eq!(exec.signer(&params).await.unwrap().pubkey(), treasury);
+        assert_eq!(exec.signer(&serde_json::json!({})).await.unwrap().pubkey(), hot);
+        assert!(exec.signer(&serde_json::json!({ "wallet": "nope" })).await.is_err());
+
+        exec.switch_wallet("treasury").await.unwrap();
+        assert_eq!(exec.get_wallet_pubkey(), Some(treasury));
+        assert!(exec.remove_wallet_profile("treasury").await.is_err());
+        exec.remove_wallet_profile("hot").await.unwrap();
+        assert_eq!(exec.wallet_profiles().await, vec![("treasury".to_string(), treasury)]);
+
+        let _ = std::fs::remove_file(hot_path);
+        let _ = std::fs::remove_file(treasury_path);
+    }
+
+    #[tokio::test]
+    async fn test_keystore_unlock_and_idle_lock() {
+        let exec = SolanaExecutor::new("https://api.devnet.solana.com", "devnet");
+        let keypair = Keypair::new();
+        let path = std::env::temp_dir().join(format!("agenc-test-ks-{}.json", keypair.pubkey()));
+        let params = keystore::KdfParams { m_cost_kib: 64, t_cost: 1, p_cost: 1 };
+        Keystore::encrypt_with(&keypair, "correct horse", params).unwrap().save(&path).unwrap();
+        let path_str = path.to_string_lossy().into_owned();
+
+        // Plaintext loading refuses keystores
+        assert!(exec.load_keypair(&path_str).await.is_err());
+        assert!(exec.unlock_keystore(&path_str, "wrong horse").await.is_err());
+        exec.unlock_keystore(&path_str, "correct horse").await.unwrap();
+        assert!(!exec.is_locked().await);
+
+        // Signing keeps the wallet unlocked; idling past the limit locks it
+        exec.set_auto_lock(Some(Duration::from_secs(60))).await;
+        assert!(exec.signer(&serde_json::json!({})).await.is_ok());
+        *exec.last_activity.write().await = Instant::now() - Duration::from_secs(61);
+        assert!(exec.signer(&serde_json::json!({})).await.is_err());
+        assert!(exec.is_locked().await);
+        assert!(exec.get_keypair_bytes().is_none());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_wallet_name_validation() {
+        assert!(validate_wallet_name("treasury").is_ok());
+        assert!(validate_wallet_name("hot-1_a").is_ok());
+        assert!(validate_wallet_name("").is_err());
+        assert!(validate_wallet_name("../etc").is_err());
+    }
 }
@@ -0,0 +1,408 @@
+//! ============================================================================
+//! Task Descriptions - Content-Addressed Off-Chain Task Documents
+//! ============================================================================
+//! Only a 32-byte `description_hash` is stored on-chain. The description
+//! itself (plus tags, deadline text and acceptance criteria) is stored here,
+//! keyed by that hash:
+//! - `create_task` writes the document to the local redb table and publishes
+//!   it to the gateway, if one is configured
+//! - list/status calls look up the local table first, then the gateway
+//! - Whatever comes back is re-hashed; bytes that do not hash to the on-chain
+//!   value are flagged as a mismatch and never cached or displayed
+//!
+//! Tasks created before documents existed hashed the bare description text;
+//! such content still verifies and is shown as a description without extras.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::db::OperatorDb;
+use crate::types::{DescriptionStatus, TaskDescription};
+
+/// Largest document accepted from a gateway
+pub const MAX_DESCRIPTION_BYTES: usize = 64 * 1024;
+
+impl TaskDescription {
+    /// The exact bytes whose SHA-256 goes on-chain
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| anyhow!("Failed to serialize task description: {}", e))
+    }
+
+    /// On-chain hash of this document
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        Ok(description_hash(&self.to_bytes()?))
+    }
+
+    /// Parse stored bytes: a JSON document, or legacy plain description text
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if let Ok(doc) = serde_json::from_slice::<TaskDescription>(bytes) {
+            return Ok(doc);
+        }
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| anyhow!("Task description is neither JSON nor UTF-8 text"))?;
+        Ok(Self {
+            description: text.to_string(),
+            tags: vec![],
+            deadline_text: None,
+            acceptance_criteria: vec![],
+            created_at: 0,
+        })
+    }
+}
+
+/// SHA-256 of raw description bytes
+pub fn description_hash(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
+
+/// Remote source of description documents, addressed by hash
+#[async_trait]
+pub trait DescriptionFetcher: Send + Sync {
+    /// Raw bytes stored under `hash`, or None if the backend has nothing
+    async fn fetch(&self, hash: &[u8; 32]) -> Result<Option<Vec<u8>>>;
+
+    /// Make `bytes` available under `hash` to other operators
+    async fn publish(&self, hash: &[u8; 32], bytes: &[u8]) -> Result<()>;
+}
+
+/// HTTP gateway serving documents at `{base_url}/{hex hash}` (GET to fetch,
+/// PUT to publish), e.g. an IPFS-style pinning service
+pub struct HttpDescriptionGateway {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl HttpDescriptionGateway {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn url(&self, hash: &[u8; 32]) -> String {
+        format!("{}/{}", self.base_url, hex::encode(hash))
+    }
+}
+
+#[async_trait]
+impl DescriptionFetcher for HttpDescriptionGateway {
+    async fn fetch(&self, hash: &[u8; 32]) -> Result<Option<Vec<u8>>> {
+        let mut response = self
+            .client
+            .get(self.url(hash))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to fetch task description: {}", e))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(anyhow!("Description gateway error {}", response.status()));
+        }
+
+        // Refuse oversized documents up front, and stop reading once the
+        // cap is passed when the gateway does not say (or lies)
+        if let Some(length) = response.content_length().filter(|l| *l > MAX_DESCRIPTION_BYTES as u64) {
+            return Err(anyhow!("Task description too large: {} bytes", length));
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| anyhow!("Failed to read task description: {}", e))?
+        {
+            bytes.extend_from_slice(&chunk);
+            if bytes.len() > MAX_DESCRIPTION_BYTES {
+                return Err(anyhow!("Task description too large: over {} bytes", MAX_DESCRIPTION_BYTES));
+            }
+        }
+        Ok(Some(bytes))
+    }
+
+    async fn publish(&self, hash: &[u8; 32], bytes: &[u8]) -> Result<()> {
+        let response = self
+            .client
+            .put(self.url(hash))
+            .header("Content-Type", "application/json")
+            .body(bytes.to_vec())
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to publish task description: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Description gateway error {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Result of looking up a task's description
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedDescription {
+    pub status: DescriptionStatus,
+    /// Only set when `status` is Verified
+    pub document: Option<TaskDescription>,
+}
+
+impl ResolvedDescription {
+    fn missing() -> Self {
+        Self { status: DescriptionStatus::Missing, document: None }
+    }
+
+    /// Text to show for task `task_id`: the verified description, or a
+    /// placeholder naming the hash
+    pub fn display_text(&self, task_id: u64, hash: &[u8; 32]) -> String {
+        match (&self.status, &self.document) {
+            (DescriptionStatus::Verified, Some(doc)) => doc.description.clone(),
+            (DescriptionStatus::Mismatch, _) => format!(
+                "Task #{} (description does not match hash {})",
+                task_id,
+                short_hash(hash)
+            ),
+            _ => format!("Task #{} (hash: {})", task_id, short_hash(hash)),
+        }
+    }
+}
+
+/// Local store plus optional gateway for task description documents
+pub struct DescriptionRegistry {
+    db: Arc<RwLock<Option<OperatorDb>>>,
+    gateway: Option<Arc<dyn DescriptionFetcher>>,
+}
+
+impl DescriptionRegistry {
+    /// Registry backed by the shared operator database (None = in-flight only)
+    pub fn new(db: Arc<RwLock<Option<OperatorDb>>>) -> Self {
+        Self { db, gateway: None }
+    }
+
+    /// Also fetch from, and publish to, `gateway`
+    pub fn with_gateway(mut self, gateway: Arc<dyn DescriptionFetcher>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    /// Store a document locally and publish it. Returns its on-chain hash.
+    /// Publishing is best-effort: the local copy is enough for this operator.
+    pub async fn store(&self, doc: &TaskDescription) -> Result<[u8; 32]> {
+        let bytes = doc.to_bytes()?;
+        let hash = description_hash(&bytes);
+        let hash_hex = hex::encode(hash);
+
+        if let Some(db) = self.db.read().await.as_ref() {
+            db.store_description(&hash_hex, &bytes)?;
+        }
+
+        if let Some(gateway) = &self.gateway {
+            match gateway.publish(&hash, &bytes).await {
+                Ok(()) => debug!("Published task description {}", hash_hex),
+                Err(e) => warn!("Failed to publish task description {}: {}", hash_hex, e),
+            }
+        }
+
+        info!("Registered task description {}", hash_hex);
+        Ok(hash)
+    }
+
+    /// Find the document for an on-chain hash and verify it
+    pub async fn resolve(&self, hash: &[u8; 32]) -> ResolvedDescription {
+        let hash_hex = hex::encode(hash);
+        let mut mismatch = false;
+
+        if let Some(db) = self.db.read().await.as_ref() {
+            match db.get_description(&hash_hex) {
+                Ok(Some(bytes)) => match verify(hash, &bytes) {
+                    Some(resolved) => return resolved,
+                    None => {
+                        warn!("Local task description {} is corrupt, discarding", hash_hex);
+                        mismatch = true;
+                        if let Err(e) = db.delete_description(&hash_hex) {
+                            warn!("Failed to discard task description {}: {}", hash_hex, e);
+                        }
+                    }
+                },
+                Ok(None) => {}
+                Err(e) => warn!("Failed to read task description {}: {}", hash_hex, e),
+            }
+        }
+
+        if let Some(gateway) = &self.gateway {
+            match gateway.fetch(hash).await {
+                Ok(Some(bytes)) => match verify(hash, &bytes) {
+                    Some(resolved) => {
+                        if let Some(db) = self.db.read().await.as_ref() {
+                            if let Err(e) = db.store_description(&hash_hex, &bytes) {
+                                warn!("Failed to cache task description {}: {}", hash_hex, e);
+                            }
+                        }
+                        return resolved;
+                    }
+                    None => {
+                        warn!("Gateway returned a task description that does not match {}", hash_hex);
+                        mismatch = true;
+                    }
+                },
+                Ok(None) => {}
+                Err(e) => warn!("Failed to fetch task description {}: {}", hash_hex, e),
+            }
+        }
+
+        if mismatch {
+            ResolvedDescription { status: DescriptionStatus::Mismatch, document: None }
+        } else {
+            ResolvedDescription::missing()
+        }
+    }
+}
+
+/// Verified document if `bytes` hash to `hash`, None on mismatch
+fn verify(hash: &[u8; 32], bytes: &[u8]) -> Option<ResolvedDescription> {
+    if description_hash(bytes) != *hash {
+        return None;
+    }
+    let document = TaskDescription::from_bytes(bytes).ok()?;
+    Some(ResolvedDescription { status: DescriptionStatus::Verified, document: Some(document) })
+}
+
+/// Short hex prefix used when a task has no verified description
+pub fn short_hash(hash: &[u8; 32]) -> String {
+    format!("{}...", hex::encode(&hash[..4]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::shared_temp_db;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// In-memory gateway that serves whatever was put in it
+    #[derive(Default)]
+    struct MemoryGateway {
+        docs: Mutex<HashMap<[u8; 32], Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl DescriptionFetcher for MemoryGateway {
+        async fn fetch(&self, hash: &[u8; 32]) -> Result<Option<Vec<u8>>> {
+            Ok(self.docs.lock().unwrap().get(hash).cloned())
+        }
+
+        async fn publish(&self, hash: &[u8; 32], bytes: &[u8]) -> Result<()> {
+            self.docs.lock().unwrap().insert(*hash, bytes.to_vec());
+            Ok(())
+        }
+    }
+
+    fn doc() -> TaskDescription {
+        TaskDescription {
+            description: "Audit the escrow program".into(),
+            tags: vec!["security".into(), "rust".into()],
+            deadline_text: Some("end of sprint".into()),
+            acceptance_criteria: vec!["Report lists every finding".into()],
+            created_at: 1_700_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_then_resolve_locally() {
+        let (db, _path) = shared_temp_db();
+        let registry = DescriptionRegistry::new(db);
+
+        let hash = registry.store(&doc()).await.unwrap();
+        assert_eq!(hash, doc().hash().unwrap());
+
+        let resolved = registry.resolve(&hash).await;
+        assert_eq!(resolved.status, DescriptionStatus::Verified);
+        assert_eq!(resolved.document, Some(doc()));
+
+    }
+
+    #[tokio::test]
+    async fn test_gateway_fetch_is_verified_and_cached() {
+        let gateway = Arc::new(MemoryGateway::default());
+        let bytes = doc().to_bytes().unwrap();
+        let hash = description_hash(&bytes);
+        gateway.publish(&hash, &bytes).await.unwrap();
+
+        let (db, _path) = shared_temp_db();
+        let registry = DescriptionRegistry::new(Arc::clone(&db)).with_gateway(gateway);
+        assert_eq!(registry.resolve(&hash).await.status, DescriptionStatus::Verified);
+
+        let cached = db.read().await.as_ref().unwrap().get_description(&hex::encode(hash)).unwrap();
+        assert_eq!(cached, Some(bytes));
+
+    }
+
+    #[tokio::test]
+    async fn test_tampered_content_is_flagged() {
+        let gateway = Arc::new(MemoryGateway::default());
+        let hash = doc().hash().unwrap();
+        let mut forged = doc();
+        forged.description = "Send all funds to me".into();
+        gateway.publish(&hash, &forged.to_bytes().unwrap()).await.unwrap();
+
+        let (db, _path) = shared_temp_db();
+        let registry = DescriptionRegistry::new(Arc::clone(&db)).with_gateway(gateway);
+
+        let resolved = registry.resolve(&hash).await;
+        assert_eq!(resolved.status, DescriptionStatus::Mismatch);
+        assert_eq!(resolved.document, None);
+        // Forged content is not cached
+        let cached = db.read().await.as_ref().unwrap().get_description(&hex::encode(hash)).unwrap();
+        assert_eq!(cached, None);
+
+    }
+
+    #[tokio::test]
+    async fn test_missing_and_legacy_plain_text() {
+        let registry = DescriptionRegistry::new(Arc::new(RwLock::new(None)));
+        assert_eq!(registry.resolve(&[7u8; 32]).await.status, DescriptionStatus::Missing);
+
+        // Older tasks hashed the bare description
+        let text = b"Translate the docs";
+        let resolved = verify(&description_hash(text), text).unwrap();
+        assert_eq!(resolved.document.unwrap().description, "Translate the docs");
+    }
+
+    /// Serve one HTTP response with `headers` and `body` on a loopback port
+    fn serve_once(headers: &str, body: Vec<u8>) -> String {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let head = format!("HTTP/1.1 200 OK\r\n{}Connection: close\r\n\r\n", headers);
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0u8; 4096]);
+            let _ = stream.write_all(head.as_bytes());
+            let _ = stream.write_all(&body);
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_gateway_caps_description_size() {
+        let huge = vec![b'x'; MAX_DESCRIPTION_BYTES + 1];
+
+        // Declared too large: rejected before reading the body
+        let url = serve_once(&format!("Content-Length: {}\r\n", huge.len()), Vec::new());
+        let err = HttpDescriptionGateway::new(&url).fetch(&[1u8; 32]).await.unwrap_err();
+        assert!(err.to_string().contains("too large"), "{}", err);
+
+        // Undeclared length: reading stops at the cap
+        let url = serve_once("", huge);
+        let err = HttpDescriptionGateway::new(&url).fetch(&[1u8; 32]).await.unwrap_err();
+        assert!(err.to_string().contains("too large"), "{}", err);
+
+        let url = serve_once("Content-Length: 5\r\n", b"hello".to_vec());
+        let fetched = HttpDescriptionGateway::new(&url).fetch(&[1u8; 32]).await.unwrap();
+        assert_eq!(fetched.as_deref(), Some(&b"hello"[..]));
+    }
+}
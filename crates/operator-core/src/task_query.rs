@@ -0,0 +1,479 @@
+//! ============================================================================
+//! Task Queries - Filtered, Sorted and Paged Task Listings
+//! ============================================================================
+//! `TaskQuery` describes which tasks to list:
+//! - State, creator and claimer become memcmp filters, so the RPC node
+//!   drops non-matching accounts
+//! - Reward range, deadline window and capabilities are checked client-side
+//! - Results are sorted before they are paged, so the first page really holds
+//!   the best matches
+//!
+//! `TaskCache` keeps a snapshot of every task account. A refresh lists only
+//! each task's state byte and re-fetches the accounts that are new or whose
+//! state changed; the other fields of a task only change with its state.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::agenc_program::{
+    program_id, OnChainTask, OnChainTaskState, TASK_CLAIMED_BY_OFFSET, TASK_CREATOR_OFFSET,
+    TASK_DISCRIMINATOR, TASK_STATUS_OFFSET,
+};
+
+/// Accounts per getMultipleAccounts call (the RPC maximum)
+const MULTIPLE_ACCOUNTS_BATCH: usize = 100;
+
+/// How long a task snapshot is served before it is refreshed
+pub const DEFAULT_TASK_CACHE_TTL: Duration = Duration::from_secs(15);
+
+/// Order of query results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSort {
+    #[default]
+    RewardDesc,
+    RewardAsc,
+    /// Soonest deadline first, tasks without a deadline last
+    DeadlineAsc,
+    /// Highest task id first
+    Newest,
+}
+
+/// Which tasks to list, in what order, and which page of them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskQuery {
+    /// Empty = every state
+    pub states: Vec<OnChainTaskState>,
+    pub creator: Option<Pubkey>,
+    pub claimer: Option<Pubkey>,
+    pub min_reward_lamports: Option<u64>,
+    pub max_reward_lamports: Option<u64>,
+    /// Deadline at or after this (unix seconds); tasks without one pass
+    pub deadline_after: Option<i64>,
+    /// Deadline at or before this; tasks without one fail
+    pub deadline_before: Option<i64>,
+    /// Every required capability is in this mask (tasks we can do)
+    pub within_capabilities: Option<u64>,
+    /// Every capability in this mask is required
+    pub requiring_capabilities: Option<u64>,
+    pub sort: TaskSort,
+    pub offset: usize,
+    /// None = everything after `offset`
+    pub limit: Option<usize>,
+}
+
+impl TaskQuery {
+    /// Every task, highest reward first
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(mut self, state: OnChainTaskState) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    pub fn states(mut self, states: impl IntoIterator<Item = OnChainTaskState>) -> Self {
+        self.states.extend(states);
+        self
+    }
+
+    pub fn creator(mut self, creator: Pubkey) -> Self {
+        self.creator = Some(creator);
+        self
+    }
+
+    pub fn claimer(mut self, claimer: Pubkey) -> Self {
+        self.claimer = Some(claimer);
+        self
+    }
+
+    /// Reward between `min` and `max` lamports, inclusive (None = unbounded)
+    pub fn reward_range(mut self, min: Option<u64>, max: Option<u64>) -> Self {
+        self.min_reward_lamports = min;
+        self.max_reward_lamports = max;
+        self
+    }
+
+    pub fn deadline_after(mut self, timestamp: i64) -> Self {
+        self.deadline_after = Some(timestamp);
+        self
+    }
+
+    pub fn deadline_before(mut self, timestamp: i64) -> Self {
+        self.deadline_before = Some(timestamp);
+        self
+    }
+
+    pub fn within_capabilities(mut self, mask: u64) -> Self {
+        self.within_capabilities = Some(mask);
+        self
+    }
+
+    pub fn requiring_capabilities(mut self, mask: u64) -> Self {
+        self.requiring_capabilities = Some(mask);
+        self
+    }
+
+    pub fn sort(mut self, sort: TaskSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn page(mut self, offset: usize, limit: usize) -> Self {
+        self.offset = offset;
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether `task` passes every filter (server-side ones included)
+    pub fn matches(&self, task: &OnChainTask) -> bool {
+        let deadline = (task.deadline != 0).then_some(task.deadline);
+        (self.states.is_empty() || self.states.contains(&task.state))
+            && self.creator.is_none_or(|c| task.creator == c.to_string())
+            && self.claimer.is_none_or(|c| task.claimed_by.as_deref() == Some(&c.to_string()))
+            && self.min_reward_lamports.is_none_or(|min| task.reward_lamports >= min)
+            && self.max_reward_lamports.is_none_or(|max| task.reward_lamports <= max)
+            && self.deadline_after.is_none_or(|after| deadline.is_none_or(|d| d >= after))
+            && self.deadline_before.is_none_or(|before| deadline.is_some_and(|d| d <= before))
+            && self.within_capabilities.is_none_or(|mask| task.required_capabilities & !mask == 0)
+            && self.requiring_capabilities.is_none_or(|mask| task.required_capabilities & mask == mask)
+    }
+
+    /// Filter, sort, then page `tasks`
+    pub fn apply<'a>(&self, tasks: impl IntoIterator<Item = &'a OnChainTask>) -> Vec<OnChainTask> {
+        let mut matched: Vec<&OnChainTask> = tasks.into_iter().filter(|t| self.matches(t)).collect();
+        // Ties are broken by PDA so pages are stable between calls
+        match self.sort {
+            TaskSort::RewardDesc => matched.sort_by(|a, b| {
+                b.reward_lamports.cmp(&a.reward_lamports).then_with(|| a.pda.cmp(&b.pda))
+            }),
+            TaskSort::RewardAsc => matched.sort_by(|a, b| {
+                a.reward_lamports.cmp(&b.reward_lamports).then_with(|| a.pda.cmp(&b.pda))
+            }),
+            TaskSort::DeadlineAsc => matched.sort_by(|a, b| {
+                (a.deadline == 0, a.deadline)
+                    .cmp(&(b.deadline == 0, b.deadline))
+                    .then_with(|| a.pda.cmp(&b.pda))
+            }),
+            TaskSort::Newest => {
+                matched.sort_by(|a, b| b.task_id.cmp(&a.task_id).then_with(|| a.pda.cmp(&b.pda)))
+            }
+        }
+        matched
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    /// getProgramAccounts filters for one of the query's states (None = any)
+    pub fn rpc_filters(&self, state: Option<OnChainTaskState>) -> Vec<RpcFilterType> {
+        let mut filters = vec![task_discriminator_filter()];
+        if let Some(state) = state {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(TASK_STATUS_OFFSET, vec![state as u8])));
+        }
+        if let Some(creator) = self.creator {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                TASK_CREATOR_OFFSET,
+                creator.to_bytes().to_vec(),
+            )));
+        }
+        if let Some(claimer) = self.claimer {
+            // Option tag 1 followed by the pubkey
+            let mut bytes = vec![1];
+            bytes.extend_from_slice(&claimer.to_bytes());
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(TASK_CLAIMED_BY_OFFSET, bytes)));
+        }
+        filters
+    }
+}
+
+fn task_discriminator_filter() -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, TASK_DISCRIMINATOR.to_vec()))
+}
+
+/// Task accounts matching `filters`, optionally only a slice of their data
+async fn program_accounts(
+    rpc: &RpcClient,
+    filters: Vec<RpcFilterType>,
+    data_slice: Option<UiDataSliceConfig>,
+) -> Result<Vec<(Pubkey, Account)>> {
+    crate::network::ensure_configured()?;
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice,
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    rpc.get_program_accounts_with_config(&program_id(), config)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch program accounts: {}", e))
+}
+
+fn decode_task(pubkey: &Pubkey, data: &[u8]) -> Option<OnChainTask> {
+    match OnChainTask::from_account_data(data, pubkey) {
+        Ok(task) => Some(task),
+        Err(e) => {
+            warn!("Failed to deserialize task {}: {}", pubkey, e);
+            None
+        }
+    }
+}
+
+/// Fetch the tasks matching `query` straight from the RPC node
+pub async fn fetch_tasks(rpc: &RpcClient, query: &TaskQuery) -> Result<Vec<OnChainTask>> {
+    // One request per state; no states means one request for all of them
+    let states: Vec<Option<OnChainTaskState>> = if query.states.is_empty() {
+        vec![None]
+    } else {
+        query.states.iter().copied().map(Some).collect()
+    };
+
+    let mut tasks = Vec::new();
+    for state in states {
+        let accounts = program_accounts(rpc, query.rpc_filters(state), None).await?;
+        tasks.extend(accounts.iter().filter_map(|(pubkey, account)| decode_task(pubkey, &account.data)));
+    }
+    Ok(query.apply(&tasks))
+}
+
+/// Which snapshot entries to re-fetch and which to drop, given a fresh
+/// listing of (task, state byte)
+fn plan_refresh(
+    known: &HashMap<Pubkey, OnChainTask>,
+    listing: &[(Pubkey, u8)],
+) -> (Vec<Pubkey>, Vec<Pubkey>) {
+    let fetch = listing
+        .iter()
+        .filter(|(pubkey, state)| known.get(pubkey).is_none_or(|t| t.state as u8 != *state))
+        .map(|(pubkey, _)| *pubkey)
+        .collect();
+    let listed: std::collections::HashSet<&Pubkey> = listing.iter().map(|(pubkey, _)| pubkey).collect();
+    let removed = known.keys().filter(|pubkey| !listed.contains(pubkey)).copied().collect();
+    (fetch, removed)
+}
+
+struct TaskSnapshot {
+    tasks: HashMap<Pubkey, OnChainTask>,
+    /// None after `invalidate`
+    refreshed_at: Option<Instant>,
+}
+
+/// Snapshot of every task account, refreshed incrementally once it is
+/// older than its TTL. Concurrent readers share a single refresh.
+pub struct TaskCache {
+    snapshot: RwLock<Option<TaskSnapshot>>,
+    ttl: Duration,
+}
+
+impl Default for TaskCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TASK_CACHE_TTL)
+    }
+}
+
+impl TaskCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { snapshot: RwLock::new(None), ttl }
+    }
+
+    /// Refresh on the next read (after one of our own transactions)
+    pub async fn invalidate(&self) {
+        if let Some(snapshot) = self.snapshot.write().await.as_mut() {
+            snapshot.refreshed_at = None;
+        }
+    }
+
+    /// Tasks matching `query`, from a snapshot no older than the TTL
+    pub async fn query(&self, rpc: &RpcClient, query: &TaskQuery) -> Result<Vec<OnChainTask>> {
+        self.refresh_if_stale(rpc).await?;
+        let guard = self.snapshot.read().await;
+        Ok(guard.as_ref().map(|s| query.apply(s.tasks.values())).unwrap_or_default())
+    }
+
+    async fn refresh_if_stale(&self, rpc: &RpcClient) -> Result<()> {
+        let mut guard = self.snapshot.write().await;
+        match guard.as_mut() {
+            Some(snapshot) if snapshot.refreshed_at.is_some_and(|at| at.elapsed() < self.ttl) => Ok(()),
+            Some(snapshot) => Self::refresh(rpc, snapshot).await,
+            None => {
+                let accounts = program_accounts(rpc, vec![task_discriminator_filter()], None).await?;
+                let tasks: HashMap<Pubkey, OnChainTask> = accounts
+                    .iter()
+                    .filter_map(|(pubkey, account)| decode_task(pubkey, &account.data).map(|t| (*pubkey, t)))
+                    .collect();
+                debug!("Task snapshot loaded ({} tasks)", tasks.len());
+                *guard = Some(TaskSnapshot { tasks, refreshed_at: Some(Instant::now()) });
+                Ok(())
+            }
+        }
+    }
+
+    /// List state bytes only, then re-fetch what changed
+    async fn refresh(rpc: &RpcClient, snapshot: &mut TaskSnapshot) -> Result<()> {
+        let state_only = UiDataSliceConfig { offset: TASK_STATUS_OFFSET, length: 1 };
+        let listing: Vec<(Pubkey, u8)> = program_accounts(rpc, vec![task_discriminator_filter()], Some(state_only))
+            .await?
+            .into_iter()
+            .filter_map(|(pubkey, account)| account.data.first().map(|state| (pubkey, *state)))
+            .collect();
+
+        let (fetch, removed) = plan_refresh(&snapshot.tasks, &listing);
+        for pubkey in &removed {
+            snapshot.tasks.remove(pubkey);
+        }
+        for batch in fetch.chunks(MULTIPLE_ACCOUNTS_BATCH) {
+            let accounts = rpc
+                .get_multiple_accounts(batch)
+                .await
+                .map_err(|e| anyhow!("Failed to fetch task accounts: {}", e))?;
+            for (pubkey, account) in batch.iter().zip(accounts) {
+                match account.and_then(|a| decode_task(pubkey, &a.data)) {
+                    Some(task) => snapshot.tasks.insert(*pubkey, task),
+                    None => snapshot.tasks.remove(pubkey),
+                };
+            }
+        }
+
+        debug!(
+            "Task snapshot refreshed ({} tasks, {} re-fetched, {} removed)",
+            snapshot.tasks.len(),
+            fetch.len(),
+            removed.len()
+        );
+        snapshot.refreshed_at = Some(Instant::now());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: u64, state: OnChainTaskState, reward_lamports: u64, deadline: i64, required: u64) -> OnChainTask {
+        OnChainTask {
+            task_id: id,
+            pda: Pubkey::new_from_array([id as u8; 32]).to_string(),
+            creator: Pubkey::new_from_array([1; 32]).to_string(),
+            escrow_account: Pubkey::new_from_array([2; 32]).to_string(),
+            required_capabilities: required,
+            description_hash: [0; 32],
+            constraint_hash: [0; 32],
+            state,
+            reward_lamports,
+            reward_skr_tokens: 0,
+            deadline,
+            claimed_by: (state != OnChainTaskState::Open).then(|| Pubkey::new_from_array([3; 32]).to_string()),
+        }
+    }
+
+    fn sample() -> Vec<OnChainTask> {
+        use OnChainTaskState::*;
+        vec![
+            task(1, Open, 100, 0, 0b01),
+            task(2, Open, 500, 2_000, 0b11),
+            task(3, InProgress, 900, 1_000, 0b01),
+            task(4, Open, 300, 1_500, 0b10),
+            task(5, Completed, 700, 0, 0),
+        ]
+    }
+
+    fn ids(tasks: &[OnChainTask]) -> Vec<u64> {
+        tasks.iter().map(|t| t.task_id).collect()
+    }
+
+    #[test]
+    fn test_sorts_before_paging() {
+        let tasks = sample();
+        let query = TaskQuery::new().state(OnChainTaskState::Open);
+        assert_eq!(ids(&query.apply(&tasks)), vec![2, 4, 1]);
+        assert_eq!(ids(&query.clone().page(1, 1).apply(&tasks)), vec![4]);
+        assert_eq!(ids(&query.clone().page(5, 10).apply(&tasks)), Vec::<u64>::new());
+        assert_eq!(ids(&query.clone().sort(TaskSort::RewardAsc).limit(2).apply(&tasks)), vec![1, 4]);
+        assert_eq!(ids(&query.clone().sort(TaskSort::DeadlineAsc).apply(&tasks)), vec![4, 2, 1]);
+        assert_eq!(ids(&TaskQuery::new().sort(TaskSort::Newest).limit(2).apply(&tasks)), vec![5, 4]);
+    }
+
+    #[test]
+    fn test_client_side_filters() {
+        let tasks = sample();
+        let q = |query: TaskQuery| ids(&query.apply(&tasks));
+
+        assert_eq!(q(TaskQuery::new().reward_range(Some(300), Some(700))), vec![5, 2, 4]);
+        assert_eq!(q(TaskQuery::new().deadline_before(1_500)), vec![3, 4]);
+        // Tasks without a deadline never expire
+        assert_eq!(q(TaskQuery::new().deadline_after(1_600)), vec![5, 2, 1]);
+        assert_eq!(q(TaskQuery::new().within_capabilities(0b01)), vec![3, 5, 1]);
+        assert_eq!(q(TaskQuery::new().requiring_capabilities(0b10)), vec![2, 4]);
+        assert_eq!(q(TaskQuery::new().claimer(Pubkey::new_from_array([3; 32]))), vec![3, 5]);
+        assert_eq!(q(TaskQuery::new().creator(Pubkey::new_from_array([9; 32]))), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_rpc_filters() {
+        let creator = Pubkey::new_unique();
+        let claimer = Pubkey::new_unique();
+        let query = TaskQuery::new().creator(creator).claimer(claimer);
+
+        assert_eq!(query.rpc_filters(None).len(), 3);
+        let filters = query.rpc_filters(Some(OnChainTaskState::InProgress));
+        let mut claimed_by = vec![1];
+        claimed_by.extend_from_slice(&claimer.to_bytes());
+        assert_eq!(
+            filters,
+            vec![
+                task_discriminator_filter(),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(TASK_STATUS_OFFSET, vec![1])),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(TASK_CREATOR_OFFSET, creator.to_bytes().to_vec())),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(TASK_CLAIMED_BY_OFFSET, claimed_by)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_refresh_fetches_new_and_changed_only() {
+        let known: HashMap<Pubkey, OnChainTask> = sample()
+            .into_iter()
+            .map(|t| (Pubkey::new_from_array([t.task_id as u8; 32]), t))
+            .collect();
+        let key = |id: u8| Pubkey::new_from_array([id; 32]);
+        let listing = vec![
+            (key(1), OnChainTaskState::Open as u8),
+            (key(2), OnChainTaskState::InProgress as u8),
+            (key(3), OnChainTaskState::InProgress as u8),
+            (key(4), OnChainTaskState::Open as u8),
+            (key(6), OnChainTaskState::Open as u8),
+        ];
+
+        let (mut fetch, removed) = plan_refresh(&known, &listing);
+        fetch.sort();
+        let mut expected = vec![key(2), key(6)];
+        expected.sort();
+        assert_eq!(fetch, expected);
+        assert_eq!(removed, vec![key(5)]);
+    }
+}
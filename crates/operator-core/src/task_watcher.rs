@@ -0,0 +1,551 @@
+//! ============================================================================
+//! Task Watcher - Live Task Events over Solana PubSub
+//! ============================================================================
+//! Subscribes to AgenC task accounts with `programSubscribe` and turns each
+//! account update into a typed event:
+//! - Every notification is decoded to an `OnChainTask` and compared with the
+//!   last version seen; a new state becomes a created / claimed / completed /
+//!   cancelled / disputed event, a closed account a closed event
+//! - Before each (re)subscription all tasks are polled once, so changes
+//!   missed while disconnected are still reported
+//! - While the websocket is down the watcher polls every `poll_interval`
+//!   and tries to subscribe again after each poll
+//!
+//! The first successful poll only records the tasks that already exist.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+use crate::agenc_program::{program_id, OnChainTask, OnChainTaskState, TASK_DISCRIMINATOR};
+use crate::rpc_pool::RpcPool;
+use crate::task_query::{fetch_tasks, TaskQuery};
+
+/// Poll interval while the websocket is unavailable
+pub const DEFAULT_WATCH_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Keepalive ping interval on the websocket
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What happened to a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventKind {
+    Created,
+    Claimed,
+    /// Proof submitted (pending validation) or completed
+    Completed,
+    Cancelled,
+    Disputed,
+    /// The task account no longer exists
+    Closed,
+}
+
+impl TaskEventKind {
+    fn for_state(state: OnChainTaskState) -> Self {
+        match state {
+            OnChainTaskState::Open => Self::Created,
+            OnChainTaskState::InProgress => Self::Claimed,
+            OnChainTaskState::PendingValidation | OnChainTaskState::Completed => Self::Completed,
+            OnChainTaskState::Cancelled => Self::Cancelled,
+            OnChainTaskState::Disputed => Self::Disputed,
+        }
+    }
+}
+
+/// Where an event was observed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventSource {
+    Subscription,
+    Poll,
+}
+
+/// A change to one task account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEvent {
+    pub kind: TaskEventKind,
+    /// Task PDA
+    pub pda: String,
+    /// The task as it is now (None once closed)
+    pub task: Option<OnChainTask>,
+    pub previous_state: Option<OnChainTaskState>,
+    pub source: TaskEventSource,
+    /// Notification slot (subscription events only)
+    pub slot: Option<u64>,
+}
+
+/// Event for `current` given the last version seen. None if nothing a
+/// listener cares about changed (e.g. pending validation -> completed).
+pub fn task_event_kind(previous: Option<&OnChainTask>, current: &OnChainTask) -> Option<TaskEventKind> {
+    let kind = TaskEventKind::for_state(current.state);
+    match previous {
+        Some(previous) if TaskEventKind::for_state(previous.state) == kind => None,
+        _ => Some(kind),
+    }
+}
+
+/// PubSub endpoint for an RPC URL: same host over ws(s), and port 8900 for
+/// a local validator on 8899
+pub fn pubsub_url(rpc_url: &str) -> String {
+    let url = if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        rpc_url.to_string()
+    };
+    url.replace(":8899", ":8900")
+}
+
+/// The `programSubscribe` request for task accounts
+fn subscribe_request() -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "programSubscribe",
+        "params": [
+            program_id().to_string(),
+            {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "filters": [{ "memcmp": { "offset": 0, "bytes": bs58::encode(TASK_DISCRIMINATOR).into_string() } }],
+            },
+        ],
+    })
+}
+
+/// Last version of every task seen, and the events that follow from updates
+#[derive(Default)]
+struct TaskTracker {
+    known: HashMap<String, OnChainTask>,
+}
+
+impl TaskTracker {
+    fn update(&mut self, task: OnChainTask, source: TaskEventSource, slot: Option<u64>) -> Option<TaskEvent> {
+        let previous = self.known.insert(task.pda.clone(), task.clone());
+        let kind = task_event_kind(previous.as_ref(), &task)?;
+        Some(TaskEvent {
+            kind,
+            pda: task.pda.clone(),
+            task: Some(task),
+            previous_state: previous.map(|p| p.state),
+            source,
+            slot,
+        })
+    }
+
+    fn close(&mut self, pda: &str, source: TaskEventSource, slot: Option<u64>) -> Option<TaskEvent> {
+        let previous = self.known.remove(pda)?;
+        Some(TaskEvent {
+            kind: TaskEventKind::Closed,
+            pda: pda.to_string(),
+            task: None,
+            previous_state: Some(previous.state),
+            source,
+            slot,
+        })
+    }
+
+    /// Reconcile with a full listing. With `emit` false the listing only
+    /// becomes the baseline.
+    fn sync(&mut self, tasks: Vec<OnChainTask>, emit: bool) -> Vec<TaskEvent> {
+        if !emit {
+            self.known = tasks.into_iter().map(|t| (t.pda.clone(), t)).collect();
+            return Vec::new();
+        }
+
+        let listed: std::collections::HashSet<String> = tasks.iter().map(|t| t.pda.clone()).collect();
+        let gone: Vec<String> = self.known.keys().filter(|pda| !listed.contains(*pda)).cloned().collect();
+
+        let mut events: Vec<TaskEvent> = tasks
+            .into_iter()
+            .filter_map(|t| self.update(t, TaskEventSource::Poll, None))
+            .collect();
+        events.extend(gone.iter().filter_map(|pda| self.close(pda, TaskEventSource::Poll, None)));
+        events
+    }
+}
+
+/// Watches task accounts and sends a `TaskEvent` for every change
+pub struct TaskWatcher {
+    /// Polled while the websocket is down, with the pool's failover
+    rpc: Arc<RpcPool>,
+    ws_url: String,
+    poll_interval: Duration,
+    tracker: TaskTracker,
+    /// Whether a poll has set the baseline yet
+    synced: bool,
+}
+
+impl TaskWatcher {
+    /// Watcher for the cluster behind `rpc`; the PubSub URL is derived from
+    /// its preferred endpoint
+    pub fn new(rpc: Arc<RpcPool>) -> Self {
+        Self {
+            ws_url: pubsub_url(&rpc.preferred_url()),
+            rpc,
+            poll_interval: DEFAULT_WATCH_POLL_INTERVAL,
+            tracker: TaskTracker::default(),
+            synced: false,
+        }
+    }
+
+    pub fn with_ws_url(mut self, ws_url: &str) -> Self {
+        self.ws_url = ws_url.to_string();
+        self
+    }
+
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Run in the background until stopped or `events` is dropped
+    pub fn spawn(self, events: mpsc::UnboundedSender<TaskEvent>) -> TaskWatcherHandle {
+        let (stop, stop_rx) = watch::channel(false);
+        let join = tokio::spawn(self.run(events, stop_rx));
+        TaskWatcherHandle { stop, join }
+    }
+
+    async fn run(mut self, events: mpsc::UnboundedSender<TaskEvent>, mut stop: watch::Receiver<bool>) {
+        info!("[TaskWatcher] Watching tasks via {}", self.ws_url);
+
+        loop {
+            if *stop.borrow() || events.is_closed() {
+                break;
+            }
+
+            self.poll(&events).await;
+            match self.subscribe(&events, &mut stop).await {
+                Ok(()) => break,
+                Err(e) => warn!(
+                    "[TaskWatcher] PubSub unavailable ({}), polling every {}s",
+                    e,
+                    self.poll_interval.as_secs()
+                ),
+            }
+
+            tokio::select! {
+                _ = stop.changed() => break,
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+        info!("[TaskWatcher] Stopped");
+    }
+
+    /// Fetch every task and report what changed since the last look
+    async fn poll(&mut self, events: &mpsc::UnboundedSender<TaskEvent>) {
+        let polled = self.rpc.read(|rpc| async move { fetch_tasks(&rpc, &TaskQuery::new()).await }).await;
+        match polled {
+            Ok(tasks) => {
+                let changes = self.tracker.sync(tasks, self.synced);
+                self.synced = true;
+                debug!("[TaskWatcher] Poll found {} changes", changes.len());
+                for event in changes {
+                    let _ = events.send(event);
+                }
+            }
+            Err(e) => warn!("[TaskWatcher] Poll failed: {}", e),
+        }
+    }
+
+    /// Stream notifications until stopped (Ok) or the connection fails (Err)
+    async fn subscribe(
+        &mut self,
+        events: &mpsc::UnboundedSender<TaskEvent>,
+        stop: &mut watch::Receiver<bool>,
+    ) -> Result<()> {
+        crate::network::ensure_configured()?;
+        let (mut ws, _) = tokio_tungstenite::connect_async(self.ws_url.as_str())
+            .await
+            .map_err(|e| anyhow!("Failed to connect to {}: {}", self.ws_url, e))?;
+        ws.send(Message::Text(subscribe_request().to_string()))
+            .await
+            .map_err(|e| anyhow!("Failed to send programSubscribe: {}", e))?;
+
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        ping.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = stop.changed() => {
+                    let _ = ws.close(None).await;
+                    return Ok(());
+                }
+                _ = ping.tick() => {
+                    ws.send(Message::Ping(Vec::new()))
+                        .await
+                        .map_err(|e| anyhow!("PubSub ping failed: {}", e))?;
+                }
+                message = ws.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Some(event) = self.handle_message(&text)? {
+                            if events.send(event).is_err() {
+                                return Ok(());
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Ping(payload))) => {
+                        ws.send(Message::Pong(payload))
+                            .await
+                            .map_err(|e| anyhow!("PubSub pong failed: {}", e))?;
+                    }
+                    Some(Ok(Message::Close(_))) | None => return Err(anyhow!("PubSub connection closed")),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(anyhow!("PubSub connection failed: {}", e)),
+                },
+            }
+        }
+    }
+
+    /// Turn one PubSub message into an event. Errors end the subscription.
+    fn handle_message(&mut self, text: &str) -> Result<Option<TaskEvent>> {
+        let message: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| anyhow!("Invalid PubSub message: {}", e))?;
+
+        if message["method"] != "programNotification" {
+            if let Some(error) = message.get("error") {
+                return Err(anyhow!("programSubscribe failed: {}", error));
+            }
+            if let Some(id) = message["result"].as_u64() {
+                info!("[TaskWatcher] Subscribed (subscription {})", id);
+            }
+            return Ok(None);
+        }
+
+        let result = &message["params"]["result"];
+        let slot = result["context"]["slot"].as_u64();
+        let value = &result["value"];
+        let pda = value["pubkey"]
+            .as_str()
+            .ok_or_else(|| anyhow!("programNotification without pubkey"))?;
+        let data = value["account"]["data"][0].as_str().unwrap_or_default();
+        let data = STANDARD
+            .decode(data)
+            .map_err(|e| anyhow!("Invalid account data for {}: {}", pda, e))?;
+
+        if data.is_empty() {
+            return Ok(self.tracker.close(pda, TaskEventSource::Subscription, slot));
+        }
+        let pubkey = Pubkey::from_str(pda).map_err(|e| anyhow!("Invalid task pubkey {}: {}", pda, e))?;
+        match OnChainTask::from_account_data(&data, &pubkey) {
+            Ok(task) => Ok(self.tracker.update(task, TaskEventSource::Subscription, slot)),
+            Err(e) => {
+                warn!("[TaskWatcher] Failed to decode task {}: {}", pda, e);
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Handle to a spawned watcher; dropping it stops the watcher too
+pub struct TaskWatcherHandle {
+    stop: watch::Sender<bool>,
+    join: JoinHandle<()>,
+}
+
+impl TaskWatcherHandle {
+    pub fn stop(&self) {
+        let _ = self.stop.send(true);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.join.is_finished()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agenc_program::derive_task_pda;
+    use tokio::net::TcpListener;
+
+    /// Borsh-encoded Task account, laid out as in the IDL
+    fn task_account(id: u64, state: OnChainTaskState, claimer: Option<Pubkey>) -> Vec<u8> {
+        let mut data = TASK_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&id.to_le_bytes());
+        data.extend_from_slice(&[1; 32]); // creator
+        data.extend_from_slice(&[2; 32]); // escrow
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&[0xAA; 32]);
+        data.extend_from_slice(&[0xBB; 32]);
+        data.extend_from_slice(&[0; 2]);
+        data.push(state as u8);
+        data.extend_from_slice(&100_000_000u64.to_le_bytes());
+        data.extend_from_slice(&0i64.to_le_bytes());
+        match claimer {
+            Some(claimer) => {
+                data.push(1);
+                data.extend_from_slice(&claimer.to_bytes());
+            }
+            None => data.push(0),
+        }
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data
+    }
+
+    fn notification(pda: &Pubkey, data: &[u8], slot: u64) -> Message {
+        Message::Text(
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "programNotification",
+                "params": {
+                    "result": {
+                        "context": { "slot": slot },
+                        "value": {
+                            "pubkey": pda.to_string(),
+                            "account": { "data": [STANDARD.encode(data), "base64"], "lamports": 1 },
+                        },
+                    },
+                    "subscription": 7,
+                },
+            })
+            .to_string(),
+        )
+    }
+
+    fn on_chain(id: u64, state: OnChainTaskState) -> OnChainTask {
+        let pda = derive_task_pda(id).0;
+        OnChainTask::from_account_data(&task_account(id, state, None), &pda).unwrap()
+    }
+
+    #[test]
+    fn test_event_kinds_and_pubsub_url() {
+        use OnChainTaskState::*;
+        assert_eq!(task_event_kind(None, &on_chain(1, Open)), Some(TaskEventKind::Created));
+        assert_eq!(task_event_kind(Some(&on_chain(1, Open)), &on_chain(1, Open)), None);
+        assert_eq!(task_event_kind(Some(&on_chain(1, Open)), &on_chain(1, InProgress)), Some(TaskEventKind::Claimed));
+        assert_eq!(task_event_kind(Some(&on_chain(1, PendingValidation)), &on_chain(1, Completed)), None);
+        assert_eq!(task_event_kind(Some(&on_chain(1, InProgress)), &on_chain(1, Disputed)), Some(TaskEventKind::Disputed));
+
+        assert_eq!(pubsub_url("https://api.devnet.solana.com"), "wss://api.devnet.solana.com");
+        assert_eq!(pubsub_url("http://127.0.0.1:8899"), "ws://127.0.0.1:8900");
+    }
+
+    #[test]
+    fn test_poll_sync_sets_baseline_then_diffs() {
+        use OnChainTaskState::*;
+        let mut tracker = TaskTracker::default();
+        assert!(tracker.sync(vec![on_chain(1, Open), on_chain(2, Open)], false).is_empty());
+
+        let events = tracker.sync(vec![on_chain(1, InProgress), on_chain(3, Open)], true);
+        let kinds: Vec<(TaskEventKind, String)> = events.iter().map(|e| (e.kind, e.pda.clone())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (TaskEventKind::Claimed, derive_task_pda(1).0.to_string()),
+                (TaskEventKind::Created, derive_task_pda(3).0.to_string()),
+                (TaskEventKind::Closed, derive_task_pda(2).0.to_string()),
+            ]
+        );
+        assert!(events.iter().all(|e| e.source == TaskEventSource::Poll));
+    }
+
+    #[tokio::test]
+    async fn test_events_from_fake_pubsub() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let pda = derive_task_pda(9).0;
+        let worker = Pubkey::new_unique();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let request = ws.next().await.unwrap().unwrap();
+            let request: serde_json::Value = serde_json::from_str(request.to_text().unwrap()).unwrap();
+            ws.send(Message::Text(serde_json::json!({ "jsonrpc": "2.0", "result": 7, "id": 1 }).to_string()))
+                .await
+                .unwrap();
+
+            let updates = [
+                (OnChainTaskState::Open, None),
+                (OnChainTaskState::InProgress, Some(worker)),
+                // Same state again: no event
+                (OnChainTaskState::InProgress, Some(worker)),
+                (OnChainTaskState::Completed, Some(worker)),
+            ];
+            for (slot, (state, claimer)) in updates.into_iter().enumerate() {
+                ws.send(notification(&pda, &task_account(9, state, claimer), slot as u64)).await.unwrap();
+            }
+            ws.send(notification(&pda, &[], 10)).await.unwrap();
+            // Keep the connection open until the watcher stops
+            let _ = ws.next().await;
+            request
+        });
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let handle = TaskWatcher::new(Arc::new(RpcPool::single("http://127.0.0.1:1")))
+            .with_ws_url(&format!("ws://{}", addr))
+            .spawn(tx);
+
+        let mut events = Vec::new();
+        for _ in 0..4 {
+            let event = tokio::time::timeout(Duration::from_secs(10), rx.recv()).await.unwrap().unwrap();
+            events.push(event);
+        }
+        let kinds: Vec<TaskEventKind> = events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![TaskEventKind::Created, TaskEventKind::Claimed, TaskEventKind::Completed, TaskEventKind::Closed]
+        );
+        assert_eq!(events[1].task.as_ref().unwrap().claimed_by, Some(worker.to_string()));
+        assert_eq!(events[1].previous_state, Some(OnChainTaskState::Open));
+        assert_eq!(events[2].slot, Some(3));
+        assert!(events.iter().all(|e| e.source == TaskEventSource::Subscription && e.pda == pda.to_string()));
+
+        handle.stop();
+        let request = server.await.unwrap();
+        assert_eq!(request["method"], "programSubscribe");
+        assert_eq!(request["params"][0], program_id().to_string());
+        assert_eq!(
+            request["params"][1]["filters"][0]["memcmp"]["bytes"],
+            bs58::encode(TASK_DISCRIMINATOR).into_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resubscribes_after_connection_drops() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            // First connection: drop it right after the subscription request
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let _ = ws.next().await;
+            drop(ws);
+
+            // The watcher polls, then subscribes again
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let request = ws.next().await.unwrap().unwrap();
+            let pda = derive_task_pda(4).0;
+            ws.send(notification(&pda, &task_account(4, OnChainTaskState::Open, None), 1)).await.unwrap();
+            let _ = ws.next().await;
+            request.to_text().unwrap().to_string()
+        });
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let handle = TaskWatcher::new(Arc::new(RpcPool::single("http://127.0.0.1:1")))
+            .with_ws_url(&format!("ws://{}", addr))
+            .with_poll_interval(Duration::from_millis(50))
+            .spawn(tx);
+
+        let event = tokio::time::timeout(Duration::from_secs(10), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(event.kind, TaskEventKind::Created);
+        handle.stop();
+        assert!(server.await.unwrap().contains("programSubscribe"));
+    }
+}
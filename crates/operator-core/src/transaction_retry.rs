@@ -4,15 +4,22 @@
 //! Provides robust transaction sending with:
 //! - Exponential backoff with jitter for retries
 //! - Transaction confirmation status polling
-//! - Blockhash refresh on expiration
+//! - Blockhash refresh on expiration: senders given a build closure fetch a
+//!   fresh blockhash and re-sign instead of resending a stale transaction
 //! - Error classification (retryable vs permanent)
+//!
+//! `TransactionSender` uses the blocking client; `AsyncTransactionSender`
+//! the nonblocking one. Both drive the same `Rebuild` decisions.
 //! ============================================================================
 
 use anyhow::{anyhow, Result};
+use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::Hash,
     signature::Signature,
-    transaction::VersionedTransaction,
+    transaction::{self, VersionedTransaction},
 };
 use std::time::Duration;
 use tokio::time::sleep;
@@ -33,6 +40,8 @@ pub struct RetryConfig {
     pub poll_interval_ms: u64,
     /// Whether to add jitter to delays
     pub jitter: bool,
+    /// Times a rebuilding send fetches a new blockhash and re-signs
+    pub max_blockhash_refreshes: u32,
 }
 
 impl Default for RetryConfig {
@@ -44,6 +53,7 @@ impl Default for RetryConfig {
             max_delay_ms: 10000,
             poll_interval_ms: 1000,
             jitter: true,
+            max_blockhash_refreshes: 3,
         }
     }
 }
@@ -74,6 +84,83 @@ pub enum ErrorKind {
     RateLimited,
 }
 
+/// What a rebuilding send does next
+#[derive(Debug)]
+enum RebuildStep {
+    /// Finished with this result
+    Done(SendResult),
+    /// Confirmation timed out: ask whether the blockhash can still land
+    CheckBlockhash,
+    /// The blockhash expired: ask whether the transaction landed after all
+    CheckLanded,
+    /// Fetch a new blockhash and re-sign
+    Resign,
+}
+
+/// Decisions of `send_and_confirm_with_rebuild`, shared by both senders,
+/// which only differ in how they ask the RPC each `RebuildStep` question
+struct Rebuild {
+    refreshes: u32,
+    max_refreshes: u32,
+    /// Signature and result of the attempt being checked
+    pending: Option<(Signature, SendResult)>,
+}
+
+impl Rebuild {
+    fn new(max_refreshes: u32) -> Self {
+        Self { refreshes: 0, max_refreshes, pending: None }
+    }
+
+    /// After the transaction signed as `signature` was sent and confirmed
+    fn sent(&mut self, signature: Signature, result: SendResult) -> RebuildStep {
+        let step = match &result {
+            SendResult::RetryableFailure(msg) if classify_error(msg) == ErrorKind::BlockhashExpired => {
+                RebuildStep::CheckLanded
+            }
+            SendResult::ConfirmationTimeout(_) => RebuildStep::CheckBlockhash,
+            _ => return RebuildStep::Done(result),
+        };
+        self.pending = Some((signature, result));
+        step
+    }
+
+    /// After a timeout: a still-valid blockhash may yet land, so stop there
+    fn blockhash_checked(&mut self, valid: bool) -> RebuildStep {
+        match self.pending.take() {
+            Some((_, result)) if valid => RebuildStep::Done(result),
+            pending => {
+                self.pending = pending;
+                RebuildStep::CheckLanded
+            }
+        }
+    }
+
+    /// After an expiry, with the signature's processed status (None if
+    /// unknown): re-sign until the refreshes run out
+    fn landed_checked(&mut self, status: Option<transaction::Result<()>>) -> RebuildStep {
+        let Some((signature, _)) = self.pending.take() else {
+            return RebuildStep::Resign;
+        };
+        match status {
+            Some(Ok(())) => RebuildStep::Done(SendResult::Confirmed(signature)),
+            Some(Err(e)) => RebuildStep::Done(SendResult::PermanentFailure(format!("Transaction failed: {}", e))),
+            None if self.refreshes >= self.max_refreshes => RebuildStep::Done(SendResult::RetryableFailure(
+                format!("Blockhash expired {} times without landing", self.refreshes + 1),
+            )),
+            None => {
+                self.refreshes += 1;
+                warn!("Blockhash expired before {} landed, re-signing (refresh {})", signature, self.refreshes);
+                RebuildStep::Resign
+            }
+        }
+    }
+}
+
+/// First signature of a built transaction
+fn first_signature(tx: &VersionedTransaction) -> Result<Signature> {
+    tx.signatures.first().copied().ok_or_else(|| anyhow!("Built transaction has no signatures"))
+}
+
 /// Classify an error to determine if retry is appropriate
 pub fn classify_error(error: &str) -> ErrorKind {
     let lower = error.to_lowercase();
@@ -252,6 +339,43 @@ impl<'a> TransactionSender<'a> {
         }
     }
 
+    /// Send and confirm the transaction `build` signs for a fresh blockhash.
+    /// If the blockhash expires before it lands (and it has not landed
+    /// after all), a new blockhash is fetched and `build` called again.
+    pub fn send_and_confirm_with_rebuild<F>(&self, mut build: F) -> Result<SendResult>
+    where
+        F: FnMut(Hash) -> Result<VersionedTransaction>,
+    {
+        let mut rebuild = Rebuild::new(self.config.max_blockhash_refreshes);
+        loop {
+            let blockhash = self
+                .rpc
+                .get_latest_blockhash()
+                .map_err(|e| anyhow!("Failed to get blockhash: {}", e))?;
+            let tx = build(blockhash)?;
+            let signature = first_signature(&tx)?;
+
+            let mut step = rebuild.sent(signature, self.send_and_confirm_with_retry(&tx)?);
+            loop {
+                step = match step {
+                    RebuildStep::Done(result) => return Ok(result),
+                    RebuildStep::CheckBlockhash => rebuild.blockhash_checked(
+                        self.rpc
+                            .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+                            .unwrap_or(true),
+                    ),
+                    RebuildStep::CheckLanded => rebuild.landed_checked(
+                        self.rpc
+                            .get_signature_status_with_commitment(&signature, CommitmentConfig::processed())
+                            .ok()
+                            .flatten(),
+                    ),
+                    RebuildStep::Resign => break,
+                };
+            }
+        }
+    }
+
     /// Poll for transaction confirmation status
     pub fn poll_confirmation(&self, signature: Signature) -> Result<SendResult> {
         info!("Polling confirmation for {}", signature);
@@ -295,15 +419,16 @@ impl<'a> TransactionSender<'a> {
     }
 }
 
-/// Async version of TransactionSender for use with tokio
+/// Async version of TransactionSender for use with tokio, on the
+/// nonblocking client
 pub struct AsyncTransactionSender<'a> {
-    rpc: &'a RpcClient,
+    rpc: &'a AsyncRpcClient,
     config: RetryConfig,
 }
 
 impl<'a> AsyncTransactionSender<'a> {
     /// Create a new AsyncTransactionSender
-    pub fn new(rpc: &'a RpcClient) -> Self {
+    pub fn new(rpc: &'a AsyncRpcClient) -> Self {
         Self {
             rpc,
             config: RetryConfig::default(),
@@ -311,7 +436,7 @@ impl<'a> AsyncTransactionSender<'a> {
     }
 
     /// Create with custom config
-    pub fn with_config(rpc: &'a RpcClient, config: RetryConfig) -> Self {
+    pub fn with_config(rpc: &'a AsyncRpcClient, config: RetryConfig) -> Self {
         Self { rpc, config }
     }
 
@@ -326,7 +451,7 @@ impl<'a> AsyncTransactionSender<'a> {
                 sleep(delay).await;
             }
 
-            match self.rpc.send_transaction(tx) {
+            match self.rpc.send_transaction(tx).await {
                 Ok(signature) => {
                     info!("Transaction sent: {} (attempt {})", signature, attempt + 1);
                     return Ok(SendResult::Confirmed(signature));
@@ -378,7 +503,7 @@ impl<'a> AsyncTransactionSender<'a> {
         for attempt in 0..self.config.max_confirm_retries {
             sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
 
-            match self.rpc.get_signature_status(&signature) {
+            match self.rpc.get_signature_status(&signature).await {
                 Ok(Some(status)) => match status {
                     Ok(()) => {
                         info!(
@@ -424,6 +549,46 @@ impl<'a> AsyncTransactionSender<'a> {
             other => Ok(other),
         }
     }
+
+    /// Send and confirm the transaction `build` signs for a fresh blockhash.
+    /// If the blockhash expires before it lands (and it has not landed
+    /// after all), a new blockhash is fetched and `build` called again.
+    pub async fn send_and_confirm_with_rebuild<F>(&self, mut build: F) -> Result<SendResult>
+    where
+        F: FnMut(Hash) -> Result<VersionedTransaction>,
+    {
+        let mut rebuild = Rebuild::new(self.config.max_blockhash_refreshes);
+        loop {
+            let blockhash = self
+                .rpc
+                .get_latest_blockhash()
+                .await
+                .map_err(|e| anyhow!("Failed to get blockhash: {}", e))?;
+            let tx = build(blockhash)?;
+            let signature = first_signature(&tx)?;
+
+            let mut step = rebuild.sent(signature, self.send_and_confirm_with_retry(&tx).await?);
+            loop {
+                step = match step {
+                    RebuildStep::Done(result) => return Ok(result),
+                    RebuildStep::CheckBlockhash => rebuild.blockhash_checked(
+                        self.rpc
+                            .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+                            .await
+                            .unwrap_or(true),
+                    ),
+                    RebuildStep::CheckLanded => rebuild.landed_checked(
+                        self.rpc
+                            .get_signature_status_with_commitment(&signature, CommitmentConfig::processed())
+                            .await
+                            .ok()
+                            .flatten(),
+                    ),
+                    RebuildStep::Resign => break,
+                };
+            }
+        }
+    }
 }
 
 /// Helper function to convert SendResult to a standard Result
@@ -609,6 +774,57 @@ mod tests {
         assert_eq!(config.max_delay_ms, 10000);
         assert_eq!(config.poll_interval_ms, 1000);
         assert!(config.jitter);
+        assert_eq!(config.max_blockhash_refreshes, 3);
+    }
+
+    #[test]
+    fn test_blockhash_expired_triggers_rebuild() {
+        let sig = Signature::default();
+        let expired = || SendResult::RetryableFailure("Blockhash expired - refresh required".to_string());
+        let mut rebuild = Rebuild::new(3);
+        assert!(matches!(rebuild.sent(sig, expired()), RebuildStep::CheckLanded));
+        assert!(matches!(rebuild.landed_checked(None), RebuildStep::Resign));
+
+        // A timeout only needs re-signing once the blockhash can no longer land
+        assert!(matches!(rebuild.sent(sig, SendResult::ConfirmationTimeout(sig)), RebuildStep::CheckBlockhash));
+        assert!(matches!(
+            rebuild.blockhash_checked(true),
+            RebuildStep::Done(SendResult::ConfirmationTimeout(_))
+        ));
+        assert!(matches!(rebuild.sent(sig, SendResult::ConfirmationTimeout(sig)), RebuildStep::CheckBlockhash));
+        assert!(matches!(rebuild.blockhash_checked(false), RebuildStep::CheckLanded));
+
+        // Landing after all ends the loop either way
+        assert!(matches!(rebuild.landed_checked(Some(Ok(()))), RebuildStep::Done(SendResult::Confirmed(_))));
+        rebuild.sent(sig, expired());
+        assert!(matches!(
+            rebuild.landed_checked(Some(Err(transaction::TransactionError::AccountNotFound))),
+            RebuildStep::Done(SendResult::PermanentFailure(_))
+        ));
+
+        let other = SendResult::RetryableFailure("Max retries (5) exceeded. Last error: timeout".to_string());
+        assert!(matches!(rebuild.sent(sig, other), RebuildStep::Done(_)));
+        assert!(matches!(rebuild.sent(sig, SendResult::Confirmed(sig)), RebuildStep::Done(_)));
+        let failed = SendResult::PermanentFailure("custom program error".into());
+        assert!(matches!(rebuild.sent(sig, failed), RebuildStep::Done(_)));
+    }
+
+    #[test]
+    fn test_rebuild_stops_after_max_refreshes() {
+        let sig = Signature::default();
+        let expired = || SendResult::RetryableFailure("Blockhash expired - refresh required".to_string());
+        let mut rebuild = Rebuild::new(2);
+        for _ in 0..2 {
+            rebuild.sent(sig, expired());
+            assert!(matches!(rebuild.landed_checked(None), RebuildStep::Resign));
+        }
+        rebuild.sent(sig, expired());
+        match rebuild.landed_checked(None) {
+            RebuildStep::Done(SendResult::RetryableFailure(msg)) => {
+                assert_eq!(msg, "Blockhash expired 3 times without landing")
+            }
+            other => panic!("expected to give up, got {:?}", other),
+        }
     }
 
     #[test]
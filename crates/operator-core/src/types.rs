@@ -18,6 +18,19 @@ pub struct VoiceIntent {
     pub raw_transcript: Option<String>,
 }
 
+impl VoiceIntent {
+    /// Named wallet profile the intent should use (`params.wallet`), if any.
+    /// None means the active wallet.
+    pub fn wallet(&self) -> Option<&str> {
+        intent_wallet(&self.params)
+    }
+}
+
+/// Read the `wallet` param from intent params (empty strings count as unset)
+pub fn intent_wallet(params: &serde_json::Value) -> Option<&str> {
+    params["wallet"].as_str().map(str::trim).filter(|w| !w.is_empty())
+}
+
 /// Supported intent actions for AgenC protocol
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -27,12 +40,14 @@ pub enum IntentAction {
     ClaimTask,
     CompleteTask,
     CancelTask,
+    ApproveCompletion,
     ListOpenTasks,
     GetTaskStatus,
 
     // Wallet/Account
     GetBalance,
     GetAddress,
+    GetHistory,
 
     // Protocol Status
     GetProtocolState,
@@ -86,6 +101,30 @@ pub enum IntentAction {
 }
 
 impl IntentAction {
+    /// Wire name of the action (its serde name, e.g. "create_task")
+    pub fn name(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default()
+    }
+
+    /// Whether the action reads or writes AgenC program accounts, and so
+    /// needs the active profile's program ID and SKR mint
+    pub fn uses_agenc_program(&self) -> bool {
+        matches!(
+            self,
+            IntentAction::CreateTask
+                | IntentAction::ClaimTask
+                | IntentAction::CompleteTask
+                | IntentAction::CancelTask
+                | IntentAction::ApproveCompletion
+                | IntentAction::ListOpenTasks
+                | IntentAction::GetTaskStatus
+                | IntentAction::GetProtocolState
+        )
+    }
+
     /// Get the Feature required for this action (for access tier gating)
     /// Returns None for actions that don't require feature gating (blockchain ops use policy gate)
     pub fn required_feature(&self) -> Option<crate::access::Feature> {
@@ -139,6 +178,30 @@ pub struct CreateTaskParams {
     pub deadline_hours: Option<u64>,
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Free-form deadline ("end of sprint"); defaults to the computed deadline
+    #[serde(default)]
+    pub deadline_text: Option<String>,
+    /// Conditions the submission must meet to be approved
+    #[serde(default)]
+    pub acceptance_criteria: Vec<String>,
+    /// Capability names a worker must have (see `capabilities`)
+    #[serde(default)]
+    pub required_capabilities: Vec<String>,
+}
+
+/// Filters for listing open tasks (all optional)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListTasksParams {
+    pub min_reward_sol: Option<f64>,
+    pub max_reward_sol: Option<f64>,
+    /// Capability names we have: only tasks requiring nothing else
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// Creator address, or "me" for the signing wallet
+    pub creator: Option<String>,
+    pub sort: Option<crate::task_query::TaskSort>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
 }
 
 /// Parameters for claiming a task
@@ -153,6 +216,27 @@ pub struct CompleteTaskParams {
     pub task_id: String,
     pub proof_url: Option<String>,
     pub notes: Option<String>,
+    /// Local files delivered, hashed into the proof manifest
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Further links (gists, PRs) that make up the result
+    #[serde(default)]
+    pub links: Vec<String>,
+    /// Transaction signatures the work produced
+    #[serde(default)]
+    pub signatures: Vec<String>,
+}
+
+/// Parameters for cancelling an open task (creator only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelTaskParams {
+    pub task_id: String,
+}
+
+/// Parameters for approving a worker's submission (creator only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApproveCompletionParams {
+    pub task_id: String,
 }
 
 /// Represents an AgenC task on-chain
@@ -169,6 +253,15 @@ pub struct AgencTask {
     pub claimer: Option<String>,
     pub created_at: i64,
     pub deadline: Option<i64>,
+    /// Off-chain description document, once verified against the on-chain hash
+    #[serde(default)]
+    pub details: Option<TaskDescription>,
+    /// Outcome of resolving the description (None if no registry is set)
+    #[serde(default)]
+    pub description_status: Option<DescriptionStatus>,
+    /// Capability names a worker must have
+    #[serde(default)]
+    pub required_capabilities: Vec<String>,
 }
 
 impl AgencTask {
@@ -183,6 +276,33 @@ impl AgencTask {
     }
 }
 
+/// Off-chain task description. The on-chain `description_hash` is the
+/// SHA-256 of this document's JSON bytes (see `task_descriptions`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskDescription {
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub deadline_text: Option<String>,
+    #[serde(default)]
+    pub acceptance_criteria: Vec<String>,
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+/// Whether a task's description could be resolved and matched its hash
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DescriptionStatus {
+    /// Found, and its SHA-256 equals the on-chain hash
+    Verified,
+    /// Found, but the content does not hash to the on-chain value
+    Mismatch,
+    /// Not in the local store or the gateway
+    Missing,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum TaskStatus {
@@ -239,9 +359,73 @@ pub struct PolicyCheck {
     pub requires_confirmation: bool,
     pub confirmation_type: ConfirmationType,
     pub reason: String,
+    /// One-time ticket to pass to `execute_confirmed` (set by execute_intent
+    /// when confirmation is required)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticket: Option<ConfirmationTicket>,
+    /// Simulated outcome of the transaction being confirmed (spending
+    /// intents only; see `simulation`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<TransactionPreview>,
+}
+
+/// What a transaction will do, from `simulateTransaction`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionPreview {
+    /// Accounts whose SOL or token balance changes
+    pub balance_changes: Vec<BalanceChange>,
+    /// Network fee, including any priority fee
+    pub fee_lamports: u64,
+    /// Rent deposited into accounts the transaction creates
+    pub rent_lamports: u64,
+    pub compute_units: Option<u64>,
+    /// AgenC protocol fee taken from the reward, when one applies
+    pub protocol_fee_lamports: Option<u64>,
+    /// Simulation error; the transaction would fail as built
+    pub error: Option<String>,
+    /// Program logs, kept only when the simulation failed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<String>,
+    /// One line per change, for the confirmation prompt
+    pub summary: Vec<String>,
+}
+
+/// Balance change of one account in a previewed transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceChange {
+    pub account: String,
+    /// Token mint (None = SOL)
+    pub mint: Option<String>,
+    /// Wallet owning the token account (token changes only)
+    pub owner: Option<String>,
+    /// Raw amounts (lamports or token base units)
+    pub before: u64,
+    pub after: u64,
+    pub decimals: u8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+impl BalanceChange {
+    /// Signed change in display units (SOL or whole tokens)
+    pub fn delta(&self) -> f64 {
+        (self.after as f64 - self.before as f64) / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// Signed, single-use approval for one exact intent (see `confirmation`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationTicket {
+    /// Random hex nonce; a ticket can be redeemed once
+    pub nonce: String,
+    /// Hex SHA-256 of the canonicalized intent
+    pub intent_hash: String,
+    /// Unix timestamp after which the ticket is rejected
+    pub expires_at: i64,
+    /// Hex HMAC-SHA256 over nonce, intent hash and expiry
+    pub signature: String,
+}
+
+/// Ordered from weakest to strictest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "snake_case")]
 pub enum ConfirmationType {
     None,
@@ -13,26 +13,53 @@
 
 use anyhow::{anyhow, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SizedSample};
+use std::collections::VecDeque;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{info, warn, error, debug};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
 /// Audio sample rate for whisper (16kHz mono)
 const WHISPER_SAMPLE_RATE: u32 = 16000;
 
-/// Buffer size for audio chunks
-const AUDIO_BUFFER_SIZE: usize = 4096;
-
 /// Wake phrases that activate Tetsuo
 const WAKE_PHRASES: &[&str] = &["tetsuo", "hey tetsuo", "ok tetsuo"];
 
+/// VAD frame length (30ms at 16kHz)
+const VAD_FRAME_SAMPLES: usize = 480;
+
+/// RMS energy above which a frame counts as speech
+const VAD_ENERGY_THRESHOLD: f32 = 0.01;
+
+/// Audio kept from before speech onset so the first word isn't clipped (~300ms)
+const VAD_PRE_ROLL_FRAMES: usize = 10;
+
+/// Trailing silence that ends an utterance (~600ms)
+const VAD_HANGOVER_FRAMES: usize = 20;
+
+/// Utterances with less speech than this are discarded as noise (~240ms)
+const VAD_MIN_SPEECH_FRAMES: usize = 8;
+
+/// Utterances are force-split at whisper's 30 second window
+const MAX_SEGMENT_SAMPLES: usize = WHISPER_SAMPLE_RATE as usize * 30;
+
+/// whisper.cpp rejects inputs shorter than one second, so pad up to this
+const MIN_INFERENCE_SAMPLES: usize = WHISPER_SAMPLE_RATE as usize + WHISPER_SAMPLE_RATE as usize / 10;
+
+/// How often the capture thread drains the cpal buffer
+const CAPTURE_POLL_MS: u64 = 100;
+
 /// Local voice processor for offline ASR
 pub struct LocalVoiceProcessor {
     /// Whether the processor is currently listening
     is_listening: Arc<AtomicBool>,
     /// Path to whisper model file
     model_path: Option<String>,
+    /// Loaded whisper model (shared with the capture thread)
+    context: Option<Arc<WhisperContext>>,
     /// Audio sample buffer for processing
     sample_buffer: Arc<std::sync::Mutex<Vec<f32>>>,
 }
@@ -43,6 +70,7 @@ impl LocalVoiceProcessor {
         Self {
             is_listening: Arc::new(AtomicBool::new(false)),
             model_path: None,
+            context: None,
             sample_buffer: Arc::new(std::sync::Mutex::new(Vec::new())),
         }
     }
@@ -54,19 +82,34 @@ impl LocalVoiceProcessor {
         info!("Loading whisper model from: {}", model_path);
 
         // Verify model file exists
-        if !std::path::Path::new(model_path).exists() {
+        if !Path::new(model_path).exists() {
             return Err(anyhow!(
                 "Whisper model not found at: {}. Download from HuggingFace.",
                 model_path
             ));
         }
 
+        // Model loading reads and parses the whole ggml file — keep it off the async runtime
+        let path = model_path.to_string();
+        let context = tokio::task::spawn_blocking(move || {
+            WhisperContext::new_with_params(&path, WhisperContextParameters::default())
+        })
+        .await
+        .map_err(|e| anyhow!("Whisper model load task failed: {}", e))?
+        .map_err(|e| anyhow!("Failed to load whisper model: {}", e))?;
+
+        self.context = Some(Arc::new(context));
         self.model_path = Some(model_path.to_string());
         info!("Whisper model loaded successfully");
 
         Ok(())
     }
 
+    /// Whether a whisper model has been loaded
+    pub fn is_model_loaded(&self) -> bool {
+        self.context.is_some()
+    }
+
     /// Get available audio input devices
     pub fn list_audio_devices() -> Result<Vec<String>> {
         let host = cpal::default_host();
@@ -85,21 +128,25 @@ impl LocalVoiceProcessor {
             return Err(anyhow!("Already listening"));
         }
 
+        let context = self.context.clone()
+            .ok_or_else(|| anyhow!("Whisper model not loaded"))?;
+
         info!("Starting local voice capture...");
 
         let (tx, rx) = mpsc::channel::<String>(32);
         let is_listening = self.is_listening.clone();
         let sample_buffer = self.sample_buffer.clone();
-        let model_path = self.model_path.clone();
 
-        // Spawn audio capture task
+        self.is_listening.store(true, Ordering::SeqCst);
+
+        // Spawn audio capture + inference thread
         std::thread::spawn(move || {
-            if let Err(e) = Self::capture_audio_loop(is_listening, sample_buffer, tx, model_path) {
+            if let Err(e) = Self::capture_audio_loop(is_listening.clone(), sample_buffer, tx, context) {
                 error!("Audio capture error: {}", e);
             }
+            is_listening.store(false, Ordering::SeqCst);
         });
 
-        self.is_listening.store(true, Ordering::SeqCst);
         Ok(rx)
     }
 
@@ -114,12 +161,14 @@ impl LocalVoiceProcessor {
         self.is_listening.load(Ordering::SeqCst)
     }
 
-    /// Audio capture loop running in separate thread
+    /// Audio capture loop running in separate thread.
+    /// Captures at the device's native format, downmixes and resamples to
+    /// 16kHz mono, segments on voice activity and transcribes each utterance.
     fn capture_audio_loop(
         is_listening: Arc<AtomicBool>,
         sample_buffer: Arc<std::sync::Mutex<Vec<f32>>>,
-        _tx: mpsc::Sender<String>,
-        _model_path: Option<String>,
+        tx: mpsc::Sender<String>,
+        context: Arc<WhisperContext>,
     ) -> Result<()> {
         let host = cpal::default_host();
         let device = host.default_input_device()
@@ -127,31 +176,84 @@ impl LocalVoiceProcessor {
 
         info!("Using audio device: {:?}", device.name());
 
-        // Configure for 16kHz mono (whisper optimal)
-        let config = cpal::StreamConfig {
-            channels: 1,
-            sample_rate: cpal::SampleRate(WHISPER_SAMPLE_RATE),
-            buffer_size: cpal::BufferSize::Fixed(AUDIO_BUFFER_SIZE as u32),
+        // Most devices don't support 16kHz mono directly, so capture at the
+        // device default and convert ourselves.
+        let supported = device.default_input_config()
+            .map_err(|e| anyhow!("No default input config: {}", e))?;
+        let sample_format = supported.sample_format();
+        let config: cpal::StreamConfig = supported.into();
+
+        info!(
+            "Capturing {} Hz, {} channel(s), {:?}",
+            config.sample_rate.0, config.channels, sample_format
+        );
+
+        if let Ok(mut buffer) = sample_buffer.lock() {
+            buffer.clear();
+        }
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => Self::build_capture_stream::<f32>(&device, &config, sample_buffer.clone())?,
+            cpal::SampleFormat::I16 => Self::build_capture_stream::<i16>(&device, &config, sample_buffer.clone())?,
+            cpal::SampleFormat::U16 => Self::build_capture_stream::<u16>(&device, &config, sample_buffer.clone())?,
+            cpal::SampleFormat::I32 => Self::build_capture_stream::<i32>(&device, &config, sample_buffer.clone())?,
+            other => return Err(anyhow!("Unsupported sample format: {:?}", other)),
         };
 
-        let sample_buffer_clone = sample_buffer.clone();
+        stream.play()?;
+
+        let mut resampler = LinearResampler::new(config.sample_rate.0, WHISPER_SAMPLE_RATE);
+        let mut segmenter = VoiceActivitySegmenter::new();
+
+        // Keep running until stopped or the receiver is dropped
+        while is_listening.load(Ordering::SeqCst) && !tx.is_closed() {
+            std::thread::sleep(std::time::Duration::from_millis(CAPTURE_POLL_MS));
+
+            let captured: Vec<f32> = match sample_buffer.lock() {
+                Ok(mut buffer) => buffer.drain(..).collect(),
+                Err(_) => continue,
+            };
+            if captured.is_empty() {
+                continue;
+            }
+
+            let resampled = resampler.process(&captured);
+            for utterance in segmenter.push(&resampled) {
+                Self::transcribe_and_send(&context, &utterance, &tx);
+            }
+        }
+
+        drop(stream);
+
+        // Transcribe whatever was still being spoken when capture stopped
+        if let Some(utterance) = segmenter.flush() {
+            Self::transcribe_and_send(&context, &utterance, &tx);
+        }
+
+        info!("Audio stream stopped");
+
+        Ok(())
+    }
+
+    /// Build a cpal input stream that downmixes to mono f32 into `sample_buffer`
+    fn build_capture_stream<T>(
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        sample_buffer: Arc<std::sync::Mutex<Vec<f32>>>,
+    ) -> Result<cpal::Stream>
+    where
+        T: SizedSample,
+        f32: FromSample<T>,
+    {
+        let channels = config.channels.max(1) as usize;
 
         let stream = device.build_input_stream(
-            &config,
-            move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                // Accumulate samples
-                if let Ok(mut buffer) = sample_buffer_clone.lock() {
-                    buffer.extend_from_slice(data);
-
-                    // Process every ~2 seconds of audio
-                    if buffer.len() >= WHISPER_SAMPLE_RATE as usize * 2 {
-                        // Clone data for processing
-                        let samples: Vec<f32> = buffer.drain(..).collect();
-
-                        // TODO: Run whisper transcription here
-                        // For now, this is a placeholder for the whisper-rs integration
-                        debug!("Collected {} samples for transcription", samples.len());
-                    }
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                if let Ok(mut buffer) = sample_buffer.lock() {
+                    buffer.extend(data.chunks(channels).map(|frame| {
+                        frame.iter().map(|s| s.to_sample::<f32>()).sum::<f32>() / frame.len() as f32
+                    }));
                 }
             },
             move |err| {
@@ -160,39 +262,73 @@ impl LocalVoiceProcessor {
             None,
         )?;
 
-        stream.play()?;
+        Ok(stream)
+    }
 
-        // Keep running until stopped
-        while is_listening.load(Ordering::SeqCst) {
-            std::thread::sleep(std::time::Duration::from_millis(100));
+    /// Run inference on one utterance and forward non-empty text to the listener
+    fn transcribe_and_send(context: &WhisperContext, samples: &[f32], tx: &mpsc::Sender<String>) {
+        match run_whisper(context, samples) {
+            Ok(text) if !text.is_empty() => {
+                debug!("Transcribed: {}", text);
+                if tx.blocking_send(text).is_err() {
+                    debug!("Transcript receiver dropped");
+                }
+            }
+            Ok(_) => debug!("Utterance produced no text"),
+            Err(e) => warn!("Whisper transcription failed: {}", e),
         }
-
-        drop(stream);
-        info!("Audio stream stopped");
-
-        Ok(())
     }
 
-    /// Transcribe audio samples using whisper-rs
+    /// Transcribe 16kHz mono samples using whisper-rs
     /// This is the core offline ASR function
-    #[allow(dead_code)]
-    async fn transcribe_whisper(&self, _samples: &[f32]) -> Result<String> {
-        let _model_path = self.model_path.as_ref()
+    pub async fn transcribe_whisper(&self, samples: &[f32]) -> Result<String> {
+        let context = self.context.clone()
             .ok_or_else(|| anyhow!("Whisper model not loaded"))?;
+        let samples = samples.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let mut parts = Vec::new();
+            for chunk in samples.chunks(MAX_SEGMENT_SAMPLES) {
+                let text = run_whisper(&context, chunk)?;
+                if !text.is_empty() {
+                    parts.push(text);
+                }
+            }
+            Ok(parts.join(" "))
+        })
+        .await
+        .map_err(|e| anyhow!("Whisper task failed: {}", e))?
+    }
+
+    /// Transcribe a WAV file (any sample rate / channel count / PCM format).
+    /// Audio is converted to 16kHz mono and split on voice activity, exactly
+    /// like live capture, so this exercises the full offline pipeline headless.
+    pub async fn transcribe_wav(&self, wav_path: &str) -> Result<String> {
+        let context = self.context.clone()
+            .ok_or_else(|| anyhow!("Whisper model not loaded"))?;
+
+        info!("Transcribing WAV file: {}", wav_path);
 
-        // TODO: Full whisper-rs integration
-        // Example usage:
-        // let ctx = whisper_rs::WhisperContext::new(model_path)?;
-        // let mut state = ctx.create_state()?;
-        // let mut params = whisper_rs::FullParams::new(whisper_rs::SamplingStrategy::Greedy);
-        // params.set_language(Some("en"));
-        // params.set_print_realtime(false);
-        // state.full(params, samples)?;
-        // let text = state.get_segment_text(0)?;
+        let samples = read_wav_mono_16k(Path::new(wav_path))?;
 
-        // Placeholder until whisper-rs is fully integrated
-        warn!("Whisper transcription not yet implemented - using placeholder");
-        Ok(String::new())
+        tokio::task::spawn_blocking(move || {
+            let mut segmenter = VoiceActivitySegmenter::new();
+            let mut utterances = segmenter.push(&samples);
+            utterances.extend(segmenter.flush());
+
+            debug!("WAV split into {} utterance(s)", utterances.len());
+
+            let mut parts = Vec::new();
+            for utterance in &utterances {
+                let text = run_whisper(&context, utterance)?;
+                if !text.is_empty() {
+                    parts.push(text);
+                }
+            }
+            Ok(parts.join(" "))
+        })
+        .await
+        .map_err(|e| anyhow!("Whisper task failed: {}", e))?
     }
 
     /// Check if text contains wake word
@@ -225,6 +361,268 @@ impl Default for LocalVoiceProcessor {
     }
 }
 
+/// Run whisper over a single chunk of 16kHz mono audio (≤ 30s).
+fn run_whisper(context: &WhisperContext, samples: &[f32]) -> Result<String> {
+    let mut state = context.create_state()
+        .map_err(|e| anyhow!("Failed to create whisper state: {}", e))?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some("en"));
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_suppress_blank(true);
+    params.set_no_context(true);
+    params.set_n_threads(
+        std::thread::available_parallelism().map(|n| n.get().min(4) as i32).unwrap_or(2),
+    );
+
+    // Pad short utterances with silence — whisper.cpp drops inputs under one second
+    let padded;
+    let input = if samples.len() < MIN_INFERENCE_SAMPLES {
+        padded = {
+            let mut v = samples.to_vec();
+            v.resize(MIN_INFERENCE_SAMPLES, 0.0);
+            v
+        };
+        &padded[..]
+    } else {
+        samples
+    };
+
+    state.full(params, input)
+        .map_err(|e| anyhow!("Whisper inference failed: {}", e))?;
+
+    let segments = state.full_n_segments()
+        .map_err(|e| anyhow!("Failed to read whisper segments: {}", e))?;
+
+    let mut text = String::new();
+    for i in 0..segments {
+        let segment = state.full_get_segment_text(i)
+            .map_err(|e| anyhow!("Failed to read segment {}: {}", i, e))?;
+        text.push_str(&segment);
+    }
+
+    Ok(clean_transcript(&text))
+}
+
+/// Collapse whitespace and drop whisper's non-speech markers like "[BLANK_AUDIO]"
+fn clean_transcript(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut depth = 0usize;
+    for c in text.chars() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => out.push(c),
+            _ => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Read a PCM WAV file and convert it to 16kHz mono f32
+fn read_wav_mono_16k(path: &Path) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| anyhow!("Failed to open WAV {}: {}", path.display(), e))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|e| anyhow!("Failed to read WAV samples: {}", e))?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<std::result::Result<_, _>>()
+                .map_err(|e| anyhow!("Failed to read WAV samples: {}", e))?
+        }
+    };
+
+    let mono: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok(LinearResampler::new(spec.sample_rate, WHISPER_SAMPLE_RATE).process(&mono))
+}
+
+/// Streaming linear-interpolation resampler.
+/// Keeps its phase between calls so chunked capture doesn't click at boundaries.
+pub struct LinearResampler {
+    /// Input samples consumed per output sample
+    step: f64,
+    /// Position of the next output sample, relative to `last`
+    position: f64,
+    /// Final sample of the previous chunk
+    last: Option<f32>,
+}
+
+impl LinearResampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        Self {
+            step: from_rate.max(1) as f64 / to_rate.max(1) as f64,
+            position: 0.0,
+            last: None,
+        }
+    }
+
+    /// Resample a chunk of mono audio
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if input.is_empty() {
+            return Vec::new();
+        }
+        if (self.step - 1.0).abs() < f64::EPSILON {
+            return input.to_vec();
+        }
+
+        // Index 0 of `source` is the previous chunk's last sample, if any
+        let offset = usize::from(self.last.is_some());
+        let sample_at = |i: usize| -> f32 {
+            match (offset, i) {
+                (1, 0) => self.last.unwrap_or(0.0),
+                _ => input[i - offset],
+            }
+        };
+        let len = input.len() + offset;
+
+        let mut output = Vec::with_capacity((input.len() as f64 / self.step) as usize + 1);
+        while self.position + 1.0 < len as f64 {
+            let idx = self.position as usize;
+            let frac = (self.position - idx as f64) as f32;
+            let a = sample_at(idx);
+            let b = sample_at(idx + 1);
+            output.push(a + (b - a) * frac);
+            self.position += self.step;
+        }
+
+        // Rebase so the last input sample becomes index 0 of the next chunk
+        self.position -= (len - 1) as f64;
+        self.last = input.last().copied();
+
+        output
+    }
+}
+
+/// Energy-based voice activity segmenter for 16kHz mono audio.
+/// Emits one buffer per utterance: speech onset (with pre-roll) through
+/// a short trailing silence, force-split at whisper's 30s window.
+pub struct VoiceActivitySegmenter {
+    /// Samples not yet forming a whole frame
+    pending: Vec<f32>,
+    /// Recent silent frames kept as pre-roll
+    pre_roll: VecDeque<Vec<f32>>,
+    /// Current utterance being accumulated
+    current: Vec<f32>,
+    in_speech: bool,
+    speech_frames: usize,
+    silent_frames: usize,
+}
+
+impl VoiceActivitySegmenter {
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            pre_roll: VecDeque::with_capacity(VAD_PRE_ROLL_FRAMES),
+            current: Vec::new(),
+            in_speech: false,
+            speech_frames: 0,
+            silent_frames: 0,
+        }
+    }
+
+    /// Feed samples; returns any utterances completed by this chunk
+    pub fn push(&mut self, samples: &[f32]) -> Vec<Vec<f32>> {
+        self.pending.extend_from_slice(samples);
+
+        let mut finished = Vec::new();
+        let whole = self.pending.len() / VAD_FRAME_SAMPLES * VAD_FRAME_SAMPLES;
+        let frames: Vec<f32> = self.pending.drain(..whole).collect();
+
+        for frame in frames.chunks(VAD_FRAME_SAMPLES) {
+            let is_speech = frame_rms(frame) >= VAD_ENERGY_THRESHOLD;
+
+            if !self.in_speech {
+                if is_speech {
+                    self.in_speech = true;
+                    self.speech_frames = 1;
+                    self.silent_frames = 0;
+                    for pre in self.pre_roll.drain(..) {
+                        self.current.extend_from_slice(&pre);
+                    }
+                    self.current.extend_from_slice(frame);
+                } else {
+                    if self.pre_roll.len() == VAD_PRE_ROLL_FRAMES {
+                        self.pre_roll.pop_front();
+                    }
+                    self.pre_roll.push_back(frame.to_vec());
+                }
+                continue;
+            }
+
+            self.current.extend_from_slice(frame);
+            if is_speech {
+                self.speech_frames += 1;
+                self.silent_frames = 0;
+            } else {
+                self.silent_frames += 1;
+            }
+
+            if self.silent_frames >= VAD_HANGOVER_FRAMES || self.current.len() >= MAX_SEGMENT_SAMPLES {
+                if let Some(utterance) = self.finish() {
+                    finished.push(utterance);
+                }
+            }
+        }
+
+        finished
+    }
+
+    /// Flush a partially-spoken utterance (e.g. when capture stops)
+    pub fn flush(&mut self) -> Option<Vec<f32>> {
+        if self.in_speech {
+            self.current.extend(self.pending.drain(..));
+        } else {
+            self.pending.clear();
+        }
+        self.finish()
+    }
+
+    fn finish(&mut self) -> Option<Vec<f32>> {
+        let utterance = std::mem::take(&mut self.current);
+        let enough_speech = self.speech_frames >= VAD_MIN_SPEECH_FRAMES;
+        self.in_speech = false;
+        self.speech_frames = 0;
+        self.silent_frames = 0;
+        self.pre_roll.clear();
+
+        if enough_speech {
+            Some(utterance)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for VoiceActivitySegmenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Root-mean-square energy of a frame
+fn frame_rms(frame: &[f32]) -> f32 {
+    if frame.is_empty() {
+        return 0.0;
+    }
+    (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt()
+}
+
 /// Audio playback for Tetsuo's responses
 pub struct AudioPlayback {
     /// Output stream for playback
@@ -273,4 +671,84 @@ mod tests {
         let cmd = LocalVoiceProcessor::extract_command("Hey Tetsuo: list open tasks");
         assert_eq!(cmd, Some("list open tasks".to_string()));
     }
+
+    fn tone(samples: usize, amplitude: f32) -> Vec<f32> {
+        (0..samples)
+            .map(|i| amplitude * (i as f32 * 440.0 * std::f32::consts::TAU / WHISPER_SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_resampler_48k_to_16k() {
+        let mut resampler = LinearResampler::new(48_000, WHISPER_SAMPLE_RATE);
+        // Feed in uneven chunks to exercise phase carry-over
+        let input: Vec<f32> = (0..48_000).map(|i| i as f32).collect();
+        let mut out = Vec::new();
+        for chunk in input.chunks(1_001) {
+            out.extend(resampler.process(chunk));
+        }
+        assert!((out.len() as i64 - 16_000).abs() <= 1);
+        // Every output sample lands exactly on every third input sample
+        for (i, v) in out.iter().enumerate().take(100) {
+            assert!((v - (i * 3) as f32).abs() < 1e-3, "sample {} = {}", i, v);
+        }
+    }
+
+    #[test]
+    fn test_vad_splits_utterances() {
+        let mut vad = VoiceActivitySegmenter::new();
+        let mut audio = vec![0.0; 8_000];
+        audio.extend(tone(16_000, 0.3)); // 1s speech
+        audio.extend(vec![0.0; 16_000]); // 1s silence
+        audio.extend(tone(8_000, 0.3)); // 0.5s speech
+        audio.extend(vec![0.0; 16_000]);
+
+        let utterances = vad.push(&audio);
+        assert_eq!(utterances.len(), 2);
+        assert!(utterances[0].len() >= 16_000);
+        assert!(vad.flush().is_none());
+    }
+
+    #[test]
+    fn test_vad_ignores_clicks_and_flushes_tail() {
+        let mut vad = VoiceActivitySegmenter::new();
+        // A 60ms click is below the minimum speech length
+        let mut audio = tone(960, 0.5);
+        audio.extend(vec![0.0; 16_000]);
+        assert!(vad.push(&audio).is_empty());
+
+        // Speech still in progress when capture stops is flushed
+        assert!(vad.push(&tone(16_000, 0.3)).is_empty());
+        assert!(vad.flush().is_some());
+    }
+
+    #[test]
+    fn test_clean_transcript() {
+        assert_eq!(clean_transcript(" [BLANK_AUDIO] "), "");
+        assert_eq!(clean_transcript(" Hey Tetsuo,  list  tasks (music)"), "Hey Tetsuo, list tasks");
+    }
+
+    /// Runs against a real ggml model, e.g. on CI with ggml-tiny.en.bin:
+    ///   WHISPER_TEST_MODEL=models/ggml-tiny.en.bin WHISPER_TEST_WAV=jfk.wav \
+    ///   cargo test -p operator-core -- --ignored test_transcribe_wav
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore]
+    async fn test_transcribe_wav() {
+        let model = std::env::var("WHISPER_TEST_MODEL").expect("WHISPER_TEST_MODEL not set");
+        let wav = std::env::var("WHISPER_TEST_WAV").expect("WHISPER_TEST_WAV not set");
+
+        let mut processor = LocalVoiceProcessor::new();
+        processor.load_model(&model).await.unwrap();
+        let text = processor.transcribe_wav(&wav).await.unwrap();
+
+        assert!(!text.is_empty(), "expected a transcript from {}", wav);
+        if let Ok(expected) = std::env::var("WHISPER_TEST_EXPECT") {
+            assert!(
+                text.to_lowercase().contains(&expected.to_lowercase()),
+                "transcript {:?} missing {:?}",
+                text,
+                expected
+            );
+        }
+    }
 }
@@ -0,0 +1,781 @@
+//! ============================================================================
+//! Autonomous Worker - Claims, Performs and Completes Matching Tasks
+//! ============================================================================
+//! Polls the open tasks and works through the ones this operator can do,
+//! with nobody at the keyboard:
+//! - Tasks are filtered by our capability mask, a minimum reward and the
+//!   time left before their deadline; the best-paying match goes first
+//! - Claims and completions go through the unattended PolicyGate, and any
+//!   intent that would need a confirmation is treated as denied
+//! - A `TaskHandler` does the work (GrokCodeExecutor for code and review
+//!   tasks); its output is written to disk and hashed into the proof
+//! - Each step is appended to the audit log, and the task record and its
+//!   VerificationLog are stored as for an interactive completion
+//! - A task whose work or completion fails after the claim is recorded as
+//!   Failed and reported; it stays claimed on chain
+//!
+//! The worker stops as soon as its kill switch is set or the kill file
+//! exists, and claims at most `max_tasks_per_day` tasks in any 24 hours.
+//! The budget is counted from the tail of the audit log, so a restart doesn't
+//! reset it.
+//! ============================================================================
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::agenc_program::OnChainTask;
+use crate::capabilities::{capability_mask, capability_names};
+use crate::db::{AuditEntry, AuditRecord, DbTaskStatus, OperatorDb, TaskRecord};
+use crate::executor::GrokCodeExecutor;
+use crate::policy_gate::PolicyGate;
+use crate::proofs::ProofManifest;
+use crate::solana_exec::SolanaExecutor;
+use crate::types::{
+    ConfirmationType, DescriptionStatus, ExecutionResult, IntentAction, TaskDescription, VoiceIntent,
+};
+
+/// `executor` value of the audit entries the worker writes
+pub const WORKER_EXECUTOR: &str = "worker";
+
+/// Open tasks fetched per poll
+const FETCH_LIMIT: usize = 100;
+
+/// Languages recognised in task tags, for code handlers
+const LANGUAGES: &[&str] = &[
+    "rust", "typescript", "javascript", "python", "go", "solidity", "c", "cpp", "java", "kotlin",
+    "swift", "ruby", "bash", "sql",
+];
+
+/// Autonomous worker settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkerConfig {
+    /// Capability names we offer; a task qualifies only if it requires no
+    /// capability outside this set
+    pub capabilities: Vec<String>,
+    /// Smallest SOL reward worth claiming
+    pub min_reward_sol: f64,
+    /// Claims allowed in any rolling 24 hours
+    pub max_tasks_per_day: u32,
+    pub poll_interval_secs: u64,
+    /// Skip tasks whose deadline is closer than this
+    pub min_time_left_secs: i64,
+    /// Wallet profile to sign with (None = the active wallet)
+    pub wallet: Option<String>,
+    /// Where work output is written (None = ~/.agenc/work)
+    pub output_dir: Option<PathBuf>,
+    /// The worker stops while this file exists (None = ~/.agenc/worker.stop)
+    pub kill_file: Option<PathBuf>,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            capabilities: vec!["code".to_string(), "code_review".to_string()],
+            min_reward_sol: 0.01,
+            max_tasks_per_day: 5,
+            poll_interval_secs: 60,
+            min_time_left_secs: 3600,
+            wallet: None,
+            output_dir: None,
+            kill_file: None,
+        }
+    }
+}
+
+impl WorkerConfig {
+    fn agenc_dir() -> Result<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| anyhow!("Cannot determine home directory"))?;
+        Ok(home.join(".agenc"))
+    }
+
+    /// Directory work output is written to
+    pub fn output_dir(&self) -> Result<PathBuf> {
+        match &self.output_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(Self::agenc_dir()?.join("work")),
+        }
+    }
+
+    /// File whose presence stops the worker
+    pub fn kill_file(&self) -> Result<PathBuf> {
+        match &self.kill_file {
+            Some(path) => Ok(path.clone()),
+            None => Ok(Self::agenc_dir()?.join("worker.stop")),
+        }
+    }
+
+    fn min_reward_lamports(&self) -> u64 {
+        (self.min_reward_sol.max(0.0) * 1_000_000_000.0) as u64
+    }
+}
+
+/// A claimed task handed to a `TaskHandler`
+#[derive(Debug, Clone)]
+pub struct WorkJob {
+    pub task: OnChainTask,
+    /// Names of the task's required capabilities
+    pub required: Vec<String>,
+    /// Verified off-chain description, if it could be resolved
+    pub description: Option<TaskDescription>,
+}
+
+/// What a handler produced for a task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkOutput {
+    /// One line for the audit log and the proof notes
+    pub summary: String,
+    /// The deliverable, written to the output directory and hashed
+    pub content: String,
+    /// Links that are part of the result (gists, PRs)
+    #[serde(default)]
+    pub links: Vec<String>,
+}
+
+/// Something that can do the work a task asks for
+#[async_trait]
+pub trait TaskHandler: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Whether this handler can do a task requiring `required`
+    fn accepts(&self, required: &[String]) -> bool;
+
+    async fn perform(&self, job: &WorkJob) -> Result<WorkOutput>;
+}
+
+#[async_trait]
+impl TaskHandler for GrokCodeExecutor {
+    fn name(&self) -> &str {
+        "grok_code"
+    }
+
+    fn accepts(&self, required: &[String]) -> bool {
+        required.iter().any(|c| c == "code" || c == "code_review")
+    }
+
+    async fn perform(&self, job: &WorkJob) -> Result<WorkOutput> {
+        let doc = job
+            .description
+            .as_ref()
+            .ok_or_else(|| anyhow!("Task {} has no verified description", job.task.pda))?;
+        let language = task_language(&doc.tags);
+
+        if job.required.iter().any(|c| c == "code_review") {
+            let review = self.review_code(&doc.description, language).await?;
+            return Ok(WorkOutput {
+                summary: format!("Reviewed {} code for task #{}", language, job.task.task_id),
+                content: review,
+                links: vec![],
+            });
+        }
+
+        let mut spec = doc.description.clone();
+        if !doc.acceptance_criteria.is_empty() {
+            spec.push_str("\n\nAcceptance criteria:\n");
+            for criterion in &doc.acceptance_criteria {
+                spec.push_str(&format!("- {}\n", criterion));
+            }
+        }
+        let code = self.generate_code(&spec, language).await?;
+        Ok(WorkOutput {
+            summary: format!("Generated {} code for task #{}", language, job.task.task_id),
+            content: code,
+            links: vec![],
+        })
+    }
+}
+
+/// First programming language named in a task's tags ("text" if none)
+fn task_language(tags: &[String]) -> &'static str {
+    tags.iter()
+        .find_map(|tag| LANGUAGES.iter().find(|l| tag.eq_ignore_ascii_case(l)))
+        .copied()
+        .unwrap_or("text")
+}
+
+/// Open tasks worth claiming, best reward first: every required capability
+/// is in `mask`, the reward is at least `min_lamports`, there are at least
+/// `min_time_left` seconds before the deadline (0 = none), and the task is
+/// not in `skip`.
+pub fn select_tasks<'a>(
+    tasks: &'a [OnChainTask],
+    mask: u64,
+    min_lamports: u64,
+    min_time_left: i64,
+    now: i64,
+    skip: &HashSet<String>,
+) -> Vec<&'a OnChainTask> {
+    let mut selected: Vec<&OnChainTask> = tasks
+        .iter()
+        .filter(|t| t.required_capabilities & !mask == 0)
+        .filter(|t| t.reward_lamports >= min_lamports)
+        .filter(|t| t.deadline == 0 || t.deadline - now >= min_time_left)
+        .filter(|t| !skip.contains(&t.pda))
+        .collect();
+    selected.sort_by_key(|t| std::cmp::Reverse(t.reward_lamports));
+    selected
+}
+
+/// Successful worker claims in `entries` started at or after `since`
+pub fn claims_since(entries: &[AuditEntry], since: i64) -> u32 {
+    entries
+        .iter()
+        .filter(|e| e.record.executor == WORKER_EXECUTOR)
+        .filter(|e| e.record.action == IntentAction::ClaimTask.name())
+        .filter(|e| e.record.success && e.record.started_at >= since)
+        .count() as u32
+}
+
+/// What one poll of the worker did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum WorkerTick {
+    /// Kill switch or kill file
+    Stopped { reason: String },
+    /// The daily claim budget is used up
+    BudgetExhausted { claimed: u32 },
+    /// Nothing to claim
+    Idle,
+    Completed { task: String, signature: Option<String>, proof_hash: Option<String> },
+    Failed { task: String, error: String },
+}
+
+/// Running worker state, as reported to the UI
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkerStatus {
+    pub running: bool,
+    pub started_at: i64,
+    pub completed: u32,
+    pub failed: u32,
+    pub last_tick: Option<WorkerTick>,
+    pub last_tick_at: Option<i64>,
+}
+
+/// Polls for tasks and works them under the unattended policy
+pub struct AutonomousWorker {
+    config: WorkerConfig,
+    mask: u64,
+    executor: Arc<RwLock<SolanaExecutor>>,
+    db: Arc<RwLock<Option<OperatorDb>>>,
+    policy: PolicyGate,
+    handlers: Vec<Arc<dyn TaskHandler>>,
+    kill: Arc<AtomicBool>,
+    /// Tasks tried this run, so a failing task isn't retried every poll
+    attempted: HashSet<String>,
+}
+
+impl AutonomousWorker {
+    /// Worker signing through `executor` and recording to `db`, using the
+    /// unattended policy rules. Fails on unknown capability names.
+    pub fn new(
+        config: WorkerConfig,
+        executor: Arc<RwLock<SolanaExecutor>>,
+        db: Arc<RwLock<Option<OperatorDb>>>,
+    ) -> Result<Self> {
+        Ok(Self {
+            mask: capability_mask(&config.capabilities)?,
+            config,
+            executor,
+            db,
+            policy: PolicyGate::unattended()?,
+            handlers: Vec::new(),
+            kill: Arc::new(AtomicBool::new(false)),
+            attempted: HashSet::new(),
+        })
+    }
+
+    /// Add a handler; the first one accepting a task performs it
+    pub fn with_handler(mut self, handler: Arc<dyn TaskHandler>) -> Self {
+        self.handlers.push(handler);
+        self
+    }
+
+    /// Setting this flag stops the worker before its next step
+    pub fn kill_switch(&self) -> Arc<AtomicBool> {
+        self.kill.clone()
+    }
+
+    /// Why the worker must stop now, if it must
+    fn stop_reason(&self) -> Option<String> {
+        if self.kill.load(Ordering::SeqCst) {
+            return Some("Kill switch set".to_string());
+        }
+        match self.config.kill_file() {
+            Ok(path) if path.exists() => Some(format!("Kill file {} exists", path.display())),
+            Ok(_) => None,
+            Err(e) => Some(format!("Cannot locate kill file: {}", e)),
+        }
+    }
+
+    /// Claims in the last 24 hours
+    async fn claims_today(&self) -> Result<u32> {
+        let guard = self.db.read().await;
+        let db = guard
+            .as_ref()
+            .ok_or_else(|| anyhow!("The worker needs the operator database for its audit trail"))?;
+        let since = chrono::Utc::now().timestamp() - 24 * 3600;
+        Ok(claims_since(&db.list_audit_since(since)?, since))
+    }
+
+    fn handler_for(&self, required: &[String]) -> Option<Arc<dyn TaskHandler>> {
+        self.handlers.iter().find(|h| h.accepts(required)).cloned()
+    }
+
+    /// Look for one task and work it through
+    pub async fn run_once(&mut self) -> Result<WorkerTick> {
+        if let Some(reason) = self.stop_reason() {
+            return Ok(WorkerTick::Stopped { reason });
+        }
+
+        let claimed = self.claims_today().await?;
+        if claimed >= self.config.max_tasks_per_day {
+            return Ok(WorkerTick::BudgetExhausted { claimed });
+        }
+
+        let tasks = self.executor.read().await.fetch_open_tasks(FETCH_LIMIT).await?;
+        let now = chrono::Utc::now().timestamp();
+        let candidate = {
+            let guard = self.db.read().await;
+            select_tasks(
+                &tasks,
+                self.mask,
+                self.config.min_reward_lamports(),
+                self.config.min_time_left_secs,
+                now,
+                &self.attempted,
+            )
+            .into_iter()
+            // Tasks we already hold a record for were worked before
+            .filter(|t| !guard.as_ref().is_some_and(|db| matches!(db.get_task(&t.pda), Ok(Some(_)))))
+            .find_map(|t| {
+                let required = capability_names(t.required_capabilities);
+                self.handler_for(&required).map(|h| (t.clone(), required, h))
+            })
+        };
+        let Some((task, required, handler)) = candidate else {
+            return Ok(WorkerTick::Idle);
+        };
+
+        self.attempted.insert(task.pda.clone());
+        let pda = task.pda.clone();
+        match self.work(task, required, handler).await {
+            Ok(tick) => Ok(tick),
+            Err(e) => {
+                warn!("[Worker] Task {} failed: {}", pda, e);
+                Ok(WorkerTick::Failed { task: pda, error: e.to_string() })
+            }
+        }
+    }
+
+    /// Claim, perform and complete one task. Once claimed, a failure marks
+    /// the task record Failed rather than leaving it in progress.
+    async fn work(
+        &self,
+        task: OnChainTask,
+        required: Vec<String>,
+        handler: Arc<dyn TaskHandler>,
+    ) -> Result<WorkerTick> {
+        info!("[Worker] Claiming task {} ({:.4} SOL) for {}", task.pda, task.reward_sol(), handler.name());
+
+        let claim = self.execute(IntentAction::ClaimTask, self.params(&task, serde_json::json!({}))).await?;
+        let description = self
+            .executor
+            .read()
+            .await
+            .resolve_description(&task)
+            .await
+            .filter(|r| r.status == DescriptionStatus::Verified);
+        let description_text = description
+            .as_ref()
+            .and_then(|r| r.document.as_ref())
+            .map(|d| d.description.clone());
+        self.store_task(&task, DbTaskStatus::InProgress, description_text.clone(), claim.signature.clone(), None)
+            .await;
+
+        let job = WorkJob { task: task.clone(), required, description: description.and_then(|r| r.document) };
+        match self.perform_and_complete(job, handler, description_text.clone()).await {
+            Ok(tick) => Ok(tick),
+            Err(e) => {
+                self.store_task(&task, DbTaskStatus::Failed, description_text, claim.signature, None).await;
+                Err(anyhow!("{} — the task stays claimed on chain", e))
+            }
+        }
+    }
+
+    /// The claimed part of `work`: run the handler, save its output and
+    /// complete the task on chain
+    async fn perform_and_complete(
+        &self,
+        job: WorkJob,
+        handler: Arc<dyn TaskHandler>,
+        description_text: Option<String>,
+    ) -> Result<WorkerTick> {
+        let task = &job.task;
+        if let Some(reason) = self.stop_reason() {
+            return Ok(WorkerTick::Stopped { reason });
+        }
+
+        let started_at = chrono::Utc::now().timestamp();
+        let performed = handler.perform(&job).await;
+        self.audit_work(task, handler.name(), &performed, started_at).await;
+        let output = performed?;
+
+        let dir = self.config.output_dir()?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| anyhow!("Failed to create {}: {}", dir.display(), e))?;
+        let path = dir.join(format!("{}.md", task.pda));
+        std::fs::write(&path, &output.content)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+
+        if let Some(reason) = self.stop_reason() {
+            return Ok(WorkerTick::Stopped { reason });
+        }
+
+        let params = self.params(
+            task,
+            serde_json::json!({
+                "notes": output.summary,
+                "files": [path.to_string_lossy()],
+                "links": output.links,
+            }),
+        );
+        let completed = self.execute(IntentAction::CompleteTask, params).await?;
+
+        let manifest: Option<ProofManifest> = completed
+            .data
+            .as_ref()
+            .and_then(|d| serde_json::from_value(d["proof_manifest"].clone()).ok());
+        self.store_task(task, DbTaskStatus::Completed, description_text, completed.signature.clone(), manifest.as_ref())
+            .await;
+
+        info!("[Worker] Completed task {}", task.pda);
+        Ok(WorkerTick::Completed {
+            task: task.pda.clone(),
+            signature: completed.signature,
+            proof_hash: manifest.map(|m| m.root),
+        })
+    }
+
+    /// Intent params for `task`, signed by the configured wallet
+    fn params(&self, task: &OnChainTask, mut extra: serde_json::Value) -> serde_json::Value {
+        extra["task_id"] = task.pda.clone().into();
+        if let Some(wallet) = &self.config.wallet {
+            extra["wallet"] = wallet.clone().into();
+        }
+        extra
+    }
+
+    /// Run an intent if the unattended policy allows it outright, and audit
+    /// it either way. Errors unless it ran and succeeded.
+    async fn execute(&self, action: IntentAction, params: serde_json::Value) -> Result<ExecutionResult> {
+        let intent = VoiceIntent { action, params, raw_transcript: None };
+        let check = self.policy.check_policy(&intent);
+        let started_at = chrono::Utc::now().timestamp();
+
+        let result = if !check.allowed || check.requires_confirmation {
+            Err(anyhow!("Policy refused {} unattended: {}", intent.action.name(), check.reason))
+        } else {
+            self.executor.read().await.execute_intent(&intent).await
+        };
+
+        let (success, message, signature) = match &result {
+            Ok(r) => (r.success, r.message.clone(), r.signature.clone()),
+            Err(e) => (false, e.to_string(), None),
+        };
+        self.audit(AuditRecord {
+            action: intent.action.name(),
+            params_json: intent.params.to_string(),
+            raw_transcript: None,
+            policy_allowed: check.allowed && !check.requires_confirmation,
+            policy_reason: check.reason,
+            confirmation_type: check.confirmation_type,
+            confirmed: false,
+            access_tier: None,
+            executor: WORKER_EXECUTOR.to_string(),
+            success,
+            message: message.clone(),
+            signature,
+            started_at,
+            finished_at: chrono::Utc::now().timestamp(),
+        })
+        .await;
+
+        let result = result?;
+        if !result.success {
+            return Err(anyhow!("{}", message));
+        }
+        Ok(result)
+    }
+
+    /// Audit the handler's part, which is not an intent of its own
+    async fn audit_work(&self, task: &OnChainTask, handler: &str, performed: &Result<WorkOutput>, started_at: i64) {
+        let (success, message) = match performed {
+            Ok(output) => (true, output.summary.clone()),
+            Err(e) => (false, e.to_string()),
+        };
+        self.audit(AuditRecord {
+            action: "perform_task".to_string(),
+            params_json: serde_json::json!({ "task_id": task.pda, "handler": handler }).to_string(),
+            raw_transcript: None,
+            policy_allowed: true,
+            policy_reason: "Off-chain work for a claimed task".to_string(),
+            confirmation_type: ConfirmationType::None,
+            confirmed: false,
+            access_tier: None,
+            executor: WORKER_EXECUTOR.to_string(),
+            success,
+            message,
+            signature: None,
+            started_at,
+            finished_at: chrono::Utc::now().timestamp(),
+        })
+        .await;
+    }
+
+    async fn audit(&self, record: AuditRecord) {
+        let guard = self.db.read().await;
+        if let Some(db) = guard.as_ref() {
+            if let Err(e) = db.append_audit(record) {
+                warn!("[Worker] Failed to append audit entry: {}", e);
+            }
+        }
+    }
+
+    /// Store the task record, and the proof once completed
+    async fn store_task(
+        &self,
+        task: &OnChainTask,
+        status: DbTaskStatus,
+        description: Option<String>,
+        signature: Option<String>,
+        manifest: Option<&ProofManifest>,
+    ) {
+        let guard = self.db.read().await;
+        let Some(db) = guard.as_ref() else { return };
+
+        let now = chrono::Utc::now().timestamp();
+        let claimed_at = match db.get_task(&task.pda) {
+            Ok(Some(existing)) => existing.claimed_at,
+            _ => now,
+        };
+        let record = TaskRecord {
+            task_id: task.pda.clone(),
+            payload: serde_json::to_vec(task).unwrap_or_default(),
+            completed_at: (status == DbTaskStatus::Completed).then_some(now),
+            status,
+            claimed_at,
+            on_chain_signature: signature.clone(),
+            description,
+            reward_lamports: Some(task.reward_lamports),
+            reward_skr_tokens: (task.reward_skr_tokens > 0).then_some(task.reward_skr_tokens),
+            creator: Some(task.creator.clone()),
+        };
+        if let Err(e) = db.store_task(&record) {
+            warn!("[Worker] Failed to store task {}: {}", task.pda, e);
+        }
+
+        if let Some(manifest) = manifest {
+            let stored = manifest.to_verification_log(signature).and_then(|proof| db.store_proof(&proof));
+            if let Err(e) = stored {
+                warn!("[Worker] Failed to store proof for task {}: {}", task.pda, e);
+            }
+        }
+    }
+
+    /// Run in the background until stopped, polling every
+    /// `poll_interval_secs` (immediately again after finishing a task)
+    pub fn spawn(mut self) -> WorkerHandle {
+        let kill = self.kill.clone();
+        let status = Arc::new(RwLock::new(WorkerStatus {
+            running: true,
+            started_at: chrono::Utc::now().timestamp(),
+            ..Default::default()
+        }));
+        let poll = Duration::from_secs(self.config.poll_interval_secs.max(1));
+
+        let task_status = status.clone();
+        let join = tokio::spawn(async move {
+            info!("[Worker] Started with capabilities {:?}", self.config.capabilities);
+            loop {
+                let tick = self.run_once().await.unwrap_or_else(|e| WorkerTick::Failed {
+                    task: String::new(),
+                    error: e.to_string(),
+                });
+                let stopped = matches!(tick, WorkerTick::Stopped { .. });
+                let busy = matches!(tick, WorkerTick::Completed { .. });
+                {
+                    let mut status = task_status.write().await;
+                    match &tick {
+                        WorkerTick::Completed { .. } => status.completed += 1,
+                        WorkerTick::Failed { .. } => status.failed += 1,
+                        _ => {}
+                    }
+                    status.last_tick = Some(tick);
+                    status.last_tick_at = Some(chrono::Utc::now().timestamp());
+                    status.running = !stopped;
+                }
+                if stopped {
+                    break;
+                }
+                if !busy {
+                    // Sleep in short steps so the kill switch takes effect promptly
+                    let mut slept = Duration::ZERO;
+                    while slept < poll && !self.kill.load(Ordering::SeqCst) {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        slept += Duration::from_secs(1);
+                    }
+                }
+            }
+            info!("[Worker] Stopped");
+        });
+
+        WorkerHandle { kill, status, join }
+    }
+}
+
+/// Handle to a spawned worker
+pub struct WorkerHandle {
+    kill: Arc<AtomicBool>,
+    status: Arc<RwLock<WorkerStatus>>,
+    join: JoinHandle<()>,
+}
+
+impl WorkerHandle {
+    /// Ask the worker to stop; it finishes the step it is in
+    pub fn stop(&self) {
+        self.kill.store(true, Ordering::SeqCst);
+    }
+
+    pub async fn status(&self) -> WorkerStatus {
+        let mut status = self.status.read().await.clone();
+        status.running &= !self.join.is_finished();
+        status
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.join.is_finished()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agenc_program::OnChainTaskState;
+
+    fn task(pda: &str, required: u64, reward_lamports: u64, deadline: i64) -> OnChainTask {
+        OnChainTask {
+            task_id: 1,
+            pda: pda.into(),
+            creator: "creator".into(),
+            escrow_account: "escrow".into(),
+            required_capabilities: required,
+            description_hash: [0; 32],
+            constraint_hash: [0; 32],
+            state: OnChainTaskState::Open,
+            reward_lamports,
+            reward_skr_tokens: 0,
+            deadline,
+            claimed_by: None,
+        }
+    }
+
+    fn entry(action: &str, executor: &str, success: bool, started_at: i64) -> AuditEntry {
+        AuditEntry {
+            seq: 1,
+            record: AuditRecord {
+                action: action.into(),
+                params_json: "{}".into(),
+                raw_transcript: None,
+                policy_allowed: true,
+                policy_reason: String::new(),
+                confirmation_type: ConfirmationType::None,
+                confirmed: false,
+                access_tier: None,
+                executor: executor.into(),
+                success,
+                message: String::new(),
+                signature: None,
+                started_at,
+                finished_at: started_at,
+            },
+            prev_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_select_tasks_filters_and_orders() {
+        let mask = capability_mask(&["code", "code_review"]).unwrap();
+        let review = capability_mask(&["code_review"]).unwrap();
+        let trading = capability_mask(&["trading"]).unwrap();
+        let now = 1_700_000_000;
+        let tasks = vec![
+            task("small", review, 1_000_000, 0),
+            task("best", review, 50_000_000, now + 7200),
+            task("foreign", trading, 90_000_000, 0),
+            task("urgent", review, 80_000_000, now + 60),
+            task("seen", 0, 70_000_000, 0),
+            task("good", mask, 20_000_000, 0),
+        ];
+        let skip: HashSet<String> = ["seen".to_string()].into();
+
+        let selected = select_tasks(&tasks, mask, 10_000_000, 3600, now, &skip);
+        let pdas: Vec<&str> = selected.iter().map(|t| t.pda.as_str()).collect();
+        assert_eq!(pdas, vec!["best", "good"]);
+    }
+
+    #[test]
+    fn test_claims_since_counts_worker_claims_only() {
+        let entries = vec![
+            entry("claim_task", WORKER_EXECUTOR, true, 100),
+            entry("claim_task", WORKER_EXECUTOR, true, 200),
+            entry("claim_task", WORKER_EXECUTOR, false, 200),
+            entry("claim_task", "solana", true, 200),
+            entry("complete_task", WORKER_EXECUTOR, true, 200),
+        ];
+        assert_eq!(claims_since(&entries, 0), 2);
+        assert_eq!(claims_since(&entries, 150), 1);
+    }
+
+    #[test]
+    fn test_task_language_and_grok_accepts() {
+        assert_eq!(task_language(&["urgent".into(), "Rust".into()]), "rust");
+        assert_eq!(task_language(&["docs".into()]), "text");
+
+        let grok = GrokCodeExecutor::new(String::new());
+        assert!(grok.accepts(&["code_review".into()]));
+        assert!(grok.accepts(&["inference".into(), "code".into()]));
+        assert!(!grok.accepts(&["trading".into()]));
+        assert!(!grok.accepts(&[]));
+    }
+
+    #[tokio::test]
+    async fn test_kill_switch_and_kill_file_stop_the_worker() {
+        let dir = std::env::temp_dir().join(format!("agenc-worker-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = WorkerConfig { kill_file: Some(dir.join("stop")), ..Default::default() };
+        let executor = Arc::new(RwLock::new(SolanaExecutor::new("http://127.0.0.1:1", "devnet")));
+        let mut worker = AutonomousWorker::new(config, executor, Arc::new(RwLock::new(None))).unwrap();
+
+        std::fs::write(dir.join("stop"), b"").unwrap();
+        assert!(matches!(worker.run_once().await.unwrap(), WorkerTick::Stopped { .. }));
+        std::fs::remove_file(dir.join("stop")).unwrap();
+
+        worker.kill_switch().store(true, Ordering::SeqCst);
+        let WorkerTick::Stopped { reason } = worker.run_once().await.unwrap() else {
+            panic!("worker did not stop");
+        };
+        assert_eq!(reason, "Kill switch set");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,774 @@
+//! Chain flows against the in-process mock RPC: task listings, token
+//! balances, the create/claim/complete flow, the worker, wallet history and
+//! the retry paths.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use operator_core::access::{TETSUO_DECIMALS, TETSUO_MINT, TIER_PRO_THRESHOLD};
+use operator_core::agenc_idl::{agenc_idl, IdlFields, IdlValue};
+use operator_core::agenc_program::{
+    derive_claim_pda, derive_escrow_pda, derive_protocol_pda, derive_task_pda, fetch_skr_balance, fetch_task_by_pda, fetch_tasks_by_state,
+    build_create_task_ix, get_skr_ata, instruction_discriminator, is_task_id_collision, program_id, skr_mint,
+    OnChainTask, OnChainTaskState,
+    ProtocolConfig,
+};
+use operator_core::rpc_pool::BlockingRpcClient;
+use operator_core::worker::{WorkJob, WorkOutput};
+use operator_core::{
+    AccessChecker, AccessTier, AutonomousWorker, DbTaskStatus, HistoryEventKind, HistoryRecord, IntentAction,
+    JupiterSwapExecutor, OperatorDb, PolicyGate, RetryConfig, RpcEndpoint, RpcPool, SendResult, SolanaExecutor,
+    SwapParams, TaskHandler, TransactionSender, VoiceIntent, WalletHistory, WorkerConfig, WorkerTick,
+};
+use operator_test_support::{Accounts, Fault, MockRpc, TempPath};
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use spl_associated_token_account::get_associated_token_address;
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+// ============================================================================
+// Account Fixtures
+// ============================================================================
+
+fn program_account(data: Vec<u8>) -> Account {
+    Account { lamports: 2_000_000, data, owner: program_id(), executable: false, rent_epoch: u64::MAX }
+}
+
+fn task_fields(task_id: u64, creator: Pubkey, state: OnChainTaskState, reward: u64) -> IdlFields {
+    let (task_pda, _) = derive_task_pda(task_id);
+    IdlFields(vec![
+        ("task_id".into(), IdlValue::U64(task_id)),
+        ("creator".into(), IdlValue::Pubkey(creator)),
+        ("escrow".into(), IdlValue::Pubkey(derive_escrow_pda(&task_pda).0)),
+        ("required_capabilities".into(), IdlValue::U64(0)),
+        ("description_hash".into(), IdlValue::Bytes(vec![7; 32])),
+        ("constraint_hash".into(), IdlValue::Bytes(vec![0; 32])),
+        ("reserved".into(), IdlValue::Bytes(vec![0; 2])),
+        ("state".into(), IdlValue::Enum { index: state as u8, name: String::new() }),
+        ("reward_amount".into(), IdlValue::U64(reward)),
+        ("deadline".into(), IdlValue::I64(0)),
+        ("claimed_by".into(), IdlValue::Option(None)),
+        ("reward_skr_amount".into(), IdlValue::U64(0)),
+    ])
+}
+
+fn protocol_fields(next_task_id: u64, protocol_fee_bps: u16) -> IdlFields {
+    IdlFields(vec![
+        ("authority".into(), IdlValue::Pubkey(Pubkey::new_unique())),
+        ("treasury".into(), IdlValue::Pubkey(Pubkey::new_unique())),
+        ("protocol_fee_bps".into(), IdlValue::U16(protocol_fee_bps)),
+        ("next_task_id".into(), IdlValue::U64(next_task_id)),
+        ("total_agents".into(), IdlValue::U64(0)),
+        ("bump".into(), IdlValue::U8(255)),
+    ])
+}
+
+fn encode(name: &str, fields: &IdlFields) -> Vec<u8> {
+    agenc_idl().encode_account(name, fields).unwrap()
+}
+
+fn set_field(fields: &mut IdlFields, name: &str, value: IdlValue) {
+    let slot = fields.0.iter_mut().find(|(n, _)| n == name).unwrap();
+    slot.1 = value;
+}
+
+fn seed_task(rpc: &MockRpc, task_id: u64, state: OnChainTaskState, reward: u64) -> Pubkey {
+    let (pda, _) = derive_task_pda(task_id);
+    let fields = task_fields(task_id, Pubkey::new_unique(), state, reward);
+    rpc.set_account(pda, program_account(encode("Task", &fields)));
+    pda
+}
+
+fn seed_protocol(rpc: &MockRpc, next_task_id: u64, protocol_fee_bps: u16) {
+    let data = encode("ProtocolConfig", &protocol_fields(next_task_id, protocol_fee_bps));
+    rpc.set_account(derive_protocol_pda().0, program_account(data));
+}
+
+fn read_task(rpc: &MockRpc, pda: &Pubkey) -> OnChainTask {
+    OnChainTask::from_account_data(&rpc.account(pda).unwrap().data, pda).unwrap()
+}
+
+fn write_keypair(rpc: &MockRpc, lamports: u64) -> (String, Keypair) {
+    let keypair = Keypair::new();
+    let path = std::env::temp_dir().join(format!("agenc-mock-rpc-{}.json", keypair.pubkey()));
+    std::fs::write(&path, serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap()).unwrap();
+    rpc.set_balance(keypair.pubkey(), lamports);
+    (path.to_string_lossy().into_owned(), keypair)
+}
+
+fn intent(action: IntentAction, params: serde_json::Value) -> VoiceIntent {
+    VoiceIntent { action, params, raw_transcript: None }
+}
+
+// ============================================================================
+// AgenC Program Stand-In
+// ============================================================================
+
+/// Applies create_task, claim_task and complete_task the way the program
+/// does, closely enough for the client flow to observe the results. The
+/// task PDA must be seeded with the protocol counter, which create_task only
+/// reads (the IDL marks `protocol_config` read-only).
+fn agenc_processor(tx: &VersionedTransaction, accounts: &mut Accounts) -> Result<(), String> {
+    let keys = tx.message.static_account_keys();
+    for ix in tx.message.instructions() {
+        if keys[ix.program_id_index as usize] != program_id() {
+            continue;
+        }
+        let account = |i: usize| keys[ix.accounts[i] as usize];
+        let (discriminator, args) = ix.data.split_at(8);
+
+        if discriminator == instruction_discriminator("create_task") {
+            let (task, creator) = (account(0), account(2));
+            if accounts.contains_key(&task) {
+                return Err(format!("Allocate: account Address {{ address: {}, base: None }} already in use", task));
+            }
+            let protocol_pda = derive_protocol_pda().0;
+            let protocol = ProtocolConfig::from_account_data(&accounts[&protocol_pda].data).map_err(|e| e.to_string())?;
+            let task_id = protocol.next_task_id;
+            if derive_task_pda(task_id).0 != task {
+                return Err("Program log: AnchorError caused by account: task. Error Code: ConstraintSeeds. \
+                    Error Number: 2006. Error Message: A seeds constraint was violated."
+                    .into());
+            }
+            let reward = u64::from_le_bytes(args[32..40].try_into().unwrap());
+            let mut fields = task_fields(task_id, creator, OnChainTaskState::Open, reward);
+            set_field(&mut fields, "description_hash", IdlValue::Bytes(args[..32].to_vec()));
+
+            let payer = accounts.get_mut(&creator).ok_or("creator has no account")?;
+            payer.lamports = payer.lamports.checked_sub(reward).ok_or("insufficient lamports")?;
+            accounts.insert(account(1), Account::new(reward, 0, &program_id()));
+            accounts.insert(task, program_account(encode("Task", &fields)));
+        } else if discriminator == instruction_discriminator("claim_task") {
+            let (task, worker) = (account(0), account(3));
+            let mut fields = agenc_idl().decode_account("Task", &accounts[&task].data).unwrap();
+            if fields.enum_index("state").unwrap() != OnChainTaskState::Open as u8 {
+                return Err("Program log: AnchorError: TaskNotOpen".into());
+            }
+            set_field(&mut fields, "state", IdlValue::Enum { index: OnChainTaskState::InProgress as u8, name: String::new() });
+            set_field(&mut fields, "claimed_by", IdlValue::Option(Some(Box::new(IdlValue::Pubkey(worker)))));
+            accounts.get_mut(&task).unwrap().data = encode("Task", &fields);
+            accounts.insert(account(1), program_account(Vec::new()));
+        } else if discriminator == instruction_discriminator("complete_task") {
+            let (task, escrow, worker) = (account(0), account(2), account(3));
+            let mut fields = agenc_idl().decode_account("Task", &accounts[&task].data).unwrap();
+            if fields.option_pubkey("claimed_by").unwrap() != Some(worker) {
+                return Err("Program log: AnchorError: NotClaimant".into());
+            }
+            set_field(&mut fields, "state", IdlValue::Enum { index: OnChainTaskState::Completed as u8, name: String::new() });
+            accounts.get_mut(&task).unwrap().data = encode("Task", &fields);
+
+            let reward = accounts.remove(&escrow).map_or(0, |a| a.lamports);
+            accounts.entry(worker).or_insert_with(|| Account::new(0, 0, &Pubkey::default())).lamports += reward;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Reads
+// ============================================================================
+
+#[tokio::test]
+async fn test_fetch_tasks_by_state_filters_on_chain() {
+    let rpc = MockRpc::start();
+    seed_protocol(&rpc, 4, 0);
+    let small = seed_task(&rpc, 1, OnChainTaskState::Open, LAMPORTS_PER_SOL);
+    let large = seed_task(&rpc, 2, OnChainTaskState::Open, 3 * LAMPORTS_PER_SOL);
+    seed_task(&rpc, 3, OnChainTaskState::InProgress, 5 * LAMPORTS_PER_SOL);
+
+    let client = RpcClient::new(rpc.url().to_string());
+    let open = fetch_tasks_by_state(&client, OnChainTaskState::Open, 10).await.unwrap();
+    let pdas: Vec<String> = open.iter().map(|t| t.pda.clone()).collect();
+    assert_eq!(pdas, vec![large.to_string(), small.to_string()]);
+    assert!(open.iter().all(|t| t.state == OnChainTaskState::Open));
+
+    let claimed = fetch_tasks_by_state(&client, OnChainTaskState::InProgress, 10).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].task_id, 3);
+}
+
+#[tokio::test]
+async fn test_fetch_skr_balance_defaults_to_zero_without_ata() {
+    let rpc = MockRpc::start();
+    let holder = Pubkey::new_unique();
+    rpc.set_mint(skr_mint(), 9, 1_000 * LAMPORTS_PER_SOL);
+    rpc.set_token_account(get_skr_ata(&holder), skr_mint(), holder, 250 * LAMPORTS_PER_SOL);
+
+    let client = RpcClient::new(rpc.url().to_string());
+    assert_eq!(fetch_skr_balance(&client, &holder).await.unwrap(), 250 * LAMPORTS_PER_SOL);
+    assert_eq!(fetch_skr_balance(&client, &Pubkey::new_unique()).await.unwrap(), 0);
+}
+
+#[test]
+fn test_access_checker_reads_tetsuo_balance() {
+    let rpc = MockRpc::start();
+    let mint = Pubkey::from_str(TETSUO_MINT).unwrap();
+    let holder = Pubkey::new_unique();
+    let raw = TIER_PRO_THRESHOLD as u64 * 10u64.pow(TETSUO_DECIMALS as u32);
+    rpc.set_mint(mint, TETSUO_DECIMALS, raw * 10);
+    rpc.set_token_account(get_associated_token_address(&holder, &mint), mint, holder, raw);
+
+    let checker = AccessChecker::new(rpc.url()).unwrap();
+    assert_eq!(checker.get_tetsuo_balance(&holder).unwrap(), raw);
+    assert_eq!(checker.get_access_tier(&holder).unwrap(), AccessTier::Pro);
+    assert_eq!(checker.get_tetsuo_balance(&Pubkey::new_unique()).unwrap(), 0);
+}
+
+// ============================================================================
+// Transaction Flows
+// ============================================================================
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_create_claim_complete_flow() {
+    let rpc = MockRpc::start();
+    rpc.on_transaction(agenc_processor);
+    seed_protocol(&rpc, 7, 250);
+
+    let exec = SolanaExecutor::new(rpc.url(), "localnet");
+    let (creator_path, creator) = write_keypair(&rpc, 5 * LAMPORTS_PER_SOL);
+    let (worker_path, worker) = write_keypair(&rpc, LAMPORTS_PER_SOL / 10);
+    exec.load_wallet_profile("creator", &creator_path, None).await.unwrap();
+    exec.load_wallet_profile("worker", &worker_path, None).await.unwrap();
+
+    let created = exec
+        .execute_intent(&intent(
+            IntentAction::CreateTask,
+            json!({ "description": "Index the devnet tasks", "reward_sol": 1.5, "wallet": "creator" }),
+        ))
+        .await
+        .unwrap();
+    assert!(created.success, "{}", created.message);
+    assert!(created.message.starts_with("Task #7 created"), "{}", created.message);
+    assert!(created.message.contains("2.5% protocol fee"));
+
+    let task_pda = derive_task_pda(7).0;
+    let task = read_task(&rpc, &task_pda);
+    assert_eq!(task.state, OnChainTaskState::Open);
+    assert_eq!(task.creator, creator.pubkey().to_string());
+    assert_eq!(task.reward_lamports, 1_500_000_000);
+
+    let claimed = exec
+        .execute_intent(&intent(IntentAction::ClaimTask, json!({ "task_id": "7", "wallet": "worker" })))
+        .await
+        .unwrap();
+    assert!(claimed.success, "{}", claimed.message);
+    assert_eq!(read_task(&rpc, &task_pda).claimed_by, Some(worker.pubkey().to_string()));
+    assert!(rpc.account(&derive_claim_pda(&task_pda, &worker.pubkey()).0).is_some());
+
+    let before = rpc.account(&worker.pubkey()).unwrap().lamports;
+    let completed = exec
+        .execute_intent(&intent(
+            IntentAction::CompleteTask,
+            json!({ "task_id": "7", "notes": "done", "wallet": "worker" }),
+        ))
+        .await
+        .unwrap();
+    assert!(completed.success, "{}", completed.message);
+    assert_eq!(read_task(&rpc, &task_pda).state, OnChainTaskState::Completed);
+    assert_eq!(rpc.account(&worker.pubkey()).unwrap().lamports, before + 1_500_000_000);
+
+    // Every send carried a compute budget ahead of the program instruction
+    let sent = rpc.sent_transactions();
+    assert_eq!(sent.len(), 3);
+    assert!(sent.iter().all(|tx| tx.message.instructions().len() >= 2));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_claim_of_taken_task_is_reported() {
+    let rpc = MockRpc::start();
+    rpc.on_transaction(agenc_processor);
+    seed_task(&rpc, 3, OnChainTaskState::InProgress, LAMPORTS_PER_SOL);
+
+    let exec = SolanaExecutor::new(rpc.url(), "localnet");
+    let (path, _) = write_keypair(&rpc, LAMPORTS_PER_SOL);
+    exec.load_keypair(&path).await.unwrap();
+
+    let err = exec
+        .execute_intent(&intent(IntentAction::ClaimTask, json!({ "task_id": "3" })))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("TaskNotOpen"), "{}", err);
+    assert!(rpc.sent_transactions().iter().all(|tx| !rpc.has_landed(&tx.signatures[0])));
+}
+
+#[tokio::test]
+async fn test_create_task_off_the_counter_fails_its_seeds_check() {
+    let rpc = MockRpc::start();
+    rpc.on_transaction(agenc_processor);
+    seed_protocol(&rpc, 7, 0);
+    let creator = Keypair::new();
+    rpc.set_balance(creator.pubkey(), 5 * LAMPORTS_PER_SOL);
+    let client = RpcClient::new(rpc.url().to_string());
+
+    // A stale counter read: another creator already took task 7
+    let ix = build_create_task_ix(8, &creator.pubkey(), [1; 32], LAMPORTS_PER_SOL, 0, 0).unwrap();
+    let message = Message::new(&[ix], Some(&creator.pubkey()));
+    let tx = Transaction::new(&[&creator], message, rpc.latest_blockhash());
+    let err = format!("{:?}", client.send_transaction(&tx).await.unwrap_err());
+    assert!(is_task_id_collision(&err), "{}", err);
+    assert!(rpc.account(&derive_task_pda(8).0).is_none());
+}
+
+#[tokio::test]
+async fn test_writes_to_read_only_accounts_are_rejected() {
+    let rpc = MockRpc::start();
+    let protocol_pda = derive_protocol_pda().0;
+    seed_protocol(&rpc, 1, 0);
+    rpc.on_transaction(move |_, accounts| {
+        accounts.get_mut(&protocol_pda).unwrap().data[8] ^= 1;
+        Ok(())
+    });
+    let payer = Keypair::new();
+    rpc.set_balance(payer.pubkey(), LAMPORTS_PER_SOL);
+    let client = RpcClient::new(rpc.url().to_string());
+
+    let ix = Instruction::new_with_bytes(program_id(), &[0], vec![AccountMeta::new_readonly(protocol_pda, false)]);
+    let message = Message::new(&[ix], Some(&payer.pubkey()));
+    let tx = Transaction::new(&[&payer], message, rpc.latest_blockhash());
+    let err = format!("{:?}", client.send_transaction(&tx).await.unwrap_err());
+    assert!(err.contains("read-only account"), "{}", err);
+}
+
+/// Handler whose work always fails
+struct FailingHandler;
+
+#[async_trait::async_trait]
+impl TaskHandler for FailingHandler {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    fn accepts(&self, _required: &[String]) -> bool {
+        true
+    }
+
+    async fn perform(&self, _job: &WorkJob) -> anyhow::Result<WorkOutput> {
+        Err(anyhow::anyhow!("model unavailable"))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_worker_marks_claimed_task_failed_when_work_fails() {
+    let rpc = MockRpc::start();
+    rpc.on_transaction(agenc_processor);
+    let task_pda = seed_task(&rpc, 4, OnChainTaskState::Open, LAMPORTS_PER_SOL);
+
+    let exec = SolanaExecutor::new(rpc.url(), "localnet");
+    let (path, worker_key) = write_keypair(&rpc, LAMPORTS_PER_SOL);
+    exec.load_keypair(&path).await.unwrap();
+    let db_path = TempPath::new("agenc-worker", "redb");
+    let db = Arc::new(tokio::sync::RwLock::new(Some(OperatorDb::open(Some(db_path.as_str())).unwrap())));
+    let kill_file = TempPath::new("agenc-worker", "stop");
+    let config = WorkerConfig { kill_file: Some(kill_file.path().to_path_buf()), ..Default::default() };
+    let mut worker = AutonomousWorker::new(config, Arc::new(tokio::sync::RwLock::new(exec)), Arc::clone(&db))
+        .unwrap()
+        .with_handler(Arc::new(FailingHandler));
+
+    let WorkerTick::Failed { task, error } = worker.run_once().await.unwrap() else {
+        panic!("the failed work was not reported");
+    };
+    assert_eq!(task, task_pda.to_string());
+    assert!(error.contains("model unavailable") && error.contains("claimed on chain"), "{}", error);
+    assert_eq!(read_task(&rpc, &task_pda).claimed_by, Some(worker_key.pubkey().to_string()));
+    let record = db.read().await.as_ref().unwrap().get_task(&task).unwrap().unwrap();
+    assert_eq!(record.status, DbTaskStatus::Failed);
+
+    // The failed task is not picked up again
+    assert!(matches!(worker.run_once().await.unwrap(), WorkerTick::Idle));
+}
+
+// ============================================================================
+// Wallet History
+// ============================================================================
+
+fn sol_transfer_ix(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Instruction::new_with_bytes(
+        Pubkey::default(),
+        &data,
+        vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+    )
+}
+
+async fn send_ix(client: &RpcClient, rpc: &MockRpc, payer: &Keypair, ix: Instruction) {
+    let message = Message::new(&[ix], Some(&payer.pubkey()));
+    let tx = Transaction::new(&[payer], message, rpc.latest_blockhash());
+    client.send_transaction(&tx).await.unwrap();
+}
+
+async fn history_records(exec: &SolanaExecutor, params: serde_json::Value) -> Vec<HistoryRecord> {
+    let result = exec.execute_intent(&intent(IntentAction::GetHistory, params)).await.unwrap();
+    assert!(result.success, "{}", result.message);
+    serde_json::from_value(result.data.unwrap()).unwrap()
+}
+
+fn kinds(records: &[HistoryRecord]) -> Vec<Vec<HistoryEventKind>> {
+    records.iter().map(|r| r.events.iter().map(|e| e.kind).collect()).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wallet_history_is_paged_decoded_and_cached() {
+    let rpc = MockRpc::start();
+    rpc.on_transaction(agenc_processor);
+    seed_protocol(&rpc, 1, 0);
+
+    let exec = SolanaExecutor::new(rpc.url(), "localnet");
+    let (path, wallet) = write_keypair(&rpc, 5 * LAMPORTS_PER_SOL);
+    exec.load_wallet_profile("main", &path, None).await.unwrap();
+    let db_path = TempPath::new("agenc-history", "redb");
+    let db = OperatorDb::open(Some(db_path.as_str())).unwrap();
+    let db = Arc::new(tokio::sync::RwLock::new(Some(db)));
+    let history = WalletHistory::new(exec.rpc_pool(), Arc::clone(&db)).with_page_size(2, 100);
+    exec.set_wallet_history(Arc::new(history)).await;
+
+    // 2023-11-14: a SOL transfer and a token transfer
+    rpc.set_block_time(1_700_000_000);
+    let client = RpcClient::new(rpc.url().to_string());
+    let friend = Pubkey::new_unique();
+    send_ix(&client, &rpc, &wallet, sol_transfer_ix(&wallet.pubkey(), &friend, LAMPORTS_PER_SOL / 4)).await;
+
+    let mint = Pubkey::new_unique();
+    let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    rpc.set_mint(mint, 6, 1_000_000_000);
+    rpc.set_token_account(source, mint, wallet.pubkey(), 5_000_000);
+    rpc.set_token_account(destination, mint, friend, 0);
+    let token_ix =
+        spl_token::instruction::transfer(&spl_token::ID, &source, &destination, &wallet.pubkey(), &[], 2_000_000)
+            .unwrap();
+    send_ix(&client, &rpc, &wallet, token_ix).await;
+
+    // Someone else's transaction never shows up
+    let stranger = Keypair::new();
+    rpc.set_balance(stranger.pubkey(), LAMPORTS_PER_SOL);
+    send_ix(&client, &rpc, &stranger, sol_transfer_ix(&stranger.pubkey(), &friend, 1)).await;
+
+    // 2023-11-24: a task created through the executor
+    rpc.set_block_time(1_700_000_000 + 10 * 86_400);
+    let created = exec
+        .execute_intent(&intent(
+            IntentAction::CreateTask,
+            json!({ "description": "Label the dataset", "reward_sol": 1.5, "wallet": "main" }),
+        ))
+        .await
+        .unwrap();
+    assert!(created.success, "{}", created.message);
+
+    let records = history_records(&exec, json!({ "wallet": "main" })).await;
+    assert_eq!(
+        kinds(&records),
+        vec![vec![HistoryEventKind::Agenc], vec![HistoryEventKind::TokenTransfer], vec![HistoryEventKind::SolTransfer]]
+    );
+    let task = &records[0].events[0];
+    assert_eq!(task.instruction.as_deref(), Some("create_task"));
+    assert_eq!(task.task, Some(derive_task_pda(1).0.to_string()));
+    assert_eq!(task.amount, Some(1_500_000_000));
+    let transfer = &records[1].events[0];
+    assert_eq!((transfer.from.clone(), transfer.to.clone()), (Some(wallet.pubkey().to_string()), Some(friend.to_string())));
+    assert_eq!((transfer.mint.clone(), transfer.amount), (Some(mint.to_string()), Some(2_000_000)));
+    assert_eq!(records[2].events[0].amount, Some(LAMPORTS_PER_SOL / 4));
+    // Pages of two: one full page, then a short one
+    assert_eq!(rpc.request_count("getSignaturesForAddress"), 2);
+    assert_eq!(rpc.request_count("getTransaction"), 3);
+
+    // Filters run over the cache without refetching anything
+    let transfers = history_records(&exec, json!({ "wallet": "main", "type": "transfer", "until": "2023-11-20" })).await;
+    assert_eq!(transfers.len(), 2);
+    let recent = history_records(&exec, json!({ "wallet": "main", "since": "2023-11-20" })).await;
+    assert_eq!(kinds(&recent), vec![vec![HistoryEventKind::Agenc]]);
+    assert!(history_records(&exec, json!({ "wallet": "main", "type": "swap" })).await.is_empty());
+    assert_eq!(rpc.request_count("getTransaction"), 3);
+
+    // Only transactions newer than the cache are fetched
+    send_ix(&client, &rpc, &wallet, sol_transfer_ix(&wallet.pubkey(), &friend, 1)).await;
+    let records = history_records(&exec, json!({ "wallet": "main", "limit": 2 })).await;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].events[0].amount, Some(1));
+    assert_eq!(rpc.request_count("getTransaction"), 4);
+    assert_eq!(db.read().await.as_ref().unwrap().stats().unwrap().total_history, 4);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wallet_history_resumes_capped_and_failed_syncs() {
+    let rpc = MockRpc::start();
+    let exec = SolanaExecutor::new(rpc.url(), "localnet");
+    let (_path, wallet) = write_keypair(&rpc, 5 * LAMPORTS_PER_SOL);
+    let db_path = TempPath::new("agenc-backfill", "redb");
+    let db = OperatorDb::open(Some(db_path.as_str())).unwrap();
+    let db = Arc::new(tokio::sync::RwLock::new(Some(db)));
+    // Pages of two, at most three signatures per sync
+    let history = WalletHistory::new(exec.rpc_pool(), Arc::clone(&db)).with_page_size(2, 3);
+
+    let client = RpcClient::new(rpc.url().to_string());
+    let friend = Pubkey::new_unique();
+    for lamports in 1..=5 {
+        send_ix(&client, &rpc, &wallet, sol_transfer_ix(&wallet.pubkey(), &friend, lamports)).await;
+    }
+    let amounts = |records: &[HistoryRecord]| records.iter().map(|r| r.events[0].amount.unwrap()).collect::<Vec<_>>();
+
+    // The first sync stops at the cap
+    assert_eq!(amounts(&history.sync(&wallet.pubkey()).await.unwrap()), vec![5, 4, 3]);
+
+    // The next fetches what is new, then resumes below the oldest fetched
+    send_ix(&client, &rpc, &wallet, sol_transfer_ix(&wallet.pubkey(), &friend, 6)).await;
+    assert_eq!(amounts(&history.sync(&wallet.pubkey()).await.unwrap()), vec![6, 2, 1]);
+    assert!(history.sync(&wallet.pubkey()).await.unwrap().is_empty());
+
+    // A failure on the second page keeps the first page and resumes after it
+    for lamports in 7..=9 {
+        send_ix(&client, &rpc, &wallet, sol_transfer_ix(&wallet.pubkey(), &friend, lamports)).await;
+    }
+    rpc.fail_next("getTransaction", 2, Fault::Pass);
+    rpc.fail_next("getTransaction", 1, Fault::HttpStatus(503));
+    assert!(history.sync(&wallet.pubkey()).await.is_err());
+    let network = operator_core::network::active_profile().name;
+    let cached = db.read().await.as_ref().unwrap().list_history(&network, &wallet.pubkey().to_string()).unwrap();
+    assert_eq!(amounts(&cached), vec![9, 8, 6, 5, 4, 3, 2, 1]);
+
+    let fetched = rpc.request_count("getTransaction");
+    assert_eq!(amounts(&history.sync(&wallet.pubkey()).await.unwrap()), vec![7]);
+    assert_eq!(rpc.request_count("getTransaction"), fetched + 1);
+    assert_eq!(db.read().await.as_ref().unwrap().stats().unwrap().total_history, 9);
+}
+
+// ============================================================================
+// Swap Previews
+// ============================================================================
+
+/// Applies System program transfers
+fn transfer_processor(tx: &VersionedTransaction, accounts: &mut Accounts) -> Result<(), String> {
+    let keys = tx.message.static_account_keys();
+    for ix in tx.message.instructions() {
+        if keys[ix.program_id_index as usize] != Pubkey::default() || ix.data[..4] != 2u32.to_le_bytes() {
+            continue;
+        }
+        let lamports = u64::from_le_bytes(ix.data[4..12].try_into().unwrap());
+        let (from, to) = (keys[ix.accounts[0] as usize], keys[ix.accounts[1] as usize]);
+        let payer = accounts.get_mut(&from).ok_or("sender has no account")?;
+        payer.lamports = payer.lamports.checked_sub(lamports).ok_or("insufficient lamports")?;
+        accounts.entry(to).or_insert_with(|| Account::new(0, 0, &Pubkey::default())).lamports += lamports;
+    }
+    Ok(())
+}
+
+/// Jupiter v6 stand-in on a loopback port. Quotes any pair; the "swap"
+/// transaction it builds sends `lamports` from the requesting wallet to
+/// `pool`. Returns the quote and swap URLs.
+fn fake_jupiter(pool: Pubkey, lamports: u64) -> (String, String) {
+    use base64::Engine as _;
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { return };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = header.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0u8; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response = if request_line.starts_with("GET") {
+                json!({
+                    "inAmount": lamports.to_string(),
+                    "outAmount": "1000000",
+                    "priceImpactPct": "0.01",
+                    "otherAmountThreshold": "990000",
+                    "swapMode": "ExactIn",
+                })
+            } else {
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let user = Pubkey::from_str(request["userPublicKey"].as_str().unwrap()).unwrap();
+                let message = Message::new(&[sol_transfer_ix(&user, &pool, lamports)], Some(&user));
+                let tx = VersionedTransaction::from(Transaction::new_unsigned(message));
+                let encoded = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&tx).unwrap());
+                json!({ "swapTransaction": encoded })
+            }
+            .to_string();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            );
+        }
+    });
+    (format!("{}/quote", base), format!("{}/swap", base))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_swap_confirmation_carries_preview_for_the_named_wallet() {
+    let rpc = MockRpc::start();
+    rpc.on_transaction(transfer_processor);
+    let exec = SolanaExecutor::new(rpc.url(), "localnet");
+    let (main_path, main_wallet) = write_keypair(&rpc, 5 * LAMPORTS_PER_SOL);
+    let (trading_path, trading) = write_keypair(&rpc, 2 * LAMPORTS_PER_SOL);
+    exec.load_wallet_profile("main", &main_path, None).await.unwrap();
+    exec.load_wallet_profile("trading", &trading_path, None).await.unwrap();
+
+    let pool = Pubkey::new_unique();
+    let (quote_url, swap_url) = fake_jupiter(pool, LAMPORTS_PER_SOL / 2);
+    let jupiter = JupiterSwapExecutor::with_pool(exec.rpc_pool()).with_endpoints(&quote_url, &swap_url);
+
+    // What execute_intent does for a swap that needs confirmation
+    let swap = intent(
+        IntentAction::SwapTokens,
+        json!({ "input_mint": "SOL", "output_mint": "USDC", "amount": LAMPORTS_PER_SOL / 2, "wallet": "trading" }),
+    );
+    let mut check = PolicyGate::new().check_and_issue(&swap);
+    assert!(check.allowed && check.requires_confirmation, "{}", check.reason);
+    let params: SwapParams = serde_json::from_value(swap.params.clone()).unwrap();
+    let wallet = exec.wallet_pubkey(swap.wallet()).await.unwrap();
+    check.preview = Some(jupiter.preview_swap_for(&params, &wallet).await.unwrap());
+
+    // The preview is the named wallet's, not the active one's
+    let preview = check.preview.unwrap();
+    let change = |owner: &Pubkey| preview.balance_changes.iter().find(|c| c.account == owner.to_string());
+    let spent = change(&trading.pubkey()).expect("trading wallet should be debited");
+    assert!(spent.delta() <= -0.5, "{:?}", spent);
+    assert_eq!(change(&pool).map(|c| c.delta()), Some(0.5));
+    assert!(change(&main_wallet.pubkey()).is_none());
+    assert!(preview.error.is_none(), "{:?}", preview.error);
+}
+
+// ============================================================================
+// Retry and Failover
+// ============================================================================
+
+fn fast_retries() -> RetryConfig {
+    RetryConfig {
+        base_delay_ms: 1,
+        max_delay_ms: 5,
+        poll_interval_ms: 5,
+        max_confirm_retries: 5,
+        jitter: false,
+        ..RetryConfig::default()
+    }
+}
+
+/// Signs a one-instruction transaction for whatever blockhash it is given
+fn tx_builder(payer: &Keypair) -> impl FnMut(Hash) -> anyhow::Result<VersionedTransaction> + '_ {
+    move |blockhash| {
+        let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[1], Vec::new());
+        let message = Message::new(&[ix], Some(&payer.pubkey()));
+        Ok(VersionedTransaction::from(Transaction::new(&[payer], message, blockhash)))
+    }
+}
+
+#[test]
+fn test_send_resigns_after_blockhash_not_found() {
+    let rpc = MockRpc::start();
+    rpc.fail_next("sendTransaction", 1, Fault::BlockhashNotFound);
+    let payer = Keypair::new();
+
+    let client = BlockingRpcClient::new(rpc.url().to_string());
+    let sender = TransactionSender::with_config(&client, fast_retries());
+    let result = sender.send_and_confirm_with_rebuild(tx_builder(&payer)).unwrap();
+
+    let SendResult::Confirmed(signature) = result else { panic!("Expected confirmation, got {:?}", result) };
+    assert!(rpc.has_landed(&signature));
+    assert_eq!(rpc.request_count("getLatestBlockhash"), 2);
+}
+
+#[test]
+fn test_expired_blockhash_is_rejected() {
+    let rpc = MockRpc::start();
+    let payer = Keypair::new();
+    let stale = rpc.latest_blockhash();
+    rpc.expire_blockhash();
+
+    let client = BlockingRpcClient::new(rpc.url().to_string());
+    let tx = tx_builder(&payer)(stale).unwrap();
+    let err = client.send_transaction(&tx).unwrap_err();
+    assert!(err.to_string().contains("Blockhash not found"), "{}", err);
+}
+
+#[test]
+fn test_send_backs_off_when_rate_limited() {
+    let rpc = MockRpc::start();
+    // The HTTP client absorbs five 429s itself; the sixth reaches the sender
+    rpc.fail_next("sendTransaction", 6, Fault::RateLimited);
+    let payer = Keypair::new();
+
+    let client = BlockingRpcClient::new(rpc.url().to_string());
+    let sender = TransactionSender::with_config(&client, fast_retries());
+    let result = sender.send_and_confirm_with_rebuild(tx_builder(&payer)).unwrap();
+
+    assert!(matches!(result, SendResult::Confirmed(_)), "{:?}", result);
+    assert_eq!(rpc.request_count("sendTransaction"), 7);
+}
+
+#[tokio::test]
+async fn test_pool_fails_over_to_healthy_endpoint() {
+    let primary = MockRpc::start();
+    let backup = MockRpc::start();
+    let wallet = Pubkey::new_unique();
+    primary.set_balance(wallet, 1);
+    backup.set_balance(wallet, 2);
+
+    let pool = Arc::new(
+        RpcPool::new(vec![RpcEndpoint::new(primary.url(), 10), RpcEndpoint::new(backup.url(), 1)]).unwrap(),
+    );
+    let balance = |pool: Arc<RpcPool>| async move {
+        pool.read(|rpc| async move { Ok(rpc.get_balance(&wallet).await?) }).await.unwrap()
+    };
+
+    assert_eq!(balance(pool.clone()).await, 1);
+
+    primary.fail_next("getBalance", 1, Fault::HttpStatus(503));
+    assert_eq!(balance(pool.clone()).await, 2);
+
+    // A JSON-RPC error is an answer, not an outage: no failover
+    primary.fail_next("getBalance", 1, Fault::RpcError { code: -32005, message: "Node is behind".into() });
+    assert!(pool.read(|rpc| async move { Ok(rpc.get_balance(&wallet).await?) }).await.is_err());
+    assert_eq!(backup.request_count("getBalance"), 1);
+}
+
+#[tokio::test]
+async fn test_rate_limited_task_and_skr_reads_fail_over() {
+    let primary = MockRpc::start();
+    let backup = MockRpc::start();
+    let pda = seed_task(&backup, 4, OnChainTaskState::Open, LAMPORTS_PER_SOL);
+    let holder = Pubkey::new_unique();
+    backup.set_mint(skr_mint(), 9, 1_000 * LAMPORTS_PER_SOL);
+    backup.set_token_account(get_skr_ata(&holder), skr_mint(), holder, 5);
+
+    // A 429 is an error, not a missing account. The HTTP client absorbs five
+    // 429s itself; the sixth reaches the caller.
+    let client = RpcClient::new(primary.url().to_string());
+    primary.fail_next("getAccountInfo", 6, Fault::RateLimited);
+    assert!(fetch_task_by_pda(&client, &pda).await.is_err());
+    primary.fail_next("getTokenAccountBalance", 6, Fault::RateLimited);
+    assert!(fetch_skr_balance(&client, &holder).await.is_err());
+
+    // ...so the pool moves on to the next endpoint, and prefers it after
+    let pool = RpcPool::new(vec![RpcEndpoint::new(primary.url(), 10), RpcEndpoint::new(backup.url(), 1)]).unwrap();
+    primary.fail_next("getAccountInfo", 6, Fault::RateLimited);
+    let task = pool.read(|rpc| async move { fetch_task_by_pda(&rpc, &pda).await }).await.unwrap();
+    assert_eq!(task.map(|t| t.task_id), Some(4));
+    assert_eq!(pool.read(|rpc| async move { fetch_skr_balance(&rpc, &holder).await }).await.unwrap(), 5);
+
+    // Accounts that do not exist still read as none
+    assert!(fetch_task_by_pda(&client, &derive_task_pda(99).0).await.unwrap().is_none());
+    assert_eq!(fetch_skr_balance(&client, &holder).await.unwrap(), 0);
+}
@@ -0,0 +1,871 @@
+//! ============================================================================
+//! OPERATOR-TEST-SUPPORT: In-Process Mock Solana RPC
+//! ============================================================================
+//! A JSON-RPC server on a loopback port that answers the calls operator-core
+//! makes from scripted state, so chain flows can be tested offline:
+//! - Accounts (AgenC program accounts, token accounts, mints, wallets) are set
+//!   directly by the test
+//! - Sent transactions are recorded and handed to a processor closure that
+//!   applies their effect to the accounts; changes to accounts the
+//!   transaction does not mark writable are rejected, as by the runtime
+//! - Landed transactions are kept with their balance changes and served by
+//!   getSignaturesForAddress and getTransaction
+//! - The current blockhash can be expired to force a re-sign
+//! - Faults can be queued per method: HTTP 429, other HTTP statuses, JSON-RPC
+//!   errors and "Blockhash not found" preflight failures
+//!
+//! Point any `RpcClient` (or an `RpcPool`) at `MockRpc::url()`. The blocking
+//! client used for sends needs a multi-threaded tokio runtime in async tests.
+//!
+//! `TempPath` hands out scratch files (test databases) that are removed when
+//! dropped.
+//! ============================================================================
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use spl_token::solana_program::program_option::COption;
+use spl_token::solana_program::program_pack::Pack;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use tiny_http::{Header, Response, Server};
+
+/// Account state the processor reads and writes
+pub type Accounts = HashMap<Pubkey, Account>;
+
+/// Applies a sent (or simulated) transaction to the accounts. An `Err` is a
+/// program log line; the transaction fails preflight with it and nothing
+/// it changed is kept.
+pub type Processor = dyn Fn(&VersionedTransaction, &mut Accounts) -> Result<(), String> + Send + Sync;
+
+/// Compute units simulations report unless set otherwise
+const DEFAULT_UNITS_CONSUMED: u64 = 20_000;
+
+/// Lamports charged per signature by getFeeForMessage
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Blocks a blockhash stays valid for, as reported by getLatestBlockhash
+const BLOCKHASH_LIFETIME: u64 = 150;
+
+/// JSON-RPC code for a failed sendTransaction preflight
+const PREFLIGHT_FAILURE: i64 = -32002;
+
+/// Block time of landed transactions unless set otherwise
+const DEFAULT_BLOCK_TIME: i64 = 1_700_000_000;
+
+/// An injected failure, answered instead of the next call to a method
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// HTTP 429 with `Retry-After: 0`
+    RateLimited,
+    /// Any other HTTP status, with an empty body
+    HttpStatus(u16),
+    /// A JSON-RPC error object
+    RpcError { code: i64, message: String },
+    /// A sendTransaction preflight failure for an unknown blockhash
+    BlockhashNotFound,
+    /// No failure: answered normally. Queue ahead of a fault to let the
+    /// first calls through.
+    Pass,
+}
+
+/// JSON-RPC error: code, message, optional data
+type RpcError = (i64, String, Option<Value>);
+
+/// A landed transaction and the balances around it
+struct Landed {
+    tx: VersionedTransaction,
+    slot: u64,
+    block_time: i64,
+    pre_balances: Vec<u64>,
+    post_balances: Vec<u64>,
+    pre_token_balances: Vec<Value>,
+    post_token_balances: Vec<Value>,
+}
+
+struct State {
+    accounts: Accounts,
+    slot: u64,
+    blockhash: Hash,
+    valid_blockhashes: HashSet<Hash>,
+    landed: HashSet<Signature>,
+    sent: Vec<VersionedTransaction>,
+    /// Landed transactions, oldest first
+    history: Vec<Landed>,
+    block_time: i64,
+    requests: Vec<String>,
+    faults: HashMap<String, VecDeque<Fault>>,
+    units_consumed: u64,
+    prioritization_fees: Vec<u64>,
+    processor: Option<Arc<Processor>>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        let blockhash = Hash::new_unique();
+        Self {
+            accounts: Accounts::new(),
+            slot: 1,
+            blockhash,
+            valid_blockhashes: HashSet::from([blockhash]),
+            landed: HashSet::new(),
+            sent: Vec::new(),
+            history: Vec::new(),
+            block_time: DEFAULT_BLOCK_TIME,
+            requests: Vec::new(),
+            faults: HashMap::new(),
+            units_consumed: DEFAULT_UNITS_CONSUMED,
+            prioritization_fees: Vec::new(),
+            processor: None,
+        }
+    }
+}
+
+/// A running mock RPC node. The server stops when this is dropped.
+pub struct MockRpc {
+    url: String,
+    state: Arc<Mutex<State>>,
+    server: Arc<Server>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockRpc {
+    /// Start a server on a free loopback port
+    pub fn start() -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("Failed to bind mock RPC server"));
+        let url = format!(
+            "http://{}",
+            server.server_addr().to_ip().expect("Mock RPC server has no IP address")
+        );
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let thread = {
+            let server = server.clone();
+            let state = state.clone();
+            std::thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    let mut body = String::new();
+                    if request.as_reader().read_to_string(&mut body).is_err() {
+                        continue;
+                    }
+                    let _ = request.respond(handle(&state, &body));
+                }
+            })
+        };
+
+        Self { url, state, server, thread: Some(thread) }
+    }
+
+    /// Endpoint URL to hand to an RPC client
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        lock(&self.state)
+    }
+
+    /// Create or replace an account
+    pub fn set_account(&self, address: Pubkey, account: Account) {
+        self.state().accounts.insert(address, account);
+    }
+
+    /// Current state of an account
+    pub fn account(&self, address: &Pubkey) -> Option<Account> {
+        self.state().accounts.get(address).cloned()
+    }
+
+    /// Give a system-owned wallet `lamports`
+    pub fn set_balance(&self, address: Pubkey, lamports: u64) {
+        let mut state = self.state();
+        let account = state
+            .accounts
+            .entry(address)
+            .or_insert_with(|| Account::new(0, 0, &solana_sdk::system_program::id()));
+        account.lamports = lamports;
+    }
+
+    /// Create an initialized SPL mint
+    pub fn set_mint(&self, mint: Pubkey, decimals: u8, supply: u64) {
+        let state = spl_token::state::Mint {
+            mint_authority: COption::None,
+            supply,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint::pack(state, &mut data).expect("Mint packs");
+        self.set_account(mint, token_program_account(data));
+    }
+
+    /// Create an initialized SPL token account holding `amount` of `mint`.
+    /// Balance lookups also need the mint (`set_mint`), as on a real node.
+    pub fn set_token_account(&self, address: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+        let state = spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account::pack(state, &mut data).expect("Token account packs");
+        self.set_account(address, token_program_account(data));
+    }
+
+    /// Apply sent and simulated transactions with `processor`. Without one,
+    /// transactions land without changing any account.
+    pub fn on_transaction(
+        &self,
+        processor: impl Fn(&VersionedTransaction, &mut Accounts) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.state().processor = Some(Arc::new(processor));
+    }
+
+    /// Answer the next `times` calls to `method` with `fault`
+    pub fn fail_next(&self, method: &str, times: usize, fault: Fault) {
+        self.state()
+            .faults
+            .entry(method.to_string())
+            .or_default()
+            .extend(std::iter::repeat_n(fault, times));
+    }
+
+    /// The blockhash getLatestBlockhash currently returns
+    pub fn latest_blockhash(&self) -> Hash {
+        self.state().blockhash
+    }
+
+    /// Invalidate every blockhash handed out so far and start a new one
+    pub fn expire_blockhash(&self) -> Hash {
+        let mut state = self.state();
+        state.blockhash = Hash::new_unique();
+        state.valid_blockhashes = HashSet::from([state.blockhash]);
+        state.slot += BLOCKHASH_LIFETIME;
+        state.blockhash
+    }
+
+    /// Move the node's slot forward
+    pub fn advance_slot(&self, slots: u64) {
+        self.state().slot += slots;
+    }
+
+    /// Compute units reported by simulateTransaction
+    pub fn set_units_consumed(&self, units: u64) {
+        self.state().units_consumed = units;
+    }
+
+    /// Block time recorded for transactions that land from now on
+    pub fn set_block_time(&self, unix_seconds: i64) {
+        self.state().block_time = unix_seconds;
+    }
+
+    /// Fees returned by getRecentPrioritizationFees, one per recent slot
+    pub fn set_prioritization_fees(&self, fees: Vec<u64>) {
+        self.state().prioritization_fees = fees;
+    }
+
+    /// Every transaction passed to sendTransaction, landed or not
+    pub fn sent_transactions(&self) -> Vec<VersionedTransaction> {
+        self.state().sent.clone()
+    }
+
+    /// Whether a transaction with this signature landed
+    pub fn has_landed(&self, signature: &Signature) -> bool {
+        self.state().landed.contains(signature)
+    }
+
+    /// Methods called so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    /// Calls made so far to `method`
+    pub fn request_count(&self, method: &str) -> usize {
+        self.state().requests.iter().filter(|m| *m == method).count()
+    }
+}
+
+impl Drop for MockRpc {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// ============================================================================
+// Scratch Files
+// ============================================================================
+
+/// A unique file path in the temp dir, removed when dropped
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    /// `<prefix>-<uuid>.<extension>`; nothing is created until the caller
+    /// opens it
+    pub fn new(prefix: &str, extension: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("{}-{}.{}", prefix, uuid::Uuid::new_v4(), extension)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// The path as UTF-8, as `OperatorDb::open` takes it
+    pub fn as_str(&self) -> &str {
+        self.0.to_str().expect("temp dir path is UTF-8")
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn token_program_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: rent_exempt_minimum(data.len()),
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: u64::MAX,
+    }
+}
+
+fn rent_exempt_minimum(size: usize) -> u64 {
+    (128 + size as u64) * 3_480 * 2
+}
+
+// ============================================================================
+// Request Handling
+// ============================================================================
+
+type HttpResponse = Response<std::io::Cursor<Vec<u8>>>;
+
+fn handle(state: &Mutex<State>, body: &str) -> HttpResponse {
+    let request: Value = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(e) => return json_response(&rpc_error(Value::Null, (-32700, format!("Parse error: {}", e), None))),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request.get("method").and_then(Value::as_str).unwrap_or_default().to_string();
+    let params = request.get("params").cloned().unwrap_or_else(|| json!([]));
+
+    let mut state = lock(state);
+    state.requests.push(method.clone());
+
+    let fault = state.faults.get_mut(&method).and_then(VecDeque::pop_front);
+    let result = match fault {
+        Some(Fault::RateLimited) => {
+            return Response::from_string("Too many requests")
+                .with_status_code(429)
+                .with_header(Header::from_bytes("Retry-After", "0").expect("Valid header"));
+        }
+        Some(Fault::HttpStatus(code)) => return Response::from_string("").with_status_code(code),
+        Some(Fault::RpcError { code, message }) => Err((code, message, None)),
+        Some(Fault::BlockhashNotFound) => Err(preflight_failure("Blockhash not found", json!("BlockhashNotFound"), Vec::new())),
+        Some(Fault::Pass) | None => dispatch(&mut state, &method, &params),
+    };
+
+    json_response(&match result {
+        Ok(value) => json!({ "jsonrpc": "2.0", "id": id, "result": value }),
+        Err(error) => rpc_error(id, error),
+    })
+}
+
+fn json_response(value: &Value) -> HttpResponse {
+    Response::from_data(value.to_string().into_bytes())
+        .with_header(Header::from_bytes("Content-Type", "application/json").expect("Valid header"))
+}
+
+fn rpc_error(id: Value, (code, message, data): RpcError) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+fn preflight_failure(message: &str, err: Value, logs: Vec<String>) -> RpcError {
+    (
+        PREFLIGHT_FAILURE,
+        format!("Transaction simulation failed: {}", message),
+        Some(json!({
+            "err": err,
+            "logs": logs,
+            "accounts": null,
+            "unitsConsumed": 0,
+            "returnData": null,
+            "innerInstructions": null,
+            "replacementBlockhash": null,
+        })),
+    )
+}
+
+fn invalid_param(message: impl Into<String>) -> RpcError {
+    (-32602, format!("Invalid param: {}", message.into()), None)
+}
+
+fn dispatch(state: &mut State, method: &str, params: &Value) -> Result<Value, RpcError> {
+    let slot = state.slot;
+    let context = |value: Value| json!({ "context": { "slot": slot, "apiVersion": "2.0.0" }, "value": value });
+
+    Ok(match method {
+        "getHealth" => json!("ok"),
+        "getVersion" => json!({ "solana-core": "2.0.0", "feature-set": 0 }),
+        "getSlot" => json!(slot),
+        "getBlockHeight" => json!(slot),
+        "getMinimumBalanceForRentExemption" => {
+            json!(rent_exempt_minimum(params[0].as_u64().unwrap_or(0) as usize))
+        }
+        "getLatestBlockhash" => context(json!({
+            "blockhash": state.blockhash.to_string(),
+            "lastValidBlockHeight": slot + BLOCKHASH_LIFETIME,
+        })),
+        "isBlockhashValid" => {
+            let hash = Hash::from_str(params[0].as_str().unwrap_or_default())
+                .map_err(|_| invalid_param("blockhash"))?;
+            context(json!(state.valid_blockhashes.contains(&hash)))
+        }
+        "getBalance" => {
+            let address = pubkey_param(&params[0])?;
+            context(json!(state.accounts.get(&address).map_or(0, |a| a.lamports)))
+        }
+        "getAccountInfo" => {
+            let address = pubkey_param(&params[0])?;
+            let slice = data_slice(&params[1]);
+            context(state.accounts.get(&address).map_or(Value::Null, |a| ui_account(a, slice)))
+        }
+        "getMultipleAccounts" => {
+            let slice = data_slice(&params[1]);
+            let mut accounts = Vec::new();
+            for address in params[0].as_array().cloned().unwrap_or_default() {
+                let address = pubkey_param(&address)?;
+                accounts.push(state.accounts.get(&address).map_or(Value::Null, |a| ui_account(a, slice)));
+            }
+            context(Value::Array(accounts))
+        }
+        "getProgramAccounts" => {
+            let program = pubkey_param(&params[0])?;
+            let config = &params[1];
+            let filters = config["filters"].as_array().cloned().unwrap_or_default();
+            let slice = data_slice(config);
+
+            let mut matches: Vec<(&Pubkey, &Account)> = state
+                .accounts
+                .iter()
+                .filter(|(_, a)| a.owner == program)
+                .filter(|(_, a)| filters.iter().all(|f| filter_matches(f, &a.data)))
+                .collect();
+            matches.sort_by_key(|(address, _)| **address);
+            let list: Vec<Value> = matches
+                .into_iter()
+                .map(|(address, a)| json!({ "pubkey": address.to_string(), "account": ui_account(a, slice) }))
+                .collect();
+            if config["withContext"].as_bool().unwrap_or(false) {
+                context(Value::Array(list))
+            } else {
+                Value::Array(list)
+            }
+        }
+        "getTokenAccountBalance" => {
+            let address = pubkey_param(&params[0])?;
+            let account = state
+                .accounts
+                .get(&address)
+                .filter(|a| a.owner == spl_token::id())
+                .ok_or_else(|| invalid_param("could not find account"))?;
+            let token = spl_token::state::Account::unpack(&account.data)
+                .map_err(|_| invalid_param("not a Token account"))?;
+            let mint = mint_state(state, &token.mint)?;
+            context(ui_token_amount(token.amount, mint.decimals))
+        }
+        "getTokenSupply" => {
+            let mint = mint_state(state, &pubkey_param(&params[0])?)?;
+            context(ui_token_amount(mint.supply, mint.decimals))
+        }
+        "getFeeForMessage" => {
+            let message = BASE64
+                .decode(params[0].as_str().unwrap_or_default())
+                .map_err(|_| invalid_param("message"))?;
+            // v0 messages carry a version prefix before the header
+            let signatures = match message.first() {
+                Some(prefix) if prefix & 0x80 != 0 => message.get(1),
+                first => first,
+            };
+            context(json!(*signatures.unwrap_or(&1) as u64 * LAMPORTS_PER_SIGNATURE))
+        }
+        "getRecentPrioritizationFees" => Value::Array(
+            state
+                .prioritization_fees
+                .iter()
+                .enumerate()
+                .map(|(i, fee)| json!({ "slot": slot.saturating_sub(i as u64), "prioritizationFee": fee }))
+                .collect(),
+        ),
+        "getSignatureStatuses" => {
+            let statuses = params[0]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .iter()
+                .map(|sig| {
+                    let landed = sig
+                        .as_str()
+                        .and_then(|s| Signature::from_str(s).ok())
+                        .is_some_and(|s| state.landed.contains(&s));
+                    if landed {
+                        json!({
+                            "slot": slot,
+                            "confirmations": null,
+                            "err": null,
+                            "status": { "Ok": null },
+                            "confirmationStatus": "finalized",
+                        })
+                    } else {
+                        Value::Null
+                    }
+                })
+                .collect();
+            context(Value::Array(statuses))
+        }
+        "simulateTransaction" => {
+            let tx = transaction_param(&params[0])?;
+            let config = &params[1];
+            let (accounts, outcome) = match &state.processor {
+                Some(processor) => match process(processor.as_ref(), &tx, &state.accounts) {
+                    Ok(accounts) => (accounts, Ok(())),
+                    Err(log) => (state.accounts.clone(), Err(log)),
+                },
+                None => (state.accounts.clone(), Ok(())),
+            };
+            let (err, logs) = match &outcome {
+                Ok(()) => (Value::Null, Vec::new()),
+                Err(log) => (custom_program_error(), vec![log.clone()]),
+            };
+            let post: Option<Vec<Value>> = config["accounts"]["addresses"].as_array().map(|addresses| {
+                addresses
+                    .iter()
+                    .map(|address| {
+                        pubkey_param(address)
+                            .ok()
+                            .and_then(|address| accounts.get(&address))
+                            .map_or(Value::Null, |a| ui_account(a, None))
+                    })
+                    .collect()
+            });
+            context(json!({
+                "err": err,
+                "logs": logs,
+                "accounts": post,
+                "unitsConsumed": state.units_consumed,
+                "returnData": null,
+                "innerInstructions": null,
+                "replacementBlockhash": null,
+            }))
+        }
+        "sendTransaction" => {
+            let tx = transaction_param(&params[0])?;
+            state.sent.push(tx.clone());
+
+            if !state.valid_blockhashes.contains(tx.message.recent_blockhash()) {
+                return Err(preflight_failure("Blockhash not found", json!("BlockhashNotFound"), Vec::new()));
+            }
+            if tx.verify_with_results().iter().any(|ok| !ok) {
+                return Err((-32003, "Transaction signature verification failure".into(), None));
+            }
+
+            let accounts = match &state.processor {
+                Some(processor) => process(processor.as_ref(), &tx, &state.accounts).map_err(|log| {
+                    preflight_failure(
+                        "Error processing Instruction 0: custom program error: 0x0",
+                        custom_program_error(),
+                        vec![log],
+                    )
+                })?,
+                None => state.accounts.clone(),
+            };
+            let keys = tx.message.static_account_keys();
+            let landed = Landed {
+                slot: state.slot,
+                block_time: state.block_time,
+                pre_balances: balances(&state.accounts, keys),
+                post_balances: balances(&accounts, keys),
+                pre_token_balances: token_balances(&state.accounts, keys),
+                post_token_balances: token_balances(&accounts, keys),
+                tx: tx.clone(),
+            };
+            state.accounts = accounts;
+            state.history.push(landed);
+            state.landed.insert(tx.signatures[0]);
+            state.slot += 1;
+            json!(tx.signatures[0].to_string())
+        }
+        "getSignaturesForAddress" => {
+            let address = pubkey_param(&params[0])?;
+            let config = &params[1];
+            let signature = |key: &str| config[key].as_str().and_then(|s| Signature::from_str(s).ok());
+            let (before, until) = (signature("before"), signature("until"));
+            let limit = config["limit"].as_u64().unwrap_or(1_000) as usize;
+
+            // Newest first, strictly after `before` and stopping at `until`
+            let mentioning = state
+                .history
+                .iter()
+                .rev()
+                .filter(|l| l.tx.message.static_account_keys().contains(&address));
+            let older = mentioning
+                .skip_while(|l| before.is_some_and(|b| l.tx.signatures[0] != b))
+                .skip(usize::from(before.is_some()));
+            Value::Array(
+                older
+                    .take_while(|l| until != Some(l.tx.signatures[0]))
+                    .take(limit)
+                    .map(|l| {
+                        json!({
+                            "signature": l.tx.signatures[0].to_string(),
+                            "slot": l.slot,
+                            "err": null,
+                            "memo": null,
+                            "blockTime": l.block_time,
+                            "confirmationStatus": "finalized",
+                        })
+                    })
+                    .collect(),
+            )
+        }
+        "getTransaction" => {
+            let signature = params[0]
+                .as_str()
+                .and_then(|s| Signature::from_str(s).ok())
+                .ok_or_else(|| invalid_param("signature"))?;
+            match state.history.iter().find(|l| l.tx.signatures[0] == signature) {
+                Some(l) => json!({
+                    "slot": l.slot,
+                    "blockTime": l.block_time,
+                    "transaction": [BASE64.encode(bincode::serialize(&l.tx).unwrap_or_default()), "base64"],
+                    "meta": {
+                        "err": null,
+                        "status": { "Ok": null },
+                        "fee": l.tx.signatures.len() as u64 * LAMPORTS_PER_SIGNATURE,
+                        "preBalances": l.pre_balances,
+                        "postBalances": l.post_balances,
+                        "innerInstructions": [],
+                        "logMessages": [],
+                        "preTokenBalances": l.pre_token_balances,
+                        "postTokenBalances": l.post_token_balances,
+                        "rewards": [],
+                        "loadedAddresses": { "writable": [], "readonly": [] },
+                    },
+                    "version": match l.tx.message {
+                        solana_sdk::message::VersionedMessage::Legacy(_) => json!("legacy"),
+                        solana_sdk::message::VersionedMessage::V0(_) => json!(0),
+                    },
+                }),
+                None => Value::Null,
+            }
+        }
+        other => return Err((-32601, format!("Method not found: {}", other), None)),
+    })
+}
+
+/// Run `processor` on a copy of `accounts`. Like the runtime, reject any
+/// change to an account the transaction does not mark writable.
+fn process(processor: &Processor, tx: &VersionedTransaction, accounts: &Accounts) -> Result<Accounts, String> {
+    let mut after = accounts.clone();
+    processor(tx, &mut after)?;
+
+    let keys = tx.message.static_account_keys();
+    let writable = |key: &Pubkey| {
+        keys.iter().position(|k| k == key).is_some_and(|i| tx.message.is_maybe_writable(i, None))
+    };
+    let changed = accounts.keys().chain(after.keys()).find(|key| accounts.get(key) != after.get(key) && !writable(key));
+    match changed {
+        Some(key) => Err(format!("Program log: Instruction modified data of a read-only account {}", key)),
+        None => Ok(after),
+    }
+}
+
+/// Lamports of each account key, zero for missing accounts
+fn balances(accounts: &Accounts, keys: &[Pubkey]) -> Vec<u64> {
+    keys.iter().map(|key| accounts.get(key).map_or(0, |a| a.lamports)).collect()
+}
+
+/// getTransaction token balances of the SPL token accounts among `keys`
+fn token_balances(accounts: &Accounts, keys: &[Pubkey]) -> Vec<Value> {
+    keys.iter()
+        .enumerate()
+        .filter_map(|(index, key)| {
+            let account = accounts.get(key).filter(|a| a.owner == spl_token::id())?;
+            let token = spl_token::state::Account::unpack(&account.data).ok()?;
+            let decimals = accounts
+                .get(&token.mint)
+                .and_then(|mint| spl_token::state::Mint::unpack(&mint.data).ok())
+                .map_or(0, |mint| mint.decimals);
+            Some(json!({
+                "accountIndex": index,
+                "mint": token.mint.to_string(),
+                "owner": token.owner.to_string(),
+                "programId": spl_token::id().to_string(),
+                "uiTokenAmount": ui_token_amount(token.amount, decimals),
+            }))
+        })
+        .collect()
+}
+
+fn custom_program_error() -> Value {
+    json!({ "InstructionError": [0, { "Custom": 0 }] })
+}
+
+fn pubkey_param(value: &Value) -> Result<Pubkey, RpcError> {
+    value
+        .as_str()
+        .and_then(|s| Pubkey::from_str(s).ok())
+        .ok_or_else(|| invalid_param(format!("invalid pubkey {}", value)))
+}
+
+fn transaction_param(value: &Value) -> Result<VersionedTransaction, RpcError> {
+    let bytes = BASE64
+        .decode(value.as_str().unwrap_or_default())
+        .map_err(|e| invalid_param(format!("base64 transaction: {}", e)))?;
+    bincode::deserialize(&bytes).map_err(|e| invalid_param(format!("transaction: {}", e)))
+}
+
+fn mint_state(state: &State, mint: &Pubkey) -> Result<spl_token::state::Mint, RpcError> {
+    state
+        .accounts
+        .get(mint)
+        .and_then(|a| spl_token::state::Mint::unpack(&a.data).ok())
+        .ok_or_else(|| invalid_param("mint could not be unpacked"))
+}
+
+/// `dataSlice` from a request config: (offset, length)
+fn data_slice(config: &Value) -> Option<(usize, usize)> {
+    let slice = config.get("dataSlice")?;
+    Some((slice["offset"].as_u64()? as usize, slice["length"].as_u64()? as usize))
+}
+
+fn ui_account(account: &Account, slice: Option<(usize, usize)>) -> Value {
+    let data = match slice {
+        Some((offset, length)) => {
+            let start = offset.min(account.data.len());
+            &account.data[start..(start + length).min(account.data.len())]
+        }
+        None => &account.data[..],
+    };
+    json!({
+        "lamports": account.lamports,
+        "owner": account.owner.to_string(),
+        "data": [BASE64.encode(data), "base64"],
+        "executable": account.executable,
+        "rentEpoch": account.rent_epoch,
+        "space": account.data.len(),
+    })
+}
+
+fn ui_token_amount(amount: u64, decimals: u8) -> Value {
+    let ui_amount = amount as f64 / 10f64.powi(decimals as i32);
+    let mut ui_string = format!("{:.*}", decimals as usize, ui_amount);
+    if ui_string.contains('.') {
+        ui_string = ui_string.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    json!({
+        "amount": amount.to_string(),
+        "decimals": decimals,
+        "uiAmount": ui_amount,
+        "uiAmountString": ui_string,
+    })
+}
+
+/// Whether account data passes a getProgramAccounts filter
+fn filter_matches(filter: &Value, data: &[u8]) -> bool {
+    if let Some(size) = filter.get("dataSize").and_then(Value::as_u64) {
+        return data.len() as u64 == size;
+    }
+    let Some(memcmp) = filter.get("memcmp") else { return false };
+    let offset = memcmp["offset"].as_u64().unwrap_or(0) as usize;
+    let encoded = memcmp["bytes"].as_str().unwrap_or_default();
+    let bytes = match memcmp["encoding"].as_str() {
+        Some("base64") => BASE64.decode(encoded).ok(),
+        _ => bs58::decode(encoded).into_vec().ok(),
+    };
+    bytes.is_some_and(|bytes| data.get(offset..offset + bytes.len()) == Some(&bytes[..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(rpc: &MockRpc, method: &str, params: Value) -> Value {
+        use std::io::{Read, Write};
+
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string();
+        let mut stream = std::net::TcpStream::connect(rpc.url().trim_start_matches("http://")).unwrap();
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn test_memcmp_filters() {
+        let data = [1u8, 2, 3, 4];
+        let base58 = json!({ "memcmp": { "offset": 1, "bytes": bs58::encode([2u8, 3]).into_string() } });
+        let base64 = json!({ "memcmp": { "offset": 2, "bytes": BASE64.encode([3u8, 4]), "encoding": "base64" } });
+        let past_end = json!({ "memcmp": { "offset": 3, "bytes": bs58::encode([4u8, 5]).into_string() } });
+        assert!(filter_matches(&base58, &data));
+        assert!(filter_matches(&base64, &data));
+        assert!(!filter_matches(&past_end, &data));
+        assert!(filter_matches(&json!({ "dataSize": 4 }), &data));
+    }
+
+    #[test]
+    fn test_ui_token_amount() {
+        assert_eq!(ui_token_amount(1_500_000, 6)["uiAmountString"], "1.5");
+        assert_eq!(ui_token_amount(42, 0)["uiAmountString"], "42");
+    }
+
+    #[test]
+    fn test_temp_path_is_unique_and_removed_on_drop() {
+        let first = TempPath::new("agenc-test-support", "db");
+        let second = TempPath::new("agenc-test-support", "db");
+        assert_ne!(first.path(), second.path());
+
+        std::fs::write(first.path(), b"scratch").unwrap();
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_faults_are_consumed_in_order() {
+        let rpc = MockRpc::start();
+        rpc.fail_next("getSlot", 1, Fault::RpcError { code: -32005, message: "Node is behind".into() });
+
+        assert_eq!(call(&rpc, "getSlot", json!([]))["error"]["code"], -32005);
+        assert_eq!(call(&rpc, "getSlot", json!([]))["result"], 1);
+        assert_eq!(rpc.request_count("getSlot"), 2);
+    }
+}
@@ -0,0 +1,114 @@
+#!/usr/bin/env bash
+# ============================================================================
+# update-agenc-idl.sh — Pull the deployed AgenC IDL and devnet golden captures
+# ============================================================================
+# Replaces crates/operator-core/src/agenc_idl.json with the IDL the deployed
+# program published on-chain, stamped with where it came from, and records
+# real transactions as golden captures for the codec tests:
+#
+#   crates/operator-core/tests/fixtures/agenc/<signature>.json
+#     - every AgenC instruction in the transaction (data + account keys)
+#     - the current data of every program-owned account it touched
+#
+# The captures are checked by `test_devnet_captures_match_idl` (ignored until
+# the first capture is committed). Pick transactions that cover create_task,
+# claim_task, complete_task, cancel_task and approve_completion.
+#
+# Requires: anchor (0.30+), solana, curl, jq
+#
+# Usage:
+#   ./scripts/update-agenc-idl.sh devnet
+#   ./scripts/update-agenc-idl.sh devnet <tx-signature>...
+# ============================================================================
+
+set -euo pipefail
+
+CLUSTER="${1:-devnet}"
+shift || true
+PROGRAM_ID="${AGENC_PROGRAM_ID:-EopUaCV2svxj9j4hd7KjbrWfdjkspmm2BCBe7jGpKzKZ}"
+RPC_URL="${AGENC_RPC_URL:-https://api.${CLUSTER}.solana.com}"
+
+IDL_PATH="crates/operator-core/src/agenc_idl.json"
+FIXTURES="crates/operator-core/tests/fixtures/agenc"
+
+for tool in anchor solana curl jq; do
+  if ! command -v "$tool" >/dev/null; then
+    echo "Error: $tool is required"
+    exit 1
+  fi
+done
+
+rpc() {
+  curl -sf "$RPC_URL" -H 'content-type: application/json' \
+    -d "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"$1\",\"params\":$2}"
+}
+
+# ----------------------------------------------------------------------------
+# IDL
+# ----------------------------------------------------------------------------
+
+echo "Fetching the on-chain IDL of $PROGRAM_ID ($CLUSTER)"
+RAW_IDL=$(mktemp)
+CONVERTED_IDL=$(mktemp)
+trap 'rm -f "$RAW_IDL" "$CONVERTED_IDL"' EXIT
+anchor idl fetch "$PROGRAM_ID" --provider.cluster "$CLUSTER" > "$RAW_IDL"
+
+# Pre-0.30 IDLs carry no discriminators; the codec needs the new spec
+if ! jq -e '.instructions[0].discriminator' "$RAW_IDL" >/dev/null; then
+  anchor idl convert "$RAW_IDL" -o "$CONVERTED_IDL"
+  mv "$CONVERTED_IDL" "$RAW_IDL"
+fi
+
+DEPLOY_SLOT=$(solana program show "$PROGRAM_ID" --url "$RPC_URL" --output json | jq '.lastDeploySlot')
+
+jq --arg cluster "$CLUSTER" \
+   --argjson slot "$DEPLOY_SLOT" \
+   --arg fetched "$(date -u +%Y-%m-%dT%H:%M:%SZ)" \
+   '.metadata.source = {
+      kind: "anchor idl fetch",
+      cluster: $cluster,
+      program_last_deploy_slot: $slot,
+      fetched_at: $fetched
+    }' "$RAW_IDL" > "$IDL_PATH"
+
+echo "Wrote $IDL_PATH (program version $(jq -r '.metadata.version' "$IDL_PATH"), deployed at slot $DEPLOY_SLOT)"
+
+# ----------------------------------------------------------------------------
+# Golden captures
+# ----------------------------------------------------------------------------
+
+mkdir -p "$FIXTURES"
+
+for SIGNATURE in "$@"; do
+  TX=$(rpc getTransaction "[\"$SIGNATURE\",{\"encoding\":\"json\",\"maxSupportedTransactionVersion\":0}]" | jq '.result')
+  if [ "$TX" = "null" ]; then
+    echo "Error: transaction $SIGNATURE not found on $CLUSTER"
+    exit 1
+  fi
+
+  INSTRUCTIONS=$(echo "$TX" | jq --arg program "$PROGRAM_ID" '
+    .transaction.message.accountKeys as $keys
+    | [.transaction.message.instructions[]
+       | select($keys[.programIdIndex] == $program)
+       | { data_base58: .data, accounts: [.accounts[] | $keys[.]] }]')
+
+  ACCOUNTS="[]"
+  for KEY in $(echo "$INSTRUCTIONS" | jq -r '[.[].accounts[]] | unique | .[]'); do
+    INFO=$(rpc getAccountInfo "[\"$KEY\",{\"encoding\":\"base64\"}]" | jq '.result.value')
+    if [ "$INFO" != "null" ] && [ "$(echo "$INFO" | jq -r '.owner')" = "$PROGRAM_ID" ]; then
+      ACCOUNTS=$(echo "$ACCOUNTS" | jq --arg key "$KEY" --argjson info "$INFO" \
+        '. + [{ pubkey: $key, data_base64: $info.data[0] }]')
+    fi
+  done
+
+  jq -n --arg cluster "$CLUSTER" \
+        --arg signature "$SIGNATURE" \
+        --argjson slot "$(echo "$TX" | jq '.slot')" \
+        --argjson instructions "$INSTRUCTIONS" \
+        --argjson accounts "$ACCOUNTS" \
+        '{ cluster: $cluster, signature: $signature, slot: $slot,
+           instructions: $instructions, accounts: $accounts }' \
+    > "$FIXTURES/$SIGNATURE.json"
+
+  echo "Captured $SIGNATURE: $(echo "$INSTRUCTIONS" | jq length) instruction(s), $(echo "$ACCOUNTS" | jq length) account(s)"
+done
@@ -8,7 +8,8 @@
 //! ============================================================================
 
 use operator_core::{
-    AgencTask, ExecutionResult, IntentAction, PolicyCheck, PolicyGate, ProtocolState, SolanaExecutor,
+    AgencTask, ConfirmationTicket, ExecutionResult, IntentAction, PolicyCheck, PolicyGate,
+    ProtocolState, SolanaExecutor,
     VoiceIntent, VoiceState, WalletInfo, TaskStatus,
     // Access control
     AccessGate, AccessTierInfo, Feature,
@@ -29,9 +30,27 @@ use operator_core::{
     DiscoveredDevice, PairedDevice, DeviceAgentConfig, DeviceCommandResult, DeviceStatus,
     // Auth
     auth::{TwitterOAuth, TwitterTokens},
+    // Encrypted keystore
+    keystore::{self, Zeroizing},
+    // Off-chain task descriptions
+    DescriptionRegistry, HttpDescriptionGateway,
+    // Proof manifests
+    ProofManifest,
+    // Wallet history
+    WalletHistory,
+    // Autonomous worker
+    AutonomousWorker, WorkerConfig, WorkerHandle, WorkerStatus,
+    // Live task feed
+    TaskWatcher, TaskWatcherHandle,
+    // RPC endpoint pool
+    RpcEndpoint, RpcPool, RpcPoolStats,
+    // Network profiles
+    NetworkProfile, set_active_profile,
     // Database
     DbTaskStatus, DbOperatorConfig, OperatorDb, TaskRecord, SessionState, TranscriptEntry,
-    VerificationLog,
+    SpendCategory, SpendRecord, AuditRecord, WalletProfile,
+    // Policy
+    policy_gate::spend_category, price_oracle, TransactionPreview, PolicyExplanation, PolicyRules,
     // Store types
     StoreItemCategory, UserInventory, UserInventoryEntry, EquippedItems,
 };
@@ -39,7 +58,7 @@ use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, State};
 use tokio::sync::RwLock;
 use tracing::{info, error, debug, warn};
 
@@ -51,6 +70,8 @@ use tracing::{info, error, debug, warn};
 /// concurrent access from multiple tokio tasks
 pub struct AppState {
     pub executor: Arc<RwLock<SolanaExecutor>>,
+    // RPC endpoints shared by the executor, access gate and swap executor
+    pub rpc_pool: Arc<RpcPool>,
     pub policy: Arc<RwLock<PolicyGate>>,
     pub voice_state: Arc<RwLock<VoiceState>>,
     pub config: Arc<RwLock<AppConfig>>,
@@ -72,6 +93,10 @@ pub struct AppState {
     pub device_executor: Arc<RwLock<DeviceExecutor>>,
     // Phase 5: Embedded database
     pub db: Arc<RwLock<Option<OperatorDb>>>,
+    // Autonomous worker (None when not running)
+    pub worker: Arc<RwLock<Option<WorkerHandle>>>,
+    // Live task feed (None until the frontend asks for it)
+    pub task_watcher: Arc<RwLock<Option<TaskWatcherHandle>>>,
     // Session tracking
     pub session_id: String,
 }
@@ -80,7 +105,14 @@ pub struct AppState {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub rpc_url: String,
+    // Fallback endpoints tried after rpc_url when it is slow or failing
+    #[serde(default)]
+    pub rpc_fallbacks: Vec<RpcEndpoint>,
+    // Name of the active network profile
     pub network: String,
+    // Custom network profiles and edited built-ins
+    #[serde(default)]
+    pub network_profiles: Vec<NetworkProfile>,
     pub whisper_model_path: Option<String>,
     pub grok_api_key: Option<String>,
     pub qdrant_url: Option<String>,
@@ -97,13 +129,70 @@ pub struct AppConfig {
     pub github_token: Option<String>,
     pub github_default_owner: Option<String>,
     pub github_default_repo: Option<String>,
+    // Lock the wallet after this many idle seconds (None = never)
+    pub wallet_auto_lock_secs: Option<u64>,
+    // Gateway serving off-chain task descriptions by hash
+    pub description_gateway_url: Option<String>,
+}
+
+/// Default idle time before the wallet locks itself
+const DEFAULT_WALLET_AUTO_LOCK_SECS: u64 = 15 * 60;
+
+/// How often every RPC endpoint is probed for latency and slot lag
+const RPC_HEALTH_INTERVAL_SECS: u64 = 30;
+
+impl AppConfig {
+    /// Pool endpoints: the primary URL first, then the fallbacks
+    fn rpc_endpoints(&self) -> Vec<RpcEndpoint> {
+        let mut endpoints = vec![RpcEndpoint::new(&self.rpc_url, 1)];
+        endpoints.extend(self.rpc_fallbacks.iter().filter(|e| e.url != self.rpc_url).cloned());
+        endpoints
+    }
+
+    /// The active network profile, carrying the configured endpoints
+    fn active_profile(&self) -> NetworkProfile {
+        let mut profile = NetworkProfile::resolve(&self.network, &self.network_profiles)
+            .unwrap_or_else(|| {
+                warn!("No network profile named '{}' - using devnet", self.network);
+                NetworkProfile::devnet()
+            });
+        profile.rpc_endpoints = self.rpc_endpoints();
+        profile
+    }
+
+    /// Switch to `profile` and take over its endpoints
+    fn use_profile(&mut self, profile: &NetworkProfile) {
+        self.network = profile.name.clone();
+        self.rpc_url = profile.rpc_url().to_string();
+        self.rpc_fallbacks = profile.rpc_endpoints.iter().skip(1).cloned().collect();
+    }
+
+    /// Remember `profile`, replacing a saved profile of the same name
+    fn save_profile(&mut self, profile: NetworkProfile) {
+        self.network_profiles.retain(|p| p.name != profile.name);
+        self.network_profiles.push(profile);
+    }
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
+        // AGENC_NETWORK=localnet|devnet|mainnet picks the starting profile
+        let profile = std::env::var("AGENC_NETWORK")
+            .ok()
+            .and_then(|name| NetworkProfile::resolve(&name, &[])
+                .or_else(|| { warn!("Ignoring unknown AGENC_NETWORK: {}", name); None }))
+            .unwrap_or_else(NetworkProfile::devnet);
         Self {
-            rpc_url: "https://api.devnet.solana.com".to_string(),
-            network: "devnet".to_string(),
+            rpc_url: profile.rpc_url().to_string(),
+            // AGENC_RPC_FALLBACKS=url[@weight],... adds fallback endpoints
+            rpc_fallbacks: std::env::var("AGENC_RPC_FALLBACKS")
+                .ok()
+                .and_then(|list| RpcEndpoint::parse_list(&list)
+                    .map_err(|e| warn!("Ignoring AGENC_RPC_FALLBACKS: {}", e))
+                    .ok())
+                .unwrap_or_else(|| profile.rpc_endpoints.iter().skip(1).cloned().collect()),
+            network: profile.name.clone(),
+            network_profiles: Vec::new(),
             whisper_model_path: None,
             grok_api_key: std::env::var("XAI_API_KEY").ok(),
             qdrant_url: std::env::var("QDRANT_URL").ok(),
@@ -119,6 +208,12 @@ impl Default for AppConfig {
             github_token: std::env::var("GITHUB_TOKEN").ok(),
             github_default_owner: std::env::var("GITHUB_DEFAULT_OWNER").ok(),
             github_default_repo: std::env::var("GITHUB_DEFAULT_REPO").ok(),
+            // AGENC_WALLET_AUTO_LOCK_SECS=0 disables auto-lock
+            wallet_auto_lock_secs: match std::env::var("AGENC_WALLET_AUTO_LOCK_SECS") {
+                Ok(v) => v.parse().ok().filter(|secs| *secs > 0),
+                Err(_) => Some(DEFAULT_WALLET_AUTO_LOCK_SECS),
+            },
+            description_gateway_url: std::env::var("AGENC_DESCRIPTION_GATEWAY").ok(),
         }
     }
 }
@@ -178,6 +273,138 @@ fn agenc_task_to_record(task: &AgencTask) -> TaskRecord {
     }
 }
 
+/// Record a successful spend in the policy gate's rolling ledger and persist
+/// it to the DB so the limits survive restarts (DB write is non-fatal)
+async fn record_spend_to_ledger(state: &AppState, intent: &VoiceIntent, result: &ExecutionResult) {
+    let Some(category) = spend_category(&intent.action) else {
+        return;
+    };
+
+    let record = {
+        let mut policy = state.policy.write().await;
+        // SOL-equivalent value, so SPL swaps and SKR rewards count at their
+        // USD price rather than as raw base units
+        let lamports = policy.spend_lamports(intent).or_else(|| match category {
+            SpendCategory::TaskCreation => result
+                .data
+                .as_ref()
+                .and_then(|d| d.get("reward_lamports"))
+                .and_then(|v| v.as_u64()),
+            SpendCategory::Swap => None,
+        });
+        let Some(lamports) = lamports.filter(|l| *l > 0) else {
+            if category == SpendCategory::Swap {
+                warn!("[Policy] Could not value swap for the spend ledger");
+            }
+            return;
+        };
+        let wallet = policy.signing_wallet(intent).map(String::from);
+        policy.record_spend(category, lamports, result.signature.clone(), wallet)
+    };
+
+    let guard = state.db.read().await;
+    if let Some(db) = guard.as_ref() {
+        if let Err(e) = db.record_spend(&record) {
+            warn!("[DB] Failed to persist spend {}: {}", record.id, e);
+        }
+    }
+}
+
+/// Fetch decimals and USD prices for any mints the intent needs valued
+/// (SPL swap input, SKR reward) before the policy check. Lookups run
+/// without holding the policy lock; on failure the mint stays unpriced and
+/// the policy fails closed to typed confirmation.
+async fn refresh_policy_prices(state: &AppState, intent: &VoiceIntent) {
+    let mints = state.policy.read().await.stale_mints(intent);
+    if mints.is_empty() {
+        return;
+    }
+
+    let quotes = {
+        let swap_executor = state.swap_executor.read().await;
+        let Some(executor) = swap_executor.as_ref() else {
+            return;
+        };
+        let oracle = state.policy.read().await.price_oracle().clone();
+        price_oracle::fetch_quotes(executor, &oracle, &mints).await
+    };
+
+    state.policy.write().await.price_oracle_mut().apply(quotes);
+}
+
+/// Component that executes an intent, as recorded in the audit log
+fn executor_name(action: &IntentAction) -> &'static str {
+    match action {
+        IntentAction::CodeFix
+        | IntentAction::CodeReview
+        | IntentAction::CodeGenerate
+        | IntentAction::CodeExplain => "grok_code",
+        IntentAction::SwapTokens | IntentAction::GetSwapQuote | IntentAction::GetTokenPrice => {
+            "jupiter"
+        }
+        IntentAction::PostTweet | IntentAction::PostThread => "twitter",
+        IntentAction::PostDiscord | IntentAction::PostDiscordEmbed => "discord",
+        IntentAction::SendEmail | IntentAction::SendBulkEmail => "email",
+        IntentAction::GenerateImage => "image",
+        IntentAction::GenerateVideo => "video",
+        IntentAction::CreateGist
+        | IntentAction::CreateGitHubIssue
+        | IntentAction::AddGitHubComment
+        | IntentAction::TriggerGitHubWorkflow => "github",
+        _ => "solana",
+    }
+}
+
+/// Append an entry for an execute_intent/execute_confirmed call to the
+/// hash-chained audit log (non-fatal)
+async fn record_audit(
+    state: &AppState,
+    intent: &VoiceIntent,
+    policy_check: &PolicyCheck,
+    confirmed: bool,
+    executor: &str,
+    response: &Result<AsyncResult<ExecutionResult>, String>,
+    started_at: i64,
+) {
+    let (success, message, signature) = match response {
+        Ok(AsyncResult { success, data: Some(result), .. }) => {
+            (*success && result.success, result.message.clone(), result.signature.clone())
+        }
+        Ok(AsyncResult { error, .. }) => (false, error.clone().unwrap_or_default(), None),
+        Err(e) => (false, e.clone(), None),
+    };
+    let access_tier = state
+        .policy
+        .read()
+        .await
+        .access_tier()
+        .map(|t| format!("{:?}", t).to_lowercase());
+
+    let record = AuditRecord {
+        action: intent.action.name(),
+        params_json: intent.params.to_string(),
+        raw_transcript: intent.raw_transcript.clone(),
+        policy_allowed: policy_check.allowed,
+        policy_reason: policy_check.reason.clone(),
+        confirmation_type: policy_check.confirmation_type.clone(),
+        confirmed,
+        access_tier,
+        executor: executor.to_string(),
+        success,
+        message,
+        signature,
+        started_at,
+        finished_at: chrono::Utc::now().timestamp(),
+    };
+
+    let guard = state.db.read().await;
+    if let Some(db) = guard.as_ref() {
+        if let Err(e) = db.append_audit(record) {
+            warn!("[DB] Failed to append audit entry: {}", e);
+        }
+    }
+}
+
 /// Persist task result to DB if the action is task-related (non-fatal)
 async fn persist_task_to_db(
     db: &RwLock<Option<OperatorDb>>,
@@ -190,6 +417,7 @@ async fn persist_task_to_db(
             | IntentAction::ClaimTask
             | IntentAction::CompleteTask
             | IntentAction::CancelTask
+            | IntentAction::ApproveCompletion
     ) {
         return;
     }
@@ -212,18 +440,18 @@ async fn persist_task_to_db(
                 debug!("[DB] Persisted task {} (status={:?})", record.task_id, record.status);
             }
 
-            // For completed tasks, also store a verification proof
-            if matches!(action, IntentAction::CompleteTask) {
-                let proof = VerificationLog {
-                    task_id: task.id.clone(),
-                    inputs: vec![],
-                    outputs: serde_json::to_vec(&result).unwrap_or_default(),
-                    proof_hash: format!("completed_{}", chrono::Utc::now().timestamp()),
-                    timestamp: chrono::Utc::now().timestamp(),
-                    submitted: result.signature.is_some(),
-                    submission_signature: result.signature.clone(),
-                };
-                if let Err(e) = db.store_proof(&proof) {
+            // For completed tasks, also store the submitted proof manifest so
+            // it can be re-verified later
+            let manifest: Option<ProofManifest> = result
+                .data
+                .as_ref()
+                .filter(|_| matches!(action, IntentAction::CompleteTask))
+                .and_then(|d| serde_json::from_value(d["proof_manifest"].clone()).ok());
+            if let Some(manifest) = manifest {
+                let stored = manifest
+                    .to_verification_log(result.signature.clone())
+                    .and_then(|proof| db.store_proof(&proof));
+                if let Err(e) = stored {
                     warn!("[DB] Failed to persist proof for task {}: {}", task.id, e);
                 }
             }
@@ -231,6 +459,43 @@ async fn persist_task_to_db(
     }
 }
 
+/// Operator config for a database that has none yet
+fn default_db_config(cfg: &AppConfig) -> DbOperatorConfig {
+    DbOperatorConfig {
+        wallet_pubkey: None,
+        rpc_url: cfg.rpc_url.clone(),
+        network: cfg.network.clone(),
+        capabilities: vec![
+            "voice".into(), "code".into(), "trading".into(),
+            "social".into(), "email".into(), "image".into(),
+        ],
+        model_preferences: None,
+        network_profile: Some(cfg.network.clone()),
+        network_profiles: cfg.network_profiles.clone(),
+    }
+}
+
+/// Record the selected network profile and saved profiles in DB (non-fatal)
+async fn persist_network_to_db(db: &RwLock<Option<OperatorDb>>, cfg: &AppConfig) {
+    let guard = db.read().await;
+    if let Some(db) = guard.as_ref() {
+        let mut config = match db.get_config() {
+            Ok(config) => config.unwrap_or_else(|| default_db_config(cfg)),
+            Err(e) => {
+                warn!("[DB] Failed to read operator config: {}", e);
+                return;
+            }
+        };
+        config.rpc_url = cfg.rpc_url.clone();
+        config.network = cfg.network.clone();
+        config.network_profile = Some(cfg.network.clone());
+        config.network_profiles = cfg.network_profiles.clone();
+        if let Err(e) = db.store_config(&config) {
+            warn!("[DB] Failed to persist network profile: {}", e);
+        }
+    }
+}
+
 /// Update session tracking in DB (non-fatal)
 async fn update_session_db(
     db: &RwLock<Option<OperatorDb>>,
@@ -301,22 +566,18 @@ async fn load_wallet(
     match handle.await {
         Ok(Ok(address)) => {
             info!("[IPC] Wallet loaded: {}", address);
+            // A bare keypair replaces any active wallet profile
+            state.policy.write().await.set_active_wallet(None);
 
-            // Persist config to DB (non-fatal)
+            // Persist config to DB (non-fatal), keeping saved capabilities
+            // and network profiles
             {
                 let config_guard = state.config.read().await;
                 let db_guard = state.db.read().await;
                 if let Some(db) = db_guard.as_ref() {
-                    let db_config = DbOperatorConfig {
-                        wallet_pubkey: Some(address.clone()),
-                        rpc_url: config_guard.rpc_url.clone(),
-                        network: config_guard.network.clone(),
-                        capabilities: vec![
-                            "voice".into(), "code".into(), "trading".into(),
-                            "social".into(), "email".into(), "image".into(),
-                        ],
-                        model_preferences: None,
-                    };
+                    let mut db_config = db.get_config().ok().flatten()
+                        .unwrap_or_else(|| default_db_config(&config_guard));
+                    db_config.wallet_pubkey = Some(address.clone());
                     if let Err(e) = db.store_config(&db_config) {
                         warn!("[DB] Failed to persist config: {}", e);
                     } else {
@@ -339,15 +600,19 @@ async fn load_wallet(
 }
 
 /// Get wallet information - non-blocking chain query
+/// `wallet` names a wallet profile; omit it for the active wallet
 #[tauri::command]
-async fn get_wallet_info(state: State<'_, AppState>) -> Result<AsyncResult<WalletInfo>, String> {
-    debug!("[IPC] get_wallet_info called");
+async fn get_wallet_info(
+    state: State<'_, AppState>,
+    wallet: Option<String>,
+) -> Result<AsyncResult<WalletInfo>, String> {
+    debug!("[IPC] get_wallet_info called (wallet={:?})", wallet);
 
     let executor = Arc::clone(&state.executor);
 
     let handle = tokio::spawn(async move {
         let exec = executor.read().await;
-        exec.get_wallet_info().await
+        exec.get_wallet_info_for(wallet.as_deref()).await
     });
 
     match handle.await {
@@ -357,6 +622,241 @@ async fn get_wallet_info(state: State<'_, AppState>) -> Result<AsyncResult<Walle
     }
 }
 
+// ============================================================================
+// Tauri Commands - Wallet Profiles
+// ============================================================================
+
+/// A loaded wallet profile as shown in the HUD
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletProfileSummary {
+    pub name: String,
+    pub pubkey: String,
+    pub active: bool,
+}
+
+/// Load a keypair or encrypted keystore (with `passphrase`) as a named
+/// wallet profile and persist it. Also unlocks a locked keystore profile.
+/// The first profile becomes active if no wallet is loaded yet.
+#[tauri::command]
+async fn add_wallet_profile(
+    state: State<'_, AppState>,
+    name: String,
+    keypair_path: String,
+    passphrase: Option<String>,
+) -> Result<AsyncResult<WalletProfile>, String> {
+    info!("[IPC] add_wallet_profile: {} ({})", name, keypair_path);
+    let passphrase = passphrase.map(Zeroizing::new);
+
+    let (address, active) = {
+        let exec = state.executor.read().await;
+        match exec
+            .load_wallet_profile(&name, &keypair_path, passphrase.as_ref().map(|p| p.as_str()))
+            .await
+        {
+            Ok(address) => (address, exec.active_wallet().await),
+            Err(e) => return Ok(AsyncResult::err(e.to_string())),
+        }
+    };
+
+    let profile = WalletProfile {
+        name: name.clone(),
+        pubkey: address,
+        keypair_path,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+
+    let db_guard = state.db.read().await;
+    if let Some(db) = db_guard.as_ref() {
+        if let Err(e) = db.store_wallet_profile(&profile) {
+            warn!("[DB] Failed to persist wallet profile {}: {}", name, e);
+        }
+        if active.as_deref() == Some(name.as_str()) {
+            if let Err(e) = db.set_active_wallet(&name) {
+                warn!("[DB] Failed to persist active wallet: {}", e);
+            }
+        }
+    }
+    if active.as_deref() == Some(name.as_str()) {
+        state.policy.write().await.set_active_wallet(active);
+    }
+
+    Ok(AsyncResult::ok(profile))
+}
+
+/// List loaded wallet profiles
+#[tauri::command]
+async fn list_wallet_profiles(
+    state: State<'_, AppState>,
+) -> Result<AsyncResult<Vec<WalletProfileSummary>>, String> {
+    debug!("[IPC] list_wallet_profiles called");
+
+    let exec = state.executor.read().await;
+    let active = exec.active_wallet().await;
+    let profiles = exec
+        .wallet_profiles()
+        .await
+        .into_iter()
+        .map(|(name, pubkey)| WalletProfileSummary {
+            active: active.as_deref() == Some(name.as_str()),
+            pubkey: pubkey.to_string(),
+            name,
+        })
+        .collect();
+
+    Ok(AsyncResult::ok(profiles))
+}
+
+/// Make a wallet profile the active signer. Refreshes the access tier used
+/// by tier-conditioned policy rules for the new wallet.
+#[tauri::command]
+async fn switch_wallet(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<AsyncResult<String>, String> {
+    info!("[IPC] switch_wallet: {}", name);
+
+    let address = {
+        let exec = state.executor.read().await;
+        match exec.switch_wallet(&name).await {
+            Ok(address) => address,
+            Err(e) => return Ok(AsyncResult::err(e.to_string())),
+        }
+    };
+
+    {
+        let db_guard = state.db.read().await;
+        if let Some(db) = db_guard.as_ref() {
+            if let Err(e) = db.set_active_wallet(&name) {
+                warn!("[DB] Failed to persist active wallet: {}", e);
+            }
+        }
+    }
+
+    // The cached tier belonged to the previous wallet
+    let tier = {
+        let access_gate = state.access_gate.read().await;
+        match (access_gate.as_ref(), Pubkey::from_str(&address)) {
+            (Some(gate), Ok(pubkey)) => gate
+                .get_access_tier_info(&pubkey)
+                .await
+                .map_err(|e| warn!("[IPC] Failed to get access tier for {}: {}", name, e))
+                .ok()
+                .map(|info| info.tier),
+            _ => None,
+        }
+    };
+
+    let mut policy = state.policy.write().await;
+    policy.set_active_wallet(Some(name));
+    policy.set_access_tier(tier);
+
+    Ok(AsyncResult::ok(address))
+}
+
+/// Unload a wallet profile and forget it. The active profile cannot be removed.
+#[tauri::command]
+async fn remove_wallet_profile(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<AsyncResult<bool>, String> {
+    info!("[IPC] remove_wallet_profile: {}", name);
+
+    {
+        let exec = state.executor.read().await;
+        if let Err(e) = exec.remove_wallet_profile(&name).await {
+            return Ok(AsyncResult::err(e.to_string()));
+        }
+    }
+
+    let db_guard = state.db.read().await;
+    let removed = match db_guard.as_ref() {
+        Some(db) => db.delete_wallet_profile(&name).map_err(|e| e.to_string())?,
+        None => true,
+    };
+
+    Ok(AsyncResult::ok(removed))
+}
+
+/// Decrypt an encrypted keystore and make it the active wallet
+#[tauri::command]
+async fn unlock_keystore(
+    state: State<'_, AppState>,
+    keystore_path: String,
+    passphrase: String,
+) -> Result<AsyncResult<String>, String> {
+    info!("[IPC] unlock_keystore: {}", keystore_path);
+    let passphrase = Zeroizing::new(passphrase);
+
+    let exec = state.executor.read().await;
+    match exec.unlock_keystore(&keystore_path, &passphrase).await {
+        Ok(address) => {
+            state.policy.write().await.set_active_wallet(None);
+            Ok(AsyncResult::ok(address))
+        }
+        Err(e) => {
+            warn!("[IPC] unlock_keystore failed: {}", e);
+            Ok(AsyncResult::err(e.to_string()))
+        }
+    }
+}
+
+/// Drop all loaded keys from memory (they are zeroized)
+#[tauri::command]
+async fn lock_wallet(state: State<'_, AppState>) -> Result<(), String> {
+    info!("[IPC] lock_wallet called");
+    state.executor.read().await.lock().await;
+    Ok(())
+}
+
+/// Whether the wallet is locked (no key loaded)
+#[tauri::command]
+async fn is_wallet_locked(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.executor.read().await.is_locked().await)
+}
+
+/// Load saved wallet profiles into the executor and re-select the active one
+fn restore_wallet_profiles(db: &OperatorDb, executor: &SolanaExecutor, policy: &mut PolicyGate) {
+    let profiles = match db.list_wallet_profiles() {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            warn!("Failed to load wallet profiles: {}", e);
+            return;
+        }
+    };
+    if profiles.is_empty() {
+        return;
+    }
+    let active = db.get_active_wallet().unwrap_or_else(|e| {
+        warn!("Failed to load active wallet: {}", e);
+        None
+    });
+
+    tauri::async_runtime::block_on(async {
+        for profile in &profiles {
+            // Keystore profiles stay locked until add_wallet_profile is
+            // called again with the passphrase
+            if keystore::is_keystore_file(std::path::Path::new(&profile.keypair_path)) {
+                info!("Wallet profile '{}' is an encrypted keystore — locked", profile.name);
+                continue;
+            }
+            if let Err(e) = executor
+                .load_wallet_profile(&profile.name, &profile.keypair_path, None)
+                .await
+            {
+                warn!("Failed to load wallet profile '{}': {}", profile.name, e);
+            }
+        }
+        if let Some(name) = active.as_deref() {
+            if let Err(e) = executor.switch_wallet(name).await {
+                warn!("Failed to restore active wallet '{}': {}", name, e);
+            }
+        }
+        policy.set_active_wallet(executor.active_wallet().await);
+    });
+
+    info!("Restored {} wallet profiles", profiles.len());
+}
+
 // ============================================================================
 // Tauri Commands - Intent Execution (Non-Blocking)
 // ============================================================================
@@ -407,103 +907,161 @@ async fn execute_intent(
         Err(e) => return Ok(AsyncResult::err(format!("Parse error: {}", e))),
     };
 
-    // Policy check is fast (in-memory), do it synchronously
-    let policy_check = {
-        let policy = state.policy.read().await;
-        policy.check_policy(&intent)
+    let started_at = chrono::Utc::now().timestamp();
+    let (policy_check, executor, response) = run_intent(&state, &intent).await;
+    record_audit(&state, &intent, &policy_check, false, executor, &response, started_at).await;
+    response
+}
+
+/// Policy check, access gating and routing for execute_intent. Returns the
+/// policy decision and the component that produced the response, for the
+/// audit log.
+async fn run_intent(
+    state: &State<'_, AppState>,
+    intent: &VoiceIntent,
+) -> (PolicyCheck, &'static str, Result<AsyncResult<ExecutionResult>, String>) {
+    // Policy check is fast (in-memory) once token prices are cached.
+    // Intents that need confirmation get a one-time ticket for execute_confirmed.
+    refresh_policy_prices(state, intent).await;
+    let mut policy_check = {
+        let mut policy = state.policy.write().await;
+        policy.check_and_issue(intent)
     };
 
     if !policy_check.allowed {
-        return Ok(AsyncResult::ok(ExecutionResult {
+        let response = AsyncResult::ok(ExecutionResult {
             success: false,
             message: format!("Policy denied: {}", policy_check.reason),
             signature: None,
             data: None,
-        }));
+        });
+        return (policy_check, "policy", Ok(response));
     }
 
-    // If confirmation required, return early
+    // Access tier gating - before confirmation, so the user is not asked to
+    // approve something their tier cannot run
+    if let Some(denied) = gate_access(state, intent).await {
+        let response = AsyncResult::ok(ExecutionResult {
+            success: false,
+            message: denied,
+            signature: None,
+            data: None,
+        });
+        return (policy_check, "access_gate", Ok(response));
+    }
+
+    // If confirmation required, return the structured PolicyCheck (with its
+    // ticket and a simulated preview) so the UI can confirm and call
+    // execute_confirmed
     if policy_check.requires_confirmation {
-        return Ok(AsyncResult::ok(ExecutionResult {
+        policy_check.preview = preview_transaction(state, intent).await;
+        let response = AsyncResult::ok(ExecutionResult {
             success: true,
-            message: format!("CONFIRM_REQUIRED:{:?}:{}",
-                policy_check.confirmation_type, policy_check.reason),
+            message: policy_check.reason.clone(),
             signature: None,
             data: Some(serde_json::to_value(&policy_check).unwrap_or_default()),
-        }));
-    }
-
-    // Access tier gating - check if this action requires a specific feature
-    if let Some(feature) = intent.action.required_feature() {
-        let access_gate = state.access_gate.read().await;
-        if let Some(gate) = access_gate.as_ref() {
-            // Get wallet pubkey from executor
-            let wallet_pubkey = {
-                let executor = state.executor.read().await;
-                executor.get_wallet_pubkey()
-            };
-
-            if let Some(pubkey) = wallet_pubkey {
-                if let Err(e) = gate.gate_feature(&pubkey, feature).await {
-                    return Ok(AsyncResult::ok(ExecutionResult {
-                        success: false,
-                        message: format!("Access denied: {}", e),
-                        signature: None,
-                        data: None,
-                    }));
-                }
-            }
-        }
+        });
+        return (policy_check, "policy", Ok(response));
     }
 
     // Route to appropriate executor based on intent action
-    match &intent.action {
+    let response = match &intent.action {
         // Code operations -> GrokCodeExecutor
-        IntentAction::CodeFix => route_code_fix(&state, &intent).await,
-        IntentAction::CodeReview => route_code_review(&state, &intent).await,
-        IntentAction::CodeGenerate => route_code_generate(&state, &intent).await,
-        IntentAction::CodeExplain => route_code_explain(&state, &intent).await,
+        IntentAction::CodeFix => route_code_fix(state, intent).await,
+        IntentAction::CodeReview => route_code_review(state, intent).await,
+        IntentAction::CodeGenerate => route_code_generate(state, intent).await,
+        IntentAction::CodeExplain => route_code_explain(state, intent).await,
 
         // Trading operations -> JupiterSwapExecutor
-        IntentAction::SwapTokens => route_swap(&state, &intent).await,
-        IntentAction::GetSwapQuote => route_quote(&state, &intent).await,
-        IntentAction::GetTokenPrice => route_price(&state, &intent).await,
+        IntentAction::SwapTokens => route_swap(state, intent).await,
+        IntentAction::GetSwapQuote => route_quote(state, intent).await,
+        IntentAction::GetTokenPrice => route_price(state, intent).await,
 
         // Twitter operations -> TwitterExecutor
-        IntentAction::PostTweet => route_tweet(&state, &intent).await,
-        IntentAction::PostThread => route_thread(&state, &intent).await,
+        IntentAction::PostTweet => route_tweet(state, intent).await,
+        IntentAction::PostThread => route_thread(state, intent).await,
 
         // Discord operations -> DiscordExecutor
-        IntentAction::PostDiscord => route_discord(&state, &intent).await,
-        IntentAction::PostDiscordEmbed => route_discord_embed(&state, &intent).await,
+        IntentAction::PostDiscord => route_discord(state, intent).await,
+        IntentAction::PostDiscordEmbed => route_discord_embed(state, intent).await,
 
         // Email operations -> EmailExecutor
-        IntentAction::SendEmail => route_email(&state, &intent).await,
-        IntentAction::SendBulkEmail => route_bulk_email(&state, &intent).await,
+        IntentAction::SendEmail => route_email(state, intent).await,
+        IntentAction::SendBulkEmail => route_bulk_email(state, intent).await,
 
         // Image generation -> ImageExecutor
-        IntentAction::GenerateImage => route_image(&state, &intent).await,
+        IntentAction::GenerateImage => route_image(state, intent).await,
 
         // Video generation -> VideoExecutor
-        IntentAction::GenerateVideo => route_video(&state, &intent).await,
+        IntentAction::GenerateVideo => route_video(state, intent).await,
 
         // GitHub operations -> GitHubExecutor
-        IntentAction::CreateGist => route_create_gist(&state, &intent).await,
-        IntentAction::CreateGitHubIssue => route_create_github_issue(&state, &intent).await,
-        IntentAction::AddGitHubComment => route_add_github_comment(&state, &intent).await,
-        IntentAction::TriggerGitHubWorkflow => route_trigger_github_workflow(&state, &intent).await,
+        IntentAction::CreateGist => route_create_gist(state, intent).await,
+        IntentAction::CreateGitHubIssue => route_create_github_issue(state, intent).await,
+        IntentAction::AddGitHubComment => route_add_github_comment(state, intent).await,
+        IntentAction::TriggerGitHubWorkflow => route_trigger_github_workflow(state, intent).await,
 
         // Blockchain operations -> SolanaExecutor (existing behavior)
-        _ => route_solana(&state, &intent).await,
+        _ => route_solana(state, intent).await,
+    };
+
+    (policy_check, executor_name(&intent.action), response)
+}
+
+/// Access tier gating - check if this action requires a specific feature the
+/// acting wallet (named profile or active wallet) does not unlock. Returns the
+/// denial message.
+async fn gate_access(state: &State<'_, AppState>, intent: &VoiceIntent) -> Option<String> {
+    let feature = intent.action.required_feature()?;
+    let access_gate = state.access_gate.read().await;
+    let gate = access_gate.as_ref()?;
+    let pubkey = {
+        let executor = state.executor.read().await;
+        executor.wallet_pubkey(intent.wallet()).await
+    }?;
+
+    gate.gate_feature(&pubkey, feature)
+        .await
+        .err()
+        .map(|e| format!("Access denied: {}", e))
+}
+
+/// Build and simulate the transaction a spending intent would send. A
+/// failed preview is logged and the prompt shown without one.
+async fn preview_transaction(state: &State<'_, AppState>, intent: &VoiceIntent) -> Option<TransactionPreview> {
+    spend_category(&intent.action)?;
+
+    let preview = if matches!(intent.action, IntentAction::SwapTokens) {
+        let swap_executor = state.swap_executor.read().await;
+        let Some(executor) = swap_executor.as_ref() else { return None };
+        // Simulated for the wallet that will sign (named profile or active wallet)
+        let wallet = state.executor.read().await.wallet_pubkey(intent.wallet()).await;
+        match (swap_params(executor, intent), wallet) {
+            (Ok(params), Some(wallet)) => executor.preview_swap_for(&params, &wallet).await.map(Some),
+            (Ok(_), None) => Err(anyhow::anyhow!("Wallet not connected")),
+            (Err(e), _) => Err(anyhow::anyhow!(e)),
+        }
+    } else {
+        state.executor.read().await.preview_intent(intent).await
+    };
+
+    match preview {
+        Ok(preview) => preview,
+        Err(e) => {
+            warn!("[Policy] Could not preview {:?}: {}", intent.action, e);
+            None
+        }
     }
 }
 
 /// Execute after confirmation - spawns chain tx in background
-/// SECURITY: Re-validates policy before execution to prevent bypass.
+/// SECURITY: Requires the one-time ticket issued by execute_intent for this
+/// exact intent, and re-validates policy before execution.
 #[tauri::command]
 async fn execute_confirmed(
     state: State<'_, AppState>,
     intent_json: String,
+    ticket: ConfirmationTicket,
 ) -> Result<AsyncResult<ExecutionResult>, String> {
     info!("[IPC] execute_confirmed");
 
@@ -512,47 +1070,87 @@ async fn execute_confirmed(
         Err(e) => return Ok(AsyncResult::err(format!("Parse error: {}", e))),
     };
 
-    // SECURITY: Re-check policy before execution to prevent bypass via direct IPC call.
-    // The original execute_intent checks policy and returns CONFIRM_REQUIRED, but a
-    // malicious caller could skip that and call execute_confirmed directly with any intent.
-    {
-        let policy = state.policy.read().await;
-        let policy_check = policy.check_policy(&intent);
+    let started_at = chrono::Utc::now().timestamp();
+    let (policy_check, executor, response) = run_confirmed(&state, &intent, &ticket).await;
+    record_audit(&state, &intent, &policy_check, true, executor, &response, started_at).await;
+    response
+}
+
+/// Policy re-check, ticket redemption, spend reservation and execution for
+/// execute_confirmed. Returns the policy decision and the component that
+/// produced the response, for the audit log.
+async fn run_confirmed(
+    state: &State<'_, AppState>,
+    intent: &VoiceIntent,
+    ticket: &ConfirmationTicket,
+) -> (PolicyCheck, &'static str, Result<AsyncResult<ExecutionResult>, String>) {
+    // SECURITY: The ticket binds this call to what the user approved in
+    // execute_intent — edited params, replays and expired tickets are rejected.
+    // Access tier is checked first; then, under one policy write lock, policy
+    // and limits are re-checked, the ticket redeemed and the spend reserved in
+    // the ledger, so concurrent confirmations can't both pass a limit only one
+    // fits under. A transient deny leaves the ticket usable, and a ticket
+    // approved with a weaker confirmation than now required is refused.
+    refresh_policy_prices(state, intent).await;
+    if let Some(denied) = gate_access(state, intent).await {
+        let policy_check = state.policy.read().await.check_policy(intent);
+        let response = AsyncResult::ok(ExecutionResult {
+            success: false,
+            message: denied,
+            signature: None,
+            data: None,
+        });
+        return (policy_check, "access_gate", Ok(response));
+    }
+
+    let (policy_check, reservation) = {
+        let mut policy = state.policy.write().await;
+        let policy_check = policy.check_policy(intent);
         if !policy_check.allowed {
-            return Ok(AsyncResult::ok(ExecutionResult {
+            let response = AsyncResult::ok(ExecutionResult {
                 success: false,
                 message: format!("Policy denied: {}", policy_check.reason),
                 signature: None,
                 data: None,
-            }));
+            });
+            return (policy_check, "policy", Ok(response));
         }
+
+        if let Err(e) = policy.redeem_ticket(intent, ticket, &policy_check.confirmation_type) {
+            let response = AsyncResult::ok(ExecutionResult {
+                success: false,
+                message: format!("Confirmation rejected: {}", e),
+                signature: None,
+                data: None,
+            });
+            return (policy_check, "confirmation", Ok(response));
+        }
+
+        let reservation = policy.reserve_spend(intent);
+        (policy_check, reservation)
+    };
+
+    // Swaps are executed by the Jupiter executor, not the chain executor
+    if matches!(intent.action, IntentAction::SwapTokens) {
+        let response = route_swap(state, intent).await;
+        let executed = response.as_ref().ok().and_then(|r| r.data.as_ref());
+        settle_reserved_spend(state, intent, reservation, executed.filter(|r| r.success)).await;
+        return (policy_check, "jupiter", response);
     }
 
     let executor = Arc::clone(&state.executor);
-    let policy = Arc::clone(&state.policy);
     let intent_clone = intent.clone();
 
     // Spawn chain operation
     let handle = tokio::spawn(async move {
         let exec = executor.read().await;
-        let result = exec.execute_intent(&intent_clone).await?;
-
-        // Record spending if successful (also async-safe)
-        if result.success {
-            if let Some(ref data) = result.data {
-                if let Some(lamports) = data.get("reward_lamports").and_then(|v| v.as_u64()) {
-                    let mut pol = policy.write().await;
-                    pol.record_spending(lamports);
-                }
-            }
-        }
-
-        Ok::<_, anyhow::Error>(result)
+        exec.execute_intent(&intent_clone).await
     });
 
-    match handle.await {
+    let response = match handle.await {
         Ok(Ok(result)) => {
-            // Persist to DB if applicable (non-fatal)
+            // Record spending and persist to DB if applicable (non-fatal)
+            settle_reserved_spend(state, intent, reservation, Some(&result).filter(|r| r.success)).await;
             if result.success {
                 let task_id = intent.params.get("task_id").and_then(|v| v.as_str());
                 persist_task_to_db(&state.db, &result, &intent.action).await;
@@ -561,8 +1159,44 @@ async fn execute_confirmed(
 
             Ok(AsyncResult::ok(result))
         }
-        Ok(Err(e)) => Ok(AsyncResult::err(e.to_string())),
-        Err(e) => Ok(AsyncResult::err(format!("Task failed: {}", e))),
+        Ok(Err(e)) => {
+            settle_reserved_spend(state, intent, reservation, None).await;
+            Ok(AsyncResult::err(e.to_string()))
+        }
+        Err(e) => {
+            settle_reserved_spend(state, intent, reservation, None).await;
+            Ok(AsyncResult::err(format!("Task failed: {}", e)))
+        }
+    };
+
+    (policy_check, "solana", response)
+}
+
+/// Finish the spend reserved for a confirmed intent: on success attach the
+/// signature and persist it, otherwise roll the reservation back. A spend
+/// that could not be valued up front is recorded from the result instead.
+async fn settle_reserved_spend(
+    state: &AppState,
+    intent: &VoiceIntent,
+    reservation: Option<SpendRecord>,
+    executed: Option<&ExecutionResult>,
+) {
+    match (reservation, executed) {
+        (Some(reservation), Some(result)) => {
+            let settled = state.policy.write().await.settle_spend(&reservation.id, result.signature.clone());
+            let guard = state.db.read().await;
+            if let (Some(record), Some(db)) = (settled, guard.as_ref()) {
+                if let Err(e) = db.record_spend(&record) {
+                    warn!("[DB] Failed to persist spend {}: {}", record.id, e);
+                }
+            }
+        }
+        (Some(reservation), None) => {
+            state.policy.write().await.release_spend(&reservation.id);
+            debug!("[Policy] Released spend reservation {}", reservation.id);
+        }
+        (None, Some(result)) => record_spend_to_ledger(state, intent, result).await,
+        (None, None) => {}
     }
 }
 
@@ -901,32 +1535,30 @@ async fn route_swap(
 
     match swap_executor.as_ref() {
         Some(executor) => {
-            let params: SwapParams = match serde_json::from_value(intent.params.clone()) {
+            let resolved_params = match swap_params(executor, intent) {
                 Ok(p) => p,
-                Err(e) => return Ok(AsyncResult::ok(ExecutionResult {
+                Err(message) => return Ok(AsyncResult::ok(ExecutionResult {
                     success: false,
-                    message: format!("Invalid swap params: {}", e),
+                    message,
                     signature: None,
                     data: None,
                 })),
             };
 
-            // Resolve token symbols to mint addresses
-            let input_mint = executor.resolve_token(&params.input_mint)
-                .map(|s| s.to_string())
-                .unwrap_or(params.input_mint.clone());
-            let output_mint = executor.resolve_token(&params.output_mint)
-                .map(|s| s.to_string())
-                .unwrap_or(params.output_mint.clone());
-
-            let resolved_params = SwapParams {
-                input_mint,
-                output_mint,
-                amount: params.amount,
-                slippage_bps: params.slippage_bps,
+            // Signed by the wallet the intent names (else the active one),
+            // matching the wallet the spend is recorded against
+            let chain = state.executor.read().await;
+            let signer = match chain.wallet_signer(intent.wallet()).await {
+                Ok(signer) => signer,
+                Err(e) => return Ok(AsyncResult::ok(ExecutionResult {
+                    success: false,
+                    message: e.to_string(),
+                    signature: None,
+                    data: None,
+                })),
             };
 
-            match executor.execute_swap(resolved_params).await {
+            match executor.execute_swap_as(resolved_params, &signer).await {
                 Ok(signature) => Ok(AsyncResult::ok(ExecutionResult {
                     success: true,
                     message: format!("Swap executed successfully"),
@@ -950,6 +1582,27 @@ async fn route_swap(
     }
 }
 
+/// Swap params from a swap intent, with token symbols resolved to mints
+fn swap_params(executor: &JupiterSwapExecutor, intent: &VoiceIntent) -> Result<SwapParams, String> {
+    let params: SwapParams = serde_json::from_value(intent.params.clone())
+        .map_err(|e| format!("Invalid swap params: {}", e))?;
+
+    // Resolve token symbols to mint addresses
+    let input_mint = executor.resolve_token(&params.input_mint)
+        .map(|s| s.to_string())
+        .unwrap_or(params.input_mint.clone());
+    let output_mint = executor.resolve_token(&params.output_mint)
+        .map(|s| s.to_string())
+        .unwrap_or(params.output_mint.clone());
+
+    Ok(SwapParams {
+        input_mint,
+        output_mint,
+        amount: params.amount,
+        slippage_bps: params.slippage_bps,
+    })
+}
+
 /// Route quote intent to JupiterSwapExecutor
 async fn route_quote(
     state: &State<'_, AppState>,
@@ -1719,10 +2372,14 @@ async fn get_protocol_state(state: State<'_, AppState>) -> Result<AsyncResult<Pr
     }
 }
 
-/// List open tasks - spawns RPC query in background
+/// List open tasks - spawns RPC query in background.
+/// `filters` are list_open_tasks params (reward range, capabilities, creator, sort, page).
 #[tauri::command]
-async fn list_tasks(state: State<'_, AppState>) -> Result<AsyncResult<Vec<AgencTask>>, String> {
-    debug!("[IPC] list_tasks");
+async fn list_tasks(
+    state: State<'_, AppState>,
+    filters: Option<serde_json::Value>,
+) -> Result<AsyncResult<Vec<AgencTask>>, String> {
+    debug!("[IPC] list_tasks (filters={:?})", filters);
 
     let executor = Arc::clone(&state.executor);
 
@@ -1730,7 +2387,7 @@ async fn list_tasks(state: State<'_, AppState>) -> Result<AsyncResult<Vec<AgencT
         let exec = executor.read().await;
         let result = exec.execute_intent(&VoiceIntent {
             action: operator_core::IntentAction::ListOpenTasks,
-            params: serde_json::json!({}),
+            params: filters.unwrap_or_else(|| serde_json::json!({})),
             raw_transcript: None,
         }).await?;
 
@@ -1756,6 +2413,10 @@ async fn create_task(
     reward_sol: f64,
     reward_skr: Option<f64>,
     deadline: Option<u64>,
+    tags: Option<Vec<String>>,
+    deadline_text: Option<String>,
+    acceptance_criteria: Option<Vec<String>>,
+    required_capabilities: Option<Vec<String>>,
     state: State<'_, AppState>,
 ) -> Result<AsyncResult<ExecutionResult>, String> {
     debug!("[IPC] create_task: {} ({} SOL)", description, reward_sol);
@@ -1776,6 +2437,18 @@ async fn create_task(
             if let Some(dl) = deadline {
                 params["deadline_hours"] = serde_json::json!(dl);
             }
+            if let Some(tags) = tags {
+                params["tags"] = serde_json::json!(tags);
+            }
+            if let Some(text) = deadline_text {
+                params["deadline_text"] = serde_json::json!(text);
+            }
+            if let Some(criteria) = acceptance_criteria {
+                params["acceptance_criteria"] = serde_json::json!(criteria);
+            }
+            if let Some(capabilities) = required_capabilities {
+                params["required_capabilities"] = serde_json::json!(capabilities);
+            }
             let result = exec.execute_intent(&VoiceIntent {
                 action: operator_core::IntentAction::CreateTask,
                 params,
@@ -1831,23 +2504,44 @@ async fn claim_task(
     }
 }
 
-/// Complete a task on-chain
+/// Complete a task on-chain, proving the work with a manifest of the
+/// delivered files, links and transaction signatures
 #[tauri::command]
 async fn complete_task(
     task_id: String,
+    proof_url: Option<String>,
+    notes: Option<String>,
+    files: Option<Vec<String>>,
+    links: Option<Vec<String>>,
+    signatures: Option<Vec<String>>,
     state: State<'_, AppState>,
 ) -> Result<AsyncResult<ExecutionResult>, String> {
     debug!("[IPC] complete_task: {}", task_id);
 
     let executor = Arc::clone(&state.executor);
+    let db = Arc::clone(&state.db);
 
     let handle = tokio::spawn(async move {
         let exec = executor.read().await;
-        exec.execute_intent(&VoiceIntent {
+        let result = exec.execute_intent(&VoiceIntent {
             action: operator_core::IntentAction::CompleteTask,
-            params: serde_json::json!({ "task_id": task_id }),
+            params: serde_json::json!({
+                "task_id": task_id,
+                "proof_url": proof_url,
+                "notes": notes,
+                "files": files.unwrap_or_default(),
+                "links": links.unwrap_or_default(),
+                "signatures": signatures.unwrap_or_default(),
+            }),
             raw_transcript: None,
-        }).await
+        }).await?;
+
+        // Persist the task and its proof manifest to the local DB
+        if result.success {
+            persist_task_to_db(&db, &result, &IntentAction::CompleteTask).await;
+        }
+
+        Ok::<_, anyhow::Error>(result)
     });
 
     match handle.await {
@@ -1883,6 +2577,29 @@ async fn cancel_task(
     }
 }
 
+/// Approve a worker's submission and release escrow (creator only).
+/// Goes through the same policy path as execute_intent: the result is the
+/// typed confirmation request, and escrow is only released once its ticket
+/// is presented to execute_confirmed.
+#[tauri::command]
+async fn approve_completion(
+    task_id: String,
+    state: State<'_, AppState>,
+) -> Result<AsyncResult<ExecutionResult>, String> {
+    debug!("[IPC] approve_completion: {}", task_id);
+
+    let intent = VoiceIntent {
+        action: operator_core::IntentAction::ApproveCompletion,
+        params: serde_json::json!({ "task_id": task_id }),
+        raw_transcript: None,
+    };
+
+    let started_at = chrono::Utc::now().timestamp();
+    let (policy_check, executor, response) = run_intent(&state, &intent).await;
+    record_audit(&state, &intent, &policy_check, false, executor, &response, started_at).await;
+    response
+}
+
 /// Get status of a specific task
 #[tauri::command]
 async fn get_task_status(
@@ -1913,7 +2630,7 @@ async fn get_task_status(
 // Tauri Commands - Policy (Fast, In-Memory)
 // ============================================================================
 
-/// Check policy - fast in-memory check, no spawn needed
+/// Check policy - in-memory check (refreshes token prices first when needed)
 #[tauri::command]
 async fn check_policy(
     state: State<'_, AppState>,
@@ -1922,10 +2639,25 @@ async fn check_policy(
     let intent: VoiceIntent = serde_json::from_str(&intent_json)
         .map_err(|e| format!("Parse error: {}", e))?;
 
+    refresh_policy_prices(&state, &intent).await;
     let policy = state.policy.read().await;
     Ok(policy.check_policy(&intent))
 }
 
+/// Explain a policy decision (which rule fired) for display in the HUD
+#[tauri::command]
+async fn explain_policy(
+    state: State<'_, AppState>,
+    intent_json: String,
+) -> Result<PolicyExplanation, String> {
+    let intent: VoiceIntent = serde_json::from_str(&intent_json)
+        .map_err(|e| format!("Parse error: {}", e))?;
+
+    refresh_policy_prices(&state, &intent).await;
+    let policy = state.policy.read().await;
+    Ok(policy.explain(&intent))
+}
+
 /// Get session spending - fast in-memory read
 #[tauri::command]
 async fn get_session_spending(state: State<'_, AppState>) -> Result<f64, String> {
@@ -2060,33 +2792,171 @@ async fn get_voice_token() -> Result<AsyncResult<String>, String> {
 // Tauri Commands - Configuration (Mixed)
 // ============================================================================
 
-/// Update RPC endpoint - spawns executor recreation
+/// Update the primary RPC endpoint. The shared pool swaps it in place, so
+/// every subsystem moves over without being rebuilt.
 #[tauri::command]
-async fn set_rpc_url(state: State<'_, AppState>, rpc_url: String) -> Result<(), String> {
+async fn set_rpc_url(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    rpc_url: String,
+) -> Result<(), String> {
     info!("[IPC] set_rpc_url: {}", rpc_url);
 
-    let config = Arc::clone(&state.config);
-    let executor = Arc::clone(&state.executor);
-    let url = rpc_url.clone();
+    // Edited endpoints are saved into the active profile
+    let profile = {
+        let mut cfg = state.config.write().await;
+        cfg.rpc_url = rpc_url;
+        let profile = cfg.active_profile();
+        cfg.save_profile(profile.clone());
+        profile
+    };
+    set_active_profile(profile.clone()).map_err(|e| e.to_string())?;
+    apply_rpc_endpoints(app, &state, profile.rpc_endpoints).await?;
+    persist_network_to_db(&state.db, &*state.config.read().await).await;
+    Ok(())
+}
 
-    // Spawn config update (writes can be slow if contested)
-    let handle = tokio::spawn(async move {
-        let mut cfg = config.write().await;
-        cfg.rpc_url = url.clone();
+/// Replace the fallback endpoints tried after the primary RPC URL
+#[tauri::command]
+async fn set_rpc_fallbacks(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    endpoints: Vec<RpcEndpoint>,
+) -> Result<(), String> {
+    info!("[IPC] set_rpc_fallbacks: {} endpoints", endpoints.len());
+
+    // Edited endpoints are saved into the active profile
+    let profile = {
+        let mut cfg = state.config.write().await;
+        cfg.rpc_fallbacks = endpoints;
+        let profile = cfg.active_profile();
+        cfg.save_profile(profile.clone());
+        profile
+    };
+    set_active_profile(profile.clone()).map_err(|e| e.to_string())?;
+    apply_rpc_endpoints(app, &state, profile.rpc_endpoints).await?;
+    persist_network_to_db(&state.db, &*state.config.read().await).await;
+    Ok(())
+}
 
-        let new_executor = SolanaExecutor::new(&url, &cfg.network);
-        *executor.write().await = new_executor;
-    });
+/// Built-in and saved network profiles, with the active one's name
+#[tauri::command]
+async fn list_network_profiles(state: State<'_, AppState>) -> Result<AsyncResult<NetworkProfiles>, String> {
+    debug!("[IPC] list_network_profiles");
+    let cfg = state.config.read().await;
+    let mut profiles = NetworkProfile::all(&cfg.network_profiles);
+    // The active profile may carry endpoints edited since it was saved
+    let active = cfg.active_profile();
+    if let Some(p) = profiles.iter_mut().find(|p| p.name == active.name) {
+        *p = active;
+    }
+    Ok(AsyncResult::ok(NetworkProfiles { active: cfg.network.clone(), profiles }))
+}
+
+/// Switch clusters: program ID, mints and RPC endpoints all follow the
+/// selected profile
+#[tauri::command]
+async fn set_network_profile(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<AsyncResult<NetworkProfile>, String> {
+    info!("[IPC] set_network_profile: {}", name);
+
+    let profile = {
+        let cfg = state.config.read().await;
+        match NetworkProfile::resolve(&name, &cfg.network_profiles) {
+            Some(profile) => profile,
+            None => return Ok(AsyncResult::err(format!("Unknown network profile: {}", name))),
+        }
+    };
+    if let Err(e) = set_active_profile(profile.clone()) {
+        return Ok(AsyncResult::err(e.to_string()));
+    }
+    state.config.write().await.use_profile(&profile);
+    apply_rpc_endpoints(app, &state, profile.rpc_endpoints.clone()).await?;
+    // Tiers were read against the old cluster's TETSUO mint
+    if let Some(gate) = state.access_gate.read().await.as_ref() {
+        gate.clear_cache().await;
+    }
+    persist_network_to_db(&state.db, &*state.config.read().await).await;
+    Ok(AsyncResult::ok(profile))
+}
+
+/// Save a custom profile, or override a built-in one by name. Saving the
+/// active profile applies it immediately.
+#[tauri::command]
+async fn save_network_profile(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile: NetworkProfile,
+) -> Result<AsyncResult<NetworkProfile>, String> {
+    info!("[IPC] save_network_profile: {}", profile.name);
+
+    if let Err(e) = profile.validate() {
+        return Ok(AsyncResult::err(e.to_string()));
+    }
+    let active = {
+        let mut cfg = state.config.write().await;
+        cfg.save_profile(profile.clone());
+        cfg.network == profile.name
+    };
+    if active {
+        if let Err(e) = set_active_profile(profile.clone()) {
+            return Ok(AsyncResult::err(e.to_string()));
+        }
+        state.config.write().await.use_profile(&profile);
+        apply_rpc_endpoints(app, &state, profile.rpc_endpoints.clone()).await?;
+        if let Some(gate) = state.access_gate.read().await.as_ref() {
+            gate.clear_cache().await;
+        }
+    }
+    persist_network_to_db(&state.db, &*state.config.read().await).await;
+    Ok(AsyncResult::ok(profile))
+}
 
-    handle.await.map_err(|e| format!("Task failed: {}", e))?;
+/// Network profiles offered to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkProfiles {
+    pub active: String,
+    pub profiles: Vec<NetworkProfile>,
+}
+
+/// Point the pool at `endpoints`, drop cached tasks from the old cluster and
+/// move a running task feed over
+async fn apply_rpc_endpoints(
+    app: tauri::AppHandle,
+    state: &AppState,
+    endpoints: Vec<RpcEndpoint>,
+) -> Result<(), String> {
+    state.rpc_pool.set_endpoints(endpoints).map_err(|e| e.to_string())?;
+    state.executor.read().await.invalidate_task_cache().await;
+
+    let pool = Arc::clone(&state.rpc_pool);
+    tokio::spawn(async move { pool.refresh_health().await });
+
+    let mut watcher = state.task_watcher.write().await;
+    if let Some(old) = watcher.take() {
+        old.stop();
+        *watcher = Some(spawn_task_watcher(app, Arc::clone(&state.executor), Arc::clone(&state.rpc_pool)));
+    }
     Ok(())
 }
 
+/// Latency, error rate and slot lag of every RPC endpoint, for the HUD
+#[tauri::command]
+async fn get_rpc_pool_stats(state: State<'_, AppState>) -> Result<AsyncResult<RpcPoolStats>, String> {
+    debug!("[IPC] get_rpc_pool_stats");
+    Ok(AsyncResult::ok(state.rpc_pool.stats()))
+}
+
 /// Public config subset — secrets stripped for frontend consumption.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublicAppConfig {
     pub rpc_url: String,
+    pub rpc_fallbacks: Vec<RpcEndpoint>,
     pub network: String,
+    pub network_profile: NetworkProfile,
     pub whisper_model_path: Option<String>,
     pub qdrant_url: Option<String>,
     pub twitter_client_id: Option<String>,
@@ -2095,13 +2965,17 @@ pub struct PublicAppConfig {
     pub email_from_name: Option<String>,
     pub github_default_owner: Option<String>,
     pub github_default_repo: Option<String>,
+    pub wallet_auto_lock_secs: Option<u64>,
+    pub description_gateway_url: Option<String>,
 }
 
 impl From<&AppConfig> for PublicAppConfig {
     fn from(cfg: &AppConfig) -> Self {
         Self {
             rpc_url: cfg.rpc_url.clone(),
+            rpc_fallbacks: cfg.rpc_fallbacks.clone(),
             network: cfg.network.clone(),
+            network_profile: cfg.active_profile(),
             whisper_model_path: cfg.whisper_model_path.clone(),
             qdrant_url: cfg.qdrant_url.clone(),
             twitter_client_id: cfg.twitter_client_id.clone(),
@@ -2110,6 +2984,8 @@ impl From<&AppConfig> for PublicAppConfig {
             email_from_name: cfg.email_from_name.clone(),
             github_default_owner: cfg.github_default_owner.clone(),
             github_default_repo: cfg.github_default_repo.clone(),
+            wallet_auto_lock_secs: cfg.wallet_auto_lock_secs,
+            description_gateway_url: cfg.description_gateway_url.clone(),
         }
     }
 }
@@ -2153,24 +3029,43 @@ async fn refresh_state_background(state: State<'_, AppState>) -> Result<(), Stri
 // Tauri Commands - Access Control (Token Gating)
 // ============================================================================
 
-/// Get user's access tier based on $TETSUO holdings
+/// Get user's access tier based on $TETSUO holdings.
+/// `wallet` names a wallet profile to look up instead of `wallet_pubkey`.
 #[tauri::command]
 async fn get_access_tier(
     state: State<'_, AppState>,
     wallet_pubkey: String,
+    wallet: Option<String>,
 ) -> Result<AsyncResult<AccessTierInfo>, String> {
-    debug!("[IPC] get_access_tier for {}", wallet_pubkey);
+    debug!("[IPC] get_access_tier for {} (wallet={:?})", wallet_pubkey, wallet);
+
+    let wallet_pubkey = match wallet.as_deref() {
+        Some(name) => {
+            let executor = state.executor.read().await;
+            match executor.wallet_pubkey(Some(name)).await {
+                Some(pubkey) => pubkey.to_string(),
+                None => return Ok(AsyncResult::err(format!("Unknown wallet profile '{}'", name))),
+            }
+        }
+        None => wallet_pubkey,
+    };
 
     let access_gate = state.access_gate.read().await;
 
     match access_gate.as_ref() {
         Some(gate) => {
-            let wallet = Pubkey::from_str(&wallet_pubkey)
+            let wallet_key = Pubkey::from_str(&wallet_pubkey)
                 .map_err(|e| format!("Invalid pubkey: {}", e))?;
 
-            match gate.get_access_tier_info(&wallet).await {
+            match gate.get_access_tier_info(&wallet_key).await {
                 Ok(info) => {
                     info!("[IPC] Access tier for {}: {:?}", wallet_pubkey, info.tier);
+                    // Keep tier-conditioned policy rules in sync with the
+                    // active wallet (not with a profile looked up by name)
+                    let mut policy = state.policy.write().await;
+                    if wallet.is_none() || wallet.as_deref() == policy.active_wallet() {
+                        policy.set_access_tier(Some(info.tier));
+                    }
                     Ok(AsyncResult::ok(info))
                 }
                 Err(e) => {
@@ -2672,7 +3567,13 @@ async fn execute_swap(
                 slippage_bps: slippage_bps.unwrap_or(50),
             };
 
-            match executor.execute_swap(params).await {
+            let chain = state.executor.read().await;
+            let signer = match chain.wallet_signer(None).await {
+                Ok(signer) => signer,
+                Err(e) => return Ok(AsyncResult::err(e.to_string())),
+            };
+
+            match executor.execute_swap_as(params, &signer).await {
                 Ok(signature) => {
                     info!("[IPC] Swap executed: {}", signature);
                     Ok(AsyncResult::ok(signature))
@@ -3365,13 +4266,16 @@ async fn pair_device(
 
     // SECURITY: Extract keypair bytes for HMAC signing (private key as secret).
     // Falls back to None for mobile wallet flow where keypair is external.
-    let keypair_bytes: Option<Vec<u8>> = {
+    let keypair_bytes: Option<Zeroizing<Vec<u8>>> = {
         let exec = state.executor.read().await;
         exec.get_keypair_bytes()
     };
 
     let executor = state.device_executor.read().await;
-    match executor.pair_device(&device, &wallet_pubkey, keypair_bytes.as_deref()).await {
+    match executor
+        .pair_device(&device, &wallet_pubkey, keypair_bytes.as_ref().map(|b| b.as_slice()))
+        .await
+    {
         Ok(result) => {
             if result.success {
                 if let Some(ref paired) = result.device {
@@ -3819,6 +4723,120 @@ async fn get_equipped(
     }
 }
 
+// ============================================================================
+// Autonomous Worker Commands
+// ============================================================================
+
+/// Start the autonomous worker. It claims and completes matching tasks under
+/// the unattended policy until stopped, using Grok for code tasks.
+#[tauri::command]
+async fn start_worker(
+    state: State<'_, AppState>,
+    config: Option<WorkerConfig>,
+) -> Result<AsyncResult<WorkerStatus>, String> {
+    let config = config.unwrap_or_default();
+    info!("[IPC] start_worker (capabilities={:?})", config.capabilities);
+
+    let mut slot = state.worker.write().await;
+    if slot.as_ref().is_some_and(|w| !w.is_finished()) {
+        return Ok(AsyncResult::err("Worker is already running"));
+    }
+    if state.db.read().await.is_none() {
+        return Ok(AsyncResult::err("The worker needs the database for its audit trail"));
+    }
+
+    let Some(api_key) = state.config.read().await.grok_api_key.clone() else {
+        return Ok(AsyncResult::err("No task handlers available — set a Grok API key"));
+    };
+    let worker = match AutonomousWorker::new(config, state.executor.clone(), state.db.clone()) {
+        Ok(worker) => worker.with_handler(Arc::new(GrokCodeExecutor::new(api_key))),
+        Err(e) => return Ok(AsyncResult::err(e.to_string())),
+    };
+
+    let handle = worker.spawn();
+    let status = handle.status().await;
+    *slot = Some(handle);
+    Ok(AsyncResult::ok(status))
+}
+
+/// Ask the worker to stop; it finishes the step it is in
+#[tauri::command]
+async fn stop_worker(state: State<'_, AppState>) -> Result<AsyncResult<bool>, String> {
+    info!("[IPC] stop_worker");
+
+    match state.worker.read().await.as_ref() {
+        Some(worker) => {
+            worker.stop();
+            Ok(AsyncResult::ok(true))
+        }
+        None => Ok(AsyncResult::ok(false)),
+    }
+}
+
+#[tauri::command]
+async fn worker_status(state: State<'_, AppState>) -> Result<AsyncResult<WorkerStatus>, String> {
+    debug!("[IPC] worker_status");
+
+    let status = match state.worker.read().await.as_ref() {
+        Some(worker) => worker.status().await,
+        None => WorkerStatus::default(),
+    };
+    Ok(AsyncResult::ok(status))
+}
+
+// ============================================================================
+// Live Task Feed
+// ============================================================================
+
+/// Watch task accounts on the cluster behind `rpc` and forward every change
+/// to the frontend as a `task_event`. Each event also drops the executor's task snapshot so
+/// the next listing reflects it.
+fn spawn_task_watcher(
+    app: tauri::AppHandle,
+    executor: Arc<RwLock<SolanaExecutor>>,
+    rpc: Arc<RpcPool>,
+) -> TaskWatcherHandle {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let handle = TaskWatcher::new(rpc).spawn(tx);
+
+    tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            debug!("[TaskFeed] {:?} {}", event.kind, event.pda);
+            executor.read().await.invalidate_task_cache().await;
+            if let Err(e) = app.emit("task_event", &event) {
+                warn!("[TaskFeed] Failed to emit task event: {}", e);
+            }
+        }
+    });
+    handle
+}
+
+/// Start the live task feed (no-op if it is already running)
+#[tauri::command]
+async fn watch_tasks(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<AsyncResult<bool>, String> {
+    info!("[IPC] watch_tasks");
+
+    let mut watcher = state.task_watcher.write().await;
+    if watcher.as_ref().is_some_and(|w| !w.is_finished()) {
+        return Ok(AsyncResult::ok(true));
+    }
+    *watcher = Some(spawn_task_watcher(app, Arc::clone(&state.executor), Arc::clone(&state.rpc_pool)));
+    Ok(AsyncResult::ok(true))
+}
+
+#[tauri::command]
+async fn unwatch_tasks(state: State<'_, AppState>) -> Result<AsyncResult<bool>, String> {
+    info!("[IPC] unwatch_tasks");
+
+    match state.task_watcher.write().await.take() {
+        Some(watcher) => {
+            watcher.stop();
+            Ok(AsyncResult::ok(true))
+        }
+        None => Ok(AsyncResult::ok(false)),
+    }
+}
+
 // ============================================================================
 // Database Commands (Phase 5)
 // ============================================================================
@@ -3839,6 +4857,7 @@ async fn db_list_tasks(
                 "completed" => Some(DbTaskStatus::Completed),
                 "disputed" => Some(DbTaskStatus::Disputed),
                 "resolved" => Some(DbTaskStatus::Resolved),
+                "failed" => Some(DbTaskStatus::Failed),
                 _ => None,
             });
 
@@ -3961,15 +4980,12 @@ async fn get_wallet_balance_by_address(
     address: String,
     state: State<'_, AppState>,
 ) -> Result<AsyncResult<f64>, String> {
-    let config = state.config.read().await;
-    let rpc_url = config.rpc_url.clone();
-    drop(config);
+    let pool = Arc::clone(&state.rpc_pool);
 
     let result = tokio::spawn(async move {
-        let client = solana_client::nonblocking::rpc_client::RpcClient::new(rpc_url);
         let pubkey = Pubkey::from_str(&address)
             .map_err(|e| format!("Invalid address: {}", e))?;
-        let lamports = client.get_balance(&pubkey).await
+        let lamports = pool.read(|rpc| async move { Ok(rpc.get_balance(&pubkey).await?) }).await
             .map_err(|e| format!("RPC error: {}", e))?;
         Ok::<f64, String>(lamports as f64 / 1_000_000_000.0)
     }).await.map_err(|e| format!("Task join error: {}", e))?;
@@ -3980,6 +4996,12 @@ async fn get_wallet_balance_by_address(
     }
 }
 
+/// Treasury named in the protocol config, paid the fee on settlement
+async fn protocol_treasury(state: &AppState) -> anyhow::Result<Pubkey> {
+    use operator_core::agenc_program::fetch_protocol_config;
+    state.rpc_pool.read(|rpc| async move { fetch_protocol_config(&rpc).await }).await?.treasury_pubkey()
+}
+
 /// Build an unsigned transaction for a given intent. Mobile path: frontend
 /// gets the unsigned bytes, passes them to MWA for signing, then sends.
 /// This reuses the same instruction builders as the desktop execute_intent path
@@ -3991,12 +5013,14 @@ async fn build_unsigned_transaction(
     state: State<'_, AppState>,
 ) -> Result<AsyncResult<Vec<u8>>, String> {
     use operator_core::agenc_program::{
-        build_create_task_ix, build_claim_task_ix, build_complete_task_ix,
-        build_skr_escrow_deposit_ix, derive_task_pda, fetch_task_by_id,
-        display_to_skr_tokens,
+        build_create_task_ix, build_claim_task_ix, build_complete_task_ixs,
+        build_approve_completion_ixs, build_cancel_task_ixs, build_skr_escrow_deposit_ix,
+        derive_task_pda, fetch_task_by_pda, display_to_skr_tokens, next_task_id, OnChainTaskState,
+    };
+    use operator_core::{
+        capability_mask, completion_items, CreateTaskParams, ClaimTaskParams, CompleteTaskParams,
+        CancelTaskParams, ApproveCompletionParams, TaskDescription,
     };
-    use operator_core::{CreateTaskParams, ClaimTaskParams, CompleteTaskParams};
-    use sha2::{Sha256, Digest};
     use solana_sdk::message::Message;
     use solana_sdk::transaction::Transaction;
 
@@ -4014,6 +5038,11 @@ async fn build_unsigned_transaction(
         Ok(i) => i,
         Err(e) => return Ok(AsyncResult::err(format!("Invalid intent JSON: {}", e))),
     };
+    if intent.action.uses_agenc_program() {
+        if let Err(e) = operator_core::network::ensure_configured() {
+            return Ok(AsyncResult::err(e.to_string()));
+        }
+    }
 
     // Build instructions based on action
     let instructions = match intent.action {
@@ -4024,15 +5053,51 @@ async fn build_unsigned_transaction(
             };
 
             let reward_lamports = (parsed.reward_sol * 1_000_000_000.0) as u64;
-            let task_id_num = chrono::Utc::now().timestamp_millis() as u64;
-            let description_hash: [u8; 32] = Sha256::digest(parsed.description.as_bytes()).into();
+            let required_capabilities = match capability_mask(&parsed.required_capabilities) {
+                Ok(mask) => mask,
+                Err(e) => return Ok(AsyncResult::err(e.to_string())),
+            };
+
+            // The program seeds the task PDA with its own counter
+            let task_id_num = match state.rpc_pool.read(|rpc| async move { next_task_id(&rpc).await }).await {
+                Ok((id, _)) => id,
+                Err(e) => return Ok(AsyncResult::err(format!("Task id error: {}", e))),
+            };
             let deadline = parsed.deadline_hours.map(|h|
                 chrono::Utc::now().timestamp() + (h as i64 * 3600)
             ).unwrap_or(0);
 
-            let create_ix = build_create_task_ix(
-                task_id_num, &payer, description_hash, reward_lamports, deadline, 0,
-            );
+            // Same document and registration as the desktop create_task, so
+            // the task resolves to its description once listed
+            let deadline_text = parsed.deadline_text.clone().or_else(|| {
+                chrono::DateTime::from_timestamp(deadline, 0)
+                    .filter(|_| deadline > 0)
+                    .map(|dt| dt.to_rfc3339())
+            });
+            let document = TaskDescription {
+                description: parsed.description.clone(),
+                tags: parsed.tags.clone(),
+                deadline_text,
+                acceptance_criteria: parsed.acceptance_criteria.clone(),
+                created_at: chrono::Utc::now().timestamp(),
+            };
+            let registry = state.executor.read().await.description_registry().await;
+            let stored = match &registry {
+                Some(registry) => registry.store(&document).await,
+                None => document.hash(),
+            };
+            let description_hash = match stored {
+                Ok(hash) => hash,
+                Err(e) => return Ok(AsyncResult::err(format!("Task description error: {}", e))),
+            };
+
+            let create_ix = match build_create_task_ix(
+                task_id_num, &payer, description_hash, reward_lamports, deadline,
+                required_capabilities,
+            ) {
+                Ok(ix) => ix,
+                Err(e) => return Ok(AsyncResult::err(e.to_string())),
+            };
             let mut ixs = vec![create_ix];
 
             // Add SKR escrow deposit if SKR reward specified
@@ -4067,7 +5132,10 @@ async fn build_unsigned_transaction(
             };
 
             let agent_id: [u8; 32] = payer.to_bytes();
-            vec![build_claim_task_ix(&task_pda, &payer, agent_id)]
+            match build_claim_task_ix(&task_pda, &payer, agent_id) {
+                Ok(ix) => vec![ix],
+                Err(e) => return Ok(AsyncResult::err(e.to_string())),
+            }
         }
 
         IntentAction::CompleteTask => {
@@ -4076,76 +5144,130 @@ async fn build_unsigned_transaction(
                 Err(e) => return Ok(AsyncResult::err(format!("Invalid complete task params: {}", e))),
             };
 
-            let (task_pda, task_id_num) = if let Ok(id) = parsed.task_id.parse::<u64>() {
-                (derive_task_pda(id).0, Some(id))
+            let task_pda = if let Ok(id) = parsed.task_id.parse::<u64>() {
+                derive_task_pda(id).0
             } else {
                 match Pubkey::from_str(&parsed.task_id) {
-                    Ok(pk) => (pk, None),
+                    Ok(pk) => pk,
                     Err(_) => return Ok(AsyncResult::err("Invalid task ID".to_string())),
                 }
             };
 
-            // Check on-chain task for SKR reward
-            let has_skr = if let Some(id) = task_id_num {
-                let config = state.config.read().await;
-                let rpc_url = config.rpc_url.clone();
-                drop(config);
-                let rpc = solana_client::nonblocking::rpc_client::RpcClient::new(rpc_url);
-                match fetch_task_by_id(&rpc, id).await {
-                    Ok(Some(task)) => task.reward_skr_tokens > 0,
-                    _ => false,
-                }
-            } else {
-                false
+            let task = match state.rpc_pool.read(|rpc| async move { fetch_task_by_pda(&rpc, &task_pda).await }).await {
+                Ok(Some(task)) => task,
+                Ok(None) => return Ok(AsyncResult::err(format!("Task {} not found on-chain", parsed.task_id))),
+                Err(e) => return Ok(AsyncResult::err(format!("Failed to fetch task: {}", e))),
             };
+            let has_skr = task.reward_skr_tokens > 0;
 
-            let timestamp = chrono::Utc::now().timestamp() as u64;
-            let mut hasher = Sha256::new();
-            hasher.update(task_pda.as_ref());
-            hasher.update(payer.as_ref());
-            hasher.update(&timestamp.to_le_bytes());
-            let proof_hash: [u8; 32] = hasher.finalize().into();
+            // Same manifest as the desktop path; its root is the proof hash
+            let manifest = match completion_items(&task.description_hash, &parsed)
+                .and_then(|items| ProofManifest::new(&task_pda, &payer, items))
+            {
+                Ok(manifest) => manifest,
+                Err(e) => return Ok(AsyncResult::err(format!("Failed to build proof manifest: {}", e))),
+            };
+            let proof_hash = match manifest.root_bytes() {
+                Ok(hash) => hash,
+                Err(e) => return Ok(AsyncResult::err(e.to_string())),
+            };
+
+            // Stored unsubmitted: the wallet signs and sends this transaction
+            if let Some(db) = state.db.read().await.as_ref() {
+                let stored = manifest.to_verification_log(None).and_then(|proof| db.store_proof(&proof));
+                if let Err(e) = stored {
+                    warn!("[DB] Failed to persist proof for task {}: {}", manifest.task, e);
+                }
+            }
 
-            vec![build_complete_task_ix(&task_pda, &payer, proof_hash, None, has_skr)]
+            let treasury = match protocol_treasury(&state).await {
+                Ok(treasury) => treasury,
+                Err(e) => return Ok(AsyncResult::err(format!("Protocol config error: {}", e))),
+            };
+            match build_complete_task_ixs(&task_pda, &payer, &treasury, proof_hash, None, has_skr) {
+                Ok(ixs) => ixs,
+                Err(e) => return Ok(AsyncResult::err(e.to_string())),
+            }
         }
 
         IntentAction::CancelTask => {
-            // Cancel uses the same PDA derivation; on-chain program verifies creator
-            let task_id: String = intent.params.get("task_id")
-                .and_then(|v| v.as_str())
-                .unwrap_or_default()
-                .to_string();
+            let parsed: CancelTaskParams = match serde_json::from_value(intent.params) {
+                Ok(p) => p,
+                Err(e) => return Ok(AsyncResult::err(format!("Invalid cancel task params: {}", e))),
+            };
 
-            if task_id.is_empty() {
-                return Ok(AsyncResult::err("Missing task_id for cancel".to_string()));
+            let task_pda = if let Ok(id) = parsed.task_id.parse::<u64>() {
+                derive_task_pda(id).0
+            } else {
+                match Pubkey::from_str(&parsed.task_id) {
+                    Ok(pk) => pk,
+                    Err(_) => return Ok(AsyncResult::err("Invalid task ID".to_string())),
+                }
+            };
+
+            // Only the creator can cancel, and only while the task is open
+            let task = match state.rpc_pool.read(|rpc| async move { fetch_task_by_pda(&rpc, &task_pda).await }).await {
+                Ok(Some(task)) => task,
+                Ok(None) => return Ok(AsyncResult::err(format!("Task {} not found on-chain", parsed.task_id))),
+                Err(e) => return Ok(AsyncResult::err(format!("Failed to fetch task: {}", e))),
+            };
+            if task.creator != payer.to_string() {
+                return Ok(AsyncResult::err("Only the task creator can cancel this task".to_string()));
+            }
+            if task.state != OnChainTaskState::Open {
+                return Ok(AsyncResult::err(format!(
+                    "Task is {} — only open tasks can be cancelled", task.state.label()
+                )));
             }
 
-            let task_pda = if let Ok(id) = task_id.parse::<u64>() {
+            match build_cancel_task_ixs(&task_pda, &payer, task.reward_skr_tokens > 0) {
+                Ok(ixs) => ixs,
+                Err(e) => return Ok(AsyncResult::err(e.to_string())),
+            }
+        }
+
+        IntentAction::ApproveCompletion => {
+            let parsed: ApproveCompletionParams = match serde_json::from_value(intent.params) {
+                Ok(p) => p,
+                Err(e) => return Ok(AsyncResult::err(format!("Invalid approve completion params: {}", e))),
+            };
+
+            let task_pda = if let Ok(id) = parsed.task_id.parse::<u64>() {
                 derive_task_pda(id).0
             } else {
-                match Pubkey::from_str(&task_id) {
+                match Pubkey::from_str(&parsed.task_id) {
                     Ok(pk) => pk,
                     Err(_) => return Ok(AsyncResult::err("Invalid task ID".to_string())),
                 }
             };
 
-            // cancel_task instruction uses same discriminator pattern
-            let disc = operator_core::agenc_program::instruction_discriminator("cancel_task");
-            let data = disc.to_vec();
-            let (escrow_pda, _) = operator_core::agenc_program::derive_escrow_pda(&task_pda);
-
-            vec![solana_sdk::instruction::Instruction {
-                program_id: operator_core::agenc_program::program_id(),
-                accounts: vec![
-                    solana_sdk::instruction::AccountMeta::new(task_pda, false),
-                    solana_sdk::instruction::AccountMeta::new(escrow_pda, false),
-                    solana_sdk::instruction::AccountMeta::new(payer, true),
-                    solana_sdk::instruction::AccountMeta::new_readonly(
-                        solana_sdk::system_program::id(), false,
-                    ),
-                ],
-                data,
-            }]
+            // Only the creator can approve, and only once a result is submitted
+            let task = match state.rpc_pool.read(|rpc| async move { fetch_task_by_pda(&rpc, &task_pda).await }).await {
+                Ok(Some(task)) => task,
+                Ok(None) => return Ok(AsyncResult::err(format!("Task {} not found on-chain", parsed.task_id))),
+                Err(e) => return Ok(AsyncResult::err(format!("Failed to fetch task: {}", e))),
+            };
+            if task.creator != payer.to_string() {
+                return Ok(AsyncResult::err("Only the task creator can approve this task".to_string()));
+            }
+            if task.state != OnChainTaskState::PendingValidation {
+                return Ok(AsyncResult::err(format!(
+                    "Task is {} — only submitted tasks can be approved", task.state.label()
+                )));
+            }
+            let worker = match task.claimed_by.as_deref().map(Pubkey::from_str) {
+                Some(Ok(pk)) => pk,
+                _ => return Ok(AsyncResult::err("Task has no claimer to pay out".to_string())),
+            };
+
+            let treasury = match protocol_treasury(&state).await {
+                Ok(treasury) => treasury,
+                Err(e) => return Ok(AsyncResult::err(format!("Protocol config error: {}", e))),
+            };
+            match build_approve_completion_ixs(&task_pda, &payer, &worker, &treasury, task.reward_skr_tokens > 0) {
+                Ok(ixs) => ixs,
+                Err(e) => return Ok(AsyncResult::err(e.to_string())),
+            }
         }
 
         _ => {
@@ -4156,12 +5278,7 @@ async fn build_unsigned_transaction(
     };
 
     // Get recent blockhash
-    let config = state.config.read().await;
-    let rpc_url = config.rpc_url.clone();
-    drop(config);
-
-    let rpc = solana_client::nonblocking::rpc_client::RpcClient::new(rpc_url);
-    let blockhash = match rpc.get_latest_blockhash().await {
+    let blockhash = match state.rpc_pool.read(|rpc| async move { Ok(rpc.get_latest_blockhash().await?) }).await {
         Ok(bh) => bh,
         Err(e) => return Ok(AsyncResult::err(format!("Failed to get blockhash: {}", e))),
     };
@@ -4206,11 +5323,23 @@ pub fn run() {
     info!("Starting Tetsuo - AgenC Operator (Async-First)");
 
     // Initialize application state
-    let config = AppConfig::default();
-    let executor = SolanaExecutor::new(&config.rpc_url, &config.network);
+    let mut config = AppConfig::default();
+    if let Err(e) = set_active_profile(config.active_profile()) {
+        warn!("Invalid network profile {}: {}", config.network, e);
+    }
+    let rpc_pool = match RpcPool::new(config.rpc_endpoints()) {
+        Ok(pool) => Arc::new(pool),
+        Err(e) => {
+            warn!("Invalid RPC endpoints: {} - using {} only", e, config.rpc_url);
+            Arc::new(RpcPool::single(&config.rpc_url))
+        }
+    };
+    let executor = SolanaExecutor::with_pool(Arc::clone(&rpc_pool), &config.network);
+    let auto_lock = config.wallet_auto_lock_secs.map(std::time::Duration::from_secs);
+    tauri::async_runtime::block_on(executor.set_auto_lock(auto_lock));
 
     // Initialize access gate (token gating)
-    let access_gate = match AccessGate::new(&config.rpc_url) {
+    let access_gate = match AccessGate::with_pool(Arc::clone(&rpc_pool)) {
         Ok(gate) => {
             info!("Access gate initialized for token gating");
             Some(gate)
@@ -4231,7 +5360,7 @@ pub fn run() {
         GrokCodeExecutor::new(key.clone())
     });
 
-    let swap_executor = Some(JupiterSwapExecutor::new(&config.rpc_url));
+    let swap_executor = Some(JupiterSwapExecutor::with_pool(Arc::clone(&rpc_pool)));
     info!("Swap executor initialized for Jupiter");
 
     // Try to load Twitter tokens from keyring (OAuth 2.0)
@@ -4313,6 +5442,12 @@ pub fn run() {
                 Err(e) => warn!("Startup prune failed for sessions: {}", e),
                 _ => {}
             }
+            // Spend ledger must outlive the longest policy window (30 days)
+            match db.prune_spends(90) {
+                Ok(n) if n > 0 => info!("Startup prune: removed {} old spend entries", n),
+                Err(e) => warn!("Startup prune failed for spend ledger: {}", e),
+                _ => {}
+            }
             // Load saved config if available
             match db.get_config() {
                 Ok(Some(saved_config)) => {
@@ -4320,6 +5455,25 @@ pub fn run() {
                         "Loaded saved config: wallet={:?}, network={}",
                         saved_config.wallet_pubkey, saved_config.network
                     );
+                    // Restore the selected network profile over the startup default;
+                    // configs saved before profiles only carry the cluster name
+                    config.network_profiles = saved_config.network_profiles;
+                    let saved_name = saved_config.network_profile.unwrap_or(saved_config.network);
+                    let saved_profile = NetworkProfile::resolve(&saved_name, &config.network_profiles);
+                    if saved_profile.is_none() {
+                        error!(
+                            "Saved network '{}' has no profile - staying on {}; add a '{}' profile to use it",
+                            saved_name, config.network, saved_name
+                        );
+                    }
+                    if let Some(profile) = saved_profile {
+                        match set_active_profile(profile.clone())
+                            .and_then(|_| rpc_pool.set_endpoints(profile.rpc_endpoints.clone()))
+                        {
+                            Ok(()) => config.use_profile(&profile),
+                            Err(e) => warn!("Failed to restore network profile {}: {}", profile.name, e),
+                        }
+                    }
                 }
                 Ok(None) => debug!("No saved config found"),
                 Err(e) => warn!("Failed to load saved config: {}", e),
@@ -4336,9 +5490,38 @@ pub fn run() {
     let session_id = format!("session_{}", chrono::Utc::now().timestamp_millis());
     info!("Session ID: {}", session_id);
 
+    // Restore the rolling spend ledger so limits survive restarts
+    let mut policy_gate = PolicyGate::new();
+    match PolicyRules::load_user_rules() {
+        Ok(Some(rules)) => policy_gate.set_rules(rules),
+        Ok(None) => debug!("No user policy rules, using defaults"),
+        Err(e) => warn!("Ignoring user policy rules: {}", e),
+    }
+    if let Some(db) = operator_db.as_ref() {
+        let since = chrono::Utc::now().timestamp() - 30 * 86400;
+        match db.list_spends(Some(since)) {
+            Ok(records) => policy_gate.load_ledger(records),
+            Err(e) => warn!("Failed to load spend ledger: {}", e),
+        }
+        restore_wallet_profiles(db, &executor, &mut policy_gate);
+    }
+
+    // Task descriptions share the operator DB; the gateway lets other
+    // operators' tasks resolve too
+    let operator_db = Arc::new(RwLock::new(operator_db));
+    let mut descriptions = DescriptionRegistry::new(Arc::clone(&operator_db));
+    if let Some(url) = config.description_gateway_url.as_deref() {
+        info!("Task descriptions resolve via gateway {}", url);
+        descriptions = descriptions.with_gateway(Arc::new(HttpDescriptionGateway::new(url)));
+    }
+    tauri::async_runtime::block_on(executor.set_description_registry(Arc::new(descriptions)));
+    let history = WalletHistory::new(Arc::clone(&rpc_pool), Arc::clone(&operator_db));
+    tauri::async_runtime::block_on(executor.set_wallet_history(Arc::new(history)));
+
     let state = AppState {
         executor: Arc::new(RwLock::new(executor)),
-        policy: Arc::new(RwLock::new(PolicyGate::new())),
+        rpc_pool: Arc::clone(&rpc_pool),
+        policy: Arc::new(RwLock::new(policy_gate)),
         voice_state: Arc::new(RwLock::new(VoiceState::Idle)),
         config: Arc::new(RwLock::new(config)),
         access_gate: Arc::new(RwLock::new(access_gate)),
@@ -4358,11 +5541,34 @@ pub fn run() {
         // AgenC One: Device executor
         device_executor: Arc::new(RwLock::new(device_executor)),
         // Phase 5: Embedded database
-        db: Arc::new(RwLock::new(operator_db)),
+        db: operator_db,
+        worker: Arc::new(RwLock::new(None)),
+        task_watcher: Arc::new(RwLock::new(None)),
         // Session tracking
         session_id,
     };
 
+    // Keep endpoint health fresh so reads route around slow or lagging nodes
+    tauri::async_runtime::spawn(async move {
+        let mut tick = tokio::time::interval(std::time::Duration::from_secs(RPC_HEALTH_INTERVAL_SECS));
+        loop {
+            tick.tick().await;
+            rpc_pool.refresh_health().await;
+        }
+    });
+
+    // Zeroize idle wallet keys even when nothing tries to sign
+    if auto_lock.is_some() {
+        let executor = Arc::clone(&state.executor);
+        tauri::async_runtime::spawn(async move {
+            let mut tick = tokio::time::interval(std::time::Duration::from_secs(30));
+            loop {
+                tick.tick().await;
+                executor.read().await.lock_if_idle().await;
+            }
+        });
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         // NOTE: Updater disabled until plugins.updater is configured in tauri.conf.json
@@ -4372,6 +5578,15 @@ pub fn run() {
             // Wallet (async spawned)
             load_wallet,
             get_wallet_info,
+            // Wallet profiles
+            add_wallet_profile,
+            list_wallet_profiles,
+            switch_wallet,
+            remove_wallet_profile,
+            // Encrypted keystore
+            unlock_keystore,
+            lock_wallet,
+            is_wallet_locked,
             // Intent execution (async spawned)
             execute_intent,
             execute_confirmed,
@@ -4384,9 +5599,11 @@ pub fn run() {
             claim_task,
             complete_task,
             cancel_task,
+            approve_completion,
             get_task_status,
             // Policy (fast in-memory)
             check_policy,
+            explain_policy,
             get_session_spending,
             // Voice state (fast in-memory)
             set_voice_state,
@@ -4445,6 +5662,11 @@ pub fn run() {
             send_device_command,
             // Config
             set_rpc_url,
+            set_rpc_fallbacks,
+            list_network_profiles,
+            set_network_profile,
+            save_network_profile,
+            get_rpc_pool_stats,
             get_config,
             // Store / Marketplace
             list_store_items,
@@ -4455,6 +5677,12 @@ pub fn run() {
             equip_item,
             unequip_item,
             get_equipped,
+            // Autonomous worker
+            start_worker,
+            stop_worker,
+            worker_status,
+            watch_tasks,
+            unwatch_tasks,
             // Database (Phase 5)
             db_list_tasks,
             db_get_task,
@@ -43,7 +43,7 @@ import { getGlobalExpressionSystem } from './hooks/useExpressionSystem';
 import type { EmotionType } from './hooks/useExpressionSystem';
 
 // Types
-import type { VoiceState, WalletInfo, ProtocolState, AgentStatus, CameraMode } from './types';
+import type { VoiceState, WalletInfo, ProtocolState, AgentStatus, CameraMode, RpcPoolStats } from './types';
 
 // ============================================================================
 // Constants
@@ -92,6 +92,7 @@ function App() {
   const mobile = isMobile();
   const [isFeedOpen, setIsFeedOpen] = useState(false);
   const [isDevicesOpen, setIsDevicesOpen] = useState(false);
+  const [rpcPool, setRpcPool] = useState<RpcPoolStats | null>(null);
   const [showOnboarding, setShowOnboarding] = useState(() => !hasSeenOnboarding());
 
   // Mobile Wallet Adapter (MWA) — only active on Android
@@ -244,6 +245,9 @@ function App() {
         console.warn('[Poll] Protocol state fetch failed:', err.message);
       }
     );
+    TetsuoAPI.config.getRpcPoolStats()
+      .then(setRpcPool)
+      .catch(() => { /* pool stats unavailable */ });
   }, [setProtocolState]);
 
   /**
@@ -533,6 +537,7 @@ function App() {
             color="cyan"
             protocolState={protocolState}
             wallet={wallet}
+            rpcPool={rpcPool}
           />
         </div>
       )}
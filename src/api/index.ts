@@ -9,15 +9,22 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type {
   WalletInfo,
+  WalletProfile,
+  WalletProfileSummary,
   ProtocolState,
   AgencTask,
   ExecutionResult,
   VoiceIntent,
   PolicyCheck,
+  ConfirmationTicket,
+  PolicyExplanation,
   VoiceState,
   AppConfig,
+  NetworkProfile,
+  NetworkProfiles,
   AccessTierInfo,
   Memory,
   UserContext,
@@ -33,6 +40,12 @@ import type {
   StoreItem,
   UserInventory,
   EquippedItems,
+  WorkerConfig,
+  WorkerStatus,
+  TaskFilters,
+  TaskEvent,
+  RpcEndpoint,
+  RpcPoolStats,
 } from '../types';
 
 // ============================================================================
@@ -86,10 +99,10 @@ export const WalletAPI = {
 
   /**
    * Get wallet info (address, balance)
-   * Non-blocking RPC call
+   * Non-blocking RPC call. Pass a profile name for a non-active wallet.
    */
-  getWalletInfo(): Promise<WalletInfo> {
-    return invoke<AsyncResult<WalletInfo>>('get_wallet_info')
+  getWalletInfo(wallet?: string): Promise<WalletInfo> {
+    return invoke<AsyncResult<WalletInfo>>('get_wallet_info', { wallet })
       .then(unwrapResult)
       .catch((err) => {
         console.error('[API] getWalletInfo failed:', err);
@@ -121,6 +134,87 @@ export const WalletAPI = {
         onError?.(err);
       });
   },
+
+  /**
+   * Unlock an encrypted keystore and make it the active wallet
+   * Returns: Promise<string> - wallet address
+   */
+  unlockKeystore(keystorePath: string, passphrase: string): Promise<string> {
+    return invoke<AsyncResult<string>>('unlock_keystore', { keystorePath, passphrase })
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] unlockKeystore failed:', err);
+        throw new TetsuoAPIError(`Failed to unlock keystore: ${err}`);
+      });
+  },
+
+  /**
+   * Drop all loaded keys from memory
+   */
+  lockWallet(): Promise<void> {
+    return invoke<void>('lock_wallet');
+  },
+
+  /**
+   * Whether no key is loaded (never unlocked, locked, or auto-locked after idling)
+   */
+  isWalletLocked(): Promise<boolean> {
+    return invoke<boolean>('is_wallet_locked').catch((err) => {
+      console.error('[API] isWalletLocked failed:', err);
+      return true;
+    });
+  },
+
+  /**
+   * Load a keypair as a named wallet profile (persisted across restarts).
+   * Encrypted keystores need their passphrase; calling this again with the
+   * passphrase unlocks a locked keystore profile.
+   */
+  addWalletProfile(name: string, keypairPath: string, passphrase?: string): Promise<WalletProfile> {
+    return invoke<AsyncResult<WalletProfile>>('add_wallet_profile', { name, keypairPath, passphrase })
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] addWalletProfile failed:', err);
+        throw new TetsuoAPIError(`Failed to add wallet profile: ${err}`);
+      });
+  },
+
+  /**
+   * List loaded wallet profiles and which one is active
+   */
+  listWalletProfiles(): Promise<WalletProfileSummary[]> {
+    return invoke<AsyncResult<WalletProfileSummary[]>>('list_wallet_profiles')
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] listWalletProfiles failed:', err);
+        return [];
+      });
+  },
+
+  /**
+   * Make a wallet profile the active signer
+   * Returns: Promise<string> - wallet address
+   */
+  switchWallet(name: string): Promise<string> {
+    return invoke<AsyncResult<string>>('switch_wallet', { name })
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] switchWallet failed:', err);
+        throw new TetsuoAPIError(`Failed to switch wallet: ${err}`);
+      });
+  },
+
+  /**
+   * Forget a wallet profile (the active profile cannot be removed)
+   */
+  removeWalletProfile(name: string): Promise<boolean> {
+    return invoke<AsyncResult<boolean>>('remove_wallet_profile', { name })
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] removeWalletProfile failed:', err);
+        throw new TetsuoAPIError(`Failed to remove wallet profile: ${err}`);
+      });
+  },
 };
 
 // ============================================================================
@@ -211,12 +305,21 @@ export const IntentAPI = {
   },
 
   /**
-   * Execute after user confirmation
+   * Confirmation request carried by an execute_intent result, if any
+   */
+  confirmationRequired(result: ExecutionResult): PolicyCheck | null {
+    const check = result.data as PolicyCheck | undefined;
+    return check?.requires_confirmation && check.ticket ? check : null;
+  },
+
+  /**
+   * Execute after user confirmation, presenting the ticket from execute_intent.
+   * The intent must be unchanged; tickets are single-use and expire.
    */
-  executeConfirmed(intent: VoiceIntent): Promise<ExecutionResult> {
+  executeConfirmed(intent: VoiceIntent, ticket: ConfirmationTicket): Promise<ExecutionResult> {
     const intentJson = JSON.stringify(intent);
 
-    return invoke<AsyncResult<ExecutionResult>>('execute_confirmed', { intentJson })
+    return invoke<AsyncResult<ExecutionResult>>('execute_confirmed', { intentJson, ticket })
       .then(unwrapResult)
       .catch((err) => {
         console.error('[API] executeConfirmed failed:', err);
@@ -277,9 +380,10 @@ export const ProtocolAPI = {
   /**
    * List tasks with optional status filter
    * @param status - Optional filter by status (open, claimed, completed, cancelled)
+   * @param filters - Reward range, capabilities, creator, sort order and page
    */
-  listTasks(status?: string): Promise<AgencTask[]> {
-    return invoke<AsyncResult<AgencTask[]>>('list_tasks', { status })
+  listTasks(status?: string, filters?: TaskFilters): Promise<AgencTask[]> {
+    return invoke<AsyncResult<AgencTask[]>>('list_tasks', { status, filters })
       .then(unwrapResult)
       .catch((err) => {
         console.error('[API] listTasks failed:', err);
@@ -330,6 +434,14 @@ export const PolicyAPI = {
     return invoke<PolicyCheck>('check_policy', { intentJson });
   },
 
+  /**
+   * Explain which policy rule decides an intent (for HUD display)
+   */
+  explainPolicy(intent: VoiceIntent): Promise<PolicyExplanation> {
+    const intentJson = JSON.stringify(intent);
+    return invoke<PolicyExplanation>('explain_policy', { intentJson });
+  },
+
   /**
    * Get current session spending
    */
@@ -382,12 +494,67 @@ export const VoiceAPI = {
 
 export const ConfigAPI = {
   /**
-   * Set RPC URL (spawns executor recreation)
+   * Set the primary RPC URL; the shared endpoint pool switches in place
    */
   setRpcUrl(rpcUrl: string): Promise<void> {
     return invoke('set_rpc_url', { rpcUrl });
   },
 
+  /**
+   * Replace the fallback endpoints tried after the primary RPC URL
+   */
+  setRpcFallbacks(endpoints: RpcEndpoint[]): Promise<void> {
+    return invoke('set_rpc_fallbacks', { endpoints });
+  },
+
+  /**
+   * Latency, error rate and slot lag of every RPC endpoint
+   */
+  getRpcPoolStats(): Promise<RpcPoolStats> {
+    return invoke<AsyncResult<RpcPoolStats>>('get_rpc_pool_stats')
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] get_rpc_pool_stats failed:', err);
+        throw new TetsuoAPIError(`Failed to get RPC pool stats: ${err}`);
+      });
+  },
+
+  /**
+   * Built-in and saved network profiles, with the active one's name
+   */
+  listNetworkProfiles(): Promise<NetworkProfiles> {
+    return invoke<AsyncResult<NetworkProfiles>>('list_network_profiles')
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] list_network_profiles failed:', err);
+        throw new TetsuoAPIError(`Failed to list network profiles: ${err}`);
+      });
+  },
+
+  /**
+   * Switch clusters; program ID, mints and RPC endpoints follow the profile
+   */
+  setNetworkProfile(name: string): Promise<NetworkProfile> {
+    return invoke<AsyncResult<NetworkProfile>>('set_network_profile', { name })
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] set_network_profile failed:', err);
+        throw new TetsuoAPIError(`Failed to switch network: ${err}`);
+      });
+  },
+
+  /**
+   * Save a custom profile, or override a built-in one by name
+   */
+  saveNetworkProfile(profile: NetworkProfile): Promise<NetworkProfile> {
+    return invoke<AsyncResult<NetworkProfile>>('save_network_profile', { profile })
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] save_network_profile failed:', err);
+        throw new TetsuoAPIError(`Failed to save network profile: ${err}`);
+      });
+  },
+
   /**
    * Get current config
    */
@@ -403,9 +570,10 @@ export const ConfigAPI = {
 export const AccessAPI = {
   /**
    * Get user's access tier based on $TETSUO holdings
+   * Pass a wallet profile name to look that profile up instead.
    */
-  getAccessTier(walletPubkey: string): Promise<AccessTierInfo> {
-    return invoke<AsyncResult<AccessTierInfo>>('get_access_tier', { walletPubkey })
+  getAccessTier(walletPubkey: string, wallet?: string): Promise<AccessTierInfo> {
+    return invoke<AsyncResult<AccessTierInfo>>('get_access_tier', { walletPubkey, wallet })
       .then(unwrapResult)
       .catch((err) => {
         console.error('[API] getAccessTier failed:', err);
@@ -1056,8 +1224,42 @@ export const TaskAPI = {
    * List tasks with optional status filter
    * @param status - Optional filter by status (open, claimed, completed, cancelled)
    */
-  listTasks(status?: string): Promise<AgencTask[]> {
-    return ProtocolAPI.listTasks(status);
+  listTasks(status?: string, filters?: TaskFilters): Promise<AgencTask[]> {
+    return ProtocolAPI.listTasks(status, filters);
+  },
+
+  /**
+   * Subscribe to the live task feed (created/claimed/completed/... events).
+   * Falls back to polling in Rust when the websocket is down.
+   * @returns Function that removes this listener
+   */
+  watchTasks(onEvent: (event: TaskEvent) => void): Promise<UnlistenFn> {
+    return listen<TaskEvent>('task_event', (e) => onEvent(e.payload))
+      .then((unlisten) =>
+        invoke<AsyncResult<boolean>>('watch_tasks')
+          .then(unwrapResult)
+          .then(() => unlisten)
+          .catch((err) => {
+            unlisten();
+            throw err;
+          })
+      )
+      .catch((err) => {
+        console.error('[API] watchTasks failed:', err);
+        throw new TetsuoAPIError(`Failed to watch tasks: ${err}`);
+      });
+  },
+
+  /**
+   * Stop the live task feed for all listeners
+   */
+  unwatchTasks(): Promise<boolean> {
+    return invoke<AsyncResult<boolean>>('unwatch_tasks')
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] unwatchTasks failed:', err);
+        throw new TetsuoAPIError(`Failed to stop task feed: ${err}`);
+      });
   },
 
   /**
@@ -1076,9 +1278,26 @@ export const TaskAPI = {
   /**
    * Complete a claimed task
    * @param taskId - ID of the task to complete
-   */
-  completeTask(taskId: string): Promise<ExecutionResult> {
-    return invoke<AsyncResult<ExecutionResult>>('complete_task', { taskId })
+   * @param proof - Delivered work, hashed into the proof manifest
+   */
+  completeTask(
+    taskId: string,
+    proof?: {
+      proofUrl?: string;
+      notes?: string;
+      files?: string[];
+      links?: string[];
+      signatures?: string[];
+    },
+  ): Promise<ExecutionResult> {
+    return invoke<AsyncResult<ExecutionResult>>('complete_task', {
+      taskId,
+      proofUrl: proof?.proofUrl,
+      notes: proof?.notes,
+      files: proof?.files,
+      links: proof?.links,
+      signatures: proof?.signatures,
+    })
       .then(unwrapResult)
       .catch((err) => {
         console.error('[API] completeTask failed:', err);
@@ -1099,19 +1318,52 @@ export const TaskAPI = {
       });
   },
 
+  /**
+   * Approve a submitted task and release escrow to the worker (owner only).
+   * Resolves to the typed confirmation request (see
+   * IntentAPI.confirmationRequired); escrow is released by
+   * IntentAPI.executeConfirmed with the approve_completion intent and its ticket.
+   * @param taskId - ID of the task to approve
+   */
+  approveCompletion(taskId: string): Promise<ExecutionResult> {
+    return invoke<AsyncResult<ExecutionResult>>('approve_completion', { taskId })
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] approveCompletion failed:', err);
+        throw new TetsuoAPIError(`Failed to approve task: ${err}`);
+      });
+  },
+
   /**
    * Create a new task
    * @param description - Task description
    * @param rewardSol - Reward amount in SOL
    * @param rewardSkr - Optional SKR token reward amount (display units)
    * @param deadline - Optional deadline timestamp
+   * @param extras - Tags, deadline text and acceptance criteria stored with the
+   *   description, and capability names a worker must have
    */
-  createTask(description: string, rewardSol: number, rewardSkr?: number, deadline?: number): Promise<ExecutionResult> {
+  createTask(
+    description: string,
+    rewardSol: number,
+    rewardSkr?: number,
+    deadline?: number,
+    extras?: {
+      tags?: string[];
+      deadlineText?: string;
+      acceptanceCriteria?: string[];
+      requiredCapabilities?: string[];
+    },
+  ): Promise<ExecutionResult> {
     return invoke<AsyncResult<ExecutionResult>>('create_task', {
       description,
       rewardSol,
       rewardSkr,
       deadline,
+      tags: extras?.tags,
+      deadlineText: extras?.deadlineText,
+      acceptanceCriteria: extras?.acceptanceCriteria,
+      requiredCapabilities: extras?.requiredCapabilities,
     })
       .then(unwrapResult)
       .catch((err) => {
@@ -1134,6 +1386,49 @@ export const TaskAPI = {
   },
 };
 
+// ============================================================================
+// Worker API (autonomous claim/perform/complete loop)
+// ============================================================================
+
+export const WorkerAPI = {
+  /**
+   * Start the autonomous worker under the unattended policy
+   * @param config - Capabilities, reward threshold and daily budget
+   */
+  start(config?: WorkerConfig): Promise<WorkerStatus> {
+    return invoke<AsyncResult<WorkerStatus>>('start_worker', { config })
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] start_worker failed:', err);
+        throw new TetsuoAPIError(`Failed to start worker: ${err}`);
+      });
+  },
+
+  /**
+   * Stop the worker after its current step. Resolves false if none is running.
+   */
+  stop(): Promise<boolean> {
+    return invoke<AsyncResult<boolean>>('stop_worker')
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] stop_worker failed:', err);
+        throw new TetsuoAPIError(`Failed to stop worker: ${err}`);
+      });
+  },
+
+  /**
+   * Worker progress and the outcome of its last poll
+   */
+  status(): Promise<WorkerStatus> {
+    return invoke<AsyncResult<WorkerStatus>>('worker_status')
+      .then(unwrapResult)
+      .catch((err) => {
+        console.error('[API] worker_status failed:', err);
+        throw new TetsuoAPIError(`Failed to get worker status: ${err}`);
+      });
+  },
+};
+
 // ============================================================================
 // Database API (redb persistence)
 // ============================================================================
@@ -1395,6 +1690,7 @@ export const TetsuoAPI = {
   debug: DebugAPI,
   // Task Marketplace
   task: TaskAPI,
+  worker: WorkerAPI,
   // Phase 2 APIs
   code: CodeAPI,
   swap: SwapAPI,
@@ -23,7 +23,7 @@ import { useNotificationStore } from '../stores/notificationStore';
 import { useAvatarStore } from '../stores/avatarStore';
 import { isMobile } from '../hooks/usePlatform';
 import { hapticLight } from '../utils/haptics';
-import type { AgentAppearance, CameraMode } from '../types';
+import type { AgentAppearance, CameraMode, NetworkProfile } from '../types';
 import type { RenderQualityLevel } from '../config/renderQuality';
 import { QUALITY_PRESETS } from '../config/renderQuality';
 
@@ -186,7 +186,8 @@ export default function AppearanceMenu({ isOpen, onClose, onToggle }: Appearance
   const audioEnabled = useAudioEnabled();
   const { setAudioEnabled } = useAppStore();
   const { addToast } = useNotificationStore();
-  const [network, setNetwork] = useState<'devnet' | 'mainnet-beta'>('devnet');
+  const [network, setNetwork] = useState('devnet');
+  const [profiles, setProfiles] = useState<NetworkProfile[]>([]);
   const [customRpc, setCustomRpc] = useState('');
   const [networkLoading, setNetworkLoading] = useState(false);
 
@@ -221,27 +222,25 @@ export default function AppearanceMenu({ isOpen, onClose, onToggle }: Appearance
     return () => window.removeEventListener('keydown', handleKeyDown);
   }, [isOpen, onClose]);
 
-  // Load current network config on mount
+  // Load network profiles on mount
   useEffect(() => {
-    TetsuoAPI.config.getConfig().then((config) => {
-      const net = config.network === 'mainnet-beta' ? 'mainnet-beta' : 'devnet';
-      setNetwork(net);
-      setCustomRpc(config.rpc_url || '');
+    TetsuoAPI.config.listNetworkProfiles().then(({ active, profiles }) => {
+      setNetwork(active);
+      setProfiles(profiles);
+      setCustomRpc(profiles.find((p) => p.name === active)?.rpc_endpoints[0]?.url || '');
     }).catch((err) => {
-      console.warn('[Settings] Config load failed:', err);
+      console.warn('[Settings] Network profiles load failed:', err);
     });
   }, []);
 
-  const handleNetworkChange = useCallback(async (net: 'devnet' | 'mainnet-beta', rpc?: string) => {
+  // Switching profiles moves the program ID and mints along with the RPC
+  const handleNetworkChange = useCallback(async (name: string) => {
     setNetworkLoading(true);
-    const rpcUrl = rpc || (net === 'mainnet-beta'
-      ? 'https://api.mainnet-beta.solana.com'
-      : 'https://api.devnet.solana.com');
     try {
-      await TetsuoAPI.config.setRpcUrl(rpcUrl);
-      setNetwork(net);
-      setCustomRpc(rpcUrl);
-      addToast({ type: 'success', title: 'Network updated', message: `Switched to ${net}` });
+      const profile = await TetsuoAPI.config.setNetworkProfile(name);
+      setNetwork(profile.name);
+      setCustomRpc(profile.rpc_endpoints[0]?.url || '');
+      addToast({ type: 'success', title: 'Network updated', message: `Switched to ${profile.name}` });
     } catch (err) {
       addToast({ type: 'error', title: 'Network error', message: String(err) });
     } finally {
@@ -249,6 +248,19 @@ export default function AppearanceMenu({ isOpen, onClose, onToggle }: Appearance
     }
   }, [addToast]);
 
+  // A custom RPC URL is saved into the active profile
+  const handleRpcChange = useCallback(async (rpcUrl: string) => {
+    setNetworkLoading(true);
+    try {
+      await TetsuoAPI.config.setRpcUrl(rpcUrl);
+      addToast({ type: 'success', title: 'Network updated', message: `${network} now uses ${rpcUrl}` });
+    } catch (err) {
+      addToast({ type: 'error', title: 'Network error', message: String(err) });
+    } finally {
+      setNetworkLoading(false);
+    }
+  }, [addToast, network]);
+
   // ============================================================================
   // Handlers
   // ============================================================================
@@ -588,19 +600,20 @@ export default function AppearanceMenu({ isOpen, onClose, onToggle }: Appearance
                 <h4 className="text-holo-silver text-xs uppercase tracking-wider border-b border-cyber-light pb-1">
                   Network
                 </h4>
-                <div className="flex gap-2">
-                  {(['devnet', 'mainnet-beta'] as const).map((net) => (
+                <div className="flex flex-wrap gap-2">
+                  {profiles.map((profile) => (
                     <button
-                      key={net}
-                      onClick={() => { hapticLight(); handleNetworkChange(net); }}
+                      key={profile.name}
+                      onClick={() => { hapticLight(); handleNetworkChange(profile.name); }}
                       disabled={networkLoading}
+                      title={profile.program_id ? `Program ${profile.program_id}` : 'AgenC addresses not set'}
                       className={`flex-1 py-1.5 text-xs uppercase tracking-wider rounded border transition-colors ${
-                        network === net
+                        network === profile.name
                           ? 'bg-neon-cyan/20 border-neon-cyan text-neon-cyan'
                           : 'border-cyber-light text-holo-silver hover:border-neon-cyan/50'
                       } ${networkLoading ? 'opacity-50' : ''}`}
                     >
-                      {net === 'mainnet-beta' ? 'Mainnet' : 'Devnet'}
+                      {profile.name}
                     </button>
                   ))}
                 </div>
@@ -617,7 +630,7 @@ export default function AppearanceMenu({ isOpen, onClose, onToggle }: Appearance
                       className="flex-1 px-2 py-1.5 bg-cyber-dark border border-cyber-light rounded text-xs text-holo-silver font-mono placeholder:text-holo-silver/20 focus:border-neon-cyan focus:outline-none"
                     />
                     <button
-                      onClick={() => { hapticLight(); handleNetworkChange(network, customRpc); }}
+                      onClick={() => { hapticLight(); handleRpcChange(customRpc); }}
                       disabled={networkLoading || !customRpc}
                       className="px-3 py-1.5 text-xs bg-neon-cyan/20 border border-neon-cyan/40 text-neon-cyan rounded hover:bg-neon-cyan/30 disabled:opacity-40"
                     >
@@ -2,19 +2,20 @@
  * ============================================================================
  * HudPanel - Cyberpunk Heads-Up Display Panel
  * ============================================================================
- * Displays protocol state, wallet info, and system status.
+ * Displays protocol state, wallet info, RPC endpoint health and system status.
  * Features neon borders, animated data, and holographic styling.
  * ============================================================================
  */
 
 import { motion } from 'framer-motion';
-import type { ProtocolState, WalletInfo } from '../types';
+import type { ProtocolState, RpcPoolStats, WalletInfo } from '../types';
 
 interface HudPanelProps {
   title: string;
   color?: 'cyan' | 'magenta' | 'purple';
   protocolState?: ProtocolState | null;
   wallet?: WalletInfo | null;
+  rpcPool?: RpcPoolStats | null;
 }
 
 export default function HudPanel({
@@ -22,6 +23,7 @@ export default function HudPanel({
   color = 'cyan',
   protocolState,
   wallet,
+  rpcPool,
 }: HudPanelProps) {
   // Color variants
   const colors = {
@@ -54,6 +56,18 @@ export default function HudPanel({
     return num.toFixed(2);
   };
 
+  // Host part of an RPC URL
+  const endpointHost = (url: string): string => {
+    try {
+      return new URL(url).host;
+    } catch {
+      return url;
+    }
+  };
+
+  const preferred = rpcPool?.endpoints.find((e) => e.url === rpcPool.preferred);
+  const healthyCount = rpcPool?.endpoints.filter((e) => e.healthy).length ?? 0;
+
   // Truncate address
   const truncateAddress = (addr: string): string => {
     if (!addr || addr.length < 10) return addr || 'Not Connected';
@@ -161,6 +175,27 @@ export default function HudPanel({
               color={color}
             />
 
+            {/* RPC Pool */}
+            {rpcPool !== undefined && (
+              <>
+                <StatRow
+                  label="RPC"
+                  value={preferred ? endpointHost(preferred.url) : '--'}
+                  color={color}
+                />
+                <StatRow
+                  label="RPC Health"
+                  value={rpcPool
+                    ? `${healthyCount}/${rpcPool.endpoints.length} up · ${preferred ? Math.round(preferred.latency_ms) : '--'} ms`
+                    : '--'}
+                  color={color}
+                />
+                {preferred && preferred.slot_lag > 0 && (
+                  <StatRow label="Slot Lag" value={preferred.slot_lag.toString()} color={color} />
+                )}
+              </>
+            )}
+
             {/* Last Updated */}
             <div className="text-xs text-holo-silver/40 text-right">
               Updated: {protocolState?.last_updated
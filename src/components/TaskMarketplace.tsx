@@ -84,6 +84,24 @@ export default function TaskMarketplace({
     return () => clearInterval(interval);
   }, [fetchTasks]);
 
+  // Refresh as soon as the live feed reports a change
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    TetsuoAPI.task.watchTasks(() => fetchTasks())
+      .then((stop) => {
+        if (cancelled) stop();
+        else unlisten = stop;
+      })
+      .catch(() => {
+        // Polling above still keeps the list fresh
+      });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [fetchTasks]);
+
   // Task actions
   const handleClaim = async (taskId: string) => {
     setActionLoading(taskId);
@@ -308,6 +326,25 @@ export default function TaskMarketplace({
                       </span>
                     </div>
                     <p className="text-sm text-white/80 line-clamp-2">{task.description}</p>
+                    {task.description_status === 'mismatch' && (
+                      <div className="mt-1 text-[10px] text-red-400">
+                        Description does not match the on-chain hash
+                      </div>
+                    )}
+                    {(task.details?.tags.length ?? 0) > 0 && (
+                      <div className="mt-1 flex flex-wrap gap-1">
+                        {task.details!.tags.map((tag) => (
+                          <span key={tag} className="px-1 text-[10px] text-neon-cyan/70 border border-neon-cyan/20 rounded">
+                            {tag}
+                          </span>
+                        ))}
+                      </div>
+                    )}
+                    {(task.required_capabilities?.length ?? 0) > 0 && (
+                      <div className="mt-1 text-[10px] text-holo-silver/50">
+                        requires {task.required_capabilities!.join(', ')}
+                      </div>
+                    )}
                     <div className="mt-1 text-[10px] text-holo-silver/40">
                       by {truncateAddress(task.creator)}
                       {task.claimer && (
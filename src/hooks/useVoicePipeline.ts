@@ -59,6 +59,7 @@ TASK MANAGEMENT:
 - "Claim task [id]"
 - "Complete task [id]"
 - "Cancel task [id]"
+- "Approve task [id]"
 - "List open tasks"
 - "Get task status [id]"
 
@@ -83,13 +84,19 @@ SOCIAL (Pro tier):
 
 When you receive a command, parse it into a JSON intent with this structure:
 {
-  "action": "create_task" | "claim_task" | "complete_task" | "cancel_task" | "list_open_tasks" | "get_task_status" | "get_balance" | "get_address" | "get_protocol_state" | "code_fix" | "code_review" | "code_generate" | "code_explain" | "swap_tokens" | "get_swap_quote" | "get_token_price" | "post_tweet" | "post_thread" | "help" | "unknown",
+  "action": "create_task" | "claim_task" | "complete_task" | "cancel_task" | "approve_completion" | "list_open_tasks" | "get_task_status" | "get_balance" | "get_address" | "get_history" | "get_protocol_state" | "code_fix" | "code_review" | "code_generate" | "code_explain" | "swap_tokens" | "get_swap_quote" | "get_token_price" | "post_tweet" | "post_thread" | "help" | "unknown",
   "params": { ... relevant parameters ... }
 }
 
+For create_task, params should include: description, reward_sol, and optionally reward_skr, deadline_hours, deadline_text, tags (array), acceptance_criteria (array), required_capabilities (array)
+For list_open_tasks, params are optional filters: min_reward_sol, max_reward_sol, capabilities (array of names the tasks may require), creator ("me" for the user's own tasks), sort ("reward_desc" | "reward_asc" | "deadline_asc" | "newest"), limit
+For complete_task, params should include: task_id, and optionally proof_url, notes, files (array of local paths), links (array of gist/PR URLs), signatures (array of transaction signatures)
+Capability names (required_capabilities, capabilities) are such as compute, inference, storage, network, validator, code, code_review, trading, social, email, image, video
 For CODE actions, params should include: file_path, issue_description (for fix), language (for generate), description (for generate)
 For SWAP actions, params should include: from_token, to_token, amount
+For get_history, params are optional filters: since and until (YYYY-MM-DD), days (number), type ("agenc" | "swap" | "transfer"), limit
 For TWITTER actions, params should include: content (for tweet), tweets (array for thread)
+If the user names a wallet ("from the treasury wallet"), add "wallet": "<profile name>" to params; otherwise omit it
 
 After parsing, respond naturally confirming what you understood and what action you'll take.
 For financial operations (swaps, task creation), ALWAYS confirm the amount before executing.`;
@@ -190,11 +197,12 @@ export function useVoicePipeline({
       (result: ExecutionResult) => {
         console.log('[Voice] Intent result:', result.success);
 
-        // Add result message
+        // Add result message, with the simulated outcome when confirming
+        const preview = TetsuoAPI.intent.confirmationRequired(result)?.preview;
         onMessage({
           id: `result_${Date.now()}`,
           role: 'system',
-          content: result.message,
+          content: preview ? [result.message, ...preview.summary].join('\n') : result.message,
           timestamp: Date.now(),
           result,
         });
@@ -722,6 +730,9 @@ export function useVoicePipeline({
     if (lower.includes('address')) {
       return { action: 'get_address', params: {}, raw_transcript: text };
     }
+    if (lower.includes('history') || lower.includes('transactions')) {
+      return { action: 'get_history', params: {}, raw_transcript: text };
+    }
     if (lower.includes('list') && lower.includes('task')) {
       return { action: 'list_open_tasks', params: {}, raw_transcript: text };
     }
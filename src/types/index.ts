@@ -16,10 +16,12 @@ export type IntentAction =
   | 'claim_task'
   | 'complete_task'
   | 'cancel_task'
+  | 'approve_completion'
   | 'list_open_tasks'
   | 'get_task_status'
   | 'get_balance'
   | 'get_address'
+  | 'get_history'
   | 'get_protocol_state'
   // Code Operations (Pro tier)
   | 'code_fix'
@@ -77,6 +79,63 @@ export interface AgencTask {
   claimer?: string;
   created_at: number;
   deadline?: number;
+  /** Off-chain description document, once verified against the on-chain hash */
+  details?: TaskDescription;
+  /** Absent when no description registry is configured */
+  description_status?: DescriptionStatus;
+  /** Capability names a worker must have (e.g. "code_review") */
+  required_capabilities?: string[];
+}
+
+/** Off-chain task document; its SHA-256 is the on-chain description_hash */
+export interface TaskDescription {
+  description: string;
+  tags: string[];
+  deadline_text?: string;
+  acceptance_criteria: string[];
+  created_at: number;
+}
+
+export type DescriptionStatus = 'verified' | 'mismatch' | 'missing';
+
+/** Filters for listing open tasks (list_open_tasks params) */
+export interface TaskFilters {
+  min_reward_sol?: number;
+  max_reward_sol?: number;
+  /** Capability names we have: only tasks requiring nothing else */
+  capabilities?: string[];
+  /** Creator address, or "me" for the signing wallet */
+  creator?: string;
+  sort?: 'reward_desc' | 'reward_asc' | 'deadline_asc' | 'newest';
+  offset?: number;
+  limit?: number;
+}
+
+/** Raw on-chain task account, as carried by task events */
+export interface OnChainTask {
+  task_id: number;
+  pda: string;
+  creator: string;
+  escrow_account: string;
+  required_capabilities: number;
+  description_hash: number[];
+  constraint_hash: number[];
+  state: 'open' | 'in_progress' | 'pending_validation' | 'completed' | 'cancelled' | 'disputed';
+  reward_lamports: number;
+  reward_skr_tokens: number;
+  deadline: number;
+  claimed_by: string | null;
+}
+
+/** Live task feed event (Tauri `task_event`) */
+export interface TaskEvent {
+  kind: 'created' | 'claimed' | 'completed' | 'cancelled' | 'disputed' | 'closed';
+  pda: string;
+  /** The task as it is now; null once the account is closed */
+  task: OnChainTask | null;
+  previous_state: OnChainTask['state'] | null;
+  source: 'subscription' | 'poll';
+  slot: number | null;
 }
 
 // Helper to convert lamports to SOL
@@ -109,17 +168,83 @@ export interface WalletInfo {
   is_connected: boolean;
 }
 
+/** Named wallet profile ("hot", "treasury", ...) loaded in the executor */
+export interface WalletProfileSummary {
+  name: string;
+  pubkey: string;
+  active: boolean;
+}
+
+/** Persisted wallet profile returned by addWalletProfile */
+export interface WalletProfile {
+  name: string;
+  pubkey: string;
+  keypair_path: string;
+  created_at: number;
+}
+
 // ============================================================================
 // Policy Types
 // ============================================================================
 
 export type ConfirmationType = 'none' | 'verbal' | 'typed' | 'hardware';
 
+/** Signed, single-use approval for one exact intent */
+export interface ConfirmationTicket {
+  nonce: string;
+  intent_hash: string;
+  expires_at: number;
+  signature: string;
+}
+
 export interface PolicyCheck {
   allowed: boolean;
   requires_confirmation: boolean;
   confirmation_type: ConfirmationType;
   reason: string;
+  /** Present when execute_intent needs confirmation; pass to executeConfirmed */
+  ticket?: ConfirmationTicket;
+  /** Simulated outcome of the transaction being confirmed (spending intents) */
+  preview?: TransactionPreview;
+}
+
+/** What a transaction will do, from simulateTransaction */
+export interface TransactionPreview {
+  balance_changes: BalanceChange[];
+  /** Network fee, including any priority fee */
+  fee_lamports: number;
+  /** Rent deposited into accounts the transaction creates */
+  rent_lamports: number;
+  compute_units: number | null;
+  /** AgenC protocol fee taken from the reward */
+  protocol_fee_lamports: number | null;
+  /** Set when the transaction would fail as built */
+  error: string | null;
+  /** Program logs, only when the simulation failed */
+  logs?: string[];
+  /** One human-readable line per change */
+  summary: string[];
+}
+
+export interface BalanceChange {
+  account: string;
+  /** Token mint; null for SOL */
+  mint: string | null;
+  /** Wallet owning the token account */
+  owner: string | null;
+  /** Raw amounts (lamports or token base units) */
+  before: number;
+  after: number;
+  decimals: number;
+}
+
+export type PolicySource = 'blocked_action' | 'spend_limit' | 'rule' | 'fallback' | 'unknown_value';
+
+export interface PolicyExplanation {
+  check: PolicyCheck;
+  source: PolicySource;
+  rule: string | null;
+  rule_index: number | null;
 }
 
 // ============================================================================
@@ -145,7 +270,11 @@ export type VoiceState = 'idle' | 'listening' | 'processing' | 'speaking' | 'err
 
 export interface AppConfig {
   rpc_url: string;
+  // Tried after rpc_url when it is slow, lagging or rate limited
+  rpc_fallbacks: RpcEndpoint[];
+  // Name of the active network profile
   network: string;
+  network_profile: NetworkProfile;
   whisper_model_path?: string;
   qdrant_url?: string;
   // Twitter OAuth 2.0 client ID (public, for PKCE flow)
@@ -155,6 +284,71 @@ export interface AppConfig {
   email_from_name?: string;
   github_default_owner?: string;
   github_default_repo?: string;
+  // Wallet locks itself after this many idle seconds (absent = never)
+  wallet_auto_lock_secs?: number;
+  // Gateway serving off-chain task descriptions by hash
+  description_gateway_url?: string;
+}
+
+// ============================================================================
+// Network Profiles
+// ============================================================================
+
+export type NetworkKind = 'localnet' | 'devnet' | 'mainnet' | 'custom';
+
+/** Endpoints, program and mints for one cluster */
+export interface NetworkProfile {
+  name: string;
+  kind: NetworkKind;
+  /** Primary first */
+  rpc_endpoints: RpcEndpoint[];
+  /** Empty while unset (only mainnet ships with addresses) */
+  program_id: string;
+  skr_mint: string;
+  tetsuo_mint: string;
+  /** `{path}` becomes `tx/<sig>` or `address/<addr>` */
+  explorer_url: string;
+}
+
+export interface NetworkProfiles {
+  active: string;
+  profiles: NetworkProfile[];
+}
+
+// ============================================================================
+// RPC Pool Types
+// ============================================================================
+
+/** An RPC endpoint and its share of traffic relative to the others */
+export interface RpcEndpoint {
+  url: string;
+  weight?: number;
+}
+
+export interface RpcEndpointStats {
+  url: string;
+  weight: number;
+  latency_ms: number;
+  /** Share of recent requests that failed (0-1) */
+  error_rate: number;
+  requests: number;
+  errors: number;
+  slot: number | null;
+  /** Slots behind the most advanced endpoint */
+  slot_lag: number;
+  /** Seconds left before a rate-limited endpoint is used again */
+  cooldown_secs: number;
+  last_error: string | null;
+  score: number;
+  healthy: boolean;
+}
+
+export interface RpcPoolStats {
+  endpoints: RpcEndpointStats[];
+  /** Endpoint reads go to first */
+  preferred: string | null;
+  highest_slot: number | null;
+  last_probe: number | null;
 }
 
 // ============================================================================
@@ -557,7 +751,7 @@ export interface CameraPreset {
 // Database Types (redb persistence)
 // ============================================================================
 
-export type DbTaskStatus = 'Claimed' | 'InProgress' | 'Completed' | 'Disputed' | 'Resolved';
+export type DbTaskStatus = 'Claimed' | 'InProgress' | 'Completed' | 'Disputed' | 'Resolved' | 'Failed';
 
 export interface TaskRecord {
   task_id: string;
@@ -599,6 +793,39 @@ export interface DbPruneResult {
   sessions_pruned: number;
 }
 
+// ============================================================================
+// Autonomous Worker Types
+// ============================================================================
+
+/** Worker settings; omitted fields use the defaults */
+export interface WorkerConfig {
+  capabilities?: string[];
+  min_reward_sol?: number;
+  max_tasks_per_day?: number;
+  poll_interval_secs?: number;
+  min_time_left_secs?: number;
+  wallet?: string | null;
+  output_dir?: string | null;
+  kill_file?: string | null;
+}
+
+/** What one poll of the worker did */
+export type WorkerTick =
+  | { outcome: 'stopped'; reason: string }
+  | { outcome: 'budget_exhausted'; claimed: number }
+  | { outcome: 'idle' }
+  | { outcome: 'completed'; task: string; signature: string | null; proof_hash: string | null }
+  | { outcome: 'failed'; task: string; error: string };
+
+export interface WorkerStatus {
+  running: boolean;
+  started_at: number;
+  completed: number;
+  failed: number;
+  last_tick: WorkerTick | null;
+  last_tick_at: number | null;
+}
+
 // ============================================================================
 // AgenC One Device Types
 // ============================================================================